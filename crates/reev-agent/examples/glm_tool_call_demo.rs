@@ -81,6 +81,9 @@ async fn main() -> Result<()> {
             allowed_tools: None,
             account_states: None,
             key_map: Some(key_map.clone()),
+            ground_truth: None,
+            budget: None,
+            seed: None,
         };
 
         match ZAIAgent::run("glm-4.6", request, key_map.clone()).await {
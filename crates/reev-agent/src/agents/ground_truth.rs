@@ -0,0 +1,350 @@
+//! Generic deterministic agent built from a benchmark's ground truth
+//!
+//! Benchmarks without a hand-written module in [`super::coding`] are answered by
+//! replaying their `expected_instructions`: placeholders are resolved through the
+//! key map, and the data of system and SPL token transfers, which benchmarks
+//! leave as `"..."`, is rebuilt with the amount the `final_state_assertions`
+//! require of the destination given its current balance. Instructions of other
+//! programs need their data written out in base58. The runner sends the ground
+//! truth to the deterministic agent only, each flow step with the instructions of
+//! that step, so a new benchmark gets a reference run without any code.
+
+use anyhow::{bail, Context, Result};
+use reev_lib::{
+    agent::{RawAccountMeta, RawInstruction},
+    benchmark::{BenchmarkInstruction, GroundTruth, StateAssertion},
+};
+use reev_protocols::native::{handle_sol_transfer, handle_spl_transfer};
+use serde_json::Value;
+use solana_sdk::pubkey::Pubkey;
+use std::{collections::HashMap, str::FromStr};
+use tracing::info;
+
+const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
+
+/// Build the instructions of `ground_truth` for the accounts of this run
+pub(crate) async fn handle_ground_truth(
+    benchmark_id: &str,
+    ground_truth: &GroundTruth,
+    account_states: &HashMap<String, Value>,
+    key_map: &HashMap<String, String>,
+) -> Result<Vec<RawInstruction>> {
+    info!("[reev-agent] No coding agent for '{benchmark_id}'. Building it from the ground truth.");
+
+    if ground_truth.expected_instructions.is_empty() {
+        bail!(
+            "Benchmark '{benchmark_id}' has no expected_instructions to build a reference run from"
+        );
+    }
+
+    let mut instructions = Vec::new();
+    for (index, expected) in ground_truth.expected_instructions.iter().enumerate() {
+        let built = build_instruction(expected, ground_truth, account_states, key_map)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to build expected instruction {} of '{benchmark_id}'",
+                    index + 1
+                )
+            })?;
+        instructions.extend(built);
+    }
+
+    info!(
+        "[reev-agent] Built {} instructions from the ground truth of '{benchmark_id}'.",
+        instructions.len()
+    );
+    Ok(instructions)
+}
+
+async fn build_instruction(
+    expected: &BenchmarkInstruction,
+    ground_truth: &GroundTruth,
+    account_states: &HashMap<String, Value>,
+    key_map: &HashMap<String, String>,
+) -> Result<Vec<RawInstruction>> {
+    let program_id = resolve(&expected.program_id, key_map)?;
+    let placeholders: Vec<&str> = expected
+        .accounts
+        .iter()
+        .map(|a| a.pubkey.as_str())
+        .collect();
+
+    match (program_id.to_string().as_str(), placeholders.as_slice()) {
+        (SYSTEM_PROGRAM_ID, [from, to]) => {
+            let lamports = transfer_amount(ground_truth, account_states, to, "lamports")?;
+            handle_sol_transfer(
+                resolve(from, key_map)?,
+                resolve(to, key_map)?,
+                lamports,
+                key_map,
+            )
+            .await
+        }
+        (program, [source, destination, authority]) if program == spl_token::ID.to_string() => {
+            let amount = transfer_amount(ground_truth, account_states, destination, "amount")?;
+            handle_spl_transfer(
+                resolve(source, key_map)?,
+                resolve(destination, key_map)?,
+                resolve(authority, key_map)?,
+                amount,
+                key_map,
+            )
+            .await
+        }
+        _ => {
+            let data = match expected.data.as_deref() {
+                Some(data) if data != "..." && bs58::decode(data).into_vec().is_ok() => data,
+                _ => bail!(
+                    "The data of {} instructions cannot be derived; write it out in base58",
+                    expected.program_id
+                ),
+            };
+            let accounts = expected
+                .accounts
+                .iter()
+                .map(|account| {
+                    Ok(RawAccountMeta {
+                        pubkey: resolve(&account.pubkey, key_map)?.to_string(),
+                        is_signer: account.is_signer,
+                        is_writable: account.is_writable,
+                    })
+                })
+                .collect::<Result<_>>()?;
+            Ok(vec![RawInstruction {
+                program_id: program_id.to_string(),
+                accounts,
+                data: data.to_string(),
+            }])
+        }
+    }
+}
+
+/// The pubkey a placeholder stands for in this run, or the pubkey written out
+fn resolve(placeholder: &str, key_map: &HashMap<String, String>) -> Result<Pubkey> {
+    let address = key_map.get(placeholder).map_or(placeholder, String::as_str);
+    Pubkey::from_str(address)
+        .with_context(|| format!("'{placeholder}' is neither in the key map nor a pubkey"))
+}
+
+/// What `destination` must receive to reach its asserted final balance
+///
+/// `field` is the balance in its account state: `lamports` or the token `amount`.
+fn transfer_amount(
+    ground_truth: &GroundTruth,
+    account_states: &HashMap<String, Value>,
+    destination: &str,
+    field: &str,
+) -> Result<u64> {
+    let expected = ground_truth
+        .final_state_assertions
+        .iter()
+        .find_map(|assertion| match assertion {
+            StateAssertion::SolBalance {
+                pubkey, expected, ..
+            } if pubkey == destination && field == "lamports" => Some(*expected),
+            StateAssertion::TokenAccountBalance {
+                pubkey,
+                expected: Some(expected),
+                ..
+            } if pubkey == destination && field == "amount" => Some(*expected),
+            _ => None,
+        })
+        .with_context(|| format!("No final balance of {destination} is asserted"))?;
+    let current = account_states
+        .get(destination)
+        .and_then(|state| state[field].as_u64())
+        .unwrap_or(0);
+
+    match expected.checked_sub(current) {
+        Some(amount) if amount > 0 => Ok(amount),
+        _ => bail!(
+            "{destination} holds {current} and must end with {expected}; no transfer reaches that"
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ground_truth(yaml: &str) -> GroundTruth {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_transfers_from_assertions() {
+        let user = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let user_ata = Pubkey::new_unique();
+        let recipient_ata = Pubkey::new_unique();
+        let key_map = HashMap::from([
+            ("USER_WALLET_PUBKEY".to_string(), user.to_string()),
+            ("RECIPIENT_WALLET_PUBKEY".to_string(), recipient.to_string()),
+            ("USER_USDC_ATA".to_string(), user_ata.to_string()),
+            ("RECIPIENT_USDC_ATA".to_string(), recipient_ata.to_string()),
+        ]);
+        let account_states = HashMap::from([
+            (
+                "RECIPIENT_WALLET_PUBKEY".to_string(),
+                json!({"lamports": 1_000}),
+            ),
+            (
+                "RECIPIENT_USDC_ATA".to_string(),
+                json!({"lamports": 2_039_280, "amount": 0}),
+            ),
+        ]);
+        let ground_truth = ground_truth(
+            r#"
+final_state_assertions:
+  - {type: SolBalance, pubkey: RECIPIENT_WALLET_PUBKEY, expected: 100001000}
+  - {type: TokenAccountBalance, pubkey: RECIPIENT_USDC_ATA, expected: 15000000}
+expected_instructions:
+  - program_id: "11111111111111111111111111111111"
+    accounts:
+      - {pubkey: USER_WALLET_PUBKEY, is_signer: true, is_writable: true}
+      - {pubkey: RECIPIENT_WALLET_PUBKEY, is_signer: false, is_writable: true}
+    data: "..."
+  - program_id: TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA
+    accounts:
+      - {pubkey: USER_USDC_ATA, is_signer: false, is_writable: true}
+      - {pubkey: RECIPIENT_USDC_ATA, is_signer: false, is_writable: true}
+      - {pubkey: USER_WALLET_PUBKEY, is_signer: true, is_writable: false}
+"#,
+        );
+
+        let instructions = handle_ground_truth("900-new", &ground_truth, &account_states, &key_map)
+            .await
+            .unwrap();
+        assert_eq!(instructions.len(), 2);
+
+        let sol = &instructions[0];
+        assert_eq!(sol.program_id, SYSTEM_PROGRAM_ID);
+        assert_eq!(sol.accounts[1].pubkey, recipient.to_string());
+        let data = bs58::decode(&sol.data).into_vec().unwrap();
+        assert_eq!(&data[4..], &100_000_000u64.to_le_bytes());
+
+        let spl = &instructions[1];
+        assert_eq!(spl.program_id, spl_token::ID.to_string());
+        assert_eq!(spl.accounts[2].pubkey, user.to_string());
+        let data = bs58::decode(&spl.data).into_vec().unwrap();
+        assert_eq!(data[0], 3); // Transfer
+        assert_eq!(&data[1..], &15_000_000u64.to_le_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_flow_steps_replay_their_own_instructions() {
+        let memo = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr";
+        let user = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let key_map = HashMap::from([
+            ("USER_WALLET_PUBKEY".to_string(), user.to_string()),
+            ("RECIPIENT_WALLET_PUBKEY".to_string(), recipient.to_string()),
+        ]);
+        let account_states = HashMap::from([(
+            "RECIPIENT_WALLET_PUBKEY".to_string(),
+            json!({"lamports": 0}),
+        )]);
+        let flow = ground_truth(&format!(
+            r#"
+final_state_assertions:
+  - {{type: SolBalance, pubkey: RECIPIENT_WALLET_PUBKEY, expected: 5000}}
+expected_instructions:
+  - program_id: "11111111111111111111111111111111"
+    accounts:
+      - {{pubkey: USER_WALLET_PUBKEY, is_signer: true, is_writable: true}}
+      - {{pubkey: RECIPIENT_WALLET_PUBKEY, is_signer: false, is_writable: true}}
+    data: "..."
+  - step: 2
+    program_id: {memo}
+    accounts:
+      - {{pubkey: USER_WALLET_PUBKEY, is_signer: true, is_writable: false}}
+    data: "2NEpo7TZRRrLZSi2U"
+"#
+        ));
+
+        // Untagged instructions belong to the first step
+        let first = handle_ground_truth(
+            "903-flow-step-1",
+            &flow.for_flow_step(1),
+            &account_states,
+            &key_map,
+        )
+        .await
+        .unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].program_id, SYSTEM_PROGRAM_ID);
+        let data = bs58::decode(&first[0].data).into_vec().unwrap();
+        assert_eq!(&data[4..], &5_000u64.to_le_bytes());
+
+        let second = handle_ground_truth(
+            "903-flow-step-2",
+            &flow.for_flow_step(2),
+            &account_states,
+            &key_map,
+        )
+        .await
+        .unwrap();
+        assert_eq!(second.len(), 1);
+        assert_eq!(second[0].program_id, memo);
+
+        let err = handle_ground_truth(
+            "903-flow-step-3",
+            &flow.for_flow_step(3),
+            &account_states,
+            &key_map,
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("no expected_instructions"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_literal_data_and_unbuildable_instructions() {
+        let memo = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr";
+        let user = Pubkey::new_unique();
+        let key_map = HashMap::from([("USER_WALLET_PUBKEY".to_string(), user.to_string())]);
+        let with_data = |data: &str| {
+            ground_truth(&format!(
+                r#"
+final_state_assertions: []
+expected_instructions:
+  - program_id: {memo}
+    accounts:
+      - {{pubkey: USER_WALLET_PUBKEY, is_signer: true, is_writable: false}}
+    data: "{data}"
+"#
+            ))
+        };
+
+        let instructions = handle_ground_truth(
+            "901-memo",
+            &with_data("2NEpo7TZRRrLZSi2U"),
+            &HashMap::new(),
+            &key_map,
+        )
+        .await
+        .unwrap();
+        assert_eq!(instructions[0].program_id, memo);
+        assert_eq!(instructions[0].accounts[0].pubkey, user.to_string());
+        assert_eq!(instructions[0].data, "2NEpo7TZRRrLZSi2U");
+
+        let err = handle_ground_truth("901-memo", &with_data("..."), &HashMap::new(), &key_map)
+            .await
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("base58"), "{err:#}");
+
+        let no_instructions = ground_truth("final_state_assertions: []\n");
+        let err = handle_ground_truth("902-api", &no_instructions, &HashMap::new(), &key_map)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("no expected_instructions"),
+            "{err}"
+        );
+    }
+}
@@ -1 +1,2 @@
 pub mod coding;
+pub mod ground_truth;
@@ -0,0 +1,539 @@
+//! Turn, tool call, token and wall time budgets of an agent run
+//!
+//! [`BudgetModel`] wraps the completion model of an agent and counts what the run
+//! spends against the [`BudgetSettings`] of the request. A call the budget no
+//! longer allows fails with a provider error instead of reaching the model, and
+//! so does a response whose tool calls would go past `max_tool_calls`, before
+//! they are executed. The agent then answers with
+//! [`BudgetTracker::stopped_response`]: the transactions its tools built so far,
+//! marked with the `budget_exceeded` limit, so the runner scores what was done
+//! and records the run as stopped at its budget.
+//!
+//! Token usage is only known once a call returns, so the token limits stop the
+//! run before its next call; a final answer is never thrown away.
+//!
+//! The tokens every call used, cached prompt tokens included, are reported to the
+//! runner under `usage` in the agent's answer ([`BudgetTracker::with_usage`]), which
+//! prices them for the cost reports. Everything the run spent is reported under
+//! `budget_usage`, so the runner can send the next step of a flow what is left of
+//! the attempt's budget. Tool calls whose arguments were rejected
+//! ([`reev_tools::validation`]) are counted under `argument_retries`.
+
+use std::{
+    fmt,
+    sync::{Arc, Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use reev_config::BudgetSettings;
+use reev_flow::TokenUsage;
+use reev_tools::validation::ValidationContext;
+use rig::{
+    completion::{self, CompletionError, CompletionModel, CompletionRequest},
+    message::{AssistantContent, Message, ToolResultContent, UserContent},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{info, warn};
+
+use crate::{
+    enhanced::common::{AgentHelper, ExecutionResult},
+    LlmRequest,
+};
+
+/// The limit that stopped a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BudgetExceeded {
+    /// Name of the limit in `[budget]`, such as `max_turns`
+    pub limit: &'static str,
+    /// Its value
+    pub max: u64,
+}
+
+impl fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} of {} reached", self.limit, self.max)
+    }
+}
+
+/// What a run has spent so far
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BudgetUsage {
+    pub turns: u32,
+    pub tool_calls: u32,
+    #[serde(flatten)]
+    pub tokens: TokenUsage,
+}
+
+#[derive(Default)]
+struct BudgetState {
+    usage: BudgetUsage,
+    exceeded: Option<BudgetExceeded>,
+    /// Text of every tool result in the conversation so far
+    tool_results: Vec<String>,
+}
+
+/// The budget of one agent run and what it has spent
+pub struct BudgetTracker {
+    budget: BudgetSettings,
+    started: Instant,
+    state: Mutex<BudgetState>,
+    /// Validation of the run's tool calls, for its `argument_retries`
+    validation: OnceLock<Arc<ValidationContext>>,
+}
+
+impl BudgetTracker {
+    pub fn new(budget: BudgetSettings) -> Arc<Self> {
+        Arc::new(Self {
+            budget,
+            started: Instant::now(),
+            state: Mutex::new(BudgetState::default()),
+            validation: OnceLock::new(),
+        })
+    }
+
+    /// The budget the runner sent with `payload`, else the `[budget]` configuration
+    pub fn for_request(payload: &LlmRequest) -> Arc<Self> {
+        Self::new(payload.budget.unwrap_or_else(|| reev_config::get().budget))
+    }
+
+    /// Report the tool calls `validation` rejects as the run's `argument_retries`
+    pub fn track_rejections(&self, validation: &Arc<ValidationContext>) {
+        let _ = self.validation.set(validation.clone());
+    }
+
+    pub fn usage(&self) -> BudgetUsage {
+        self.state.lock().unwrap().usage
+    }
+
+    /// The limit that stopped the run, if one did
+    pub fn exceeded(&self) -> Option<BudgetExceeded> {
+        self.state.lock().unwrap().exceeded
+    }
+
+    /// The answer of a run the budget stopped, or `error` if the run failed otherwise
+    pub fn stopped_response(
+        &self,
+        error: impl Into<anyhow::Error>,
+        agent_name: &str,
+    ) -> Result<String> {
+        let Some(exceeded) = self.exceeded() else {
+            return Err(error.into());
+        };
+        let (usage, transactions) = {
+            let state = self.state.lock().unwrap();
+            (state.usage, built_transactions(&state.tool_results))
+        };
+        warn!(
+            "[{agent_name}] Stopped at the budget ({exceeded}) after {} turns and {} tool calls; answering with {} transactions",
+            usage.turns,
+            usage.tool_calls,
+            transactions.len()
+        );
+
+        let execution_result = ExecutionResult {
+            transactions,
+            summary: format!("Stopped before finishing: {exceeded}"),
+            signatures: vec![],
+        };
+        let tool_calls = AgentHelper::extract_tool_calls_from_otel();
+        let response = AgentHelper::format_comprehensive_response(
+            execution_result,
+            Some(tool_calls),
+            agent_name,
+        )?;
+        let mut response: Value = serde_json::from_str(&response)?;
+        response["budget_exceeded"] = Value::String(exceeded.limit.to_string());
+        self.with_usage(response.to_string())
+    }
+
+    /// `response` with the tokens the run used under `usage`, all it spent under
+    /// `budget_usage` and its rejected tool calls under `argument_retries`
+    ///
+    /// Answers that are not a JSON object are returned unchanged.
+    pub fn with_usage(&self, response: String) -> Result<String> {
+        match serde_json::from_str::<Value>(&response) {
+            Ok(Value::Object(mut object)) => {
+                object.insert(
+                    "usage".to_string(),
+                    serde_json::to_value(self.usage().tokens)?,
+                );
+                object.insert(
+                    "budget_usage".to_string(),
+                    serde_json::to_value(self.usage())?,
+                );
+                if let Some(validation) = self.validation.get() {
+                    object.insert(
+                        "argument_retries".to_string(),
+                        Value::from(validation.retries()),
+                    );
+                }
+                Ok(Value::Object(object).to_string())
+            }
+            _ => Ok(response),
+        }
+    }
+
+    /// The first limit the run has reached before another call
+    fn reached(&self, usage: &BudgetUsage) -> Option<BudgetExceeded> {
+        let budget = &self.budget;
+        let limits = [
+            (
+                "max_turns",
+                budget.max_turns.map(u64::from),
+                u64::from(usage.turns),
+            ),
+            (
+                "max_input_tokens",
+                budget.max_input_tokens,
+                usage.tokens.prompt_tokens,
+            ),
+            (
+                "max_output_tokens",
+                budget.max_output_tokens,
+                usage.tokens.completion_tokens,
+            ),
+            (
+                "max_wall_time_secs",
+                budget.max_wall_time_secs,
+                self.started.elapsed().as_secs(),
+            ),
+        ];
+        limits.into_iter().find_map(|(limit, max, used)| {
+            max.filter(|max| used >= *max)
+                .map(|max| BudgetExceeded { limit, max })
+        })
+    }
+
+    /// Time left before `max_wall_time_secs`, if it is set
+    fn time_left(&self) -> Option<Duration> {
+        self.budget
+            .max_wall_time_secs
+            .map(|max| Duration::from_secs(max).saturating_sub(self.started.elapsed()))
+    }
+
+    /// Record that `exceeded` stopped the run, as the error ending its loop
+    fn stop(&self, exceeded: BudgetExceeded) -> CompletionError {
+        info!("Budget: {exceeded}");
+        self.state.lock().unwrap().exceeded = Some(exceeded);
+        CompletionError::ProviderError(format!("Budget exceeded: {exceeded}"))
+    }
+
+    /// Count a call before it is made, or stop the run
+    fn before_call(&self, request: &CompletionRequest) -> Result<(), CompletionError> {
+        let mut state = self.state.lock().unwrap();
+        state.tool_results = request
+            .chat_history
+            .iter()
+            .filter_map(|message| match message {
+                Message::User { content } => Some(content.iter()),
+                Message::Assistant { .. } => None,
+            })
+            .flatten()
+            .filter_map(|content| match content {
+                UserContent::ToolResult(result) => Some(result.content.iter()),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|content| match content {
+                ToolResultContent::Text(text) => Some(text.text.clone()),
+                ToolResultContent::Image(_) => None,
+            })
+            .collect();
+        // The first call is always made, so every run has an answer to give
+        if state.usage.turns > 0 {
+            if let Some(exceeded) = self.reached(&state.usage) {
+                drop(state);
+                return Err(self.stop(exceeded));
+            }
+        }
+        state.usage.turns += 1;
+        Ok(())
+    }
+
+    /// Count what a response spent, or stop the run before its tool calls
+    fn after_call(
+        &self,
+        response: &completion::CompletionResponse<impl Serialize>,
+    ) -> Result<(), CompletionError> {
+        let tool_calls = response
+            .choice
+            .iter()
+            .filter(|content| matches!(content, AssistantContent::ToolCall(_)))
+            .count() as u32;
+        let tokens = token_usage(response);
+        let mut state = self.state.lock().unwrap();
+        state.usage.tokens += tokens;
+
+        if let Some(max) = self
+            .budget
+            .max_tool_calls
+            .filter(|max| state.usage.tool_calls + tool_calls > *max)
+        {
+            drop(state);
+            return Err(self.stop(BudgetExceeded {
+                limit: "max_tool_calls",
+                max: u64::from(max),
+            }));
+        }
+        state.usage.tool_calls += tool_calls;
+        Ok(())
+    }
+}
+
+/// The tokens a response used
+///
+/// rig reports prompt and completion tokens only, so the cache figures are read
+/// from the provider's own usage in the raw response. OpenAI-compatible APIs count
+/// cached tokens within the prompt tokens; Anthropic counts cache reads and writes
+/// apart from `input_tokens`, and they are added to the prompt tokens here.
+fn token_usage(response: &completion::CompletionResponse<impl Serialize>) -> TokenUsage {
+    let raw = serde_json::to_value(&response.raw_response).unwrap_or_default();
+    let usage = &raw["usage"];
+    let count = |value: &Value| value.as_u64().unwrap_or(0);
+    let cache_read = count(&usage["cache_read_input_tokens"]);
+    let cache_write = count(&usage["cache_creation_input_tokens"]);
+    let cached = count(&usage["prompt_tokens_details"]["cached_tokens"])
+        .max(count(&usage["input_tokens_details"]["cached_tokens"]));
+
+    TokenUsage {
+        prompt_tokens: response.usage.input_tokens + cache_read + cache_write,
+        completion_tokens: response.usage.output_tokens,
+        cached_tokens: cached + cache_read,
+    }
+}
+
+/// The instructions built by the tools of a stopped run
+///
+/// Tools answer with an instruction list or, like `jupiter_swap`, an object
+/// holding one under `instructions`; rejected arguments and discovery results
+/// build nothing.
+fn built_transactions(tool_results: &[String]) -> Vec<Value> {
+    tool_results
+        .iter()
+        .filter_map(|text| serde_json::from_str::<Value>(text).ok())
+        .map(|value| match value {
+            // rig passes a tool's string output on JSON-encoded
+            Value::String(inner) => serde_json::from_str(&inner).unwrap_or(Value::Null),
+            value => value,
+        })
+        .flat_map(|value| match value {
+            Value::Array(items) => items,
+            Value::Object(mut object) => match object.remove("instructions") {
+                Some(Value::Array(items)) => items,
+                _ => vec![],
+            },
+            _ => vec![],
+        })
+        .filter(|item| item.get("program_id").is_some())
+        .collect()
+}
+
+/// A completion model whose calls are counted against a budget
+#[derive(Clone)]
+pub struct BudgetModel<M> {
+    inner: M,
+    tracker: Arc<BudgetTracker>,
+}
+
+impl<M: CompletionModel> BudgetModel<M> {
+    pub fn new(inner: M, tracker: Arc<BudgetTracker>) -> Self {
+        Self { inner, tracker }
+    }
+}
+
+impl<M: CompletionModel> CompletionModel for BudgetModel<M> {
+    type Response = M::Response;
+    type StreamingResponse = M::StreamingResponse;
+
+    async fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<completion::CompletionResponse<Self::Response>, CompletionError> {
+        self.tracker.before_call(&request)?;
+        let response = match self.tracker.time_left() {
+            Some(left) => tokio::time::timeout(left, self.inner.completion(request))
+                .await
+                .map_err(|_| {
+                    self.tracker.stop(BudgetExceeded {
+                        limit: "max_wall_time_secs",
+                        max: self.tracker.budget.max_wall_time_secs.unwrap_or_default(),
+                    })
+                })??,
+            None => self.inner.completion(request).await?,
+        };
+        self.tracker.after_call(&response)?;
+        Ok(response)
+    }
+
+    /// Streamed calls are passed through uncounted
+    async fn stream(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<rig::streaming::StreamingCompletionResponse<Self::StreamingResponse>, CompletionError>
+    {
+        self.inner.stream(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rig::OneOrMany;
+    use serde_json::json;
+
+    /// Answers every request with `calls` calls of `sol_transfer`
+    #[derive(Clone)]
+    struct FakeModel {
+        calls: usize,
+    }
+
+    impl CompletionModel for FakeModel {
+        type Response = Value;
+        type StreamingResponse = rig::providers::openai::StreamingCompletionResponse;
+
+        async fn completion(
+            &self,
+            _request: CompletionRequest,
+        ) -> Result<completion::CompletionResponse<Value>, CompletionError> {
+            let calls: Vec<_> = (0..self.calls)
+                .map(|i| {
+                    AssistantContent::tool_call(format!("call_{i}"), "sol_transfer", json!({}))
+                })
+                .collect();
+            Ok(completion::CompletionResponse {
+                choice: OneOrMany::many(calls).unwrap(),
+                usage: completion::Usage {
+                    input_tokens: 100,
+                    output_tokens: 10,
+                    total_tokens: 110,
+                },
+                raw_response: Value::Null,
+            })
+        }
+
+        async fn stream(
+            &self,
+            _request: CompletionRequest,
+        ) -> Result<
+            rig::streaming::StreamingCompletionResponse<Self::StreamingResponse>,
+            CompletionError,
+        > {
+            unimplemented!()
+        }
+    }
+
+    fn request(tool_results: &[&str]) -> CompletionRequest {
+        let mut history = vec![Message::user("Send 0.1 SOL")];
+        history.extend(
+            tool_results
+                .iter()
+                .map(|text| Message::tool_result("call", *text)),
+        );
+        CompletionRequest {
+            preamble: None,
+            chat_history: OneOrMany::many(history).unwrap(),
+            documents: vec![],
+            tools: vec![],
+            temperature: None,
+            max_tokens: None,
+            additional_params: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_limits_stop_the_run() {
+        let budget = BudgetSettings {
+            max_turns: Some(2),
+            ..Default::default()
+        };
+        let tracker = BudgetTracker::new(budget);
+        let model = BudgetModel::new(FakeModel { calls: 1 }, tracker.clone());
+        model.completion(request(&[])).await.unwrap();
+        model.completion(request(&[])).await.unwrap();
+        let err = model.completion(request(&[])).await.unwrap_err();
+        assert!(err.to_string().contains("max_turns of 2 reached"), "{err}");
+        assert_eq!(tracker.usage().turns, 2);
+        assert_eq!(tracker.usage().tokens.prompt_tokens, 200);
+
+        let budget = BudgetSettings {
+            max_tool_calls: Some(3),
+            ..Default::default()
+        };
+        let tracker = BudgetTracker::new(budget);
+        let model = BudgetModel::new(FakeModel { calls: 2 }, tracker.clone());
+        model.completion(request(&[])).await.unwrap();
+        model.completion(request(&[])).await.unwrap_err();
+        assert_eq!(tracker.exceeded().unwrap().limit, "max_tool_calls");
+        assert_eq!(tracker.usage().tool_calls, 2);
+
+        let budget = BudgetSettings {
+            max_output_tokens: Some(15),
+            ..Default::default()
+        };
+        let tracker = BudgetTracker::new(budget);
+        let model = BudgetModel::new(FakeModel { calls: 1 }, tracker.clone());
+        model.completion(request(&[])).await.unwrap();
+        model.completion(request(&[])).await.unwrap();
+        model.completion(request(&[])).await.unwrap_err();
+        assert_eq!(tracker.exceeded().unwrap().limit, "max_output_tokens");
+    }
+
+    #[test]
+    fn test_token_usage_reads_cache_figures() {
+        let response = |raw: Value| completion::CompletionResponse {
+            choice: OneOrMany::one(AssistantContent::text("done")),
+            usage: completion::Usage {
+                input_tokens: 1000,
+                output_tokens: 50,
+                total_tokens: 1050,
+            },
+            raw_response: raw,
+        };
+        let tokens = |prompt_tokens, cached_tokens| TokenUsage {
+            prompt_tokens,
+            completion_tokens: 50,
+            cached_tokens,
+        };
+
+        let openai = json!({"usage": {"prompt_tokens": 1000, "prompt_tokens_details": {"cached_tokens": 800}}});
+        assert_eq!(token_usage(&response(openai)), tokens(1000, 800));
+        let anthropic = json!({"usage": {"input_tokens": 1000, "cache_read_input_tokens": 3000, "cache_creation_input_tokens": 500}});
+        assert_eq!(token_usage(&response(anthropic)), tokens(4500, 3000));
+        assert_eq!(token_usage(&response(Value::Null)), tokens(1000, 0));
+    }
+
+    #[tokio::test]
+    async fn test_stopped_response_keeps_built_transactions() {
+        let instruction = json!({"program_id": "11111111111111111111111111111111", "accounts": [], "data": "3Bxs"});
+        let transfer = Value::String(json!([instruction]).to_string()).to_string();
+        let swap = json!({"instructions": [instruction], "status": "success"}).to_string();
+        let rejected = json!({"status": "invalid_arguments", "issues": []}).to_string();
+
+        let tracker = BudgetTracker::new(BudgetSettings {
+            max_turns: Some(1),
+            ..Default::default()
+        });
+        let model = BudgetModel::new(FakeModel { calls: 1 }, tracker.clone());
+        model.completion(request(&[])).await.unwrap();
+        let err = model
+            .completion(request(&[&transfer, &swap, &rejected]))
+            .await
+            .unwrap_err();
+
+        let response: Value =
+            serde_json::from_str(&tracker.stopped_response(err, "TestAgent").unwrap()).unwrap();
+        assert_eq!(response["budget_exceeded"], "max_turns");
+        assert_eq!(response["budget_usage"]["turns"], 1);
+        assert_eq!(response["usage"]["completion_tokens"], 10);
+        assert_eq!(response["transactions"], json!([instruction, instruction]));
+
+        // Without a budget stop the error is the run's
+        let tracker = BudgetTracker::new(BudgetSettings::default());
+        let err = tracker
+            .stopped_response(anyhow::anyhow!("model unreachable"), "TestAgent")
+            .unwrap_err();
+        assert_eq!(err.to_string(), "model unreachable");
+    }
+}
@@ -0,0 +1,390 @@
+//! Record and replay of LLM calls
+//!
+//! [`CassetteModel`] wraps the completion model of an agent. With
+//! `[cassette] mode = "record"` every request and response is stored in the
+//! cassette directory; with `mode = "replay"` the stored responses are served
+//! without calling the model, and a request that was never recorded fails, so
+//! LLM agents run as deterministic regression tests without API keys.
+//!
+//! A recording is keyed by the registered model name, the seed, the prompt and
+//! the tool schemas. Wallets are created anew for every run, so the addresses of
+//! the run's key map are replaced by their placeholders (`{{USER_WALLET_PUBKEY}}`)
+//! in the key and in the stored response, and put back on replay.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use reev_config::{CassetteMode, CassetteSettings};
+use rig::{
+    completion::{self, CompletionError, CompletionModel, CompletionRequest},
+    message::AssistantContent,
+    OneOrMany,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tracing::{debug, error, info};
+
+/// A stored request and its response
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Recording {
+    pub model: String,
+    pub key: String,
+    /// The normalized request the key was computed from
+    pub request: Value,
+    pub choice: OneOrMany<AssistantContent>,
+    pub usage: completion::Usage,
+    pub raw_response: Value,
+}
+
+/// A completion model whose calls are recorded or replayed
+#[derive(Clone)]
+pub struct CassetteModel<M> {
+    inner: M,
+    model_name: String,
+    settings: CassetteSettings,
+    /// Addresses of the run's key map and their placeholders, longest address first
+    placeholders: Vec<(String, String)>,
+}
+
+impl<M: CompletionModel> CassetteModel<M> {
+    /// Wrap `inner`, serving the model registered as `model_name`, with the
+    /// `[cassette]` settings of the configuration
+    pub fn new(inner: M, model_name: &str, key_map: &HashMap<String, String>) -> Self {
+        Self::with_settings(inner, model_name, &reev_config::get().cassette, key_map)
+    }
+
+    /// Wrap `inner` with the given settings
+    pub fn with_settings(
+        inner: M,
+        model_name: &str,
+        settings: &CassetteSettings,
+        key_map: &HashMap<String, String>,
+    ) -> Self {
+        let mut placeholders: Vec<(String, String)> = key_map
+            .iter()
+            .filter(|(_, address)| !address.is_empty())
+            .map(|(name, address)| (address.clone(), format!("{{{{{name}}}}}")))
+            .collect();
+        placeholders.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+
+        Self {
+            inner,
+            model_name: model_name.to_string(),
+            settings: settings.clone(),
+            placeholders,
+        }
+    }
+
+    /// The request as it is keyed, and its key
+    pub fn recording_key(
+        &self,
+        request: &CompletionRequest,
+    ) -> Result<(Value, String), CompletionError> {
+        let messages: Vec<Value> = request
+            .normalized_documents()
+            .into_iter()
+            .chain(request.chat_history.iter().cloned())
+            .map(|message| serde_json::to_value(message).map_err(CompletionError::JsonError))
+            .collect::<Result<_, _>>()?;
+        // Agents register their tools in no fixed order
+        let mut tools: Vec<Value> = request
+            .tools
+            .iter()
+            .map(|tool| json!({"name": tool.name, "description": tool.description, "parameters": tool.parameters}))
+            .collect();
+        tools.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+        let seed = request
+            .additional_params
+            .as_ref()
+            .and_then(|params| params["seed"].as_u64())
+            .unwrap_or(self.settings.seed);
+
+        let mut normalized = json!({
+            "model": self.model_name,
+            "seed": seed,
+            "preamble": request.preamble,
+            "messages": messages,
+            "tools": tools,
+        });
+        map_strings(&mut normalized, |text| {
+            self.to_placeholders(&text.split_whitespace().collect::<Vec<_>>().join(" "))
+        });
+
+        let key = hex::encode(Sha256::digest(normalized.to_string().as_bytes()));
+        Ok((normalized, key))
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        let model_dir: String = self
+            .model_name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        Path::new(&self.settings.dir)
+            .join(model_dir)
+            .join(format!("{key}.json"))
+    }
+
+    fn to_placeholders(&self, text: &str) -> String {
+        self.placeholders
+            .iter()
+            .fold(text.to_string(), |text, (address, placeholder)| {
+                text.replace(address, placeholder)
+            })
+    }
+
+    fn restore_addresses(&self, text: &str) -> String {
+        self.placeholders
+            .iter()
+            .fold(text.to_string(), |text, (address, placeholder)| {
+                text.replace(placeholder, address)
+            })
+    }
+
+    fn replay(
+        &self,
+        key: &str,
+    ) -> Result<completion::CompletionResponse<M::Response>, CompletionError> {
+        let path = self.path(key);
+        let text = std::fs::read_to_string(&path).map_err(|_| {
+            let message = format!(
+                "Cassette miss: no recording of this request to '{}' at {}; record it with REEV_CASSETTE_MODE=record",
+                self.model_name,
+                path.display()
+            );
+            error!("{message}");
+            CompletionError::ProviderError(message)
+        })?;
+
+        let mut recording: Value = serde_json::from_str(&text)?;
+        map_strings(&mut recording, |text| self.restore_addresses(text));
+        let recording: Recording = serde_json::from_value(recording)?;
+        debug!("Cassette: replayed {} for '{}'", key, self.model_name);
+
+        Ok(completion::CompletionResponse {
+            choice: recording.choice,
+            usage: recording.usage,
+            raw_response: serde_json::from_value(recording.raw_response)?,
+        })
+    }
+
+    fn record(
+        &self,
+        key: String,
+        request: Value,
+        response: &completion::CompletionResponse<M::Response>,
+    ) -> Result<(), CompletionError> {
+        let recording = Recording {
+            model: self.model_name.clone(),
+            key,
+            request,
+            choice: response.choice.clone(),
+            usage: response.usage,
+            raw_response: serde_json::to_value(&response.raw_response)?,
+        };
+        let mut recording = serde_json::to_value(recording)?;
+        map_strings(&mut recording, |text| self.to_placeholders(text));
+
+        let path = self.path(recording["key"].as_str().unwrap_or_default());
+        let write = || -> std::io::Result<()> {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, serde_json::to_string_pretty(&recording)?)
+        };
+        write().map_err(|e| {
+            CompletionError::ProviderError(format!(
+                "Failed to write cassette {}: {e}",
+                path.display()
+            ))
+        })?;
+        info!("Cassette: recorded {}", path.display());
+        Ok(())
+    }
+}
+
+/// Apply `f` to every string of `value`
+fn map_strings(value: &mut Value, f: impl Fn(&str) -> String + Copy) {
+    match value {
+        Value::String(text) => *text = f(text),
+        Value::Array(items) => items.iter_mut().for_each(|item| map_strings(item, f)),
+        Value::Object(object) => object.values_mut().for_each(|item| map_strings(item, f)),
+        _ => {}
+    }
+}
+
+impl<M: CompletionModel> CompletionModel for CassetteModel<M> {
+    type Response = M::Response;
+    type StreamingResponse = M::StreamingResponse;
+
+    async fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<completion::CompletionResponse<Self::Response>, CompletionError> {
+        match self.settings.mode {
+            CassetteMode::Off => self.inner.completion(request).await,
+            CassetteMode::Replay => {
+                let (_, key) = self.recording_key(&request)?;
+                self.replay(&key)
+            }
+            CassetteMode::Record => {
+                let (normalized, key) = self.recording_key(&request)?;
+                let response = self.inner.completion(request).await?;
+                self.record(key, normalized, &response)?;
+                Ok(response)
+            }
+        }
+    }
+
+    /// Streamed calls are passed through; they cannot be replayed
+    async fn stream(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<rig::streaming::StreamingCompletionResponse<Self::StreamingResponse>, CompletionError>
+    {
+        match self.settings.mode {
+            CassetteMode::Replay => Err(CompletionError::ProviderError(
+                "Streaming calls cannot be replayed from a cassette".to_string(),
+            )),
+            CassetteMode::Off | CassetteMode::Record => self.inner.stream(request).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rig::{completion::ToolDefinition, message::Message};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    /// Answers every request by calling `sol_transfer` to the recipient of its key map
+    #[derive(Clone)]
+    struct FakeModel {
+        recipient: String,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl CompletionModel for FakeModel {
+        type Response = Value;
+        type StreamingResponse = rig::providers::openai::StreamingCompletionResponse;
+
+        async fn completion(
+            &self,
+            _request: CompletionRequest,
+        ) -> Result<completion::CompletionResponse<Value>, CompletionError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let arguments = json!({"recipient_pubkey": self.recipient, "amount": 1});
+            Ok(completion::CompletionResponse {
+                choice: OneOrMany::one(AssistantContent::tool_call(
+                    "call_1",
+                    "sol_transfer",
+                    arguments.clone(),
+                )),
+                usage: completion::Usage {
+                    input_tokens: 10,
+                    output_tokens: 5,
+                    total_tokens: 15,
+                },
+                raw_response: arguments,
+            })
+        }
+
+        async fn stream(
+            &self,
+            _request: CompletionRequest,
+        ) -> Result<
+            rig::streaming::StreamingCompletionResponse<Self::StreamingResponse>,
+            CompletionError,
+        > {
+            unimplemented!()
+        }
+    }
+
+    fn request(recipient: &str, tools: &[&str]) -> CompletionRequest {
+        CompletionRequest {
+            preamble: Some("You are a Solana agent.".to_string()),
+            chat_history: OneOrMany::one(Message::user(format!(
+                "Send 1 lamport  to\\n{recipient}"
+            ))),
+            documents: vec![],
+            tools: tools
+                .iter()
+                .map(|name| ToolDefinition {
+                    name: name.to_string(),
+                    description: String::new(),
+                    parameters: json!({"type": "object"}),
+                })
+                .collect(),
+            temperature: None,
+            max_tokens: None,
+            additional_params: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay_with_new_wallets() {
+        let dir = std::env::temp_dir().join(format!("reev-cassettes-{}", uuid::Uuid::new_v4()));
+        let settings = |mode| CassetteSettings {
+            mode,
+            dir: dir.to_string_lossy().to_string(),
+            seed: 0,
+        };
+        let model = |recipient: &str, calls: &Arc<AtomicUsize>, mode| {
+            let fake = FakeModel {
+                recipient: recipient.to_string(),
+                calls: calls.clone(),
+            };
+            let key_map =
+                HashMap::from([("RECIPIENT_WALLET_PUBKEY".to_string(), recipient.to_string())]);
+            CassetteModel::with_settings(fake, "glm-4.6", &settings(mode), &key_map)
+        };
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let first = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+        model(first, &calls, CassetteMode::Record)
+            .completion(request(first, &["sol_transfer", "spl_transfer"]))
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        let stored = std::fs::read_dir(dir.join("glm-4.6")).unwrap().count();
+        assert_eq!(stored, 1);
+
+        // A later run has other wallets and lists the tools in another order
+        let second = "7xKXtg2CW87d97TXJSDpbD5jBkheTqA83TZRuJosgAsU";
+        let replayed = model(second, &calls, CassetteMode::Replay)
+            .completion(request(second, &["spl_transfer", "sol_transfer"]))
+            .await
+            .unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(replayed.usage.total_tokens, 15);
+        assert_eq!(replayed.raw_response["recipient_pubkey"], second);
+        match replayed.choice.first() {
+            AssistantContent::ToolCall(call) => {
+                assert_eq!(call.function.arguments["recipient_pubkey"], second)
+            }
+            other => panic!("expected a tool call, got {other:?}"),
+        }
+
+        // Another prompt was never recorded
+        let err = model(second, &calls, CassetteMode::Replay)
+            .completion(request(second, &["sol_transfer"]))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Cassette miss"), "{err}");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,6 +1,5 @@
 //! Jupiter protocol configuration
 
-use crate::config::{get_env_string, get_env_var};
 use std::time::Duration;
 
 /// Configuration for Jupiter protocol APIs
@@ -28,13 +27,14 @@ impl Default for JupiterConfig {
 }
 
 impl JupiterConfig {
-    /// Load configuration from environment variables
-    pub fn from_env() -> Self {
+    /// Configuration from the `[jupiter]` section of the reev configuration
+    pub fn from_config() -> Self {
+        let settings = &reev_config::get().jupiter;
         Self {
-            api_base_url: get_env_string("JUPITER_API_BASE_URL", "https://lite-api.jup.ag"),
-            timeout_seconds: get_env_var("JUPITER_TIMEOUT_SECONDS", 30),
-            max_retries: get_env_var("JUPITER_MAX_RETRIES", 3),
-            user_agent: get_env_string("JUPITER_USER_AGENT", "reev-agent/0.1.0"),
+            api_base_url: settings.api_base_url.clone(),
+            timeout_seconds: settings.timeout_seconds,
+            max_retries: settings.max_retries,
+            user_agent: settings.user_agent.clone(),
         }
     }
 
@@ -25,10 +25,10 @@ impl Default for Config {
 }
 
 impl Config {
-    /// Load configuration from environment variables
+    /// Load configuration from `reev.toml` and environment variables
     pub fn from_env() -> Self {
         Self {
-            jupiter: jupiter::JupiterConfig::from_env(),
+            jupiter: jupiter::JupiterConfig::from_config(),
             native: native::NativeConfig::from_env(),
         }
     }
@@ -0,0 +1,120 @@
+//! 🤖 Anthropic Agent using the native Messages API
+//!
+//! Runs Claude models with the same enhanced context, reev-tools and multi-turn
+//! execution as OpenAIAgent, through the Anthropic provider instead of an
+//! OpenAI-compatible shim.
+
+use anyhow::Result;
+use rig::{agent::AgentBuilder, client::CompletionClient, completion::Prompt};
+use std::collections::HashMap;
+use tracing::info;
+
+use crate::{
+    budget::{BudgetModel, BudgetTracker},
+    cassette::CassetteModel,
+    enhanced::common::{extract_execution_results, AgentHelper, AgentTools},
+    providers::anthropic,
+    LlmRequest,
+};
+
+pub struct AnthropicAgent;
+
+impl AnthropicAgent {
+    /// 🧠 Run the Anthropic agent with multi-turn tool use
+    pub async fn run(
+        model_name: &str,
+        payload: LlmRequest,
+        key_map: HashMap<String, String>,
+    ) -> Result<String> {
+        info!("[AnthropicAgent] Running multi-turn agent with model: {model_name}");
+
+        // 🔑 Initialize the Anthropic client from the model's registry entry
+        let config = reev_config::get();
+        let entry = config.model(model_name)?;
+        let api_key = config.require_api_key(model_name)?.ok_or_else(|| {
+            anyhow::anyhow!("Model '{model_name}' sets no api_key_env for the Anthropic API")
+        })?;
+        let mut client_builder = anthropic::Client::builder(&api_key);
+        if let Some(base_url) = &entry.base_url {
+            client_builder = client_builder.base_url(base_url);
+        }
+        let client = client_builder.build();
+        let actual_model_name = entry.model_id(model_name);
+
+        // 🧠 Build enhanced context with account information using common helper
+        let (context_integration, enhanced_prompt_data, enhanced_prompt) =
+            AgentHelper::build_enhanced_context(&payload, &key_map)?;
+
+        let conversation_depth = AgentHelper::determine_conversation_depth(
+            &context_integration,
+            &enhanced_prompt_data,
+            payload.initial_state.as_deref().unwrap_or(&[]),
+            &key_map,
+            &payload.id,
+        );
+
+        AgentHelper::log_prompt_info(
+            "AnthropicAgent",
+            &payload,
+            &enhanced_prompt_data,
+            &enhanced_prompt,
+            conversation_depth,
+        );
+
+        // 🛠️ Same tools as the other agents, filtered in flow mode
+        let allowed_tools = payload.allowed_tools.as_ref();
+        let tools = AgentTools::new_with_flow_mode(key_map.clone(), allowed_tools.is_some())
+            .with_account_states(payload.account_states.as_ref());
+        let budget = BudgetTracker::for_request(&payload);
+        budget.track_rejections(&tools.validation);
+        let model = BudgetModel::new(
+            CassetteModel::new(
+                client.completion_model(actual_model_name),
+                model_name,
+                &key_map,
+            ),
+            budget.clone(),
+        );
+        // The Messages API has no seed parameter, so only the temperature is pinned
+        let builder = AgentHelper::seeded_sampling(
+            AgentBuilder::new(model).preamble(&enhanced_prompt),
+            payload.seed,
+            false,
+        );
+        let agent = tools
+            .attach(builder, allowed_tools, "AnthropicAgent")
+            .build();
+
+        let enhanced_user_request = AgentHelper::enhance_user_request(
+            &enhanced_prompt_data.prompt,
+            conversation_depth,
+            "AnthropicAgent",
+        );
+
+        info!("[AnthropicAgent] Starting agent execution with model: {actual_model_name}");
+        let response = match agent
+            .prompt(&enhanced_user_request)
+            .multi_turn(conversation_depth)
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => return budget.stopped_response(e, "AnthropicAgent"),
+        };
+
+        let response_str = response.to_string();
+        info!(
+            "[AnthropicAgent] Raw response from multi-turn agent: {}",
+            response_str
+        );
+
+        // 🎯 Extract tool results from the conversation and tool calls from the traces
+        let execution_result = extract_execution_results(&response_str, "AnthropicAgent").await?;
+        let tool_calls = AgentHelper::extract_tool_calls_from_otel();
+
+        budget.with_usage(AgentHelper::format_comprehensive_response(
+            execution_result,
+            Some(tool_calls),
+            "AnthropicAgent",
+        )?)
+    }
+}
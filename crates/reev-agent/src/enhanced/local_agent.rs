@@ -0,0 +1,126 @@
+//! 🤖 Local Agent for models served by Ollama or llama.cpp
+//!
+//! Runs open models with the same enhanced context, reev-tools and multi-turn
+//! execution as the hosted agents, through the native local provider so the
+//! benchmarks run on machines without network access.
+
+use anyhow::Result;
+use reev_config::ProviderKind;
+use rig::{agent::AgentBuilder, client::CompletionClient, completion::Prompt};
+use std::collections::HashMap;
+use tracing::info;
+
+use crate::{
+    budget::{BudgetModel, BudgetTracker},
+    cassette::CassetteModel,
+    enhanced::common::{extract_execution_results, AgentHelper, AgentTools},
+    providers::local::{self, Backend},
+    LlmRequest,
+};
+
+pub struct LocalAgent;
+
+impl LocalAgent {
+    /// 🧠 Run the local agent with multi-turn tool use
+    pub async fn run(
+        model_name: &str,
+        payload: LlmRequest,
+        key_map: HashMap<String, String>,
+    ) -> Result<String> {
+        info!("[LocalAgent] Running multi-turn agent with model: {model_name}");
+
+        // 🔌 Initialize the server client from the model's registry entry
+        let config = reev_config::get();
+        let entry = config.model(model_name)?;
+        let backend = match entry.provider {
+            ProviderKind::Ollama => Backend::Ollama,
+            ProviderKind::LlamaCpp => Backend::LlamaCpp,
+            other => anyhow::bail!("Model '{model_name}' is served by {other}, not a local server"),
+        };
+        let api_key = config.require_api_key(model_name)?;
+        let mut client_builder = local::Client::builder(backend);
+        if let Some(base_url) = &entry.base_url {
+            client_builder = client_builder.base_url(base_url);
+        }
+        if let Some(api_key) = &api_key {
+            client_builder = client_builder.api_key(api_key);
+        }
+        let client = client_builder.build();
+        let actual_model_name = entry.model_id(model_name);
+        let budget = BudgetTracker::for_request(&payload);
+        let model = BudgetModel::new(
+            CassetteModel::new(
+                client
+                    .completion_model(actual_model_name)
+                    .with_context_size(entry.context_size),
+                model_name,
+                &key_map,
+            ),
+            budget.clone(),
+        );
+
+        // 🧠 Build enhanced context with account information using common helper
+        let (context_integration, enhanced_prompt_data, enhanced_prompt) =
+            AgentHelper::build_enhanced_context(&payload, &key_map)?;
+
+        let conversation_depth = AgentHelper::determine_conversation_depth(
+            &context_integration,
+            &enhanced_prompt_data,
+            payload.initial_state.as_deref().unwrap_or(&[]),
+            &key_map,
+            &payload.id,
+        );
+
+        AgentHelper::log_prompt_info(
+            "LocalAgent",
+            &payload,
+            &enhanced_prompt_data,
+            &enhanced_prompt,
+            conversation_depth,
+        );
+
+        // 🛠️ Same tools as the other agents, filtered in flow mode
+        let allowed_tools = payload.allowed_tools.as_ref();
+        let tools = AgentTools::new_with_flow_mode(key_map.clone(), allowed_tools.is_some())
+            .with_account_states(payload.account_states.as_ref());
+        budget.track_rejections(&tools.validation);
+        let builder = AgentHelper::seeded_sampling(
+            AgentBuilder::new(model).preamble(&enhanced_prompt),
+            payload.seed,
+            true,
+        );
+        let agent = tools.attach(builder, allowed_tools, "LocalAgent").build();
+
+        let enhanced_user_request = AgentHelper::enhance_user_request(
+            &enhanced_prompt_data.prompt,
+            conversation_depth,
+            "LocalAgent",
+        );
+
+        info!("[LocalAgent] Starting agent execution on {backend} with model: {actual_model_name}");
+        let response = match agent
+            .prompt(&enhanced_user_request)
+            .multi_turn(conversation_depth)
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => return budget.stopped_response(e, "LocalAgent"),
+        };
+
+        let response_str = response.to_string();
+        info!(
+            "[LocalAgent] Raw response from multi-turn agent: {}",
+            response_str
+        );
+
+        // 🎯 Extract tool results from the conversation and tool calls from the traces
+        let execution_result = extract_execution_results(&response_str, "LocalAgent").await?;
+        let tool_calls = AgentHelper::extract_tool_calls_from_otel();
+
+        budget.with_usage(AgentHelper::format_comprehensive_response(
+            execution_result,
+            Some(tool_calls),
+            "LocalAgent",
+        )?)
+    }
+}
@@ -8,6 +8,8 @@
 //! - **Gemini Agent**: Advanced reasoning with Google's Gemini models
 //! - **OpenAI Agent**: Multi-turn conversation with OpenAI-compatible models
 //! - **GLM Agent**: Tool-based agent using GLM's OpenAI-compatible API
+//! - **Anthropic Agent**: Claude models through the native Messages API
+//! - **Local Agent**: Open models served by Ollama or llama.cpp on this machine
 //!
 //! These agents showcase superior AI capabilities including:
 //! - Multi-step DeFi workflow orchestration
@@ -15,19 +17,23 @@
 //! - Intelligent error recovery and retry mechanisms
 //! - Context-aware decision making that exceeds deterministic patterns
 
+pub mod anthropic_agent;
 pub mod common;
 pub mod enhanced_context;
+pub mod local_agent;
 // pub mod glm_coding_agent; // Removed - now using ZAIAgent for GLM models
 // pub mod gemini; // Not implemented yet
 pub mod openai;
 pub mod zai_agent;
 
 // Re-export main components for easier access
+pub use anthropic_agent::AnthropicAgent;
 pub use common::{
     extract_execution_results, AgentHelper, AgentTools, ExecutionResult, UnifiedGLMAgent,
     UnifiedGLMData,
 };
 pub use enhanced_context::{EnhancedContextAgent, RequestAnalysis};
+pub use local_agent::LocalAgent;
 // pub use glm_coding_agent::GlmCodingAgent; // Removed - now using ZAIAgent for GLM models
 // pub use gemini::GeminiAgent; // Not implemented yet
 pub use openai::OpenAIAgent;
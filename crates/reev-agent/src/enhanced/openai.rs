@@ -9,14 +9,18 @@
 //! request/response handling differs from other implementations.
 
 use anyhow::Result;
-use rig::{completion::Prompt, prelude::*, providers::openai::Client};
+use reev_config::ProviderKind;
+use rig::{agent::AgentBuilder, completion::Prompt, prelude::*, providers::openai::Client};
 use std::collections::HashMap;
-use tracing::{info, warn};
+use tracing::info;
 
 use crate::{
+    budget::{BudgetModel, BudgetTracker},
+    cassette::CassetteModel,
     enhanced::common::{extract_execution_results, AgentHelper, AgentTools, UnifiedGLMAgent},
     LlmRequest,
 };
+use reev_tools::validation::Validated;
 pub struct OpenAIAgent;
 
 impl OpenAIAgent {
@@ -33,7 +37,7 @@ impl OpenAIAgent {
         info!("[OpenAIAgent] Running enhanced multi-turn agent with model: {model_name}");
 
         // 🎯 Check if this is a GLM model that should use unified logic
-        if model_name.starts_with("glm-") {
+        if reev_config::get().model(model_name)?.provider == ProviderKind::Zai {
             return Self::run_glm_with_unified_logic(model_name, payload, key_map).await;
         }
 
@@ -54,62 +58,8 @@ impl OpenAIAgent {
         // 🤖 MULTI-TURN CONVERSATION: Enable step-by-step reasoning
         let _user_request = enhanced_prompt_data.prompt.clone();
 
-        // Check API priority based on model selection
-        let (client, actual_model_name) = if model_name == "local" {
-            // Local model - always use local endpoint regardless of API keys
-            let local_url = std::env::var("LOCAL_MODEL_URL")
-                .unwrap_or_else(|_| "http://localhost:1234/v1".to_string());
-            let dummy_key = "dummy-key-for-local-model";
-            let actual_model = std::env::var("LOCAL_MODEL_NAME")
-                .unwrap_or_else(|_| "qwen3-coder-30b-a3b-instruct-mlx".to_string());
-
-            info!("[OpenAIAgent] Using local model at: {}", local_url);
-            info!("[OpenAIAgent] Using model name: {}", actual_model);
-            (
-                Client::builder(dummy_key).base_url(&local_url).build()?,
-                actual_model,
-            )
-        } else if model_name.starts_with("glm-") {
-            // GLM models - use ZAI_API_KEY if available
-            if let Ok(zai_api_key) = std::env::var("ZAI_API_KEY") {
-                let zai_api_url = std::env::var("ZAI_API_URL")
-                    .unwrap_or_else(|_| "https://api.z.ai/api/paas/v4".to_string());
-
-                info!(
-                    "[OpenAIAgent] Using Regular GLM API (ZAI_API_KEY): {}",
-                    zai_api_url
-                );
-                info!("[OpenAIAgent] Model: {} (regular GLM)", model_name);
-
-                let client = Client::builder(&zai_api_key)
-                    .base_url(&zai_api_url)
-                    .build()?;
-                (client, model_name.to_string())
-            } else {
-                return Err(anyhow::anyhow!(
-                    "GLM model '{model_name}' requires ZAI_API_KEY environment variable"
-                ));
-            }
-        } else if let Ok(openai_api_key) = std::env::var("OPENAI_API_KEY") {
-            // Standard OpenAI client for other models
-            info!("[OpenAIAgent] Using OpenAI API");
-            let client = Client::new(&openai_api_key);
-            (client, model_name.to_string())
-        } else {
-            // Fallback to local model for unknown models
-            let local_url = std::env::var("LOCAL_MODEL_URL")
-                .unwrap_or_else(|_| "http://localhost:1234/v1".to_string());
-            let dummy_key = "dummy-key-for-local-model";
-            let actual_model = std::env::var("LOCAL_MODEL_NAME")
-                .unwrap_or_else(|_| "qwen3-coder-30b-a3b-instruct-mlx".to_string());
-
-            warn!(
-                "[OpenAIAgent] No API key found for model '{}', using local model at: {}",
-                model_name, local_url
-            );
-            let client = Client::builder(dummy_key).base_url(&local_url).build()?;
-            (client, actual_model)
-        };
+        // 🔑 Client for the model's registry entry
+        let (client, actual_model_name) = Self::registered_client(model_name)?;
 
         // 🧠 ADAPTIVE CONVERSATION DEPTH: Use context-aware depth optimization
         let conversation_depth = AgentHelper::determine_conversation_depth(
@@ -134,94 +84,28 @@ impl OpenAIAgent {
 
         // 🛠️ Instantiate tools using common helper with flow mode detection
         let flow_mode = allowed_tools.is_some(); // Flow mode when allowed_tools is Some
-        let tools = AgentTools::new_with_flow_mode(key_map.clone(), flow_mode);
+        let tools = AgentTools::new_with_flow_mode(key_map.clone(), flow_mode)
+            .with_account_states(payload.account_states.as_ref());
 
         // 🧠 Build enhanced multi-turn agent with conditional tool filtering
-        let agent = if let Some(allowed_tools) = allowed_tools {
-            // Flow mode: only add tools that are explicitly allowed
-            info!(
-                "[OpenAIAgent] Flow mode: Only allowing {} tools: {:?}",
-                allowed_tools.len(),
-                allowed_tools
-            );
-            // Helper function to check if a tool is allowed
-            let is_tool_allowed =
-                |tool_name: &str| -> bool { allowed_tools.contains(&tool_name.to_string()) };
-
-            let mut builder = client
-                .completion_model(&actual_model_name)
-                .completions_api()
-                .into_agent_builder()
-                .preamble(&enhanced_prompt);
-
-            // Add each tool only if it's allowed
-            if is_tool_allowed("sol_transfer") {
-                builder = builder.tool(tools.sol_tool);
-            }
-            if is_tool_allowed("spl_transfer") {
-                builder = builder.tool(tools.spl_tool);
-            }
-            if is_tool_allowed("jupiter_swap") {
-                // Use flow-aware tool in flow mode for proper swap_details structure
-                if flow_mode {
-                    if let Some(ref flow_tool) = tools.jupiter_swap_flow_tool {
-                        builder = builder.tool(flow_tool.clone());
-                        info!("[OpenAIAgent] Using JupiterSwapFlowTool in flow mode");
-                    } else {
-                        builder = builder.tool(tools.jupiter_swap_tool.clone());
-                        info!("[OpenAIAgent] Falling back to JupiterSwapTool (flow tool not available)");
-                    }
-                } else {
-                    builder = builder.tool(tools.jupiter_swap_tool.clone());
-                    info!("[OpenAIAgent] Using JupiterSwapTool in normal mode");
-                }
-            }
-            if is_tool_allowed("jupiter_lend_earn_deposit") {
-                builder = builder.tool(tools.jupiter_lend_earn_deposit_tool);
-            }
-            if is_tool_allowed("jupiter_lend_earn_withdraw") {
-                builder = builder.tool(tools.jupiter_lend_earn_withdraw_tool);
-            }
-            if is_tool_allowed("jupiter_lend_earn_mint") {
-                builder = builder.tool(tools.jupiter_lend_earn_mint_tool);
-            }
-            if is_tool_allowed("jupiter_lend_earn_redeem") {
-                builder = builder.tool(tools.jupiter_lend_earn_redeem_tool);
-            }
-            if is_tool_allowed("get_lend_earn_tokens") {
-                builder = builder.tool(tools.lend_earn_tokens_tool);
-            }
-            // TODO: Temporarily disabled - comment out balance_tool to fix SOL transfers
-            // if is_tool_allowed("get_account_balance") {
-            //     builder = builder.tool(tools.balance_tool);
-            // }
-            if is_tool_allowed("jupiter_earn") {
-                builder = builder.tool(tools.jupiter_earn_tool);
-            }
-
-            builder.build()
-        } else {
-            // Normal mode: add all discovery tools
-            info!("[OpenAIAgent] Normal mode: Adding all discovery tools");
-            client
-                .completion_model(&actual_model_name)
-                .completions_api()
-                .into_agent_builder()
-                .preamble(&enhanced_prompt)
-                .tool(tools.sol_tool)
-                .tool(tools.spl_tool)
-                .tool(tools.jupiter_swap_tool.clone())
-                .tool(tools.jupiter_lend_earn_deposit_tool)
-                .tool(tools.jupiter_lend_earn_withdraw_tool)
-                .tool(tools.jupiter_lend_earn_mint_tool)
-                .tool(tools.jupiter_lend_earn_redeem_tool)
-                // jupiter_earn_tool only available for position/earnings benchmarks (114-*.yml)
-                // TODO: Temporarily disabled - comment out balance_tool to fix SOL transfers
-                // .tool(tools.balance_tool)
-                // .tool(tools.jupiter_earn_tool) - REMOVED: Should only be available for position/earnings benchmarks
-                .tool(tools.lend_earn_tokens_tool)
-                .build()
-        };
+        let budget = BudgetTracker::for_request(&payload);
+        budget.track_rejections(&tools.validation);
+        let model = BudgetModel::new(
+            CassetteModel::new(
+                client
+                    .completion_model(&actual_model_name)
+                    .completions_api(),
+                model_name,
+                &key_map,
+            ),
+            budget.clone(),
+        );
+        let builder = AgentHelper::seeded_sampling(
+            AgentBuilder::new(model).preamble(&enhanced_prompt),
+            payload.seed,
+            true,
+        );
+        let agent = tools.attach(builder, allowed_tools, "OpenAIAgent").build();
 
         // Add explicit stop instruction to the user request for simple operations
         let enhanced_user_request = AgentHelper::enhance_user_request(
@@ -244,10 +128,14 @@ impl OpenAIAgent {
         // when the agent is spawned from the API server which already has tracing initialized
         info!("[OpenAIAgent] Starting agent execution");
 
-        let response = agent
+        let response = match agent
             .prompt(&enhanced_user_request)
             .multi_turn(conversation_depth)
-            .await?;
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => return budget.stopped_response(e, "OpenAIAgent"),
+        };
 
         info!("[OpenAIAgent] Agent execution completed");
 
@@ -269,11 +157,34 @@ impl OpenAIAgent {
         let tool_calls = AgentHelper::extract_tool_calls_from_otel();
 
         // 🎯 FORMAT COMPREHENSIVE RESPONSE WITH FLOWS
-        AgentHelper::format_comprehensive_response(
+        budget.with_usage(AgentHelper::format_comprehensive_response(
             execution_result,
             Some(tool_calls),
             "OpenAIAgent",
-        )
+        )?)
+    }
+
+    /// 🔑 OpenAI-compatible client for the model's registry entry, with the model
+    /// identifier to request
+    fn registered_client(model_name: &str) -> Result<(Client, String)> {
+        let config = reev_config::get();
+        let entry = config.model(model_name)?;
+        // Servers without an `api_key_env` accept any key
+        let api_key = config
+            .require_api_key(model_name)?
+            .unwrap_or_else(|| "dummy-key-for-local-model".to_string());
+
+        let mut client_builder = Client::builder(&api_key);
+        if let Some(base_url) = &entry.base_url {
+            info!(
+                "[OpenAIAgent] Using {} API at: {}",
+                entry.provider, base_url
+            );
+            client_builder = client_builder.base_url(base_url);
+        }
+        let actual_model = entry.model_id(model_name).to_string();
+        info!("[OpenAIAgent] Using model name: {}", actual_model);
+        Ok((client_builder.build()?, actual_model))
     }
 
     /// 🧠 Run GLM models using unified logic for identical context and wallet handling
@@ -289,7 +200,9 @@ impl OpenAIAgent {
         info!("[OpenAIAgent] Running GLM model with unified logic: {model_name}");
 
         // 🎯 Use unified GLM logic for shared components
-        let unified_data = UnifiedGLMAgent::run(model_name, payload, key_map).await?;
+        let budget = BudgetTracker::for_request(&payload);
+        let seed = payload.seed;
+        let unified_data = UnifiedGLMAgent::run(model_name, payload, key_map.clone()).await?;
 
         info!("[OpenAIAgent] === OPENAI-SPECIFIC GLM REQUEST HANDLING ===");
         info!(
@@ -298,52 +211,70 @@ impl OpenAIAgent {
         );
 
         // 🔑 Initialize OpenAI client for GLM models
-        let (client, actual_model_name) = if let Ok(zai_api_key) = std::env::var("ZAI_API_KEY") {
-            let zai_api_url = std::env::var("ZAI_API_URL")
-                .unwrap_or_else(|_| "https://api.z.ai/api/paas/v4".to_string());
-
-            info!(
-                "[OpenAIAgent] Using GLM via OpenAI client with ZAI endpoint: {}",
-                zai_api_url
-            );
-
-            let client = Client::builder(&zai_api_key)
-                .base_url(&zai_api_url)
-                .build()?;
-            (client, model_name.to_string())
-        } else {
-            return Err(anyhow::anyhow!(
-                "GLM model '{model_name}' requires ZAI_API_KEY environment variable"
-            ));
-        };
+        let (client, actual_model_name) = Self::registered_client(model_name)?;
 
         // 🛠️ Build agent using unified tools and context
-        let mut agent_builder = client
-            .completion_model(&actual_model_name)
-            .completions_api()
-            .into_agent_builder()
+        let model = BudgetModel::new(
+            CassetteModel::new(
+                client
+                    .completion_model(&actual_model_name)
+                    .completions_api(),
+                model_name,
+                &key_map,
+            ),
+            budget.clone(),
+        );
+        let validation = unified_data.tools.validation.clone();
+        budget.track_rejections(&validation);
+        let builder = AgentHelper::seeded_sampling(AgentBuilder::new(model), seed, true);
+        let mut agent_builder = builder
             .preamble(&unified_data.enhanced_prompt)
-            .tool(unified_data.tools.sol_tool)
-            .tool(unified_data.tools.spl_tool);
+            .tool(Validated::new(
+                unified_data.tools.sol_tool,
+                validation.clone(),
+            ))
+            .tool(Validated::new(
+                unified_data.tools.spl_tool,
+                validation.clone(),
+            ));
 
         // Add appropriate Jupiter swap tool based on flow mode
         if let Some(ref flow_tool) = unified_data.tools.jupiter_swap_flow_tool {
             info!("[OpenAIAgent] Using JupiterSwapFlowTool in flow mode");
-            agent_builder = agent_builder.tool(flow_tool.clone());
+            agent_builder =
+                agent_builder.tool(Validated::new(flow_tool.clone(), validation.clone()));
         } else {
             info!("[OpenAIAgent] Using JupiterSwapTool in normal mode");
-            agent_builder = agent_builder.tool(unified_data.tools.jupiter_swap_tool.clone());
+            agent_builder = agent_builder.tool(Validated::new(
+                unified_data.tools.jupiter_swap_tool.clone(),
+                validation.clone(),
+            ));
         }
 
         let agent = agent_builder
-            .tool(unified_data.tools.jupiter_lend_earn_deposit_tool)
-            .tool(unified_data.tools.jupiter_lend_earn_withdraw_tool)
-            .tool(unified_data.tools.jupiter_lend_earn_mint_tool)
-            .tool(unified_data.tools.jupiter_lend_earn_redeem_tool)
+            .tool(Validated::new(
+                unified_data.tools.jupiter_lend_earn_deposit_tool,
+                validation.clone(),
+            ))
+            .tool(Validated::new(
+                unified_data.tools.jupiter_lend_earn_withdraw_tool,
+                validation.clone(),
+            ))
+            .tool(Validated::new(
+                unified_data.tools.jupiter_lend_earn_mint_tool,
+                validation.clone(),
+            ))
+            .tool(Validated::new(
+                unified_data.tools.jupiter_lend_earn_redeem_tool,
+                validation.clone(),
+            ))
             // jupiter_earn_tool only available for position/earnings benchmarks (114-*.yml)
             // TODO: Temporarily disabled - comment out balance_tool to fix SOL transfers
-            // .tool(unified_data.tools.balance_tool)
-            .tool(unified_data.tools.lend_earn_tokens_tool)
+            // .tool(Validated::new(unified_data.tools.balance_tool, validation.clone()))
+            .tool(Validated::new(
+                unified_data.tools.lend_earn_tokens_tool,
+                validation.clone(),
+            ))
             .build();
 
         info!("[OpenAIAgent] === OPENAI GLM EXECUTION START ===");
@@ -353,10 +284,14 @@ impl OpenAIAgent {
         );
 
         // Execute the request using OpenAI's multi-turn agent
-        let response = agent
+        let response = match agent
             .prompt(&unified_data.enhanced_user_request)
             .multi_turn(unified_data.conversation_depth as usize)
-            .await?;
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => return budget.stopped_response(e, "OpenAIAgent-GM"),
+        };
 
         let response_str = response.to_string();
         info!("[OpenAIAgent] OpenAI GLM execution completed");
@@ -369,6 +304,9 @@ impl OpenAIAgent {
         let tool_calls = AgentHelper::extract_tool_calls_from_otel();
 
         // 🎯 Use unified response formatting
-        UnifiedGLMAgent::format_response(&response_str, "OpenAIAgent-GM", Some(tool_calls)).await
+        budget.with_usage(
+            UnifiedGLMAgent::format_response(&response_str, "OpenAIAgent-GM", Some(tool_calls))
+                .await?,
+        )
     }
 }
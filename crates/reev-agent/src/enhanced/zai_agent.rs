@@ -10,8 +10,9 @@
 
 use anyhow::Result;
 
+use reev_tools::validation::{ValidateArgs, ValidationContext};
 use rig::{
-    completion::{CompletionModel, CompletionRequestBuilder},
+    completion::{CompletionModel, CompletionRequestBuilder, Message},
     prelude::*,
     tool::Tool,
 };
@@ -21,6 +22,8 @@ use tracing::{debug, info};
 
 use crate::providers::zai;
 use crate::{
+    budget::{BudgetModel, BudgetTracker},
+    cassette::CassetteModel,
     enhanced::common::{AgentHelper, AgentTools, UnifiedGLMAgent},
     LlmRequest,
 };
@@ -60,7 +63,11 @@ impl ZAIAgent {
         let flow_mode_indicator = payload.allowed_tools.clone();
 
         // 🎯 Use unified GLM logic for shared components
-        let unified_data = UnifiedGLMAgent::run(model_name, payload, key_map_to_use).await?;
+        let budget = BudgetTracker::for_request(&payload);
+        let seed = payload.seed;
+        let unified_data =
+            UnifiedGLMAgent::run(model_name, payload, key_map_to_use.clone()).await?;
+        budget.track_rejections(&unified_data.tools.validation);
 
         info!("[ZAIAgent] === ZAI-SPECIFIC REQUEST HANDLING ===");
         info!(
@@ -72,16 +79,30 @@ impl ZAIAgent {
             unified_data.conversation_depth == 1
         );
 
-        // 🔑 Initialize ZAI client (provider-specific)
-        let api_key = std::env::var("ZAI_API_KEY")
-            .map_err(|_| anyhow::anyhow!("ZAI_API_KEY environment variable is required"))?;
+        // 🔑 Initialize ZAI client from the model's registry entry (provider-specific)
+        let config = reev_config::get();
+        let entry = config.model(model_name)?;
+        let api_key = config.require_api_key(model_name)?.ok_or_else(|| {
+            anyhow::anyhow!("Model '{model_name}' sets no api_key_env for the ZAI API")
+        })?;
 
-        let client = zai::Client::builder(&api_key).build();
+        let mut client_builder = zai::Client::builder(&api_key);
+        if let Some(base_url) = &entry.base_url {
+            client_builder = client_builder.base_url(base_url);
+        }
+        let client = client_builder.build();
 
         info!("[ZAIAgent] Starting ZAI completion request");
 
         // Create completion model using unified data
-        let model = client.completion_model(zai::GLM_4_6);
+        let model = BudgetModel::new(
+            CassetteModel::new(
+                client.completion_model(entry.model_id(model_name)),
+                model_name,
+                &key_map_to_use,
+            ),
+            budget.clone(),
+        );
 
         // Helper function to check if a tool is allowed
         let is_tool_allowed = |tool_name: &str| -> bool {
@@ -184,54 +205,81 @@ impl ZAIAgent {
                     .await,
             );
         }
+        // Force LLM to use tools instead of generating transactions directly
+        let mut additional_params = json!({"tool_choice": "required"});
+        // A seeded run pins the sampling seed and the configured temperature
+        if let Some(seed) = seed {
+            additional_params["seed"] = json!(seed);
+            request_builder = request_builder.temperature(reev_config::get().llm.temperature);
+        }
         let request_builder = request_builder;
 
-        let request = request_builder
+        let mut request = request_builder
             // TODO: Temporarily disabled - comment out balance_tool to fix SOL transfers
             // .tool(balance_tool_def)
-            .additional_params(json!({"tool_choice": "required"})) // Force LLM to use tools instead of generating transactions directly
+            .additional_params(additional_params)
             .build();
 
-        let result = model.completion(request).await?;
+        // Rejected tool arguments are answered and the model asked again until
+        // the retries run out, see `reev_tools::validation`
+        let response_str = loop {
+            let result = match model.completion(request.clone()).await {
+                Ok(result) => result,
+                Err(e) => return budget.stopped_response(e, "ZAIAgent"),
+            };
 
-        info!("[ZAIAgent] ZAI completion completed");
+            info!("[ZAIAgent] ZAI completion completed");
+
+            // Extract tool calls from the result (provider-specific)
+            let tool_calls: Vec<_> = result
+                .choice
+                .iter()
+                .filter_map(|content| {
+                    if let rig::message::AssistantContent::ToolCall(tool_call) = content {
+                        Some(tool_call)
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+
+            if !tool_calls.is_empty() {
+                let tool_call = tool_calls[0];
+                info!("[ZAIAgent] Tool called: {}", tool_call.function.name);
+                info!("[ZAIAgent] Arguments: {}", tool_call.function.arguments);
+
+                // Route tool call to appropriate tool using unified tools
+                let tool_result = match Self::execute_tool_call(
+                    tool_call,
+                    &unified_data.tools,
+                    &flow_mode_indicator,
+                )
+                .await?
+                {
+                    ToolReply::Done(tool_result) => tool_result,
+                    ToolReply::Rejected(rejection) => {
+                        request.chat_history.push(tool_call.clone().into());
+                        request
+                            .chat_history
+                            .push(Message::tool_result(tool_call.id.clone(), rejection));
+                        continue;
+                    }
+                };
+
+                info!("[ZAIAgent] Tool result: {}", tool_result);
+
+                // Determine appropriate summary based on tool type
+                let summary = Self::get_tool_summary(tool_call.function.name.as_str());
+
+                // Format as JSON response
+                break json!({
+                    "transactions": [tool_result],
+                    "summary": summary,
+                    "signatures": ["estimated_signature"]
+                })
+                .to_string();
+            }
 
-        // Extract tool calls from the result (provider-specific)
-        let tool_calls: Vec<_> = result
-            .choice
-            .iter()
-            .filter_map(|content| {
-                if let rig::message::AssistantContent::ToolCall(tool_call) = content {
-                    Some(tool_call)
-                } else {
-                    None
-                }
-            })
-            .collect();
-
-        let response_str = if !tool_calls.is_empty() {
-            let tool_call = &tool_calls[0];
-            info!("[ZAIAgent] Tool called: {}", tool_call.function.name);
-            info!("[ZAIAgent] Arguments: {}", tool_call.function.arguments);
-
-            // Route tool call to appropriate tool using unified tools
-            let tool_result =
-                Self::execute_tool_call(tool_call, &unified_data.tools, &flow_mode_indicator)
-                    .await?;
-
-            info!("[ZAIAgent] Tool result: {}", tool_result);
-
-            // Determine appropriate summary based on tool type
-            let summary = Self::get_tool_summary(tool_call.function.name.as_str());
-
-            // Format as JSON response
-            json!({
-                "transactions": [tool_result],
-                "summary": summary,
-                "signatures": ["estimated_signature"]
-            })
-            .to_string()
-        } else {
             // Extract text response
             let response_text = result
                 .choice
@@ -246,152 +294,112 @@ impl ZAIAgent {
                 .unwrap_or_default();
 
             info!("[ZAIAgent] Text response: {}", response_text);
-            response_text
+            break response_text;
         };
 
         // 🎯 Extract tool calls from OpenTelemetry traces
         let tool_calls = AgentHelper::extract_tool_calls_from_otel();
 
         // 🎯 Use unified response formatting
-        UnifiedGLMAgent::format_response(&response_str, "ZAIAgent", Some(tool_calls)).await
+        budget.with_usage(
+            UnifiedGLMAgent::format_response(&response_str, "ZAIAgent", Some(tool_calls)).await?,
+        )
     }
 
-    /// 🔧 Execute tool call using unified tools
+    /// 🔧 Execute tool call using unified tools, with its arguments validated
     async fn execute_tool_call(
         tool_call: &rig::message::ToolCall,
         tools: &AgentTools,
         allowed_tools: &Option<Vec<String>>,
-    ) -> Result<serde_json::Value> {
+    ) -> Result<ToolReply> {
+        let validation = &tools.validation;
+        let arguments = &tool_call.function.arguments;
         match tool_call.function.name.as_str() {
             "sol_transfer" => {
-                let args: reev_tools::tools::native::NativeTransferArgs =
-                    serde_json::from_value(tool_call.function.arguments.clone())?;
-                let result = tools
-                    .sol_tool
-                    .call(args)
-                    .await
-                    .map_err(|e| anyhow::anyhow!("SOL transfer error: {e}"))?;
-                serde_json::to_value(result)
-                    .map_err(|e| anyhow::anyhow!("JSON serialization error: {e}"))
+                call_tool(&tools.sol_tool, validation, arguments, "SOL transfer").await
             }
             "spl_transfer" => {
-                let args: reev_tools::tools::native::NativeTransferArgs =
-                    serde_json::from_value(tool_call.function.arguments.clone())?;
-                let result = tools
-                    .spl_tool
-                    .call(args)
-                    .await
-                    .map_err(|e| anyhow::anyhow!("SPL transfer error: {e}"))?;
-                serde_json::to_value(result)
-                    .map_err(|e| anyhow::anyhow!("JSON serialization error: {e}"))
+                call_tool(&tools.spl_tool, validation, arguments, "SPL transfer").await
             }
             "jupiter_swap" => {
                 // Check if we should use flow-aware tool based on flow mode
                 let flow_mode = allowed_tools
                     .as_ref()
                     .is_some_and(|tools| !tools.is_empty());
-                let result = if flow_mode {
-                    if let Some(ref flow_tool) = tools.jupiter_swap_flow_tool {
-                        let args: reev_tools::tools::flow::jupiter_swap_flow::JupiterSwapFlowArgs =
-                            serde_json::from_value(tool_call.function.arguments.clone())?;
-                        flow_tool
-                            .call(args)
-                            .await
-                            .map_err(|e| anyhow::anyhow!("Jupiter swap flow error: {e}"))?
-                    } else {
-                        let args: reev_tools::tools::jupiter_swap::JupiterSwapArgs =
-                            serde_json::from_value(tool_call.function.arguments.clone())?;
-                        tools
-                            .jupiter_swap_tool
-                            .call(args)
-                            .await
-                            .map_err(|e| anyhow::anyhow!("Jupiter swap error: {e}"))?
+                match &tools.jupiter_swap_flow_tool {
+                    Some(flow_tool) if flow_mode => {
+                        call_tool(flow_tool, validation, arguments, "Jupiter swap flow").await
                     }
-                } else {
-                    let args: reev_tools::tools::jupiter_swap::JupiterSwapArgs =
-                        serde_json::from_value(tool_call.function.arguments.clone())?;
-                    tools
-                        .jupiter_swap_tool
-                        .call(args)
+                    _ => {
+                        call_tool(
+                            &tools.jupiter_swap_tool,
+                            validation,
+                            arguments,
+                            "Jupiter swap",
+                        )
                         .await
-                        .map_err(|e| anyhow::anyhow!("Jupiter swap error: {e}"))?
-                };
-                serde_json::to_value(result)
-                    .map_err(|e| anyhow::anyhow!("JSON serialization error: {e}"))
+                    }
+                }
             }
             "jupiter_lend_earn_deposit" => {
-                let args: reev_tools::tools::jupiter_lend_earn_deposit::JupiterLendEarnDepositArgs =
-                    serde_json::from_value(tool_call.function.arguments.clone())?;
-                let result = tools
-                    .jupiter_lend_earn_deposit_tool
-                    .call(args)
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Jupiter lend deposit error: {e}"))?;
-                serde_json::to_value(result)
-                    .map_err(|e| anyhow::anyhow!("JSON serialization error: {e}"))
+                call_tool(
+                    &tools.jupiter_lend_earn_deposit_tool,
+                    validation,
+                    arguments,
+                    "Jupiter lend deposit",
+                )
+                .await
             }
             "jupiter_lend_earn_withdraw" => {
-                let args: reev_tools::tools::jupiter_lend_earn_withdraw::JupiterLendEarnWithdrawArgs =
-                    serde_json::from_value(tool_call.function.arguments.clone())?;
-                let result = tools
-                    .jupiter_lend_earn_withdraw_tool
-                    .call(args)
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Jupiter lend withdraw error: {e}"))?;
-                serde_json::to_value(result)
-                    .map_err(|e| anyhow::anyhow!("JSON serialization error: {e}"))
+                call_tool(
+                    &tools.jupiter_lend_earn_withdraw_tool,
+                    validation,
+                    arguments,
+                    "Jupiter lend withdraw",
+                )
+                .await
             }
             "jupiter_lend_earn_mint" => {
-                let args: reev_tools::tools::jupiter_lend_earn_mint_redeem::JupiterLendEarnMintArgs =
-                    serde_json::from_value(tool_call.function.arguments.clone())?;
-                let result = tools
-                    .jupiter_lend_earn_mint_tool
-                    .call(args)
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Jupiter lend mint error: {e}"))?;
-                serde_json::to_value(result)
-                    .map_err(|e| anyhow::anyhow!("JSON serialization error: {e}"))
+                call_tool(
+                    &tools.jupiter_lend_earn_mint_tool,
+                    validation,
+                    arguments,
+                    "Jupiter lend mint",
+                )
+                .await
             }
             "jupiter_lend_earn_redeem" => {
-                let args: reev_tools::tools::jupiter_lend_earn_mint_redeem::JupiterLendEarnRedeemArgs =
-                    serde_json::from_value(tool_call.function.arguments.clone())?;
-                let result = tools
-                    .jupiter_lend_earn_redeem_tool
-                    .call(args)
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Jupiter lend redeem error: {e}"))?;
-                serde_json::to_value(result)
-                    .map_err(|e| anyhow::anyhow!("JSON serialization error: {e}"))
+                call_tool(
+                    &tools.jupiter_lend_earn_redeem_tool,
+                    validation,
+                    arguments,
+                    "Jupiter lend redeem",
+                )
+                .await
             }
             "jupiter_earn" => {
-                let args: reev_tools::tools::jupiter_earn::JupiterEarnArgs =
-                    serde_json::from_value(tool_call.function.arguments.clone())?;
-                let result = tools
-                    .jupiter_earn_tool
-                    .call(args)
-                    .await
-                    .map_err(|e| anyhow::anyhow!("jupiter_earn execution error: {e}"))?;
-                serde_json::to_value(result)
-                    .map_err(|e| anyhow::anyhow!("JSON serialization error: {e}"))
+                call_tool(
+                    &tools.jupiter_earn_tool,
+                    validation,
+                    arguments,
+                    "jupiter_earn execution",
+                )
+                .await
             }
             // TODO: Temporarily disabled - comment out balance_tool to fix SOL transfers
             /*
             "get_account_balance" => {
-                let args: reev_tools::tools::discovery::balance_tool::AccountBalanceArgs =
-                    serde_json::from_value(tool_call.function.arguments.clone())?;
-                tools.balance_tool.call(args).await
+                call_tool(&tools.balance_tool, validation, arguments, "Account balance").await
             }
             */
             "get_lend_earn_tokens" => {
-                let args: reev_tools::tools::discovery::lend_earn_tokens::LendEarnTokensArgs =
-                    serde_json::from_value(tool_call.function.arguments.clone())?;
-                let result = tools
-                    .lend_earn_tokens_tool
-                    .call(args)
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Lend earn tokens error: {e}"))?;
-                serde_json::to_value(result)
-                    .map_err(|e| anyhow::anyhow!("JSON serialization error: {e}"))
+                call_tool(
+                    &tools.lend_earn_tokens_tool,
+                    validation,
+                    arguments,
+                    "Lend earn tokens",
+                )
+                .await
             }
             _ => Err(anyhow::anyhow!(
                 "Unknown tool called: {}",
@@ -418,3 +426,34 @@ impl ZAIAgent {
         }
     }
 }
+
+/// Outcome of a tool call the model made
+enum ToolReply {
+    /// The tool's result
+    Done(serde_json::Value),
+    /// The arguments were rejected; the model is answered with this and asked again
+    Rejected(String),
+}
+
+/// Call `tool` with `arguments` once `validation` accepts them
+async fn call_tool<T>(
+    tool: &T,
+    validation: &ValidationContext,
+    arguments: &serde_json::Value,
+    operation: &str,
+) -> Result<ToolReply>
+where
+    T: Tool + ValidateArgs,
+{
+    if let Some(rejection) = validation.check_call(tool, arguments).await? {
+        return Ok(ToolReply::Rejected(rejection));
+    }
+    let args = serde_json::from_value(arguments.clone())?;
+    let result = tool
+        .call(args)
+        .await
+        .map_err(|e| anyhow::anyhow!("{operation} error: {e}"))?;
+    let result = serde_json::to_value(result)
+        .map_err(|e| anyhow::anyhow!("JSON serialization error: {e}"))?;
+    Ok(ToolReply::Done(result))
+}
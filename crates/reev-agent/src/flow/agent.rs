@@ -269,6 +269,9 @@ impl FlowAgent {
             model_name: self.model_name.clone(),
             initial_state: None,
             key_map: Some(resolved_context.key_map.clone()),
+            ground_truth: None,
+            budget: None,
+            seed: None,
             mock: false,
             allowed_tools: Some(all_tools.clone()),
             account_states: None,
@@ -0,0 +1,104 @@
+//! Agent runs in flight, by session, so that a run the runner stopped waiting for
+//! can be cancelled instead of spending tokens on an answer nobody reads
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+use tokio::task::{AbortHandle, JoinError};
+
+/// Runs of the AI agents that have not answered yet, keyed by session id
+#[derive(Clone, Default)]
+pub struct InFlight(Arc<Mutex<HashMap<String, AbortHandle>>>);
+
+impl InFlight {
+    /// Run `work` as the task of `session_id`, which [`InFlight::cancel`] aborts.
+    ///
+    /// The task is also aborted when the returned future is dropped, as happens
+    /// when the request it answers goes away.
+    pub async fn run<T: Send + 'static>(
+        &self,
+        session_id: &str,
+        work: impl Future<Output = T> + Send + 'static,
+    ) -> Result<T, JoinError> {
+        let task = tokio::spawn(work);
+        let _registered = Registered::new(self.clone(), session_id, task.abort_handle());
+        task.await
+    }
+
+    /// Abort the run of `session_id`; false when it has none in flight
+    pub fn cancel(&self, session_id: &str) -> bool {
+        match self.0.lock().unwrap().remove(session_id) {
+            Some(task) => {
+                task.abort();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// A task registered under its session until the request it answers ends
+struct Registered {
+    in_flight: InFlight,
+    session_id: String,
+    task: AbortHandle,
+}
+
+impl Registered {
+    fn new(in_flight: InFlight, session_id: &str, task: AbortHandle) -> Self {
+        in_flight
+            .0
+            .lock()
+            .unwrap()
+            .insert(session_id.to_string(), task.clone());
+        Self {
+            in_flight,
+            session_id: session_id.to_string(),
+            task,
+        }
+    }
+}
+
+impl Drop for Registered {
+    fn drop(&mut self) {
+        let mut tasks = self.in_flight.0.lock().unwrap();
+        // A later request of the same session may have replaced this task
+        if tasks
+            .get(&self.session_id)
+            .is_some_and(|task| task.id() == self.task.id())
+        {
+            tasks.remove(&self.session_id);
+        }
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_cancel_aborts_the_session_run() {
+        let in_flight = InFlight::default();
+        let run = tokio::spawn({
+            let in_flight = in_flight.clone();
+            async move {
+                in_flight
+                    .run("session-1", tokio::time::sleep(Duration::from_secs(60)))
+                    .await
+            }
+        });
+        while !in_flight.0.lock().unwrap().contains_key("session-1") {
+            tokio::task::yield_now().await;
+        }
+
+        assert!(!in_flight.cancel("session-2"));
+        assert!(in_flight.cancel("session-1"));
+        assert!(run.await.unwrap().unwrap_err().is_cancelled());
+        assert!(!in_flight.cancel("session-1"));
+    }
+}
@@ -1,6 +1,6 @@
 use anyhow::{Context, Result};
 use axum::{
-    extract::Query,
+    extract::{Path, Query, State},
     http::StatusCode,
     response::{IntoResponse, Response},
     routing::{get, post},
@@ -321,9 +321,30 @@ async fn handle_flow_step_benchmarks(
     }
 }
 
+/// Handle benchmarks without a coding agent from the ground truth sent along
+async fn handle_ground_truth_benchmarks(
+    payload: &LlmRequest,
+    key_map: &HashMap<String, String>,
+) -> Result<String> {
+    let Some(ground_truth) = &payload.ground_truth else {
+        anyhow::bail!("Coding agent does not support this id: '{}'", payload.id)
+    };
+    let ixs = agents::ground_truth::handle_ground_truth(
+        &payload.id,
+        ground_truth,
+        payload.account_states.as_ref().unwrap_or(&HashMap::new()),
+        key_map,
+    )
+    .await?;
+    Ok(serde_json::to_string(&ixs)?)
+}
+
+pub mod budget;
+pub mod cassette;
 pub mod context;
 pub mod enhanced;
 pub mod flow;
+pub mod in_flight;
 pub mod providers;
 pub mod run;
 
@@ -331,6 +352,7 @@ mod agents;
 pub mod common;
 mod prompt;
 
+use in_flight::InFlight;
 use reev_protocols::jupiter::lend_deposit::handle_jupiter_lend_deposit;
 use reev_protocols::jupiter::swap::handle_jupiter_swap;
 
@@ -352,6 +374,15 @@ pub struct LlmRequest {
     pub account_states: Option<std::collections::HashMap<String, serde_json::Value>>,
     #[serde(default)]
     pub key_map: Option<std::collections::HashMap<String, String>>,
+    /// The benchmark's ground truth, sent to the deterministic agent only
+    #[serde(default)]
+    pub ground_truth: Option<reev_lib::benchmark::GroundTruth>,
+    /// Limits on the run, resolved by the runner; the `[budget]` configuration when absent
+    #[serde(default)]
+    pub budget: Option<reev_config::BudgetSettings>,
+    /// Seed of the attempt; pins the provider's sampling seed and temperature
+    #[serde(default)]
+    pub seed: Option<u64>,
 }
 
 fn default_model() -> String {
@@ -375,6 +406,18 @@ struct LlmResponse {
     signatures: Option<Vec<String>>,
     // Flow information containing tool calls and execution order
     flows: Option<reev_lib::agent::FlowData>,
+    /// The budget limit that stopped the run, if one did
+    #[serde(skip_serializing_if = "Option::is_none")]
+    budget_exceeded: Option<String>,
+    /// Tokens the run's LLM calls used
+    #[serde(skip_serializing_if = "Option::is_none")]
+    usage: Option<reev_flow::TokenUsage>,
+    /// Everything the run spent against its budget
+    #[serde(skip_serializing_if = "Option::is_none")]
+    budget_usage: Option<budget::BudgetUsage>,
+    /// Tool calls whose arguments were rejected and retried by the model
+    #[serde(skip_serializing_if = "Option::is_none")]
+    argument_retries: Option<u32>,
 }
 
 /// Structs for deserializing the multi-step flow context YAML.
@@ -457,6 +500,7 @@ async fn health_check() -> StatusCode {
 /// This function routes the request to either the deterministic agent or the AI agent
 /// based on the `mock` query parameter.
 async fn generate_transaction(
+    State(in_flight): State<InFlight>,
     Query(params): Query<MockParams>,
     Json(payload): Json<LlmRequest>,
 ) -> Response {
@@ -468,7 +512,14 @@ async fn generate_transaction(
         run_deterministic_agent(payload).await
     } else {
         info!("[reev-agent] Routing to AI Agent.");
-        run_ai_agent(payload).await
+        let session_id = payload.session_id.clone();
+        match in_flight.run(&session_id, run_ai_agent(payload)).await {
+            Ok(result) => result,
+            Err(e) if e.is_cancelled() => {
+                Err(anyhow::anyhow!("Session {session_id} was cancelled"))
+            }
+            Err(e) => Err(anyhow::anyhow!("Agent task failed: {e}")),
+        }
     };
 
     match result {
@@ -488,6 +539,21 @@ async fn generate_transaction(
     }
 }
 
+/// Axum handler for the `POST /sessions/{session_id}/cancel` endpoint.
+///
+/// Aborts the session's agent run, answering 404 when it has none in flight.
+async fn cancel_session(
+    State(in_flight): State<InFlight>,
+    Path(session_id): Path<String>,
+) -> StatusCode {
+    if in_flight.cancel(&session_id) {
+        info!("[reev-agent] Cancelled the agent run of session {session_id}");
+        StatusCode::OK
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
 /// Executes the AI agent logic using the dynamically selected model.
 async fn run_ai_agent(payload: LlmRequest) -> Result<Json<LlmResponse>> {
     let model_name = payload.model_name.clone();
@@ -529,6 +595,20 @@ async fn run_ai_agent(payload: LlmRequest) -> Result<Json<LlmResponse>> {
                             .collect(),
                     ),
                     flows, // Include flow data if available
+                    budget_exceeded: json_value
+                        .get("budget_exceeded")
+                        .and_then(|limit| limit.as_str())
+                        .map(str::to_string),
+                    usage: json_value
+                        .get("usage")
+                        .and_then(|usage| serde_json::from_value(usage.clone()).ok()),
+                    budget_usage: json_value
+                        .get("budget_usage")
+                        .and_then(|usage| serde_json::from_value(usage.clone()).ok()),
+                    argument_retries: json_value
+                        .get("argument_retries")
+                        .and_then(|retries| retries.as_u64())
+                        .map(|retries| retries as u32),
                 };
                 return Ok(Json(response));
             }
@@ -581,6 +661,20 @@ async fn run_ai_agent(payload: LlmRequest) -> Result<Json<LlmResponse>> {
                         .collect(),
                 ),
                 flows, // Include flow data if available
+                budget_exceeded: json_value
+                    .get("budget_exceeded")
+                    .and_then(|limit| limit.as_str())
+                    .map(str::to_string),
+                usage: json_value
+                    .get("usage")
+                    .and_then(|usage| serde_json::from_value(usage.clone()).ok()),
+                budget_usage: json_value
+                    .get("budget_usage")
+                    .and_then(|usage| serde_json::from_value(usage.clone()).ok()),
+                argument_retries: json_value
+                    .get("argument_retries")
+                    .and_then(|retries| retries.as_u64())
+                    .map(|retries| retries as u32),
             };
             return Ok(Json(response));
         }
@@ -596,6 +690,10 @@ async fn run_ai_agent(payload: LlmRequest) -> Result<Json<LlmResponse>> {
         summary: None,
         signatures: None,
         flows: None, // Flow data not available in legacy responses
+        budget_exceeded: None,
+        usage: None,
+        budget_usage: None,
+        argument_retries: None,
     };
 
     Ok(Json(response))
@@ -647,9 +745,7 @@ async fn run_deterministic_agent(payload: LlmRequest) -> Result<Json<LlmResponse
                     Ok(result) => result,
                     Err(_) => match handle_flow_benchmarks(&payload.id, &key_map).await {
                         Ok(result) => result,
-                        Err(_) => {
-                            anyhow::bail!("Coding agent does not support this id: '{}'", payload.id)
-                        }
+                        Err(_) => handle_ground_truth_benchmarks(&payload, &key_map).await?,
                     },
                 },
             },
@@ -668,6 +764,10 @@ async fn run_deterministic_agent(payload: LlmRequest) -> Result<Json<LlmResponse
         summary: None,
         signatures: None,
         flows: None, // Flow data not available in legacy responses
+        budget_exceeded: None,
+        usage: None,
+        budget_usage: None,
+        argument_retries: None,
     };
 
     Ok(Json(response))
@@ -675,17 +775,17 @@ async fn run_deterministic_agent(payload: LlmRequest) -> Result<Json<LlmResponse
 
 /// The main entry point for the mock agent server.
 pub async fn run_server() -> anyhow::Result<()> {
-    dotenvy::dotenv().ok();
+    let config = reev_config::load()?;
+    let address = format!("{}:{}", config.agent.host, config.agent.port);
+    reev_config::init(config);
 
     // Initialize protocol configurations
     initialize_configurations()?;
 
-    let app = Router::new()
-        .route("/gen/tx", post(generate_transaction))
-        .route("/health", get(health_check));
+    let app = router();
 
-    let listener = tokio::net::TcpListener::bind("127.0.0.1:9090").await?;
-    info!("[reev-agent] Mock LLM server listening on http://127.0.0.1:9090");
+    let listener = tokio::net::TcpListener::bind(&address).await?;
+    info!("[reev-agent] Mock LLM server listening on http://{address}");
     info!("[reev-agent] POST /gen/tx is ready to accept requests.");
 
     axum::serve(listener, app).await?;
@@ -693,12 +793,21 @@ pub async fn run_server() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// The agent server's routes, without the configuration `run_server` loads
+pub fn router() -> Router {
+    Router::new()
+        .route("/gen/tx", post(generate_transaction))
+        .route("/sessions/{session_id}/cancel", post(cancel_session))
+        .route("/health", get(health_check))
+        .with_state(InFlight::default())
+}
+
 /// Initialize all protocol configurations
 fn initialize_configurations() -> anyhow::Result<()> {
     info!("[reev-agent] Initializing protocol configurations...");
 
     // Initialize Jupiter configuration
-    let jupiter_config = reev_protocols::jupiter::JupiterConfig::from_env();
+    let jupiter_config = reev_protocols::jupiter::JupiterConfig::from_config();
     jupiter_config.validate()?;
     reev_protocols::jupiter::init_jupiter_config(jupiter_config);
     info!("[reev-agent] Jupiter configuration initialized");
@@ -0,0 +1,201 @@
+//! Anthropic API client implementation
+use serde::{Deserialize, Serialize};
+
+use super::completion::CompletionModel as AnthropicCompletionModel;
+use rig::client::{
+    impl_conversion_traits, CompletionClient, ProviderValue, VerifyClient, VerifyError,
+};
+use rig::{completion::CompletionError, prelude::ProviderClient};
+
+const ANTHROPIC_API_BASE_URL: &str = "https://api.anthropic.com/v1";
+
+/// Version of the Messages API requests are written against
+pub const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// Anthropic client builder
+pub struct ClientBuilder<'a> {
+    api_key: &'a str,
+    base_url: Option<&'a str>,
+    http_client: reqwest::Client,
+}
+
+impl<'a> ClientBuilder<'a> {
+    /// Create a new Anthropic client builder
+    pub fn new(api_key: &'a str) -> Self {
+        Self {
+            api_key,
+            base_url: None,
+            http_client: reqwest::Client::new(),
+        }
+    }
+
+    /// Set a custom base URL for the Anthropic API
+    pub fn base_url(mut self, base_url: &'a str) -> Self {
+        self.base_url = Some(base_url);
+        self
+    }
+
+    /// Set a custom HTTP client
+    pub fn custom_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = client;
+        self
+    }
+
+    /// Build the Anthropic client
+    pub fn build(self) -> Client {
+        Client::new(
+            self.base_url.unwrap_or(ANTHROPIC_API_BASE_URL),
+            self.api_key,
+            self.http_client,
+        )
+    }
+}
+
+/// Anthropic client
+#[derive(Clone)]
+pub struct Client {
+    pub base_url: String,
+    pub api_key: String,
+    pub http_client: reqwest::Client,
+}
+
+impl std::fmt::Debug for Client {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Client")
+            .field("base_url", &self.base_url)
+            .field("api_key", &"***")
+            .finish()
+    }
+}
+
+/// Body of an Anthropic API error response
+#[derive(Deserialize, Debug)]
+struct ErrorResponse {
+    error: ApiError,
+}
+
+#[derive(Deserialize, Debug)]
+struct ApiError {
+    #[serde(rename = "type")]
+    kind: String,
+    message: String,
+}
+
+impl Client {
+    /// Create a new Anthropic client
+    pub fn new(base_url: impl Into<String>, api_key: &str, http_client: reqwest::Client) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+            http_client,
+        }
+    }
+
+    /// Create a new Anthropic client builder
+    pub fn builder(api_key: &str) -> ClientBuilder<'_> {
+        ClientBuilder::new(api_key)
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        self.http_client
+            .request(method, format!("{}/{}", self.base_url, path))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+    }
+
+    /// Send a POST request to the Anthropic API
+    pub(crate) async fn post<T: Serialize, R: for<'de> Deserialize<'de>>(
+        &self,
+        path: &str,
+        request: &T,
+    ) -> Result<R, CompletionError> {
+        let response = self
+            .request(reqwest::Method::POST, path)
+            .json(request)
+            .send()
+            .await
+            .map_err(CompletionError::HttpError)?;
+        Self::read(response).await
+    }
+
+    /// Send a GET request to the Anthropic API
+    pub(crate) async fn get<R: for<'de> Deserialize<'de>>(
+        &self,
+        path: &str,
+    ) -> Result<R, CompletionError> {
+        let response = self
+            .request(reqwest::Method::GET, path)
+            .send()
+            .await
+            .map_err(CompletionError::HttpError)?;
+        Self::read(response).await
+    }
+
+    /// Decode a successful response, or turn the API's error body into a provider error
+    async fn read<R: for<'de> Deserialize<'de>>(
+        response: reqwest::Response,
+    ) -> Result<R, CompletionError> {
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Failed to read error response".to_string());
+            let detail = match serde_json::from_str::<ErrorResponse>(&text) {
+                Ok(body) => format!("{}: {}", body.error.kind, body.error.message),
+                Err(_) => text,
+            };
+
+            return Err(CompletionError::ProviderError(format!(
+                "Anthropic API error {status}: {detail}"
+            )));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| CompletionError::ProviderError(e.to_string()))
+    }
+}
+
+impl ProviderClient for Client {
+    fn from_env() -> Self {
+        let api_key = std::env::var("ANTHROPIC_API_KEY")
+            .expect("ANTHROPIC_API_KEY environment variable not set");
+        Self::new(ANTHROPIC_API_BASE_URL, &api_key, reqwest::Client::new())
+    }
+
+    fn from_val(input: ProviderValue) -> Self {
+        let ProviderValue::Simple(api_key) = input else {
+            panic!("Incorrect provider value type")
+        };
+        Self::new(ANTHROPIC_API_BASE_URL, &api_key, reqwest::Client::new())
+    }
+}
+
+impl CompletionClient for Client {
+    type CompletionModel = AnthropicCompletionModel;
+
+    fn completion_model(&self, model: &str) -> Self::CompletionModel {
+        AnthropicCompletionModel::new(self.clone(), model.to_string())
+    }
+}
+
+impl VerifyClient for Client {
+    async fn verify(&self) -> Result<(), VerifyError> {
+        let _: serde_json::Value = self.get("models").await.map_err(|e| match e {
+            CompletionError::HttpError(http_err) => VerifyError::HttpError(http_err),
+            CompletionError::ProviderError(_) => VerifyError::InvalidAuthentication,
+            _ => VerifyError::ProviderError(e.to_string()),
+        })?;
+        Ok(())
+    }
+}
+
+// Implement conversion traits for the Anthropic client
+impl_conversion_traits!(
+    AsEmbeddings,
+    AsTranscription,
+    AsImageGeneration,
+    AsAudioGeneration for Client
+);
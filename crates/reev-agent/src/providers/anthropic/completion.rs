@@ -0,0 +1,485 @@
+//! Anthropic Messages API completion model and related types
+
+use rig::{
+    completion::{self, AssistantContent, CompletionError, CompletionRequest},
+    message::{self, Message, Text, ToolCall, ToolFunction},
+    OneOrMany,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::providers::anthropic::Client;
+
+/// Output token limit of a request that does not set one; the API requires it
+pub const DEFAULT_MAX_TOKENS: u64 = 4096;
+
+/// Author of a message
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    User,
+    Assistant,
+}
+
+/// A content block of a message
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentBlock {
+    Text {
+        text: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
+    ToolResult {
+        tool_use_id: String,
+        content: String,
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        is_error: bool,
+    },
+    /// Blocks this provider does not use, such as extended thinking
+    #[serde(other)]
+    Other,
+}
+
+/// A message of the conversation
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AnthropicMessage {
+    pub role: Role,
+    pub content: Vec<ContentBlock>,
+}
+
+/// A tool the model may call
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AnthropicTool {
+    pub name: String,
+    pub description: String,
+    pub input_schema: serde_json::Value,
+}
+
+impl From<completion::ToolDefinition> for AnthropicTool {
+    fn from(value: completion::ToolDefinition) -> Self {
+        Self {
+            name: value.name,
+            description: value.description,
+            input_schema: value.parameters,
+        }
+    }
+}
+
+/// Body of a `POST /messages` request
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MessagesRequest {
+    pub model: String,
+    pub max_tokens: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+    pub messages: Vec<AnthropicMessage>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<AnthropicTool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+}
+
+/// Why the model stopped generating
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+    EndTurn,
+    MaxTokens,
+    StopSequence,
+    ToolUse,
+    PauseTurn,
+    Refusal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct AnthropicUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    #[serde(default)]
+    pub cache_creation_input_tokens: Option<u64>,
+    #[serde(default)]
+    pub cache_read_input_tokens: Option<u64>,
+}
+
+/// Body of a `POST /messages` response
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CompletionResponse {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub model: String,
+    pub content: Vec<ContentBlock>,
+    pub stop_reason: Option<StopReason>,
+    #[serde(default)]
+    pub stop_sequence: Option<String>,
+    #[serde(default)]
+    pub usage: AnthropicUsage,
+}
+
+fn tool_result_text(content: OneOrMany<message::ToolResultContent>) -> String {
+    content
+        .into_iter()
+        .filter_map(|content| match content {
+            message::ToolResultContent::Text(text) => Some(text.text),
+            message::ToolResultContent::Image(_) => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn message_to_anthropic_message(value: Message) -> Result<AnthropicMessage, String> {
+    match value {
+        Message::User { content } => {
+            let mut blocks = vec![];
+
+            for content in content.into_iter() {
+                match content {
+                    message::UserContent::Text(text) => {
+                        blocks.push(ContentBlock::Text { text: text.text });
+                    }
+                    message::UserContent::ToolResult(tool_result) => {
+                        blocks.push(ContentBlock::ToolResult {
+                            tool_use_id: tool_result.id,
+                            content: tool_result_text(tool_result.content),
+                            is_error: false,
+                        });
+                    }
+                    message::UserContent::Document(document) => {
+                        blocks.push(ContentBlock::Text {
+                            text: document.data,
+                        });
+                    }
+                    other => return Err(format!("unsupported user content: {other:?}")),
+                }
+            }
+
+            Ok(AnthropicMessage {
+                role: Role::User,
+                content: blocks,
+            })
+        }
+        Message::Assistant { id: _, content } => {
+            let mut blocks = vec![];
+
+            for content in content.into_iter() {
+                match content {
+                    AssistantContent::Text(text) => {
+                        blocks.push(ContentBlock::Text { text: text.text });
+                    }
+                    AssistantContent::ToolCall(tool_call) => {
+                        blocks.push(ContentBlock::ToolUse {
+                            id: tool_call.id,
+                            name: tool_call.function.name,
+                            input: tool_call.function.arguments,
+                        });
+                    }
+                    AssistantContent::Reasoning(_) => {
+                        // Thinking is only replayed with its signature, which rig does not keep
+                    }
+                }
+            }
+
+            Ok(AnthropicMessage {
+                role: Role::Assistant,
+                content: blocks,
+            })
+        }
+    }
+}
+
+/// Convert rig messages to Anthropic messages
+///
+/// The API rejects empty text blocks and expects the roles to alternate, so empty
+/// text is dropped and consecutive messages of one role are merged.
+pub fn to_anthropic_messages(
+    messages: impl IntoIterator<Item = Message>,
+) -> Result<Vec<AnthropicMessage>, String> {
+    let mut merged: Vec<AnthropicMessage> = Vec::new();
+    for message in messages {
+        let mut message = message_to_anthropic_message(message)?;
+        message.content.retain(
+            |block| !matches!(block, ContentBlock::Text { text } if text.trim().is_empty()),
+        );
+        if message.content.is_empty() {
+            continue;
+        }
+        match merged.last_mut() {
+            Some(last) if last.role == message.role => last.content.extend(message.content),
+            _ => merged.push(message),
+        }
+    }
+    Ok(merged)
+}
+
+impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionResponse> {
+    type Error = CompletionError;
+
+    fn try_from(value: CompletionResponse) -> Result<Self, Self::Error> {
+        match value.stop_reason {
+            Some(StopReason::Refusal) => {
+                return Err(CompletionError::ProviderError(
+                    "Anthropic declined the request (stop reason: refusal)".to_string(),
+                ));
+            }
+            // A tool call cut off by the token limit has incomplete arguments
+            Some(StopReason::MaxTokens)
+                if value
+                    .content
+                    .iter()
+                    .any(|block| matches!(block, ContentBlock::ToolUse { .. })) =>
+            {
+                return Err(CompletionError::ResponseError(
+                    "Anthropic response reached max_tokens during a tool call".to_string(),
+                ));
+            }
+            Some(StopReason::MaxTokens) => {
+                warn!("Anthropic: response truncated at max_tokens");
+            }
+            _ => {}
+        }
+
+        let content: Vec<_> = value
+            .content
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text { text } => {
+                    Some(AssistantContent::Text(Text { text: text.clone() }))
+                }
+                ContentBlock::ToolUse { id, name, input } => {
+                    Some(AssistantContent::ToolCall(ToolCall {
+                        id: id.clone(),
+                        call_id: None,
+                        function: ToolFunction {
+                            name: name.clone(),
+                            arguments: input.clone(),
+                        },
+                    }))
+                }
+                ContentBlock::ToolResult { .. } | ContentBlock::Other => None,
+            })
+            .collect();
+
+        let choice = OneOrMany::many(content).map_err(|_| {
+            CompletionError::ResponseError(format!(
+                "Anthropic response contained no text or tool call (stop reason: {:?})",
+                value.stop_reason
+            ))
+        })?;
+
+        let usage = completion::Usage {
+            input_tokens: value.usage.input_tokens,
+            output_tokens: value.usage.output_tokens,
+            total_tokens: value.usage.input_tokens + value.usage.output_tokens,
+        };
+
+        Ok(completion::CompletionResponse {
+            choice,
+            usage,
+            raw_response: value,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct CompletionModel {
+    pub client: Client,
+    pub model: String,
+}
+
+impl CompletionModel {
+    /// Create a new completion model
+    pub fn new(client: Client, model: String) -> Self {
+        Self { client, model }
+    }
+
+    /// The Messages API request for `completion_request`, with its additional
+    /// parameters merged in
+    pub fn create_completion_request(
+        &self,
+        completion_request: CompletionRequest,
+    ) -> Result<serde_json::Value, CompletionError> {
+        let documents = completion_request.normalized_documents();
+        let messages =
+            to_anthropic_messages(documents.into_iter().chain(completion_request.chat_history))
+                .map_err(|e| {
+                    CompletionError::ProviderError(format!("Failed to convert messages: {e}"))
+                })?;
+
+        let request = MessagesRequest {
+            model: self.model.clone(),
+            max_tokens: completion_request.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS),
+            system: completion_request
+                .preamble
+                .filter(|preamble| !preamble.trim().is_empty()),
+            messages,
+            tools: completion_request
+                .tools
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            temperature: completion_request.temperature,
+        };
+        debug!(
+            "Anthropic: request with {} messages and {} tools",
+            request.messages.len(),
+            request.tools.len()
+        );
+
+        let mut request = serde_json::to_value(request).map_err(CompletionError::JsonError)?;
+        if let Some(serde_json::Value::Object(additional_params)) =
+            completion_request.additional_params
+        {
+            for (key, value) in additional_params {
+                request[key] = value;
+            }
+        }
+
+        Ok(request)
+    }
+}
+
+impl completion::CompletionModel for CompletionModel {
+    type Response = CompletionResponse;
+    type StreamingResponse = rig::providers::openai::StreamingCompletionResponse;
+
+    async fn completion(
+        &self,
+        completion_request: CompletionRequest,
+    ) -> Result<completion::CompletionResponse<Self::Response>, CompletionError> {
+        let request = self.create_completion_request(completion_request)?;
+
+        let response: CompletionResponse = self.client.post("messages", &request).await?;
+        debug!(
+            "Anthropic: response {} stopped with {:?}",
+            response.id, response.stop_reason
+        );
+
+        response.try_into()
+    }
+
+    /// Streaming is not supported; the agents prompt without it
+    async fn stream(
+        &self,
+        _completion_request: CompletionRequest,
+    ) -> Result<rig::streaming::StreamingCompletionResponse<Self::StreamingResponse>, CompletionError>
+    {
+        Err(CompletionError::ProviderError(
+            "Streaming is not supported by the Anthropic provider".to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_deserialize_tool_use_response() {
+        let data = json!({
+            "id": "msg_01",
+            "type": "message",
+            "role": "assistant",
+            "model": "claude-sonnet-4-5",
+            "content": [
+                {"type": "thinking", "thinking": "...", "signature": "sig"},
+                {"type": "text", "text": "Transferring now."},
+                {
+                    "type": "tool_use",
+                    "id": "toolu_01",
+                    "name": "sol_transfer",
+                    "input": {"amount": 1000}
+                }
+            ],
+            "stop_reason": "tool_use",
+            "stop_sequence": null,
+            "usage": {"input_tokens": 120, "output_tokens": 40}
+        });
+
+        let parsed: CompletionResponse = serde_json::from_value(data).unwrap();
+        assert_eq!(parsed.stop_reason, Some(StopReason::ToolUse));
+        assert_eq!(parsed.content[0], ContentBlock::Other);
+
+        let response: completion::CompletionResponse<CompletionResponse> =
+            parsed.try_into().unwrap();
+        assert_eq!(response.usage.total_tokens, 160);
+        let choice: Vec<_> = response.choice.into_iter().collect();
+        assert_eq!(choice.len(), 2);
+        match &choice[1] {
+            AssistantContent::ToolCall(call) => {
+                assert_eq!(call.id, "toolu_01");
+                assert_eq!(call.function.arguments, json!({"amount": 1000}));
+            }
+            other => panic!("expected a tool call, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_stop_reasons() {
+        let response = |content: serde_json::Value, stop_reason: &str| -> CompletionResponse {
+            serde_json::from_value(json!({
+                "content": content,
+                "stop_reason": stop_reason,
+                "usage": {"input_tokens": 1, "output_tokens": 1}
+            }))
+            .unwrap()
+        };
+
+        let refused = response(json!([]), "refusal");
+        assert!(completion::CompletionResponse::try_from(refused).is_err());
+
+        let truncated_call = response(
+            json!([{"type": "tool_use", "id": "t", "name": "x", "input": {}}]),
+            "max_tokens",
+        );
+        assert!(completion::CompletionResponse::try_from(truncated_call).is_err());
+
+        let truncated_text = response(json!([{"type": "text", "text": "partial"}]), "max_tokens");
+        assert!(completion::CompletionResponse::try_from(truncated_text).is_ok());
+
+        let empty = response(json!([]), "end_turn");
+        assert!(completion::CompletionResponse::try_from(empty).is_err());
+    }
+
+    #[test]
+    fn test_messages_alternate_roles() {
+        let messages = vec![
+            Message::user("Send 1 SOL"),
+            Message::Assistant {
+                id: None,
+                content: OneOrMany::many(vec![
+                    AssistantContent::text(""),
+                    AssistantContent::tool_call("toolu_01", "sol_transfer", json!({"amount": 1})),
+                ])
+                .unwrap(),
+            },
+            Message::User {
+                content: OneOrMany::one(message::UserContent::tool_result(
+                    "toolu_01",
+                    OneOrMany::one(message::ToolResultContent::text("{\"ok\":true}")),
+                )),
+            },
+            Message::user("Anything else?"),
+        ];
+
+        let converted = to_anthropic_messages(messages).unwrap();
+        let json = serde_json::to_value(&converted).unwrap();
+        assert_eq!(
+            json,
+            json!([
+                {"role": "user", "content": [{"type": "text", "text": "Send 1 SOL"}]},
+                {"role": "assistant", "content": [
+                    {"type": "tool_use", "id": "toolu_01", "name": "sol_transfer", "input": {"amount": 1}}
+                ]},
+                {"role": "user", "content": [
+                    {"type": "tool_result", "tool_use_id": "toolu_01", "content": "{\"ok\":true}"},
+                    {"type": "text", "text": "Anything else?"}
+                ]}
+            ])
+        );
+    }
+}
@@ -0,0 +1,22 @@
+//! Anthropic provider speaking the Messages API natively, with tool use
+//!
+//! Tool calls and results travel as `tool_use`/`tool_result` content blocks and the
+//! preamble as the `system` prompt, so rig agents run their reev-tools without an
+//! OpenAI-compatible shim.
+//!
+//! # Example
+//! ```
+//! use reev_agent::providers::anthropic;
+//! use rig::client::completion::CompletionClient;
+//!
+//! let client = anthropic::Client::builder("ANTHROPIC_API_KEY").build();
+//!
+//! let claude = client.completion_model(anthropic::CLAUDE_SONNET_4_5);
+//! ```
+
+pub mod client;
+pub mod completion;
+
+pub use client::Client;
+
+pub const CLAUDE_SONNET_4_5: &str = "claude-sonnet-4-5";
@@ -0,0 +1,231 @@
+//! Ollama and llama.cpp server client implementation
+use serde::{Deserialize, Serialize};
+
+use super::completion::CompletionModel as LocalCompletionModel;
+use rig::client::{
+    impl_conversion_traits, CompletionClient, ProviderValue, VerifyClient, VerifyError,
+};
+use rig::{completion::CompletionError, prelude::ProviderClient};
+
+/// Server a local model runs on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// Ollama, through its native chat API
+    Ollama,
+    /// llama.cpp's `llama-server`
+    LlamaCpp,
+}
+
+impl Backend {
+    /// Address the server listens on by default
+    pub fn default_base_url(self) -> &'static str {
+        match self {
+            Backend::Ollama => "http://localhost:11434",
+            Backend::LlamaCpp => "http://localhost:8080",
+        }
+    }
+
+    /// Path of the chat endpoint
+    pub fn chat_path(self) -> &'static str {
+        match self {
+            Backend::Ollama => "api/chat",
+            Backend::LlamaCpp => "v1/chat/completions",
+        }
+    }
+
+    /// Path answering once the server is up
+    fn health_path(self) -> &'static str {
+        match self {
+            Backend::Ollama => "api/version",
+            Backend::LlamaCpp => "health",
+        }
+    }
+}
+
+impl std::fmt::Display for Backend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Backend::Ollama => "Ollama",
+            Backend::LlamaCpp => "llama.cpp",
+        })
+    }
+}
+
+/// Local server client builder
+pub struct ClientBuilder<'a> {
+    backend: Backend,
+    api_key: Option<&'a str>,
+    base_url: Option<&'a str>,
+    http_client: reqwest::Client,
+}
+
+impl<'a> ClientBuilder<'a> {
+    /// Create a new local server client builder
+    pub fn new(backend: Backend) -> Self {
+        Self {
+            backend,
+            api_key: None,
+            base_url: None,
+            http_client: reqwest::Client::new(),
+        }
+    }
+
+    /// Set the key of a `llama-server` started with `--api-key`
+    pub fn api_key(mut self, api_key: &'a str) -> Self {
+        self.api_key = Some(api_key);
+        self
+    }
+
+    /// Set a custom base URL for the server
+    pub fn base_url(mut self, base_url: &'a str) -> Self {
+        self.base_url = Some(base_url);
+        self
+    }
+
+    /// Set a custom HTTP client
+    pub fn custom_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = client;
+        self
+    }
+
+    /// Build the local server client
+    pub fn build(self) -> Client {
+        Client::new(
+            self.backend,
+            self.base_url.unwrap_or(self.backend.default_base_url()),
+            self.api_key,
+            self.http_client,
+        )
+    }
+}
+
+/// Local server client
+#[derive(Clone)]
+pub struct Client {
+    pub backend: Backend,
+    pub base_url: String,
+    pub api_key: Option<String>,
+    pub http_client: reqwest::Client,
+}
+
+impl std::fmt::Debug for Client {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Client")
+            .field("backend", &self.backend)
+            .field("base_url", &self.base_url)
+            .field("api_key", &self.api_key.as_ref().map(|_| "***"))
+            .finish()
+    }
+}
+
+impl Client {
+    /// Create a new local server client
+    pub fn new(
+        backend: Backend,
+        base_url: impl Into<String>,
+        api_key: Option<&str>,
+        http_client: reqwest::Client,
+    ) -> Self {
+        Self {
+            backend,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key: api_key.map(str::to_string),
+            http_client,
+        }
+    }
+
+    /// Create a new local server client builder
+    pub fn builder<'a>(backend: Backend) -> ClientBuilder<'a> {
+        ClientBuilder::new(backend)
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let request = self
+            .http_client
+            .request(method, format!("{}/{}", self.base_url, path));
+        match &self.api_key {
+            Some(api_key) => request.bearer_auth(api_key),
+            None => request,
+        }
+    }
+
+    /// Send a POST request to the server
+    pub(crate) async fn post<T: Serialize, R: for<'de> Deserialize<'de>>(
+        &self,
+        path: &str,
+        request: &T,
+    ) -> Result<R, CompletionError> {
+        let response = self
+            .request(reqwest::Method::POST, path)
+            .json(request)
+            .send()
+            .await
+            .map_err(CompletionError::HttpError)?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Failed to read error response".to_string());
+
+            return Err(CompletionError::ProviderError(format!(
+                "{} server error {status}: {error_text}",
+                self.backend
+            )));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| CompletionError::ProviderError(e.to_string()))
+    }
+}
+
+impl ProviderClient for Client {
+    fn from_env() -> Self {
+        let base_url = std::env::var("OLLAMA_URL")
+            .unwrap_or_else(|_| Backend::Ollama.default_base_url().to_string());
+        Self::new(Backend::Ollama, base_url, None, reqwest::Client::new())
+    }
+
+    fn from_val(input: ProviderValue) -> Self {
+        let ProviderValue::Simple(base_url) = input else {
+            panic!("Incorrect provider value type")
+        };
+        Self::new(Backend::Ollama, base_url, None, reqwest::Client::new())
+    }
+}
+
+impl CompletionClient for Client {
+    type CompletionModel = LocalCompletionModel;
+
+    fn completion_model(&self, model: &str) -> Self::CompletionModel {
+        LocalCompletionModel::new(self.clone(), model.to_string())
+    }
+}
+
+impl VerifyClient for Client {
+    async fn verify(&self) -> Result<(), VerifyError> {
+        let response = self
+            .request(reqwest::Method::GET, self.backend.health_path())
+            .send()
+            .await?;
+        match response.status() {
+            reqwest::StatusCode::OK => Ok(()),
+            reqwest::StatusCode::UNAUTHORIZED => Err(VerifyError::InvalidAuthentication),
+            status => Err(VerifyError::ProviderError(format!(
+                "{} server answered {status}",
+                self.backend
+            ))),
+        }
+    }
+}
+
+// Implement conversion traits for the local server client
+impl_conversion_traits!(
+    AsEmbeddings,
+    AsTranscription,
+    AsImageGeneration,
+    AsAudioGeneration for Client
+);
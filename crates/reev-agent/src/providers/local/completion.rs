@@ -0,0 +1,720 @@
+//! Ollama and llama.cpp completion model and related types
+
+use std::collections::HashMap;
+
+use rig::{
+    completion::{self, AssistantContent, CompletionError, CompletionRequest},
+    message::{self, Message, Text, ToolCall, ToolFunction},
+    OneOrMany,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::{debug, warn};
+
+use super::{
+    client::{Backend, Client},
+    tool_text,
+};
+
+/// Characters per token assumed when estimating the size of a prompt
+pub const CHARS_PER_TOKEN: usize = 4;
+
+/// A tool call of an assistant message
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LocalToolCall {
+    pub id: String,
+    pub name: String,
+    pub arguments: Value,
+}
+
+/// A message of the conversation, in the shape both servers share
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LocalMessage {
+    /// `system`, `user`, `assistant` or `tool`
+    pub role: String,
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<LocalToolCall>,
+    /// Call a `tool` message answers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+    /// Tool a `tool` message comes from; Ollama matches results by name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+}
+
+impl LocalMessage {
+    fn new(role: &str, content: String) -> Self {
+        Self {
+            role: role.to_string(),
+            content,
+            tool_calls: vec![],
+            tool_call_id: None,
+            tool_name: None,
+        }
+    }
+
+    /// The message as `backend` expects it
+    pub fn to_json(&self, backend: Backend) -> Value {
+        let mut message = json!({"role": self.role, "content": self.content});
+        if !self.tool_calls.is_empty() {
+            message["tool_calls"] = self
+                .tool_calls
+                .iter()
+                .map(|call| match backend {
+                    Backend::Ollama => json!({
+                        "function": {"name": call.name, "arguments": call.arguments}
+                    }),
+                    Backend::LlamaCpp => json!({
+                        "id": call.id,
+                        "type": "function",
+                        "function": {"name": call.name, "arguments": call.arguments.to_string()}
+                    }),
+                })
+                .collect();
+        }
+        match backend {
+            Backend::Ollama => {
+                if let Some(tool_name) = &self.tool_name {
+                    message["tool_name"] = json!(tool_name);
+                }
+            }
+            Backend::LlamaCpp => {
+                if let Some(tool_call_id) = &self.tool_call_id {
+                    message["tool_call_id"] = json!(tool_call_id);
+                }
+            }
+        }
+        message
+    }
+}
+
+fn tool_result_text(content: OneOrMany<message::ToolResultContent>) -> String {
+    content
+        .into_iter()
+        .filter_map(|content| match content {
+            message::ToolResultContent::Text(text) => Some(text.text),
+            message::ToolResultContent::Image(_) => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Convert rig messages to local messages
+///
+/// Tool results become `tool` messages of their own, named after the call they
+/// answer since Ollama does not use call ids.
+pub fn to_local_messages(
+    messages: impl IntoIterator<Item = Message>,
+) -> Result<Vec<LocalMessage>, String> {
+    let mut tool_names: HashMap<String, String> = HashMap::new();
+    let mut converted = Vec::new();
+
+    for message in messages {
+        match message {
+            Message::User { content } => {
+                let mut text = Vec::new();
+                for content in content.into_iter() {
+                    match content {
+                        message::UserContent::Text(t) => text.push(t.text),
+                        message::UserContent::Document(document) => text.push(document.data),
+                        message::UserContent::ToolResult(tool_result) => {
+                            converted.push(LocalMessage {
+                                tool_name: tool_names.get(&tool_result.id).cloned(),
+                                tool_call_id: Some(tool_result.id),
+                                ..LocalMessage::new("tool", tool_result_text(tool_result.content))
+                            });
+                        }
+                        other => return Err(format!("unsupported user content: {other:?}")),
+                    }
+                }
+                if !text.is_empty() {
+                    converted.push(LocalMessage::new("user", text.join("\n")));
+                }
+            }
+            Message::Assistant { id: _, content } => {
+                let mut text = Vec::new();
+                let mut tool_calls = Vec::new();
+                for content in content.into_iter() {
+                    match content {
+                        AssistantContent::Text(t) => text.push(t.text),
+                        AssistantContent::ToolCall(tool_call) => {
+                            tool_names
+                                .insert(tool_call.id.clone(), tool_call.function.name.clone());
+                            tool_calls.push(LocalToolCall {
+                                id: tool_call.id,
+                                name: tool_call.function.name,
+                                arguments: tool_call.function.arguments,
+                            });
+                        }
+                        AssistantContent::Reasoning(_) => {}
+                    }
+                }
+                converted.push(LocalMessage {
+                    tool_calls,
+                    ..LocalMessage::new("assistant", text.join("\n"))
+                });
+            }
+        }
+    }
+
+    Ok(converted)
+}
+
+/// Rough token count of a request body, from its length
+pub fn estimate_tokens(request: &Value) -> usize {
+    request.to_string().len().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// Remove the `<think>` blocks reasoning models put before their answer
+fn strip_thinking(text: &str) -> String {
+    let mut text = text.to_string();
+    while let (Some(start), Some(end)) = (text.find("<think>"), text.find("</think>")) {
+        if end < start {
+            break;
+        }
+        text.replace_range(start..end + "</think>".len(), "");
+    }
+    text.trim().to_string()
+}
+
+#[derive(Deserialize, Debug)]
+struct OllamaToolCall {
+    function: OllamaFunction,
+}
+
+#[derive(Deserialize, Debug)]
+struct OllamaFunction {
+    name: String,
+    #[serde(default)]
+    arguments: Value,
+}
+
+#[derive(Deserialize, Debug)]
+struct OllamaMessage {
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    tool_calls: Vec<OllamaToolCall>,
+}
+
+/// Body of an Ollama `POST /api/chat` response
+#[derive(Deserialize, Debug)]
+struct OllamaResponse {
+    #[serde(default)]
+    model: String,
+    message: OllamaMessage,
+    done_reason: Option<String>,
+    #[serde(default)]
+    prompt_eval_count: u64,
+    #[serde(default)]
+    eval_count: u64,
+}
+
+#[derive(Deserialize, Debug)]
+struct LlamaCppToolCall {
+    #[serde(default)]
+    id: Option<String>,
+    function: LlamaCppFunction,
+}
+
+#[derive(Deserialize, Debug)]
+struct LlamaCppFunction {
+    name: String,
+    #[serde(default)]
+    arguments: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct LlamaCppMessage {
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<LlamaCppToolCall>,
+}
+
+#[derive(Deserialize, Debug)]
+struct LlamaCppChoice {
+    message: LlamaCppMessage,
+    finish_reason: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+struct LlamaCppUsage {
+    prompt_tokens: u64,
+    completion_tokens: u64,
+}
+
+/// Body of a `llama-server` `POST /v1/chat/completions` response
+#[derive(Deserialize, Debug)]
+struct LlamaCppResponse {
+    #[serde(default)]
+    model: String,
+    choices: Vec<LlamaCppChoice>,
+    #[serde(default)]
+    usage: LlamaCppUsage,
+}
+
+/// A response of either server
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CompletionResponse {
+    pub model: String,
+    pub content: String,
+    pub tool_calls: Vec<LocalToolCall>,
+    /// `stop`, `length` or `tool_calls`, as reported by the server
+    pub finish_reason: Option<String>,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+fn new_call_id() -> String {
+    format!("call_{}", uuid::Uuid::new_v4().simple())
+}
+
+impl CompletionResponse {
+    /// Read the response body of `backend`
+    pub fn from_json(backend: Backend, body: Value) -> Result<Self, CompletionError> {
+        match backend {
+            Backend::Ollama => {
+                let response: OllamaResponse =
+                    serde_json::from_value(body).map_err(CompletionError::JsonError)?;
+                Ok(Self {
+                    model: response.model,
+                    content: response.message.content,
+                    tool_calls: response
+                        .message
+                        .tool_calls
+                        .into_iter()
+                        .map(|call| LocalToolCall {
+                            id: new_call_id(),
+                            name: call.function.name,
+                            arguments: call.function.arguments,
+                        })
+                        .collect(),
+                    finish_reason: response.done_reason,
+                    prompt_tokens: response.prompt_eval_count,
+                    completion_tokens: response.eval_count,
+                })
+            }
+            Backend::LlamaCpp => {
+                let response: LlamaCppResponse =
+                    serde_json::from_value(body).map_err(CompletionError::JsonError)?;
+                let choice = response.choices.into_iter().next().ok_or_else(|| {
+                    CompletionError::ResponseError("llama.cpp response has no choices".to_string())
+                })?;
+                let tool_calls = choice
+                    .message
+                    .tool_calls
+                    .into_iter()
+                    .map(|call| {
+                        let arguments = if call.function.arguments.trim().is_empty() {
+                            json!({})
+                        } else {
+                            serde_json::from_str(&call.function.arguments)?
+                        };
+                        Ok(LocalToolCall {
+                            id: call.id.unwrap_or_else(new_call_id),
+                            name: call.function.name,
+                            arguments,
+                        })
+                    })
+                    .collect::<Result<_, serde_json::Error>>()
+                    .map_err(CompletionError::JsonError)?;
+                Ok(Self {
+                    model: response.model,
+                    content: choice.message.content.unwrap_or_default(),
+                    tool_calls,
+                    finish_reason: choice.finish_reason,
+                    prompt_tokens: response.usage.prompt_tokens,
+                    completion_tokens: response.usage.completion_tokens,
+                })
+            }
+        }
+    }
+
+    /// The rig response, recovering tool calls the model wrote in its text for
+    /// tools named in `tool_names`
+    pub fn into_completion(
+        mut self,
+        tool_names: &[String],
+    ) -> Result<completion::CompletionResponse<Self>, CompletionError> {
+        let text = strip_thinking(&self.content);
+        if self.tool_calls.is_empty() && !tool_names.is_empty() {
+            let recovered = tool_text::parse_tool_calls(&text, tool_names);
+            if !recovered.is_empty() {
+                debug!(
+                    "Local: recovered {} tool calls from the response text",
+                    recovered.len()
+                );
+                self.tool_calls = recovered
+                    .into_iter()
+                    .map(|call| LocalToolCall {
+                        id: new_call_id(),
+                        name: call.name,
+                        arguments: call.arguments,
+                    })
+                    .collect();
+            }
+        }
+
+        if self.finish_reason.as_deref() == Some("length") {
+            // A tool call cut off by the token limit has incomplete arguments
+            if !self.tool_calls.is_empty() {
+                return Err(CompletionError::ResponseError(
+                    "Local model reached its token limit during a tool call".to_string(),
+                ));
+            }
+            warn!("Local: response truncated at the token limit");
+        }
+
+        let mut content = Vec::new();
+        // Text the calls were recovered from is not repeated as an answer
+        if self.tool_calls.is_empty() && !text.is_empty() {
+            content.push(AssistantContent::Text(Text { text }));
+        }
+        content.extend(self.tool_calls.iter().map(|call| {
+            AssistantContent::ToolCall(ToolCall {
+                id: call.id.clone(),
+                call_id: None,
+                function: ToolFunction {
+                    name: call.name.clone(),
+                    arguments: call.arguments.clone(),
+                },
+            })
+        }));
+
+        let choice = OneOrMany::many(content).map_err(|_| {
+            CompletionError::ResponseError(format!(
+                "Local model returned no text or tool call (finish reason: {:?})",
+                self.finish_reason
+            ))
+        })?;
+
+        let usage = completion::Usage {
+            input_tokens: self.prompt_tokens,
+            output_tokens: self.completion_tokens,
+            total_tokens: self.prompt_tokens + self.completion_tokens,
+        };
+
+        Ok(completion::CompletionResponse {
+            choice,
+            usage,
+            raw_response: self,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct CompletionModel {
+    pub client: Client,
+    pub model: String,
+    /// Context window the prompt and the answer have to fit in
+    pub context_size: Option<u32>,
+}
+
+impl CompletionModel {
+    /// Create a new completion model
+    pub fn new(client: Client, model: String) -> Self {
+        Self {
+            client,
+            model,
+            context_size: None,
+        }
+    }
+
+    /// Hold requests to a context window of `context_size` tokens
+    pub fn with_context_size(mut self, context_size: impl Into<Option<u32>>) -> Self {
+        self.context_size = context_size.into();
+        self
+    }
+
+    /// The chat request for `completion_request`, with its additional parameters
+    /// merged in
+    ///
+    /// With a context size, a prompt that does not fit is an error and the answer
+    /// is limited to the room left; Ollama is also told the size, as it otherwise
+    /// truncates long prompts to its small default context without notice.
+    pub fn create_completion_request(
+        &self,
+        completion_request: CompletionRequest,
+    ) -> Result<Value, CompletionError> {
+        let backend = self.client.backend;
+        let documents = completion_request.normalized_documents();
+        let mut messages: Vec<LocalMessage> = completion_request
+            .preamble
+            .filter(|preamble| !preamble.trim().is_empty())
+            .map(|preamble| LocalMessage::new("system", preamble))
+            .into_iter()
+            .collect();
+        messages.extend(
+            to_local_messages(documents.into_iter().chain(completion_request.chat_history))
+                .map_err(|e| {
+                    CompletionError::ProviderError(format!("Failed to convert messages: {e}"))
+                })?,
+        );
+
+        let tools: Vec<Value> = completion_request
+            .tools
+            .into_iter()
+            .map(|tool| {
+                json!({
+                    "type": "function",
+                    "function": {
+                        "name": tool.name,
+                        "description": tool.description,
+                        "parameters": tool.parameters,
+                    }
+                })
+            })
+            .collect();
+
+        let mut request = json!({
+            "model": self.model,
+            "messages": messages.iter().map(|m| m.to_json(backend)).collect::<Vec<_>>(),
+            "stream": false,
+        });
+        if !tools.is_empty() {
+            request["tools"] = Value::Array(tools);
+        }
+
+        let mut max_tokens = completion_request.max_tokens;
+        if let Some(context_size) = self.context_size {
+            let prompt_tokens = estimate_tokens(&request);
+            let room = (context_size as usize).saturating_sub(prompt_tokens);
+            if room == 0 {
+                return Err(CompletionError::ProviderError(format!(
+                    "Prompt of about {prompt_tokens} tokens does not fit the {context_size}-token context of {}",
+                    self.model
+                )));
+            }
+            max_tokens = Some(max_tokens.map_or(room as u64, |max| max.min(room as u64)));
+        }
+
+        let mut additional_params = match completion_request.additional_params {
+            Some(Value::Object(additional_params)) => additional_params,
+            _ => serde_json::Map::new(),
+        };
+        match backend {
+            Backend::Ollama => {
+                let mut options = serde_json::Map::new();
+                if let Some(temperature) = completion_request.temperature {
+                    options.insert("temperature".to_string(), json!(temperature));
+                }
+                // Ollama reads the sampling seed from the options
+                if let Some(seed) = additional_params.remove("seed") {
+                    options.insert("seed".to_string(), seed);
+                }
+                if let Some(context_size) = self.context_size {
+                    options.insert("num_ctx".to_string(), json!(context_size));
+                }
+                if let Some(max_tokens) = max_tokens {
+                    options.insert("num_predict".to_string(), json!(max_tokens));
+                }
+                request["options"] = Value::Object(options);
+            }
+            Backend::LlamaCpp => {
+                if let Some(temperature) = completion_request.temperature {
+                    request["temperature"] = json!(temperature);
+                }
+                if let Some(max_tokens) = max_tokens {
+                    request["max_tokens"] = json!(max_tokens);
+                }
+            }
+        }
+
+        for (key, value) in additional_params {
+            request[key] = value;
+        }
+
+        debug!(
+            "{backend}: request with {} messages, {} tools and max tokens {max_tokens:?}",
+            messages.len(),
+            request["tools"].as_array().map_or(0, Vec::len)
+        );
+        Ok(request)
+    }
+}
+
+impl completion::CompletionModel for CompletionModel {
+    type Response = CompletionResponse;
+    type StreamingResponse = rig::providers::openai::StreamingCompletionResponse;
+
+    async fn completion(
+        &self,
+        completion_request: CompletionRequest,
+    ) -> Result<completion::CompletionResponse<Self::Response>, CompletionError> {
+        let tool_names: Vec<String> = completion_request
+            .tools
+            .iter()
+            .map(|tool| tool.name.clone())
+            .collect();
+        let request = self.create_completion_request(completion_request)?;
+
+        let backend = self.client.backend;
+        let body: Value = self.client.post(backend.chat_path(), &request).await?;
+        let response = CompletionResponse::from_json(backend, body)?;
+        debug!(
+            "{backend}: response with {} tool calls finished with {:?}",
+            response.tool_calls.len(),
+            response.finish_reason
+        );
+
+        response.into_completion(&tool_names)
+    }
+
+    /// Streaming is not supported; the agents prompt without it
+    async fn stream(
+        &self,
+        _completion_request: CompletionRequest,
+    ) -> Result<rig::streaming::StreamingCompletionResponse<Self::StreamingResponse>, CompletionError>
+    {
+        Err(CompletionError::ProviderError(
+            "Streaming is not supported by the local provider".to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_responses() {
+        let ollama = json!({
+            "model": "qwen3-coder:30b",
+            "message": {
+                "role": "assistant",
+                "content": "",
+                "tool_calls": [
+                    {"function": {"name": "sol_transfer", "arguments": {"amount": 1}}}
+                ]
+            },
+            "done": true,
+            "done_reason": "stop",
+            "prompt_eval_count": 300,
+            "eval_count": 25
+        });
+        let response = CompletionResponse::from_json(Backend::Ollama, ollama).unwrap();
+        assert_eq!(response.tool_calls[0].arguments, json!({"amount": 1}));
+        let response = response.into_completion(&[]).unwrap();
+        assert_eq!(response.usage.total_tokens, 325);
+
+        let llama_cpp = json!({
+            "model": "gpt-3.5-turbo",
+            "choices": [{
+                "index": 0,
+                "finish_reason": "tool_calls",
+                "message": {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": {"name": "sol_transfer", "arguments": "{\"amount\":1}"}
+                    }]
+                }
+            }],
+            "usage": {"prompt_tokens": 10, "completion_tokens": 5, "total_tokens": 15}
+        });
+        let response = CompletionResponse::from_json(Backend::LlamaCpp, llama_cpp).unwrap();
+        assert_eq!(response.tool_calls[0].id, "call_1");
+        assert_eq!(response.tool_calls[0].arguments, json!({"amount": 1}));
+    }
+
+    #[test]
+    fn test_text_tool_calls_and_thinking() {
+        let response = CompletionResponse {
+            model: "qwen3".to_string(),
+            content: "<think>The user wants SOL sent.</think>\n```json\n\
+                      {\"name\": \"sol_transfer\", \"arguments\": {\"amount\": 1}}\n```"
+                .to_string(),
+            tool_calls: vec![],
+            finish_reason: Some("stop".to_string()),
+            prompt_tokens: 1,
+            completion_tokens: 1,
+        };
+
+        let tools = vec!["sol_transfer".to_string()];
+        let choice: Vec<_> = response
+            .clone()
+            .into_completion(&tools)
+            .unwrap()
+            .choice
+            .into_iter()
+            .collect();
+        assert_eq!(choice.len(), 1);
+        assert!(matches!(&choice[0], AssistantContent::ToolCall(call)
+            if call.function.name == "sol_transfer"));
+
+        // Without tools the same text is the answer, minus the thinking
+        let choice: Vec<_> = response
+            .into_completion(&[])
+            .unwrap()
+            .choice
+            .into_iter()
+            .collect();
+        assert!(matches!(&choice[0], AssistantContent::Text(text)
+            if text.text.starts_with("```json")));
+    }
+
+    #[test]
+    fn test_tool_results_named_for_ollama() {
+        let messages = vec![
+            Message::user("Send 1 SOL"),
+            Message::Assistant {
+                id: None,
+                content: OneOrMany::one(AssistantContent::tool_call(
+                    "call_1",
+                    "sol_transfer",
+                    json!({"amount": 1}),
+                )),
+            },
+            Message::User {
+                content: OneOrMany::one(message::UserContent::tool_result(
+                    "call_1",
+                    OneOrMany::one(message::ToolResultContent::text("ok")),
+                )),
+            },
+        ];
+
+        let converted = to_local_messages(messages).unwrap();
+        assert_eq!(
+            converted[2].to_json(Backend::Ollama),
+            json!({"role": "tool", "content": "ok", "tool_name": "sol_transfer"})
+        );
+        assert_eq!(
+            converted[2].to_json(Backend::LlamaCpp),
+            json!({"role": "tool", "content": "ok", "tool_call_id": "call_1"})
+        );
+        assert_eq!(
+            converted[1].to_json(Backend::LlamaCpp)["tool_calls"][0]["function"]["arguments"],
+            "{\"amount\":1}"
+        );
+    }
+
+    #[test]
+    fn test_seed_in_ollama_options() {
+        let request = |backend| {
+            CompletionModel::new(Client::builder(backend).build(), "qwen3".to_string())
+                .create_completion_request(CompletionRequest {
+                    preamble: None,
+                    chat_history: OneOrMany::one(Message::user("Send 1 SOL")),
+                    documents: vec![],
+                    tools: vec![],
+                    temperature: Some(0.7),
+                    max_tokens: None,
+                    additional_params: Some(json!({"seed": 42})),
+                })
+                .unwrap()
+        };
+
+        let ollama = request(Backend::Ollama);
+        assert_eq!(ollama["options"]["seed"], 42);
+        assert_eq!(ollama["options"]["temperature"], 0.7);
+        assert!(ollama.get("seed").is_none(), "{ollama}");
+
+        let llama_cpp = request(Backend::LlamaCpp);
+        assert_eq!(llama_cpp["seed"], 42);
+        assert_eq!(llama_cpp["temperature"], 0.7);
+    }
+}
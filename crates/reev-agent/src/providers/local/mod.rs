@@ -0,0 +1,24 @@
+//! Provider for models served on this machine by Ollama or llama.cpp
+//!
+//! Ollama is driven through its native `/api/chat` endpoint, llama.cpp through the
+//! chat endpoint of `llama-server`. Both take care of local-model quirks: tool calls
+//! a model writes as JSON in its text are recovered as tool calls, and prompts are
+//! held to the model's context size instead of being truncated by the server.
+//!
+//! # Example
+//! ```
+//! use reev_agent::providers::local::{self, Backend};
+//! use rig::client::completion::CompletionClient;
+//!
+//! let client = local::Client::builder(Backend::Ollama).build();
+//!
+//! let qwen = client
+//!     .completion_model("qwen3-coder:30b")
+//!     .with_context_size(32_768);
+//! ```
+
+pub mod client;
+pub mod completion;
+pub mod tool_text;
+
+pub use client::{Backend, Client};
@@ -0,0 +1,136 @@
+//! Recovery of tool calls that a local model writes as JSON in its text
+//!
+//! Many open models ignore the server's tool-call format and answer with the call
+//! itself, bare or wrapped in a code fence or a `<tool_call>` tag:
+//!
+//! ```text
+//! <tool_call>
+//! {"name": "sol_transfer", "arguments": {"amount": 100000000}}
+//! </tool_call>
+//! ```
+//!
+//! Only JSON naming one of the request's tools counts, so a model quoting a tool
+//! result back is not mistaken for a call.
+
+use serde_json::{Map, Value};
+
+/// A tool call found in the text of a response
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextToolCall {
+    pub name: String,
+    pub arguments: Value,
+}
+
+/// The tool calls written in `text` for tools named in `tool_names`
+pub fn parse_tool_calls(text: &str, tool_names: &[String]) -> Vec<TextToolCall> {
+    let mut calls = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find(['{', '[']) {
+        let candidate = &rest[start..];
+        let mut values = serde_json::Deserializer::from_str(candidate).into_iter::<Value>();
+        match values.next() {
+            Some(Ok(value)) => match tool_calls(&value, tool_names) {
+                Some(found) => {
+                    calls.extend(found);
+                    rest = &candidate[values.byte_offset()..];
+                }
+                // Valid JSON but not a call, such as a quoted tool result
+                None => rest = &candidate[values.byte_offset()..],
+            },
+            _ => rest = &candidate[1..],
+        }
+    }
+
+    calls
+}
+
+/// The calls `value` stands for: one call, a list of calls, or a `tool_calls` list
+fn tool_calls(value: &Value, tool_names: &[String]) -> Option<Vec<TextToolCall>> {
+    match value {
+        Value::Array(items) if !items.is_empty() => items
+            .iter()
+            .map(|item| tool_call(item.as_object()?, tool_names))
+            .collect(),
+        Value::Object(object) => match object.get("tool_calls") {
+            Some(list) => tool_calls(list, tool_names),
+            None => tool_call(object, tool_names).map(|call| vec![call]),
+        },
+        _ => None,
+    }
+}
+
+fn tool_call(object: &Map<String, Value>, tool_names: &[String]) -> Option<TextToolCall> {
+    // OpenAI style: {"type": "function", "function": {"name": .., "arguments": ..}}
+    if let Some(Value::Object(function)) = object.get("function") {
+        return tool_call(function, tool_names);
+    }
+
+    let name = object.get("name")?.as_str()?;
+    if !tool_names.iter().any(|tool| tool == name) {
+        return None;
+    }
+
+    let arguments = match object.get("arguments").or_else(|| object.get("parameters")) {
+        None | Some(Value::Null) => Value::Object(Map::new()),
+        // Some models send the arguments JSON-encoded, as the OpenAI API does
+        Some(Value::String(encoded)) => serde_json::from_str(encoded).ok()?,
+        Some(arguments @ Value::Object(_)) => arguments.clone(),
+        Some(_) => return None,
+    };
+
+    Some(TextToolCall {
+        name: name.to_string(),
+        arguments,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tools() -> Vec<String> {
+        vec![
+            "sol_transfer".to_string(),
+            "get_account_balance".to_string(),
+        ]
+    }
+
+    #[test]
+    fn test_wrapped_tool_calls() {
+        let tagged = r#"I'll transfer the SOL now.
+<tool_call>
+{"name": "sol_transfer", "arguments": {"amount": 100000000}}
+</tool_call>"#;
+        assert_eq!(
+            parse_tool_calls(tagged, &tools()),
+            vec![TextToolCall {
+                name: "sol_transfer".to_string(),
+                arguments: json!({"amount": 100000000}),
+            }]
+        );
+
+        let fenced = "```json\n[{\"name\": \"get_account_balance\", \"parameters\": {}}, \
+                      {\"type\": \"function\", \"function\": {\"name\": \"sol_transfer\", \
+                      \"arguments\": \"{\\\"amount\\\": 1}\"}}]\n```";
+        let calls = parse_tool_calls(fenced, &tools());
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].arguments, json!({}));
+        assert_eq!(calls[1].name, "sol_transfer");
+        assert_eq!(calls[1].arguments, json!({"amount": 1}));
+    }
+
+    #[test]
+    fn test_text_without_tool_calls() {
+        assert!(parse_tool_calls("Done, the transfer succeeded.", &tools()).is_empty());
+        // JSON that names no known tool is kept as text
+        assert!(parse_tool_calls(r#"Result: {"name": "alice", "amount": 5}"#, &tools()).is_empty());
+        assert!(
+            parse_tool_calls(r#"{"name": "sol_transfer", "arguments": 5"#, &tools()).is_empty()
+        );
+        assert!(
+            parse_tool_calls(r#"{"name": "sol_transfer", "arguments": [1]}"#, &tools()).is_empty()
+        );
+    }
+}
@@ -3,6 +3,8 @@
 //! This module contains various LLM provider implementations for the reev-agent framework.
 //! Each provider offers a standardized interface for interacting with different LLM services.
 
+pub mod anthropic;
+pub mod local;
 pub mod zai;
 
 // Re-export commonly used items
@@ -1,9 +1,13 @@
 use anyhow::Result;
+use reev_config::ProviderKind;
 use serde::Deserialize;
 use std::collections::HashMap;
 use tracing::{debug, info};
 
-use crate::{enhanced::openai::OpenAIAgent, enhanced::zai_agent::ZAIAgent, LlmRequest};
+use crate::{
+    enhanced::anthropic_agent::AnthropicAgent, enhanced::local_agent::LocalAgent,
+    enhanced::openai::OpenAIAgent, enhanced::zai_agent::ZAIAgent, LlmRequest,
+};
 
 /// A minimal struct for deserializing the `key_map` from the `context_prompt` YAML.
 #[derive(Debug, Deserialize)]
@@ -13,7 +17,8 @@ struct AgentContext {
 }
 
 /// Main dispatcher for AI agents with enhanced context capabilities
-/// Routes requests to appropriate agent based on model type and provides enhanced context.
+/// Routes requests to the agent of the model's provider in the model registry;
+/// models missing from the registry are an error.
 pub async fn run_agent(model_name: &str, payload: LlmRequest) -> Result<String> {
     info!("[run_agent] Dispatching to enhanced agent with model: {model_name}");
 
@@ -83,48 +88,46 @@ pub async fn run_agent(model_name: &str, payload: LlmRequest) -> Result<String>
     // Debug: Log key_map being passed to tools
     debug!("[run_agent] Key map for tools: {:?}", key_map);
 
-    // Route to appropriate enhanced agent based on model type
-    if model_name.starts_with("glm-") {
-        if std::env::var("ZAI_API_KEY").is_ok() {
-            // All GLM models - use ZAIAgent with ZAI_API_KEY
-            info!("[run_agent] Using GLM model via ZAIAgent with ZAI_API_KEY");
+    // Route to the agent of the model's provider in the registry
+    let model_name = reev_config::canonical_model_name(model_name);
+    let entry = reev_config::get().model(model_name)?;
+    match entry.provider {
+        ProviderKind::Deterministic => {
+            info!("[run_agent] Routing '{model_name}' to deterministic agent");
+            let response = crate::run_deterministic_agent(payload).await?;
+            // Extract the text field from LlmResponse
+            let response_text = response
+                .result
+                .as_ref()
+                .map(|r| r.text.clone())
+                .unwrap_or_else(String::new);
+            info!(
+                "[run_agent] Deterministic agent response: {}",
+                response_text
+            );
+            Ok(response_text)
+        }
+        ProviderKind::Zai => {
+            info!("[run_agent] Using GLM model via ZAIAgent: {model_name}");
             ZAIAgent::run(model_name, payload, key_map).await
-        } else {
-            Err(anyhow::anyhow!(
-                "GLM model '{model_name}' requires ZAI_API_KEY environment variable"
-            ))
         }
-    } else if model_name == "local" {
-        // Real local model - route to OpenAI agent which supports local LLM servers
-        info!("[run_agent] Using real local model via OpenAI agent");
-        OpenAIAgent::run(model_name, payload, key_map).await
-    } else if model_name.starts_with("gpt-")
-        || model_name.starts_with("claude-")
-        || model_name.starts_with("o1-")
-    {
-        // Other cloud models - route to OpenAI agent
-        info!(
-            "[run_agent] Using cloud model via OpenAI agent: {}",
-            model_name
-        );
-        OpenAIAgent::run(model_name, payload, key_map).await
-    } else {
-        // Route to deterministic agent for unknown models
-        info!(
-            "[run_agent] Unknown model '{}' detected, routing to deterministic agent",
-            model_name
-        );
-        let response = crate::run_deterministic_agent(payload).await?;
-        // Extract the text field from LlmResponse
-        let response_text = response
-            .result
-            .as_ref()
-            .map(|r| r.text.clone())
-            .unwrap_or_else(String::new);
-        info!(
-            "[run_agent] Deterministic agent response: {}",
-            response_text
-        );
-        Ok(response_text)
+        ProviderKind::Anthropic => {
+            info!("[run_agent] Using Anthropic model via AnthropicAgent: {model_name}");
+            AnthropicAgent::run(model_name, payload, key_map).await
+        }
+        ProviderKind::Ollama | ProviderKind::LlamaCpp => {
+            info!(
+                "[run_agent] Using {} model via LocalAgent: {model_name}",
+                entry.provider
+            );
+            LocalAgent::run(model_name, payload, key_map).await
+        }
+        ProviderKind::OpenAi | ProviderKind::Local => {
+            info!(
+                "[run_agent] Using {} model via OpenAI agent: {model_name}",
+                entry.provider
+            );
+            OpenAIAgent::run(model_name, payload, key_map).await
+        }
     }
 }
@@ -0,0 +1,192 @@
+//! Anthropic provider tests against a local stub of the Messages API
+//!
+//! The stub answers the first request with a `tool_use` block for `sol_transfer` and
+//! the second with the final text, so one multi-turn prompt exercises the request
+//! format, tool execution with the reev-tools and the `tool_result` round trip.
+
+use anyhow::Result;
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+    Json, Router,
+};
+use reev_agent::providers::anthropic;
+use reev_tools::tools::SolTransferTool;
+use rig::{client::CompletionClient, completion::Prompt};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+#[derive(Clone, Default)]
+struct Stub {
+    /// Headers and body of each request received
+    requests: Arc<Mutex<Vec<(HeaderMap, Value)>>>,
+    /// Status and body of each response to send, in order
+    responses: Arc<Mutex<VecDeque<(StatusCode, Value)>>>,
+}
+
+async fn messages(
+    State(stub): State<Stub>,
+    headers: HeaderMap,
+    Json(body): Json<Value>,
+) -> (StatusCode, Json<Value>) {
+    stub.requests.lock().unwrap().push((headers, body));
+    let (status, response) = stub
+        .responses
+        .lock()
+        .unwrap()
+        .pop_front()
+        .expect("no response left in the stub");
+    (status, Json(response))
+}
+
+/// Serve `responses` on a local port, returning the stub and its base URL
+async fn start_stub(responses: Vec<(StatusCode, Value)>) -> Result<(Stub, String)> {
+    let stub = Stub {
+        responses: Arc::new(Mutex::new(responses.into())),
+        ..Stub::default()
+    };
+    let app = Router::new()
+        .route("/v1/messages", post(messages))
+        .with_state(stub.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("http://{}/v1", listener.local_addr()?);
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    Ok((stub, url))
+}
+
+fn message(content: Value, stop_reason: &str) -> (StatusCode, Value) {
+    (
+        StatusCode::OK,
+        json!({
+            "id": "msg_stub",
+            "type": "message",
+            "role": "assistant",
+            "model": "claude-sonnet-4-5",
+            "content": content,
+            "stop_reason": stop_reason,
+            "usage": {"input_tokens": 100, "output_tokens": 20}
+        }),
+    )
+}
+
+#[tokio::test]
+async fn test_tool_use_round_trip() -> Result<()> {
+    let (stub, url) = start_stub(vec![
+        message(
+            json!([
+                {"type": "text", "text": "Transferring 0.1 SOL."},
+                {
+                    "type": "tool_use",
+                    "id": "toolu_01",
+                    "name": "sol_transfer",
+                    "input": {
+                        "user_pubkey": "USER_WALLET_PUBKEY",
+                        "recipient_pubkey": "RECIPIENT_WALLET_PUBKEY",
+                        "amount": 100000000,
+                        "operation": "sol"
+                    }
+                }
+            ]),
+            "tool_use",
+        ),
+        message(
+            json!([{"type": "text", "text": "Sent 0.1 SOL."}]),
+            "end_turn",
+        ),
+    ])
+    .await?;
+
+    let key_map = HashMap::from([
+        (
+            "USER_WALLET_PUBKEY".to_string(),
+            Pubkey::new_unique().to_string(),
+        ),
+        (
+            "RECIPIENT_WALLET_PUBKEY".to_string(),
+            Pubkey::new_unique().to_string(),
+        ),
+    ]);
+    let client = anthropic::Client::builder("test-key")
+        .base_url(&url)
+        .build();
+    let agent = client
+        .agent(anthropic::CLAUDE_SONNET_4_5)
+        .preamble("You are a Solana agent.")
+        .tool(SolTransferTool { key_map })
+        .build();
+
+    let response = agent.prompt("Send 0.1 SOL").multi_turn(3).await?;
+    assert_eq!(response, "Sent 0.1 SOL.");
+
+    let requests = stub.requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    let (headers, first) = &requests[0];
+    assert_eq!(headers["x-api-key"], "test-key");
+    assert_eq!(
+        headers["anthropic-version"],
+        anthropic::client::ANTHROPIC_VERSION
+    );
+    assert_eq!(first["model"], "claude-sonnet-4-5");
+    assert_eq!(first["system"], "You are a Solana agent.");
+    assert_eq!(first["max_tokens"], 4096);
+    assert_eq!(first["tools"][0]["name"], "sol_transfer");
+    assert_eq!(first["tools"][0]["input_schema"]["type"], "object");
+    assert_eq!(
+        first["messages"],
+        json!([{"role": "user", "content": [{"type": "text", "text": "Send 0.1 SOL"}]}])
+    );
+
+    // The tool ran and its result went back as a tool_result block
+    let (_, second) = &requests[1];
+    let messages = second["messages"].as_array().unwrap();
+    assert_eq!(messages.len(), 3);
+    assert_eq!(messages[1]["role"], "assistant");
+    assert_eq!(messages[1]["content"][1]["type"], "tool_use");
+    assert_eq!(messages[2]["role"], "user");
+    let result = &messages[2]["content"][0];
+    assert_eq!(result["type"], "tool_result");
+    assert_eq!(result["tool_use_id"], "toolu_01");
+    assert!(
+        result["content"]
+            .as_str()
+            .unwrap()
+            .contains("11111111111111111111111111111111"),
+        "expected a system program instruction, got {result}"
+    );
+    println!("✅ Tool use round trip through the Messages API");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_api_errors() -> Result<()> {
+    let (_stub, url) = start_stub(vec![
+        (
+            StatusCode::UNAUTHORIZED,
+            json!({
+                "type": "error",
+                "error": {"type": "authentication_error", "message": "invalid x-api-key"}
+            }),
+        ),
+        message(json!([]), "refusal"),
+    ])
+    .await?;
+
+    let client = anthropic::Client::builder("bad-key").base_url(&url).build();
+    let agent = client.agent(anthropic::CLAUDE_SONNET_4_5).build();
+
+    let err = agent.prompt("hello").await.unwrap_err().to_string();
+    assert!(
+        err.contains("401") && err.contains("authentication_error: invalid x-api-key"),
+        "{err}"
+    );
+
+    let err = agent.prompt("hello").await.unwrap_err().to_string();
+    assert!(err.contains("refusal"), "{err}");
+    println!("✅ API errors and refusals are reported");
+    Ok(())
+}
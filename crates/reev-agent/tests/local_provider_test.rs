@@ -0,0 +1,230 @@
+//! Local provider tests against stubs of the Ollama and llama.cpp servers
+//!
+//! The Ollama stub answers with a tool call written as text, the way many open
+//! models do, so the round trip checks that it is recovered, run with the
+//! reev-tools and answered with a `tool` message.
+
+use anyhow::Result;
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+    Json, Router,
+};
+use reev_agent::providers::local::{self, Backend};
+use reev_tools::tools::SolTransferTool;
+use rig::{agent::AgentBuilder, client::CompletionClient, completion::Prompt};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+#[derive(Clone, Default)]
+struct Stub {
+    /// Headers and body of each request received
+    requests: Arc<Mutex<Vec<(HeaderMap, Value)>>>,
+    /// Body of each response to send, in order
+    responses: Arc<Mutex<VecDeque<Value>>>,
+}
+
+async fn chat(
+    State(stub): State<Stub>,
+    headers: HeaderMap,
+    Json(body): Json<Value>,
+) -> (StatusCode, Json<Value>) {
+    stub.requests.lock().unwrap().push((headers, body));
+    let response = stub
+        .responses
+        .lock()
+        .unwrap()
+        .pop_front()
+        .expect("no response left in the stub");
+    (StatusCode::OK, Json(response))
+}
+
+/// Serve `responses` on the chat endpoint of `backend`, returning the stub and its
+/// base URL
+async fn start_stub(backend: Backend, responses: Vec<Value>) -> Result<(Stub, String)> {
+    let stub = Stub {
+        responses: Arc::new(Mutex::new(responses.into())),
+        ..Stub::default()
+    };
+    let app = Router::new()
+        .route(&format!("/{}", backend.chat_path()), post(chat))
+        .with_state(stub.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("http://{}", listener.local_addr()?);
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    Ok((stub, url))
+}
+
+fn ollama_message(content: &str) -> Value {
+    json!({
+        "model": "qwen3-coder:30b",
+        "message": {"role": "assistant", "content": content},
+        "done": true,
+        "done_reason": "stop",
+        "prompt_eval_count": 300,
+        "eval_count": 40
+    })
+}
+
+fn key_map() -> HashMap<String, String> {
+    HashMap::from([
+        (
+            "USER_WALLET_PUBKEY".to_string(),
+            Pubkey::new_unique().to_string(),
+        ),
+        (
+            "RECIPIENT_WALLET_PUBKEY".to_string(),
+            Pubkey::new_unique().to_string(),
+        ),
+    ])
+}
+
+#[tokio::test]
+async fn test_ollama_text_tool_call_round_trip() -> Result<()> {
+    let (stub, url) = start_stub(
+        Backend::Ollama,
+        vec![
+            ollama_message(
+                "<tool_call>\n{\"name\": \"sol_transfer\", \"arguments\": \
+                 {\"user_pubkey\": \"USER_WALLET_PUBKEY\", \
+                 \"recipient_pubkey\": \"RECIPIENT_WALLET_PUBKEY\", \
+                 \"amount\": 100000000, \"operation\": \"sol\"}}\n</tool_call>",
+            ),
+            ollama_message("Sent 0.1 SOL."),
+        ],
+    )
+    .await?;
+
+    let client = local::Client::builder(Backend::Ollama)
+        .base_url(&url)
+        .build();
+    let model = client
+        .completion_model("qwen3-coder:30b")
+        .with_context_size(32_768);
+    let agent = AgentBuilder::new(model)
+        .preamble("You are a Solana agent.")
+        .tool(SolTransferTool { key_map: key_map() })
+        .build();
+
+    let response = agent.prompt("Send 0.1 SOL").multi_turn(3).await?;
+    assert_eq!(response, "Sent 0.1 SOL.");
+
+    let requests = stub.requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    let (_, first) = &requests[0];
+    assert_eq!(first["model"], "qwen3-coder:30b");
+    assert_eq!(first["stream"], false);
+    assert_eq!(first["options"]["num_ctx"], 32_768);
+    let num_predict = first["options"]["num_predict"].as_u64().unwrap();
+    assert!(num_predict < 32_768, "{num_predict}");
+    assert_eq!(
+        first["messages"][0],
+        json!({"role": "system", "content": "You are a Solana agent."})
+    );
+    assert_eq!(first["tools"][0]["function"]["name"], "sol_transfer");
+
+    // The recovered call went back as a structured call and its result as a tool message
+    let (_, second) = &requests[1];
+    let messages = second["messages"].as_array().unwrap();
+    assert_eq!(messages.len(), 4);
+    assert_eq!(messages[2]["role"], "assistant");
+    assert_eq!(
+        messages[2]["tool_calls"][0]["function"]["arguments"]["amount"],
+        100_000_000
+    );
+    assert_eq!(messages[3]["role"], "tool");
+    assert_eq!(messages[3]["tool_name"], "sol_transfer");
+    assert!(
+        messages[3]["content"]
+            .as_str()
+            .unwrap()
+            .contains("11111111111111111111111111111111"),
+        "expected a system program instruction, got {}",
+        messages[3]
+    );
+    println!("✅ Tool call written as text is run through the reev-tools");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_llama_cpp_tool_calls_and_context_limit() -> Result<()> {
+    let (stub, url) = start_stub(
+        Backend::LlamaCpp,
+        vec![
+            json!({
+                "choices": [{
+                    "finish_reason": "tool_calls",
+                    "message": {
+                        "role": "assistant",
+                        "content": null,
+                        "tool_calls": [{
+                            "id": "call_01",
+                            "type": "function",
+                            "function": {
+                                "name": "sol_transfer",
+                                "arguments": "{\"user_pubkey\":\"USER_WALLET_PUBKEY\",\
+                                    \"recipient_pubkey\":\"RECIPIENT_WALLET_PUBKEY\",\
+                                    \"amount\":1000,\"operation\":\"sol\"}"
+                            }
+                        }]
+                    }
+                }],
+                "usage": {"prompt_tokens": 200, "completion_tokens": 30}
+            }),
+            json!({
+                "choices": [{
+                    "finish_reason": "stop",
+                    "message": {"role": "assistant", "content": "Done."}
+                }],
+                "usage": {"prompt_tokens": 300, "completion_tokens": 2}
+            }),
+        ],
+    )
+    .await?;
+
+    let client = local::Client::builder(Backend::LlamaCpp)
+        .base_url(&url)
+        .api_key("server-key")
+        .build();
+    let agent = |context_size: u32| {
+        AgentBuilder::new(
+            client
+                .completion_model("llama-cpp")
+                .with_context_size(context_size),
+        )
+        .preamble("You are a Solana agent.")
+        .tool(SolTransferTool { key_map: key_map() })
+        .build()
+    };
+
+    // A prompt larger than the context fails before reaching the server
+    let err = agent(64)
+        .prompt("Send 1000 lamports")
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("64-token context"), "{err}");
+    assert!(stub.requests.lock().unwrap().is_empty());
+
+    let response = agent(8192)
+        .prompt("Send 1000 lamports")
+        .multi_turn(3)
+        .await?;
+    assert_eq!(response, "Done.");
+
+    let requests = stub.requests.lock().unwrap();
+    let (headers, first) = &requests[0];
+    assert_eq!(headers["authorization"], "Bearer server-key");
+    assert!(first["max_tokens"].as_u64().unwrap() < 8192);
+    let (_, second) = &requests[1];
+    let result = &second["messages"][3];
+    assert_eq!(result["role"], "tool");
+    assert_eq!(result["tool_call_id"], "call_01");
+    println!("✅ llama.cpp tool calls run within the context size");
+    Ok(())
+}
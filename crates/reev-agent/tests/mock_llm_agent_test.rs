@@ -0,0 +1,387 @@
+//! Agent-loop tests against the scripted mock LLM server
+//!
+//! The `local` model is pointed at a `reev-mock-llm` server, so OpenAIAgent runs
+//! its real tool-calling path (and ZAIAgent, through `glm-4.6`, its own): the scripted tool call is executed by the
+//! reev-tools, the final answer goes through the agent's JSON extraction and the
+//! comprehensive response through `ResponseParser`, as the runner would parse it.
+
+use reev_agent::{
+    enhanced::{openai::OpenAIAgent, zai_agent::ZAIAgent},
+    LlmRequest,
+};
+use reev_config::{BudgetSettings, ReevConfig};
+use reev_lib::parsing::ResponseParser;
+use reev_mock_llm::{Conversation, MockLlmServer, Script, Turn};
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+use std::{collections::HashMap, sync::OnceLock};
+
+fn transfer_call() -> Turn {
+    Turn::tool_call(
+        "sol_transfer",
+        json!({
+            "user_pubkey": "USER_WALLET_PUBKEY",
+            "recipient_pubkey": "RECIPIENT_WALLET_PUBKEY",
+            "amount": 100000000,
+            "operation": "sol"
+        }),
+    )
+}
+
+/// A transfer of 0.1 SOL rather than lamports, to a recipient not in the key map
+fn bad_transfer_call() -> Turn {
+    Turn::tool_call(
+        "sol_transfer",
+        json!({
+            "user_pubkey": "USER_WALLET_PUBKEY",
+            "recipient_pubkey": "BOB_WALLET",
+            "amount": 0.1,
+            "operation": "sol"
+        }),
+    )
+}
+
+fn script() -> Script {
+    Script::new()
+        .conversation(
+            Conversation::new(
+                "transfer",
+                vec![
+                    transfer_call(),
+                    Turn::text(
+                        r#"The transfer is ready: {"transactions": {{tool_result}}, "summary": "Sent 0.1 SOL", "signatures": []}"#,
+                    ),
+                ],
+            )
+            .matching("[transfer]"),
+        )
+        .conversation(
+            Conversation::new(
+                "seeded",
+                vec![
+                    transfer_call(),
+                    Turn::text(
+                        r#"{"transactions": {{tool_result}}, "summary": "Sent 0.1 SOL", "signatures": []}"#,
+                    ),
+                ],
+            )
+            .matching("[seeded]"),
+        )
+        .conversation(
+            Conversation::new(
+                "trailing-commas",
+                vec![
+                    transfer_call(),
+                    Turn::text(
+                        r#"{"transactions": {{tool_result}}, "summary": "Sent 0.1 SOL", "signatures": [],}"#,
+                    ),
+                ],
+            )
+            .matching("[trailing-commas]"),
+        )
+        .conversation(
+            Conversation::new(
+                "self-correction",
+                vec![
+                    bad_transfer_call(),
+                    transfer_call(),
+                    Turn::text(
+                        r#"{"transactions": {{tool_result}}, "summary": "Sent 0.1 SOL", "signatures": []}"#,
+                    ),
+                ],
+            )
+            .matching("[self-correction]"),
+        )
+        .conversation(
+            Conversation::new(
+                "retries-exhausted",
+                vec![bad_transfer_call(), bad_transfer_call(), bad_transfer_call()],
+            )
+            .matching("[retries-exhausted]"),
+        )
+        .conversation(
+            Conversation::new(
+                "zai-self-correction",
+                vec![bad_transfer_call(), transfer_call()],
+            )
+            .matching("[zai-self-correction]"),
+        )
+        .conversation(
+            Conversation::new(
+                "zai-retries-exhausted",
+                vec![bad_transfer_call(), bad_transfer_call(), bad_transfer_call()],
+            )
+            .matching("[zai-retries-exhausted]"),
+        )
+        .conversation(
+            Conversation::new(
+                "looping",
+                vec![transfer_call(), transfer_call(), transfer_call()],
+            )
+            .matching("[looping]"),
+        )
+        .conversation(
+            Conversation::new(
+                "malformed-arguments",
+                vec![Turn::MalformedToolCall {
+                    name: "sol_transfer".to_string(),
+                    arguments: r#"{"user_pubkey": "USER_WALLET_PUBKEY", "amount": 1"#.to_string(),
+                }],
+            )
+            .matching("[malformed-arguments]"),
+        )
+        .conversation(
+            Conversation::new(
+                "refusal",
+                vec![Turn::Refusal {
+                    refusal: "I can't help with moving funds.".to_string(),
+                }],
+            )
+            .matching("[refusal]"),
+        )
+        .conversation(
+            Conversation::new(
+                "long-output",
+                vec![Turn::LongText {
+                    unit: "Still thinking about the transfer. ".to_string(),
+                    repeat: 10_000,
+                }],
+            )
+            .matching("[long-output]"),
+        )
+}
+
+/// The mock server, registered as the `local` and `glm-4.6` models of the process
+/// configuration
+fn server() -> &'static MockLlmServer {
+    static SERVER: OnceLock<MockLlmServer> = OnceLock::new();
+    SERVER.get_or_init(|| {
+        let server = MockLlmServer::spawn(script()).unwrap();
+        let mut config = ReevConfig::default();
+        config.models.get_mut("local").unwrap().base_url = Some(server.base_url());
+        config.models.get_mut("glm-4.6").unwrap().base_url = Some(server.base_url());
+        config.llm.zai_api_key = Some("mock-key".to_string());
+        assert!(reev_config::init(config), "configuration already installed");
+        server
+    })
+}
+
+fn key_map() -> HashMap<String, String> {
+    HashMap::from([
+        (
+            "USER_WALLET_PUBKEY".to_string(),
+            Pubkey::new_unique().to_string(),
+        ),
+        (
+            "RECIPIENT_WALLET_PUBKEY".to_string(),
+            Pubkey::new_unique().to_string(),
+        ),
+    ])
+}
+
+/// Run OpenAIAgent on the `local` model with a prompt selecting `conversation`
+async fn run_agent(conversation: &str) -> anyhow::Result<String> {
+    run_request(request(conversation)).await
+}
+
+/// A request whose prompt selects `conversation`
+fn request(conversation: &str) -> LlmRequest {
+    LlmRequest {
+        id: "001-sol-transfer".to_string(),
+        session_id: format!("mock-llm-{conversation}"),
+        prompt: format!("[{conversation}] Send 0.1 SOL to the recipient."),
+        context_prompt: String::new(),
+        model_name: "local".to_string(),
+        mock: false,
+        initial_state: None,
+        allowed_tools: None,
+        account_states: None,
+        key_map: Some(key_map()),
+        ground_truth: None,
+        budget: None,
+        seed: None,
+    }
+}
+
+async fn run_request(payload: LlmRequest) -> anyhow::Result<String> {
+    server();
+    let key_map = payload.key_map.clone().unwrap_or_default();
+    OpenAIAgent::run("local", payload, key_map).await
+}
+
+/// Run ZAIAgent on the `glm-4.6` model with a prompt selecting `conversation`
+async fn run_zai_agent(conversation: &str) -> anyhow::Result<String> {
+    server();
+    let payload = LlmRequest {
+        model_name: "glm-4.6".to_string(),
+        ..request(conversation)
+    };
+    let key_map = payload.key_map.clone().unwrap_or_default();
+    ZAIAgent::run("glm-4.6", payload, key_map).await
+}
+
+fn assert_transfer_parsed(response: &str) {
+    let parsed = ResponseParser::new(false).parse_with_fallback(response);
+    let transactions = parsed
+        .transactions
+        .unwrap_or_else(|| panic!("no transactions parsed from {response}"));
+    assert_eq!(transactions.len(), 1, "{response}");
+    assert_eq!(
+        transactions[0].program_id, "11111111111111111111111111111111",
+        "{response}"
+    );
+}
+
+#[tokio::test]
+async fn test_tool_call_to_transaction() {
+    let response = run_agent("transfer").await.unwrap();
+    assert_transfer_parsed(&response);
+    let usage = &serde_json::from_str::<serde_json::Value>(&response).unwrap()["usage"];
+    assert!(usage["prompt_tokens"].as_u64().unwrap() > 0, "{response}");
+    assert!(
+        usage["completion_tokens"].as_u64().unwrap() > 0,
+        "{response}"
+    );
+
+    let requests: Vec<_> = server()
+        .requests()
+        .into_iter()
+        .filter(|request| request.to_string().contains("[transfer]"))
+        .collect();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[0]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|tool| tool["function"]["name"] == "sol_transfer"));
+    println!("✅ Scripted tool call became a system transfer instruction, with its token usage");
+}
+
+#[tokio::test]
+async fn test_final_answer_json_fallback() {
+    let response = run_agent("trailing-commas").await.unwrap();
+    assert_transfer_parsed(&response);
+    println!("✅ Trailing commas in the final answer are cleaned up");
+}
+
+#[tokio::test]
+async fn test_rejected_arguments_are_corrected() {
+    let response = run_agent("self-correction").await.unwrap();
+    assert_transfer_parsed(&response);
+    let answer: serde_json::Value = serde_json::from_str(&response).unwrap();
+    assert_eq!(answer["argument_retries"], 1, "{response}");
+
+    let requests: Vec<_> = server()
+        .requests()
+        .into_iter()
+        .filter(|request| request.to_string().contains("[self-correction]"))
+        .collect();
+    assert_eq!(requests.len(), 3);
+    let rejection = requests[1]["messages"].to_string();
+    assert!(rejection.contains("invalid_arguments"), "{rejection}");
+    assert!(rejection.contains("100000000"), "{rejection}");
+    println!("✅ The model corrected its arguments after a structured rejection");
+}
+
+#[tokio::test]
+async fn test_rejected_arguments_are_bounded() {
+    let err = run_agent("retries-exhausted").await.unwrap_err();
+    assert!(err.to_string().contains("rejected 3 times"), "{err}");
+    println!("✅ Rejections beyond the retry budget fail the run: {err}");
+}
+
+#[tokio::test]
+async fn test_zai_rejected_arguments_are_corrected() {
+    let response = run_zai_agent("zai-self-correction").await.unwrap();
+    assert_transfer_parsed(&response);
+    let answer: serde_json::Value = serde_json::from_str(&response).unwrap();
+    assert_eq!(answer["argument_retries"], 1, "{response}");
+
+    let requests: Vec<_> = server()
+        .requests()
+        .into_iter()
+        .filter(|request| request.to_string().contains("[zai-self-correction]"))
+        .collect();
+    assert_eq!(requests.len(), 2);
+    let rejection = requests[1]["messages"].to_string();
+    assert!(rejection.contains("invalid_arguments"), "{rejection}");
+    assert!(rejection.contains("100000000"), "{rejection}");
+    println!("✅ ZAIAgent answered the rejected call and the model corrected it");
+}
+
+#[tokio::test]
+async fn test_zai_rejected_arguments_are_bounded() {
+    let err = run_zai_agent("zai-retries-exhausted").await.unwrap_err();
+    assert!(err.to_string().contains("rejected 3 times"), "{err}");
+    println!("✅ ZAIAgent fails the run beyond the retry budget: {err}");
+}
+
+#[tokio::test]
+async fn test_budget_stops_a_looping_model() {
+    let budget = BudgetSettings {
+        max_turns: Some(2),
+        ..Default::default()
+    };
+    let mut payload = request("looping");
+    payload.budget = Some(budget);
+    let response = run_request(payload).await.unwrap();
+
+    let answer: serde_json::Value = serde_json::from_str(&response).unwrap();
+    assert_eq!(answer["budget_exceeded"], "max_turns", "{response}");
+    assert_eq!(answer["budget_usage"]["tool_calls"], 2, "{response}");
+    let parsed = ResponseParser::new(false).parse_with_fallback(&response);
+    assert_eq!(parsed.transactions.unwrap().len(), 2, "{response}");
+
+    let requests = server()
+        .requests()
+        .into_iter()
+        .filter(|request| request.to_string().contains("[looping]"))
+        .count();
+    assert_eq!(requests, 2);
+    println!("✅ A looping model is stopped at its turn budget with what it built");
+}
+
+#[tokio::test]
+async fn test_seed_pins_sampling() {
+    let mut payload = request("seeded");
+    payload.seed = Some(42);
+    let response = run_request(payload).await.unwrap();
+    assert_transfer_parsed(&response);
+
+    let requests: Vec<_> = server()
+        .requests()
+        .into_iter()
+        .filter(|request| request.to_string().contains("[seeded]"))
+        .collect();
+    assert_eq!(requests.len(), 2);
+    for request in &requests {
+        assert_eq!(request["seed"], 42, "{request}");
+        assert_eq!(request["temperature"], 0.7, "{request}");
+    }
+    println!("✅ A seeded request pins the provider seed and temperature");
+}
+
+#[tokio::test]
+async fn test_malformed_tool_arguments_fail_the_run() {
+    let err = run_agent("malformed-arguments").await.unwrap_err();
+    assert!(err.to_string().contains("JsonError"), "{err}");
+    println!("✅ Tool arguments that are not JSON fail the run: {err}");
+}
+
+#[tokio::test]
+async fn test_refusal_fails_the_run() {
+    let err = run_agent("refusal").await.unwrap_err();
+    assert!(err.to_string().contains("no message or tool call"), "{err}");
+    println!("✅ A refusal fails the run: {err}");
+}
+
+#[tokio::test]
+async fn test_long_output_without_json() {
+    let response = run_agent("long-output").await.unwrap();
+    let parsed = ResponseParser::new(false).parse_with_fallback(&response);
+    assert!(parsed
+        .transactions
+        .as_ref()
+        .is_none_or(|transactions| transactions.is_empty()));
+    println!("✅ A long answer without JSON is parsed to no transactions");
+}
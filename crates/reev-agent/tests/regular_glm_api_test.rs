@@ -31,6 +31,9 @@ async fn test_regular_glm_api_support() -> Result<()> {
         allowed_tools: None,
         account_states: None,
         key_map: Some(key_map.clone()),
+        ground_truth: None,
+        budget: None,
+        seed: None,
     };
 
     println!("🚀 Testing regular GLM API with model: glm-4.6");
@@ -87,6 +90,9 @@ async fn test_api_priority_order() -> Result<()> {
         allowed_tools: None,
         account_states: None,
         key_map: Some(key_map.clone()),
+        ground_truth: None,
+        budget: None,
+        seed: None,
     };
     let result = OpenAIAgent::run("glm-4.6", payload, key_map).await;
 
@@ -143,6 +149,9 @@ async fn test_local_fallback() -> Result<()> {
         allowed_tools: None,
         account_states: None,
         key_map: Some(key_map.clone()),
+        ground_truth: None,
+        budget: None,
+        seed: None,
     };
     let result = OpenAIAgent::run("glm-4.6", payload, key_map).await;
 
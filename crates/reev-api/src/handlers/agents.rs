@@ -7,15 +7,9 @@ use axum::{
 };
 use tracing::{error, info};
 
-/// List all available agents
+/// List all available agents: the models of the registry in reev.toml
 pub async fn list_agents() -> Json<Vec<String>> {
-    let agents = vec![
-        "deterministic".to_string(),
-        "local".to_string(),
-        "gemini".to_string(),
-        "glm-4.6".to_string(),
-    ];
-    Json(agents)
+    Json(reev_config::get().models.keys().cloned().collect())
 }
 
 /// Get agent performance summary
@@ -161,6 +155,9 @@ pub async fn debug_insert_test_data(State(state): State<ApiState>) -> impl IntoR
         timestamp: chrono::Utc::now().to_rfc3339(),
         flow_log_id: None,
         prompt_md5: Some("".to_string()),
+        trial_index: 0,
+        run_id: None,
+        rescored: false,
         additional_metrics: std::collections::HashMap::new(),
     };
 
@@ -3,6 +3,7 @@
 use crate::services::*;
 use crate::types::{
     ApiState, BenchmarkExecutionRequest, ExecutionResponse, ExecutionState, ExecutionStatus,
+    SelectedExecution, SelectionExecutionRequest, SelectionExecutionResponse,
 };
 use axum::{
     extract::{Path, Query, State},
@@ -132,20 +133,7 @@ pub async fn run_benchmark(
     Json(request): Json<BenchmarkExecutionRequest>,
 ) -> impl IntoResponse {
     let execution_id = Uuid::new_v4().to_string();
-    let now = chrono::Utc::now();
-
-    let execution_state = ExecutionState {
-        id: execution_id.clone(),
-        benchmark_id: benchmark_id.clone(),
-        agent: request.agent.clone(),
-        status: ExecutionStatus::Pending,
-        progress: 0,
-        start_time: now,
-        end_time: None,
-        trace: String::new(),
-        logs: String::new(),
-        error: None,
-    };
+    let execution_state = pending_execution(&execution_id, &benchmark_id, &request.agent);
 
     // Store execution state
     {
@@ -164,6 +152,10 @@ pub async fn run_benchmark(
         benchmark_id, request.agent
     );
 
+    let run_id =
+        start_evaluation_run(&state, &request.agent, std::slice::from_ref(&benchmark_id)).await;
+    let response_run_id = run_id.clone();
+
     // Start the benchmark execution in background using blocking task for non-Send dependencies
     let state_clone = state.clone();
     let execution_id_clone = execution_id.clone();
@@ -176,12 +168,14 @@ pub async fn run_benchmark(
             let rt = tokio::runtime::Runtime::new().unwrap();
             rt.block_on(async move {
                 execute_benchmark_background(
-                    state_clone,
+                    state_clone.clone(),
                     execution_id_clone,
                     benchmark_id_clone,
                     agent,
+                    run_id.clone(),
                 )
                 .await;
+                finish_evaluation_run(&state_clone, run_id).await;
             })
         })
         .await
@@ -192,8 +186,122 @@ pub async fn run_benchmark(
 
     Json(ExecutionResponse {
         execution_id,
+        run_id: response_run_id,
+        status: "started".to_string(),
+    })
+}
+
+/// Run every benchmark matching a selection, one after another
+pub async fn run_benchmark_selection(
+    State(state): State<ApiState>,
+    Json(request): Json<SelectionExecutionRequest>,
+) -> impl IntoResponse {
+    let benchmarks_dir = match project_root::get_project_root() {
+        Ok(root) => root.join("benchmarks"),
+        Err(e) => {
+            error!("Failed to get project root: {}", e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to locate benchmarks",
+            )
+                .into_response();
+        }
+    };
+    let benchmark_ids: Vec<String> = match request.selection.resolve(&benchmarks_dir) {
+        Ok(benchmarks) => benchmarks.into_iter().map(|b| b.test_case.id).collect(),
+        Err(e) => {
+            error!("Failed to resolve benchmark selection: {:#}", e);
+            return (StatusCode::BAD_REQUEST, format!("Invalid selection: {e:#}")).into_response();
+        }
+    };
+
+    if let Some(config) = request.config {
+        let mut configs = state.agent_configs.lock().await;
+        configs.insert(request.agent.clone(), config);
+    }
+
+    let executions: Vec<SelectedExecution> = benchmark_ids
+        .into_iter()
+        .map(|benchmark_id| SelectedExecution {
+            benchmark_id,
+            execution_id: Uuid::new_v4().to_string(),
+        })
+        .collect();
+    {
+        let mut states = state.executions.lock().await;
+        for execution in &executions {
+            states.insert(
+                execution.execution_id.clone(),
+                pending_execution(
+                    &execution.execution_id,
+                    &execution.benchmark_id,
+                    &request.agent,
+                ),
+            );
+        }
+    }
+
+    info!(
+        "Starting {} selected benchmark(s) for agent: {}",
+        executions.len(),
+        request.agent
+    );
+
+    let benchmark_ids: Vec<String> = executions.iter().map(|e| e.benchmark_id.clone()).collect();
+    let run_id = start_evaluation_run(&state, &request.agent, &benchmark_ids).await;
+    let response_run_id = run_id.clone();
+
+    // Benchmarks share surfpool and reev-agent ports, so they run sequentially
+    let queue: Vec<(String, String)> = executions
+        .iter()
+        .map(|e| (e.execution_id.clone(), e.benchmark_id.clone()))
+        .collect();
+    let state_clone = state.clone();
+    let agent = request.agent.clone();
+    tokio::spawn(async move {
+        tokio::task::spawn_blocking(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            rt.block_on(async move {
+                for (execution_id, benchmark_id) in queue {
+                    execute_benchmark_background(
+                        state_clone.clone(),
+                        execution_id,
+                        benchmark_id,
+                        agent.clone(),
+                        run_id.clone(),
+                    )
+                    .await;
+                }
+                finish_evaluation_run(&state_clone, run_id).await;
+            })
+        })
+        .await
+        .unwrap_or_else(|e| {
+            error!("Selected benchmark execution task failed: {}", e);
+        });
+    });
+
+    Json(SelectionExecutionResponse {
+        executions,
+        run_id: response_run_id,
         status: "started".to_string(),
     })
+    .into_response()
+}
+
+fn pending_execution(execution_id: &str, benchmark_id: &str, agent: &str) -> ExecutionState {
+    ExecutionState {
+        id: execution_id.to_string(),
+        benchmark_id: benchmark_id.to_string(),
+        agent: agent.to_string(),
+        status: ExecutionStatus::Pending,
+        progress: 0,
+        start_time: chrono::Utc::now(),
+        end_time: None,
+        trace: String::new(),
+        logs: String::new(),
+        error: None,
+    }
 }
 
 /// Get execution status
@@ -0,0 +1,195 @@
+//! Evaluation run handlers
+use crate::types::*;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use reev_db::{trials::latest_trial_stats, QueryFilter, ScoreMatrix};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{error, info};
+
+/// Default number of runs returned by [`list_runs`]
+const DEFAULT_RUNS_LIMIT: u32 = 20;
+
+#[derive(Debug, Deserialize)]
+pub struct RunsQuery {
+    limit: Option<u32>,
+}
+
+/// List evaluation runs, newest first, with the outcome of their sessions
+pub async fn list_runs(
+    State(state): State<ApiState>,
+    Query(query): Query<RunsQuery>,
+) -> impl IntoResponse {
+    let limit = query.limit.unwrap_or(DEFAULT_RUNS_LIMIT);
+    info!("Listing up to {} evaluation runs", limit);
+
+    match state.db.list_runs(Some(limit)).await {
+        Ok(runs) => Json(runs).into_response(),
+        Err(e) => {
+            error!("Failed to list evaluation runs: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": "Failed to list evaluation runs",
+                    "details": e.to_string()
+                })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Get an evaluation run's provenance, sessions and the LLM cost of each benchmark
+pub async fn get_run(
+    State(state): State<ApiState>,
+    Path(run_id): Path<String>,
+) -> impl IntoResponse {
+    info!("Getting evaluation run: {}", run_id);
+
+    let run = match state.db.get_run(&run_id).await {
+        Ok(Some(run)) => run,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(json!({ "error": format!("Evaluation run '{run_id}' not found") })),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            error!("Failed to get evaluation run {}: {}", run_id, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": "Failed to get evaluation run",
+                    "details": e.to_string()
+                })),
+            )
+                .into_response();
+        }
+    };
+
+    let sessions = match state.db.list_run_sessions(&run_id).await {
+        Ok(sessions) => sessions,
+        Err(e) => {
+            error!("Failed to list sessions of run {}: {}", run_id, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": "Failed to list run sessions",
+                    "details": e.to_string()
+                })),
+            )
+                .into_response();
+        }
+    };
+
+    match state.db.get_run_benchmark_costs(&run_id).await {
+        Ok(costs) => {
+            Json(json!({ "run": run, "sessions": sessions, "costs": costs })).into_response()
+        }
+        Err(e) => {
+            error!("Failed to get LLM costs of run {}: {}", run_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": "Failed to get run costs",
+                    "details": e.to_string()
+                })),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MatrixQuery {
+    /// Comma-separated evaluation run ids, one column per run's agent
+    runs: Option<String>,
+    /// Comma-separated agents, one column per agent's latest results
+    agents: Option<String>,
+}
+
+fn split_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Get a benchmark × agent score matrix
+///
+/// With `runs`, the columns are the agents of those evaluation runs, as recorded
+/// by `run --agents`; with `agents`, their latest results; otherwise every agent's
+/// latest results.
+pub async fn get_matrix(
+    State(state): State<ApiState>,
+    Query(query): Query<MatrixQuery>,
+) -> impl IntoResponse {
+    info!("Getting score matrix: {:?}", query);
+
+    let mut agents = Vec::new();
+    let mut filters = Vec::new();
+    if let Some(runs) = &query.runs {
+        for run_id in split_list(runs) {
+            match state.db.get_run(&run_id).await {
+                Ok(Some(run)) => {
+                    agents.push(run.agent_type);
+                    filters.push(QueryFilter::new().run_id(&run_id));
+                }
+                Ok(None) => {
+                    return (
+                        StatusCode::NOT_FOUND,
+                        Json(json!({ "error": format!("Evaluation run '{run_id}' not found") })),
+                    )
+                        .into_response();
+                }
+                Err(e) => {
+                    error!("Failed to get evaluation run {}: {}", run_id, e);
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(json!({
+                            "error": "Failed to get evaluation run",
+                            "details": e.to_string()
+                        })),
+                    )
+                        .into_response();
+                }
+            }
+        }
+    } else if let Some(list) = &query.agents {
+        for agent in split_list(list) {
+            filters.push(QueryFilter::new().agent_type(&agent));
+            agents.push(agent);
+        }
+    } else {
+        filters.push(QueryFilter::new());
+    }
+
+    let mut stats = Vec::new();
+    for filter in filters {
+        match state.db.get_agent_performance(&filter).await {
+            Ok(records) => stats.extend(latest_trial_stats(records.iter().map(Into::into), None)),
+            Err(e) => {
+                error!("Failed to get agent performance: {}", e);
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({
+                        "error": "Failed to get agent performance",
+                        "details": e.to_string()
+                    })),
+                )
+                    .into_response();
+            }
+        }
+    }
+    if query.runs.is_none() && query.agents.is_none() {
+        agents = stats.iter().map(|s| s.agent_type.clone()).collect();
+        agents.dedup();
+    }
+
+    Json(ScoreMatrix::from_stats(&agents, &stats)).into_response()
+}
@@ -137,11 +137,15 @@ pub async fn get_transaction_logs(
                                 initial_state: vec![],
                                 prompt: trace.prompt.clone(),
                                 flow: None,
+                                faults: None,
+                                timeout: None,
+                                budget: None,
                                 ground_truth: reev_lib::benchmark::GroundTruth {
                                     transaction_status: "unknown".to_string(),
                                     final_state_assertions: vec![],
                                     expected_instructions: vec![],
                                     skip_instruction_validation: false,
+                                    efficiency: None,
                                 },
                             },
                             reev_lib::results::FinalStatus::Succeeded,
@@ -151,14 +155,8 @@ pub async fn get_transaction_logs(
 
                         // Use appropriate transaction log extraction
                         let logs = if format_param == "yaml" {
-                            match crate::services::generate_transaction_logs_yaml(
-                                &test_result
-                                    .trace
-                                    .steps
-                                    .iter()
-                                    .flat_map(|step| &step.observation.last_transaction_logs)
-                                    .cloned()
-                                    .collect::<Vec<_>>(),
+                            match crate::services::generate_trace_logs_yaml(
+                                &test_result.trace,
                                 show_cu,
                             ) {
                                 Ok(yaml_logs) => yaml_logs,
@@ -222,11 +220,15 @@ pub async fn get_transaction_logs(
                         initial_state: vec![],
                         prompt: trace.prompt.clone(),
                         flow: None,
+                        faults: None,
+                        timeout: None,
+                        budget: None,
                         ground_truth: reev_lib::benchmark::GroundTruth {
                             transaction_status: "unknown".to_string(),
                             final_state_assertions: vec![],
                             expected_instructions: vec![],
                             skip_instruction_validation: false,
+                            efficiency: None,
                         },
                     },
                     reev_lib::results::FinalStatus::Succeeded,
@@ -248,16 +250,7 @@ pub async fn get_transaction_logs(
 
                 // Use appropriate transaction log extraction
                 let transaction_logs = if use_yaml {
-                    match crate::services::generate_transaction_logs_yaml(
-                        &test_result
-                            .trace
-                            .steps
-                            .iter()
-                            .flat_map(|step| &step.observation.last_transaction_logs)
-                            .cloned()
-                            .collect::<Vec<_>>(),
-                        show_cu,
-                    ) {
+                    match crate::services::generate_trace_logs_yaml(&test_result.trace, show_cu) {
                         Ok(yaml_logs) => yaml_logs,
                         Err(e) => {
                             error!("Failed to generate YAML logs from execution: {}", e);
@@ -344,11 +337,15 @@ pub async fn get_transaction_logs(
                                         initial_state: vec![],
                                         prompt: trace.prompt.clone(),
                                         flow: None,
+                                        faults: None,
+                                        timeout: None,
+                                        budget: None,
                                         ground_truth: reev_lib::benchmark::GroundTruth {
                                             transaction_status: "unknown".to_string(),
                                             final_state_assertions: vec![],
                                             expected_instructions: vec![],
                                             skip_instruction_validation: false,
+                                            efficiency: None,
                                         },
                                     },
                                     reev_lib::results::FinalStatus::Succeeded,
@@ -370,16 +367,8 @@ pub async fn get_transaction_logs(
 
                                 // Use appropriate transaction log extraction
                                 let transaction_logs = if use_yaml {
-                                    match crate::services::generate_transaction_logs_yaml(
-                                        &test_result
-                                            .trace
-                                            .steps
-                                            .iter()
-                                            .flat_map(|step| {
-                                                &step.observation.last_transaction_logs
-                                            })
-                                            .cloned()
-                                            .collect::<Vec<_>>(),
+                                    match crate::services::generate_trace_logs_yaml(
+                                        &test_result.trace,
                                         show_cu,
                                     ) {
                                         Ok(yaml_logs) => yaml_logs,
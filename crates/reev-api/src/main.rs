@@ -31,6 +31,11 @@ async fn main() -> Result<()> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    // Load reev.toml with the selected profile and environment overrides
+    let config = reev_config::load()?;
+    let database = config.database.clone();
+    reev_config::init(config);
+
     // Clean up any existing API processes on the default port
     let default_port = std::env::var("PORT")
         .unwrap_or_else(|_| "3001".to_string())
@@ -40,12 +45,10 @@ async fn main() -> Result<()> {
     kill_existing_api(default_port).await?;
 
     // Initialize database
-    let db_path =
-        std::env::var("DATABASE_PATH").unwrap_or_else(|_| "db/reev_results.db".to_string());
-    info!("Connecting to database at: {}", db_path);
+    info!("Connecting to database at: {}", database.path);
 
-    let db_config = DatabaseConfig::new(&db_path);
-    let db = PooledDatabaseWriter::new(db_config, 10).await?;
+    let db_config = DatabaseConfig::from_settings(&database);
+    let db = PooledDatabaseWriter::new(db_config, database.max_connections as usize).await?;
     info!("Database connection pool established");
 
     // Sync benchmarks to database on startup
@@ -89,6 +92,7 @@ async fn main() -> Result<()> {
             get(debug_insert_test_data),
         )
         // Benchmark execution endpoints
+        .route("/api/v1/benchmarks/run", post(run_benchmark_selection))
         .route("/api/v1/benchmarks/{id}/run", post(run_benchmark))
         .route(
             "/api/v1/benchmarks/{id}/status/{execution_id}",
@@ -102,6 +106,10 @@ async fn main() -> Result<()> {
             "/api/v1/benchmarks/{id}/stop/{execution_id}",
             post(stop_benchmark),
         )
+        // Evaluation runs
+        .route("/api/v1/runs", get(list_runs))
+        .route("/api/v1/runs/{run_id}", get(get_run))
+        .route("/api/v1/matrix", get(get_matrix))
         // Agent configuration endpoints
         .route("/api/v1/agents/config", post(save_agent_config))
         .route("/api/v1/agents/config/{agent_type}", get(get_agent_config))
@@ -3,8 +3,11 @@ use anyhow::Result;
 use reev_flow::{
     EventContent, ExecutionResult, ExecutionStatistics, FlowEvent, FlowEventType, FlowLog,
 };
+use reev_lib::balance_delta::AccountBalanceDelta;
 use reev_lib::db::DatabaseWriter;
+use reev_lib::program_logs::{InvocationStatus, ProgramInvocation, ProgramLogTree};
 use reev_lib::results::TestResult;
+use reev_lib::trace::ExecutionTrace;
 
 use std::path::PathBuf;
 use std::time::SystemTime;
@@ -12,12 +15,66 @@ use text_trees::{FormatCharacters, TreeFormatting, TreeNode};
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
+/// Record a new evaluation run for benchmarks started through the API
+///
+/// Returns `None` if the run cannot be recorded; the runner then records each
+/// benchmark under a run of its own.
+pub async fn start_evaluation_run(
+    state: &ApiState,
+    agent: &str,
+    benchmark_ids: &[String],
+) -> Option<String> {
+    let paths: Vec<PathBuf> = benchmark_ids
+        .iter()
+        .filter_map(|id| find_benchmark_file(id))
+        .collect();
+    let run = match reev_runner::evaluation::new_evaluation_run(agent, &paths, 1, None) {
+        Ok(run) => run,
+        Err(e) => {
+            warn!("Failed to describe evaluation run: {:#}", e);
+            return None;
+        }
+    };
+    match state.db.create_run(&run).await {
+        Ok(()) => {
+            info!(
+                "Created evaluation run: {} for agent: {}",
+                run.run_id, agent
+            );
+            Some(run.run_id)
+        }
+        Err(e) => {
+            warn!("Failed to create evaluation run: {}", e);
+            None
+        }
+    }
+}
+
+/// Mark an evaluation run started by [`start_evaluation_run`] as completed
+pub async fn finish_evaluation_run(state: &ApiState, run_id: Option<String>) {
+    let Some(run_id) = run_id else {
+        return;
+    };
+    if let Err(e) = state
+        .db
+        .complete_run(
+            &run_id,
+            reev_runner::evaluation::RUN_COMPLETED,
+            chrono::Utc::now().timestamp(),
+        )
+        .await
+    {
+        warn!("Failed to complete evaluation run {}: {}", run_id, e);
+    }
+}
+
 /// Background task to execute benchmark
 pub async fn execute_benchmark_background(
     state: ApiState,
     execution_id: String,
     benchmark_id: String,
     agent: String,
+    run_id: Option<String>,
 ) {
     info!("=== STARTING BENCHMARK EXECUTION ===");
     info!("Execution ID: {}", execution_id);
@@ -35,6 +92,7 @@ pub async fn execute_benchmark_background(
         status: "running".to_string(),
         score: None,
         final_status: None,
+        run_id: run_id.clone(),
     };
 
     match state.db.create_session(&session_info).await {
@@ -123,26 +181,35 @@ pub async fn execute_benchmark_background(
     }
 
     info!("CALLING BENCHMARK RUNNER");
-    let execution_result =
-        match reev_runner::run_benchmarks(benchmark_path.clone(), &agent, false, false).await {
-            Ok(mut results) => {
-                info!("Benchmark runner returned {} results", results.len());
-                if let Some(result) = results.pop() {
-                    info!("Successfully got result from benchmark runner");
-                    Ok(result)
-                } else {
-                    error!("Benchmark runner returned no results");
-                    Err(anyhow::anyhow!("Benchmark runner returned no results"))
-                }
-            }
-            Err(e) => {
-                error!("BENCHMARK RUNNER FAILED");
-                error!("Detailed error: {:?}", e);
-                error!("Error source chain: {}", e);
-                error!("Error debug: {:?}", e);
-                Err(e)
+    let options = reev_runner::RunOptions {
+        run_id,
+        ..Default::default()
+    };
+    let execution_result = match reev_runner::run_benchmark_paths(
+        vec![benchmark_path.clone()],
+        &agent,
+        &options,
+    )
+    .await
+    {
+        Ok(mut results) => {
+            info!("Benchmark runner returned {} results", results.len());
+            if let Some(result) = results.pop() {
+                info!("Successfully got result from benchmark runner");
+                Ok(result)
+            } else {
+                error!("Benchmark runner returned no results");
+                Err(anyhow::anyhow!("Benchmark runner returned no results"))
             }
-        };
+        }
+        Err(e) => {
+            error!("BENCHMARK RUNNER FAILED");
+            error!("Detailed error: {:?}", e);
+            error!("Error source chain: {}", e);
+            error!("Error debug: {:?}", e);
+            Err(e)
+        }
+    };
 
     info!("BENCHMARK RUNNER CALL COMPLETED");
 
@@ -190,6 +257,8 @@ pub async fn execute_benchmark_background(
             let final_status = match test_result.final_status {
                 reev_lib::results::FinalStatus::Succeeded => "Succeeded",
                 reev_lib::results::FinalStatus::Failed => "Failed",
+                reev_lib::results::FinalStatus::TimedOut => "TimedOut",
+                reev_lib::results::FinalStatus::BudgetExceeded => "BudgetExceeded",
             };
 
             // Update database session with final status and full execution log
@@ -217,16 +286,26 @@ pub async fn execute_benchmark_background(
                 final_status: final_status.to_string(),
             };
 
-            if let Err(e) = state
-                .db
-                .update_session_status(
-                    &session_id,
-                    "completed",
-                    Some(final_status),
-                    test_result.score,
-                )
-                .await
-            {
+            let completion = match test_result.final_status {
+                reev_lib::results::FinalStatus::TimedOut => {
+                    state
+                        .db
+                        .time_out_session(&session_id, chrono::Utc::now().timestamp())
+                        .await
+                }
+                _ => {
+                    state
+                        .db
+                        .update_session_status(
+                            &session_id,
+                            "completed",
+                            Some(final_status),
+                            test_result.score,
+                        )
+                        .await
+                }
+            };
+            if let Err(e) = completion {
                 error!(
                     "Failed to complete database session {}: {:?}",
                     session_id, e
@@ -422,242 +501,61 @@ pub fn generate_transaction_logs(result: &TestResult) -> String {
     for (i, step) in result.trace.steps.iter().enumerate() {
         logs.push_str(&format!("Step {}:\n", i + 1));
 
-        for log in &step.observation.last_transaction_logs {
-            logs.push_str(&format!("  {log}\n"));
+        let log_tree = ProgramLogTree::parse(&step.observation.last_transaction_logs);
+        for line in log_tree.render_text(true).lines() {
+            logs.push_str(&format!("  {line}\n"));
         }
 
         if let Some(error) = &step.observation.last_transaction_error {
             logs.push_str(&format!("  Error: {error}\n"));
         }
 
-        logs.push('\n');
-    }
-
-    logs
-}
-
-/// Generate beautiful ASCII tree visualization of transaction logs
-/// Parsed transaction log entry
-#[derive(Debug, Clone)]
-struct LogEntry {
-    level: usize,
-    program_id: Option<String>,
-    program_name: Option<String>,
-    instruction: Option<String>,
-    log_message: Option<String>,
-    compute_units: Option<u64>,
-    is_instruction: bool,
-    is_success: bool,
-    is_last_child: bool,
-    return_data: Option<String>,
-}
-
-/// Parse raw transaction log lines into structured entries
-fn parse_transaction_logs(logs: &[String]) -> Vec<LogEntry> {
-    let mut entries = Vec::new();
-    let mut program_stack: Vec<(String, usize)> = Vec::new();
-
-    // Pre-compile regex patterns for performance
-    let program_invoke_regex =
-        regex::Regex::new(r"Program ([a-zA-Z0-9]+) invoke \[(\d+)\]").unwrap();
-    let compute_units_regex = regex::Regex::new(r"consumed (\d+) of (\d+) compute units").unwrap();
-    let program_return_regex = regex::Regex::new(r"Program return: ([a-zA-Z0-9]+) (.+)").unwrap();
-
-    for log_line in logs {
-        let trimmed = log_line.trim();
-
-        // Skip empty lines
-        if trimmed.is_empty() {
-            continue;
-        }
-
-        // Parse program invocation
-        if let Some(caps) = program_invoke_regex.captures(trimmed) {
-            let program_id = caps[1].to_string();
-            let level = caps[2].parse::<usize>().unwrap_or(0);
-
-            // Update stack for nesting
-            while program_stack.len() > level {
-                program_stack.pop();
-            }
-            program_stack.push((program_id.clone(), entries.len()));
-
-            entries.push(LogEntry {
-                level,
-                program_id: Some(program_id.clone()),
-                program_name: get_program_name(&program_id),
-                instruction: None,
-                log_message: None,
-                compute_units: None,
-                is_instruction: false,
-                is_success: false,
-                is_last_child: false,
-                return_data: None,
-            });
-            continue;
-        }
-
-        // Parse program success - attach to the most recent program invocation of the same ID
-        if trimmed.contains("Program") && trimmed.contains("success") {
-            if let Some(program_id) = extract_program_id_from_success(trimmed) {
-                // Check if this program already has a success entry to avoid duplicates
-                let has_success = entries.iter().any(|entry| {
-                    entry.program_id.as_ref() == Some(&program_id) && entry.is_success
-                });
-
-                if !has_success {
-                    // Find the most recent program invocation with this ID
-                    if let Some(program_level) = entries
-                        .iter()
-                        .rev()
-                        .find(|entry| {
-                            entry.program_id.as_ref() == Some(&program_id) && !entry.is_success
-                        })
-                        .map(|entry| entry.level)
-                    {
-                        // Add success as a child of this program
-                        entries.push(LogEntry {
-                            level: program_level + 1, // Child level
-                            program_id: Some(program_id.clone()),
-                            program_name: get_program_name(&program_id),
-                            instruction: None,
-                            log_message: None,
-                            compute_units: None,
-                            is_instruction: false,
-                            is_success: true,
-                            is_last_child: false,
-                            return_data: None,
-                        });
-                    }
-                }
-            }
-            continue;
-        }
-
-        // Parse compute units - attach to the most recent program or success entry
-        if let Some(caps) = compute_units_regex.captures(trimmed) {
-            let compute_units = caps[1].parse::<u64>().unwrap_or(0);
-            // Find the most recent entry that doesn't have compute units yet
-            for entry in entries.iter_mut().rev() {
-                if entry.compute_units.is_none() {
-                    entry.compute_units = Some(compute_units);
-                    break;
-                }
-            }
-            continue;
-        }
-
-        // Parse instruction log
-        if trimmed.contains("Program log: Instruction:") {
-            let instruction = trimmed
-                .replace("Program log: Instruction:", "")
-                .trim()
-                .to_string();
-            if let Some(entry) = entries.last_mut() {
-                entry.instruction = Some(instruction);
-                entry.is_instruction = true;
-            }
-            continue;
-        }
-
-        // Parse program log
-        if trimmed.contains("Program log:") && !trimmed.contains("Instruction:") {
-            let log_msg = trimmed.replace("Program log:", "").trim().to_string();
-            if let Some(entry) = entries.last_mut() {
-                entry.log_message = Some(log_msg);
-            }
-            continue;
-        }
-
-        // Parse program return
-        if trimmed.contains("Program return:") {
-            if let Some(caps) = program_return_regex.captures(trimmed) {
-                let _program_id = caps[1].to_string();
-                let return_data = caps[2].to_string();
-                if let Some(entry) = entries.last_mut() {
-                    entry.return_data = Some(return_data);
-                }
+        let changed: Vec<AccountBalanceDelta> = step
+            .observation
+            .balance_deltas
+            .iter()
+            .filter(|d| d.has_change())
+            .cloned()
+            .collect();
+        if !changed.is_empty() {
+            logs.push_str("  Balance changes:\n");
+            for line in reev_lib::balance_delta::format_balance_delta_table(&changed).lines() {
+                logs.push_str(&format!("    {line}\n"));
             }
-            continue;
         }
-    }
-
-    // Mark last children for proper tree rendering
-    mark_last_children(&mut entries);
-    entries
-}
 
-/// Get human-readable program name from program ID
-fn get_program_name(program_id: &str) -> Option<String> {
-    match program_id {
-        "11111111111111111111111111111111" => Some("System".to_string()),
-        "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA" => Some("SPL Token".to_string()),
-        "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL" => Some("Associated Token".to_string()),
-        "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4" => Some("Jupiter Router".to_string()),
-        "TessVdML9pBGgG9yGks7o4HewRaXVAMuoVj4x83GLQH" => Some("Tessellate".to_string()),
-        "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM" => Some("Serum DEX".to_string()),
-        "9H6tua7jkLhdm3w8BvgpTn5LZNU7g4ZynDmCiNN3q6Rp" => Some("Raydium DEX".to_string()),
-        "SysvarRent111111111111111111111111111111111" => Some("Sysvar Rent".to_string()),
-        "SysvarC1ock11111111111111111111111111111111" => Some("Sysvar Clock".to_string()),
-        _ => Some(format!("Program {}", &program_id[..8])), // Show first 8 chars for unknown programs
+        logs.push('\n');
     }
-}
-
-/// Extract program ID from success message
-fn extract_program_id_from_success(message: &str) -> Option<String> {
-    regex::Regex::new(r"Program ([a-zA-Z0-9]+) success")
-        .unwrap()
-        .captures(message)
-        .map(|caps| caps[1].to_string())
-}
-
-/// Mark which entries are the last child of their parent and track vertical connectors
-fn mark_last_children(entries: &mut [LogEntry]) {
-    let mut i = 0;
-    while i < entries.len() {
-        let current_level = entries[i].level;
-        let mut j = i + 1;
-
-        // Find next entry at same or higher level
-        while j < entries.len() && entries[j].level > current_level {
-            j += 1;
-        }
 
-        // If no next entry at same level, this is last child
-        if j >= entries.len() || entries[j].level < current_level {
-            entries[i].is_last_child = true;
-        }
-
-        i += 1;
-    }
+    logs
 }
 
 /// Get appropriate icon for program type
-fn get_program_icon(program_id: &Option<String>) -> &'static str {
-    match program_id.as_deref() {
-        Some("11111111111111111111111111111111") => "🔹",
-        Some("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA") => "🪙",
-        Some("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL") => "🏦",
-        Some("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4") => "🚀",
-        Some("TessVdML9pBGgG9yGks7o4HewRaXVAMuoVj4x83GLQH") => "🔸",
-        Some("9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM") => "📈",
-        Some("9H6tua7jkLhdm3w8BvgpTn5LZNU7g4ZynDmCiNN3q6Rp") => "🦐",
-        Some(_) => "📄",
-        None => "❓",
+fn get_program_icon(program_id: &str) -> &'static str {
+    match program_id {
+        "11111111111111111111111111111111" => "🔹",
+        "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA" => "🪙",
+        "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL" => "🏦",
+        "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4" => "🚀",
+        "TessVdML9pBGgG9yGks7o4HewRaXVAMuoVj4x83GLQH" => "🔸",
+        "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM" => "📈",
+        "9H6tua7jkLhdm3w8BvgpTn5LZNU7g4ZynDmCiNN3q6Rp" => "🦐",
+        _ => "📄",
     }
 }
 
 /// Generate transaction logs as tree structure using text_trees
 pub fn generate_transaction_logs_yaml(logs: &[String], show_cu: bool) -> Result<String> {
-    let parsed_logs = parse_transaction_logs(logs);
+    let log_tree = ProgramLogTree::parse(logs);
 
     // Create the root tree node
-    let root_node = create_tree_from_logs(&parsed_logs, show_cu)?;
+    let root_node = create_tree_from_logs(&log_tree, show_cu);
 
     // Format and return the ASCII tree
-    match root_node.to_string_with_format(&TreeFormatting::dir_tree(FormatCharacters::box_chars()))
+    let mut output = match root_node
+        .to_string_with_format(&TreeFormatting::dir_tree(FormatCharacters::box_chars()))
     {
-        Ok(tree_str) => Ok(tree_str),
+        Ok(tree_str) => tree_str,
         Err(e) => {
             warn!(
                 "text_trees formatting failed, falling back to plain format: {}",
@@ -669,122 +567,183 @@ pub fn generate_transaction_logs_yaml(logs: &[String], show_cu: bool) -> Result<
             for (i, log) in logs.iter().enumerate() {
                 plain.push_str(&format!("{}: {}\n", i + 1, log));
             }
-            Ok(plain)
+            return Ok(plain);
+        }
+    };
+
+    if show_cu {
+        if let Some(cu_tree) = create_compute_units_tree(&log_tree) {
+            if let Ok(tree_str) = cu_tree
+                .to_string_with_format(&TreeFormatting::dir_tree(FormatCharacters::box_chars()))
+            {
+                output.push('\n');
+                output.push_str(&tree_str);
+            }
         }
     }
+
+    Ok(output)
 }
 
-/// Create a TreeNode from parsed transaction logs
-fn create_tree_from_logs(parsed_logs: &[LogEntry], show_cu: bool) -> Result<TreeNode<String>> {
-    let mut children = Vec::new();
+/// Generate the transaction log tree for a whole trace, followed by the
+/// per-step balance changes of every account the transactions referenced
+pub fn generate_trace_logs_yaml(trace: &ExecutionTrace, show_cu: bool) -> Result<String> {
+    let logs: Vec<String> = trace
+        .steps
+        .iter()
+        .flat_map(|step| &step.observation.last_transaction_logs)
+        .cloned()
+        .collect();
+    let mut output = generate_transaction_logs_yaml(&logs, show_cu)?;
+
+    if let Some(balance_tree) = create_balance_deltas_tree(trace) {
+        match balance_tree
+            .to_string_with_format(&TreeFormatting::dir_tree(FormatCharacters::box_chars()))
+        {
+            Ok(tree_str) => {
+                output.push('\n');
+                output.push_str(&tree_str);
+            }
+            Err(e) => warn!("text_trees formatting failed for balance changes: {}", e),
+        }
+    }
 
-    // Create a hierarchical tree based on actual transaction flow
-    let mut i = 0;
-    while i < parsed_logs.len() {
-        let entry = &parsed_logs[i];
+    Ok(output)
+}
 
-        // Create node for current entry
-        let entry_node = create_entry_node(entry, false, show_cu)?;
+/// Create a TreeNode listing the accounts whose balances changed in each step
+fn create_balance_deltas_tree(trace: &ExecutionTrace) -> Option<TreeNode<String>> {
+    let multi_step = trace.steps.len() > 1;
+    let mut children = Vec::new();
 
-        // Find and add children (entries with higher level that come immediately after)
-        let mut child_nodes = Vec::new();
-        let mut j = i + 1;
+    for (i, step) in trace.steps.iter().enumerate() {
+        let account_nodes: Vec<TreeNode<String>> = step
+            .observation
+            .balance_deltas
+            .iter()
+            .filter(|d| d.has_change())
+            .map(|delta| {
+                let mut lines = vec![format!("◎ {}", delta.format_sol_change())];
+                if let Some(token_change) = delta.format_token_change() {
+                    lines.push(format!("🪙 {token_change}"));
+                }
+                TreeNode::with_child_nodes(
+                    format!("👤 {}", delta.label()),
+                    lines.into_iter().map(TreeNode::new),
+                )
+            })
+            .collect();
 
-        while j < parsed_logs.len() && parsed_logs[j].level > entry.level {
-            let child_entry = &parsed_logs[j];
-            let child_node = create_entry_node(child_entry, false, show_cu)?;
-            child_nodes.push(child_node);
-            j += 1;
+        if account_nodes.is_empty() {
+            continue;
         }
-
-        // If we found children, create a parent node with children
-        if !child_nodes.is_empty() {
-            let mut all_children = Vec::new();
-            all_children.push(entry_node);
-            all_children.extend(child_nodes);
-
-            // Create a combined node showing the program and its operations
-            let program_name = entry
-                .program_name
-                .as_deref()
-                .or_else(|| entry.program_id.as_ref().map(|id| &id[..8]))
-                .unwrap_or("Unknown");
-
-            let icon = get_program_icon(&entry.program_id);
-            let header = format!(
-                "{} {} ({})",
-                icon,
-                program_name,
-                entry.program_id.as_deref().unwrap_or("unknown")
-            );
-
-            children.push(TreeNode::with_child_nodes(header, all_children.into_iter()));
-            i = j; // Skip the children we processed
+        if multi_step {
+            children.push(TreeNode::with_child_nodes(
+                format!("Step {}", i + 1),
+                account_nodes.into_iter(),
+            ));
         } else {
-            children.push(entry_node);
-            i += 1;
+            children.extend(account_nodes);
         }
     }
 
-    Ok(TreeNode::with_child_nodes(
-        "🔄 TRANSACTION LOGS".to_string(),
+    if children.is_empty() {
+        return None;
+    }
+    Some(TreeNode::with_child_nodes(
+        "💰 BALANCE CHANGES".to_string(),
         children.into_iter(),
     ))
 }
 
-/// Create a tree node for a log entry
-fn create_entry_node(entry: &LogEntry, _is_last: bool, show_cu: bool) -> Result<TreeNode<String>> {
-    let program_display = entry
-        .program_name
-        .clone()
-        .or_else(|| entry.program_id.as_ref().map(|id| format!("Program {id}")))
-        .unwrap_or_else(|| "Unknown Program".to_string());
+/// Create a TreeNode from parsed transaction logs
+fn create_tree_from_logs(log_tree: &ProgramLogTree, show_cu: bool) -> TreeNode<String> {
+    let mut children: Vec<TreeNode<String>> = log_tree
+        .invocations
+        .iter()
+        .map(|invocation| create_invocation_node(invocation, show_cu))
+        .collect();
+    children.extend(
+        log_tree
+            .unparsed
+            .iter()
+            .map(|line| TreeNode::new(format!("📝 {line}"))),
+    );
 
-    let icon = get_program_icon(&entry.program_id);
+    TreeNode::with_child_nodes("🔄 TRANSACTION LOGS".to_string(), children.into_iter())
+}
 
-    // Create main content with status and compute units (if enabled)
-    let main_content = if entry.is_success {
-        if show_cu && entry.compute_units.is_some() {
-            format!("✅ {program_display} ({} CU)", entry.compute_units.unwrap())
-        } else {
-            format!("✅ {program_display}")
-        }
-    } else {
-        format!(
-            "{} {} ({})",
-            icon,
-            program_display,
-            entry.program_id.as_deref().unwrap_or("unknown")
-        )
-    };
+/// Create a tree node for a program invocation and the CPIs it made
+fn create_invocation_node(invocation: &ProgramInvocation, show_cu: bool) -> TreeNode<String> {
+    let header = format!(
+        "{} {} ({})",
+        get_program_icon(&invocation.program_id),
+        invocation.program_name(),
+        invocation.program_id
+    );
 
     let mut children = Vec::new();
 
     // Add details as children
-    if let Some(instruction) = &entry.instruction {
+    if let Some(instruction) = &invocation.instruction {
         children.push(TreeNode::new(format!("📋 {instruction}")));
     }
-    if let Some(log_msg) = &entry.log_message {
+    for log_msg in &invocation.logs {
         children.push(TreeNode::new(format!("📝 {log_msg}")));
     }
-    if let Some(return_data) = &entry.return_data {
+    for data in &invocation.data_events {
+        children.push(TreeNode::new(format!("📡 {data}")));
+    }
+    for child in &invocation.children {
+        children.push(create_invocation_node(child, show_cu));
+    }
+    if let Some(return_data) = &invocation.return_data {
         children.push(TreeNode::new(format!("💾 {return_data}")));
     }
-    if show_cu && !entry.is_success && entry.compute_units.is_some() {
-        children.push(TreeNode::new(format!(
-            "⚡ {} CU",
-            entry.compute_units.unwrap()
-        )));
+
+    let cu = match (show_cu, invocation.compute_units) {
+        (true, Some(cu)) => format!(" ({cu} CU)"),
+        _ => String::new(),
+    };
+    let status = match &invocation.status {
+        InvocationStatus::Success => format!("✅ {}{cu}", invocation.program_name()),
+        InvocationStatus::Failed { error } => {
+            format!("❌ {}{cu}: {error}", invocation.program_name())
+        }
+        InvocationStatus::Incomplete => {
+            format!("⏳ {} did not complete{cu}", invocation.program_name())
+        }
+    };
+    children.push(TreeNode::new(status));
+
+    TreeNode::with_child_nodes(header, children.into_iter())
+}
+
+/// Create a TreeNode with the compute units spent in each program
+fn create_compute_units_tree(log_tree: &ProgramLogTree) -> Option<TreeNode<String>> {
+    let breakdown = log_tree.compute_units_by_program();
+    if breakdown.iter().all(|program| program.compute_units == 0) {
+        return None;
     }
 
-    if children.is_empty() {
-        Ok(TreeNode::new(main_content))
-    } else {
-        Ok(TreeNode::with_child_nodes(
-            main_content,
-            children.into_iter(),
+    let children = breakdown.into_iter().map(|program| {
+        TreeNode::new(format!(
+            "{} {}: {} CU ({} call{})",
+            get_program_icon(&program.program_id),
+            program.program_name,
+            program.compute_units,
+            program.invocations,
+            if program.invocations == 1 { "" } else { "s" }
         ))
-    }
+    });
+
+    Some(TreeNode::with_child_nodes(
+        format!(
+            "⚡ COMPUTE UNITS BY PROGRAM (total {} CU)",
+            log_tree.total_compute_units()
+        ),
+        children,
+    ))
 }
 
 /// Update execution as failed
@@ -861,6 +820,9 @@ pub async fn update_execution_failed(
             timestamp: chrono::Utc::now().to_rfc3339(),
             flow_log_id: None,
             prompt_md5: None,
+            trial_index: 0,
+            run_id: session_info.run_id.clone(),
+            rescored: false,
             additional_metrics: std::collections::HashMap::new(),
         };
 
@@ -978,6 +940,7 @@ pub async fn store_flow_log_from_result(
         },
         score: Some(test_result.score),
         final_status: Some(format!("{:?}", test_result.final_status)),
+        run_id: test_result.run_id.clone(),
     };
 
     // Create and complete session
@@ -1078,6 +1041,7 @@ pub async fn store_flow_log(
                 "Failed".to_string()
             },
         ),
+        run_id: None,
     };
 
     // Store session and log
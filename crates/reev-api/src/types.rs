@@ -61,6 +61,34 @@ pub struct BenchmarkExecutionRequest {
 #[derive(Debug, Serialize)]
 pub struct ExecutionResponse {
     pub execution_id: String,
+    /// Evaluation run grouping the execution, when it could be recorded
+    pub run_id: Option<String>,
+    pub status: String,
+}
+
+/// Request to run every benchmark matching a selection
+#[derive(Debug, Deserialize)]
+pub struct SelectionExecutionRequest {
+    pub agent: String,
+    pub config: Option<AgentConfig>,
+    /// Filters, tags (`!tag` excludes), id globs and/or a suite name
+    #[serde(default)]
+    pub selection: reev_runner::selection::BenchmarkSelection,
+}
+
+/// A benchmark execution started for a selection
+#[derive(Debug, Serialize)]
+pub struct SelectedExecution {
+    pub benchmark_id: String,
+    pub execution_id: String,
+}
+
+/// Selection execution response
+#[derive(Debug, Serialize)]
+pub struct SelectionExecutionResponse {
+    pub executions: Vec<SelectedExecution>,
+    /// Evaluation run grouping the executions, when it could be recorded
+    pub run_id: Option<String>,
     pub status: String,
 }
 
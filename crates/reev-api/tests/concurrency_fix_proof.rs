@@ -35,6 +35,7 @@ async fn test_mutex_fix_proves_concurrent_access_works() -> Result<()> {
                 status: "running".to_string(),
                 score: None,
                 final_status: None,
+                run_id: None,
             })
             .await?;
     }
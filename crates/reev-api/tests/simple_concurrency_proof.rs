@@ -97,6 +97,7 @@ async fn test_mutex_prevents_data_races() -> Result<()> {
                 status: "running".to_string(),
                 score: None,
                 final_status: None,
+                run_id: None,
             })
             .await?;
     }
@@ -0,0 +1,422 @@
+//! # Reev Configuration
+//!
+//! One typed configuration for the runner, reev-agent, the API server and the
+//! libraries they share, resolved in three layers:
+//!
+//! 1. The built-in defaults of [`ReevConfig`]
+//! 2. `reev.toml` (or the file named by `REEV_CONFIG`), with the table of the
+//!    selected profile from `[profiles.<name>]` merged over the base values
+//! 3. Environment variables listed in [`ENV_OVERRIDES`]
+//!
+//! The profile is chosen with `REEV_PROFILE` or the runner's `--profile` flag.
+//! The `[models]` table is the [model registry](registry) every agent name is
+//! looked up in.
+//!
+//! ```toml
+//! [database]
+//! path = "db/reev_results.db"
+//!
+//! [profiles.ci.runner]
+//! auto_start = false
+//! ```
+//!
+//! Services call [`init`] with the result of [`load`] at startup so a broken file
+//! fails loudly; everything else reads the configuration with [`get`].
+
+pub mod registry;
+pub mod settings;
+
+pub use registry::{canonical_model_name, ModelEntry, ModelPrice, ProviderKind};
+pub use settings::{
+    AgentSettings, BudgetSettings, BudgetSpent, CassetteMode, CassetteSettings, DatabaseSettings,
+    JupiterSettings, LlmSettings, ReevConfig, RunnerSettings, SurfpoolSettings,
+};
+
+use std::{
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+use thiserror::Error;
+use toml::Value;
+use tracing::warn;
+
+/// Configuration file read from the working directory
+pub const CONFIG_FILE: &str = "reev.toml";
+/// Environment variable naming another configuration file
+pub const CONFIG_PATH_VAR: &str = "REEV_CONFIG";
+/// Environment variable selecting a profile
+pub const PROFILE_VAR: &str = "REEV_PROFILE";
+
+/// Environment variables overriding the configuration, with the path of the key each sets
+pub const ENV_OVERRIDES: &[(&str, &[&str])] = &[
+    ("LLM_API_URL", &["llm", "api_url"]),
+    ("LLM_API_KEY", &["llm", "api_key"]),
+    ("LLM_TEMPERATURE", &["llm", "temperature"]),
+    ("ZAI_API_KEY", &["llm", "zai_api_key"]),
+    ("ZAI_API_URL", &["models", "glm-4.6", "base_url"]),
+    ("GLM_CODING_API_KEY", &["llm", "glm_coding_api_key"]),
+    (
+        "GLM_CODING_API_URL",
+        &["models", "glm-4.6-coding", "base_url"],
+    ),
+    ("OPENAI_API_KEY", &["llm", "openai_api_key"]),
+    ("GEMINI_API_KEY", &["llm", "gemini_api_key"]),
+    ("ANTHROPIC_API_KEY", &["llm", "anthropic_api_key"]),
+    ("LOCAL_MODEL_URL", &["models", "local", "base_url"]),
+    ("LOCAL_MODEL_NAME", &["models", "local", "model"]),
+    ("OLLAMA_URL", &["models", "ollama", "base_url"]),
+    ("OLLAMA_MODEL", &["models", "ollama", "model"]),
+    ("LLAMA_CPP_URL", &["models", "llama-cpp", "base_url"]),
+    ("REEV_AGENT_HOST", &["agent", "host"]),
+    ("REEV_AGENT_PORT", &["agent", "port"]),
+    ("REEV_ENHANCED_OTEL", &["agent", "enhanced_otel"]),
+    ("REEV_MAX_TOOL_RETRIES", &["agent", "max_tool_retries"]),
+    ("SURFPOOL_HOST", &["surfpool", "host"]),
+    ("SURFPOOL_RPC_PORT", &["surfpool", "rpc_port"]),
+    ("REEV_FAULT_PROXY_PORT", &["surfpool", "fault_proxy_port"]),
+    ("REEV_AUTO_START", &["runner", "auto_start"]),
+    ("REEV_VERBOSE_LOGGING", &["runner", "verbose_logging"]),
+    ("REEV_PULL_MODELS", &["runner", "pull_models"]),
+    ("REEV_CACHE_DIR", &["runner", "cache_dir"]),
+    ("REEV_LOG_DIR", &["runner", "log_dir"]),
+    ("REEV_SESSION_LOG_PATH", &["runner", "session_log_dir"]),
+    ("REEV_FLOW_LOG_PATH", &["runner", "flow_log_dir"]),
+    ("DATABASE_PATH", &["database", "path"]),
+    ("DB_ENABLE_POOLING", &["database", "enable_pooling"]),
+    ("DB_MAX_CONNECTIONS", &["database", "max_connections"]),
+    ("JUPITER_API_BASE_URL", &["jupiter", "api_base_url"]),
+    ("JUPITER_TIMEOUT_SECONDS", &["jupiter", "timeout_seconds"]),
+    ("JUPITER_MAX_RETRIES", &["jupiter", "max_retries"]),
+    ("JUPITER_USER_AGENT", &["jupiter", "user_agent"]),
+    (
+        "JUPITER_DEFAULT_SLIPPAGE_BPS",
+        &["jupiter", "default_slippage_bps"],
+    ),
+    ("JUPITER_MAX_SLIPPAGE_BPS", &["jupiter", "max_slippage_bps"]),
+    ("JUPITER_DEBUG", &["jupiter", "debug"]),
+    ("JUPITER_SURFPOOL_RPC_URL", &["jupiter", "surfpool_rpc_url"]),
+    ("REEV_CASSETTE_MODE", &["cassette", "mode"]),
+    ("REEV_CASSETTE_DIR", &["cassette", "dir"]),
+    ("REEV_CASSETTE_SEED", &["cassette", "seed"]),
+    ("REEV_MAX_TURNS", &["budget", "max_turns"]),
+    ("REEV_MAX_TOOL_CALLS", &["budget", "max_tool_calls"]),
+    ("REEV_MAX_INPUT_TOKENS", &["budget", "max_input_tokens"]),
+    ("REEV_MAX_OUTPUT_TOKENS", &["budget", "max_output_tokens"]),
+    ("REEV_MAX_WALL_TIME_SECS", &["budget", "max_wall_time_secs"]),
+];
+
+/// Stands in for an unset `[budget]` limit when it is overridden
+static UNSET_LIMIT: Value = Value::Integer(0);
+
+/// Result type for configuration loading
+pub type Result<T> = std::result::Result<T, ConfigError>;
+
+/// Errors loading the configuration
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    /// The configuration file could not be read
+    #[error("Failed to read configuration file '{path}'")]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// The configuration is not valid TOML or does not match [`ReevConfig`]
+    #[error("Invalid configuration in '{origin}': {source}")]
+    Parse {
+        origin: String,
+        #[source]
+        source: toml::de::Error,
+    },
+
+    /// The selected profile is not defined
+    #[error("Unknown profile '{name}' (available: {})", available_list(.available))]
+    UnknownProfile {
+        name: String,
+        available: Vec<String>,
+    },
+
+    /// The model is not in the registry
+    #[error("Unknown model '{name}' (available: {})", available_list(.available))]
+    UnknownModel {
+        name: String,
+        available: Vec<String>,
+    },
+
+    /// The model's API key is not set
+    #[error("Model '{model}' requires {var}")]
+    MissingApiKey { model: String, var: String },
+
+    /// An environment override has a value of the wrong type
+    #[error("Invalid value '{value}' of {var} for {key}: expected {expected}")]
+    InvalidEnv {
+        var: String,
+        key: String,
+        value: String,
+        expected: &'static str,
+    },
+}
+
+fn available_list(available: &[String]) -> String {
+    if available.is_empty() {
+        "none".to_string()
+    } else {
+        available.join(", ")
+    }
+}
+
+impl ReevConfig {
+    /// Resolve `source` as a `reev.toml` document with `profile` applied
+    ///
+    /// Environment overrides are not applied; see [`ReevConfig::apply_env`].
+    pub fn from_toml(source: &str, profile: Option<&str>) -> Result<Self> {
+        Self::from_document(source, profile, "reev.toml")
+    }
+
+    /// Load the file at `path` with `profile` applied, then apply environment overrides
+    ///
+    /// A missing file resolves to the defaults, which a profile cannot select from.
+    pub fn load_from(path: &Path, profile: Option<&str>) -> Result<Self> {
+        let source = match std::fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(source) => {
+                return Err(ConfigError::Read {
+                    path: path.to_path_buf(),
+                    source,
+                })
+            }
+        };
+        let mut config = Self::from_document(&source, profile, &path.display().to_string())?;
+        config.apply_env(|var| std::env::var(var).ok())?;
+        Ok(config)
+    }
+
+    /// Names of the profiles defined in `source`
+    pub fn profiles(source: &str) -> Result<Vec<String>> {
+        let document = parse(source, "reev.toml")?;
+        Ok(profile_names(&document))
+    }
+
+    /// The registry entry of the model `name` (or one of its [aliases](registry::MODEL_ALIASES))
+    pub fn model(&self, name: &str) -> Result<&ModelEntry> {
+        self.models
+            .get(canonical_model_name(name))
+            .ok_or_else(|| ConfigError::UnknownModel {
+                name: name.to_string(),
+                available: self.models.keys().cloned().collect(),
+            })
+    }
+
+    /// The API key of `entry`: the `[llm]` key read from its `api_key_env`, else that
+    /// variable itself
+    pub fn api_key(&self, entry: &ModelEntry) -> Option<String> {
+        let var = entry.api_key_env.as_deref()?;
+        let configured = match var {
+            "LLM_API_KEY" => self.llm.api_key.clone(),
+            "ZAI_API_KEY" => self.llm.zai_api_key.clone(),
+            "GLM_CODING_API_KEY" => self.llm.glm_coding_api_key.clone(),
+            "OPENAI_API_KEY" => self.llm.openai_api_key.clone(),
+            "GEMINI_API_KEY" => self.llm.gemini_api_key.clone(),
+            "ANTHROPIC_API_KEY" => self.llm.anthropic_api_key.clone(),
+            _ => None,
+        };
+        configured
+            .or_else(|| std::env::var(var).ok())
+            .filter(|key| !key.is_empty())
+    }
+
+    /// The API key of the model `name`, an error when it needs one that is not set
+    pub fn require_api_key(&self, name: &str) -> Result<Option<String>> {
+        let entry = self.model(name)?;
+        match (&entry.api_key_env, self.api_key(entry)) {
+            (Some(var), None) => Err(ConfigError::MissingApiKey {
+                model: name.to_string(),
+                var: var.clone(),
+            }),
+            (_, key) => Ok(key),
+        }
+    }
+
+    fn from_document(source: &str, profile: Option<&str>, origin: &str) -> Result<Self> {
+        let mut document = parse(source, origin)?;
+        let available = profile_names(&document);
+        let profiles = match &mut document {
+            Value::Table(table) => table.remove("profiles"),
+            _ => None,
+        };
+
+        if let Some(name) = profile {
+            let overlay = profiles
+                .and_then(|mut p| p.as_table_mut().and_then(|t| t.remove(name)))
+                .ok_or_else(|| ConfigError::UnknownProfile {
+                    name: name.to_string(),
+                    available,
+                })?;
+            merge(&mut document, overlay);
+        }
+
+        // Merge over the defaults so `[models]` entries change the built-in registry
+        // key by key instead of replacing it
+        let mut resolved =
+            Value::try_from(Self::default()).expect("configuration serializes to TOML");
+        merge(&mut resolved, document);
+        resolved.try_into().map_err(|source| ConfigError::Parse {
+            origin: origin.to_string(),
+            source,
+        })
+    }
+
+    /// Apply the overrides of [`ENV_OVERRIDES`] that `lookup` returns a value for
+    ///
+    /// Values are converted to the type of the key they set, so `SURFPOOL_RPC_PORT=abc`
+    /// is an error naming the variable rather than a silently ignored setting.
+    pub fn apply_env(&mut self, lookup: impl Fn(&str) -> Option<String>) -> Result<()> {
+        let mut document = Value::try_from(&*self).expect("configuration serializes to TOML");
+        let mut changed = false;
+
+        for (var, path) in ENV_OVERRIDES {
+            let Some(raw) = lookup(var) else { continue };
+            let (field, tables) = path.split_last().expect("override paths are not empty");
+            // A model removed from the registry has nothing to override
+            let Some(table) = tables
+                .iter()
+                .try_fold(&mut document, |value, key| value.get_mut(*key))
+                .and_then(Value::as_table_mut)
+            else {
+                continue;
+            };
+            // Budget limits are optional integers, so an unset one has no type to go by
+            let current = table
+                .get(*field)
+                .or_else(|| (tables == ["budget"]).then_some(&UNSET_LIMIT));
+            let value = convert(current, &raw).map_err(|expected| ConfigError::InvalidEnv {
+                var: var.to_string(),
+                key: path.join("."),
+                value: raw.clone(),
+                expected,
+            })?;
+            table.insert(field.to_string(), value);
+            changed = true;
+        }
+
+        if changed {
+            *self = document.try_into().map_err(|source| ConfigError::Parse {
+                origin: "environment".to_string(),
+                source,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// The configuration as TOML, with API keys masked
+    ///
+    /// Only `*api_key` values are masked; `api_key_env` names a variable, not a key.
+    pub fn to_toml_masked(&self) -> String {
+        let mut document = Value::try_from(self).expect("configuration serializes to TOML");
+        if let Some(sections) = document.as_table_mut() {
+            for (_, section) in sections.iter_mut() {
+                let Some(section) = section.as_table_mut() else {
+                    continue;
+                };
+                for (key, value) in section.iter_mut() {
+                    if key.ends_with("api_key") {
+                        *value = Value::String("********".to_string());
+                    }
+                }
+            }
+        }
+        toml::to_string(&document).expect("configuration serializes to TOML")
+    }
+}
+
+fn parse(source: &str, origin: &str) -> Result<Value> {
+    toml::from_str(source).map_err(|source| ConfigError::Parse {
+        origin: origin.to_string(),
+        source,
+    })
+}
+
+fn profile_names(document: &Value) -> Vec<String> {
+    document
+        .get("profiles")
+        .and_then(Value::as_table)
+        .map(|t| t.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Merge `overlay` into `base`, replacing values and merging tables key by key
+fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Table(base), Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Convert an environment value to the type of `current`, strings when unset
+fn convert(current: Option<&Value>, raw: &str) -> std::result::Result<Value, &'static str> {
+    match current {
+        Some(Value::Boolean(_)) => match raw.to_ascii_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Ok(Value::Boolean(true)),
+            "0" | "false" | "no" | "off" => Ok(Value::Boolean(false)),
+            _ => Err("a boolean"),
+        },
+        Some(Value::Integer(_)) => raw
+            .trim()
+            .parse()
+            .map(Value::Integer)
+            .map_err(|_| "an integer"),
+        Some(Value::Float(_)) => raw.trim().parse().map(Value::Float).map_err(|_| "a number"),
+        _ => Ok(Value::String(raw.to_string())),
+    }
+}
+
+/// Path of the configuration file: `REEV_CONFIG`, or `reev.toml` in the working directory
+pub fn config_path() -> PathBuf {
+    std::env::var_os(CONFIG_PATH_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(CONFIG_FILE))
+}
+
+/// Profile selected with `REEV_PROFILE`
+pub fn selected_profile() -> Option<String> {
+    std::env::var(PROFILE_VAR).ok().filter(|p| !p.is_empty())
+}
+
+/// Load the configuration of the selected profile from the configuration file and
+/// the environment, including a `.env` file
+pub fn load() -> Result<ReevConfig> {
+    let _ = dotenvy::dotenv();
+    ReevConfig::load_from(&config_path(), selected_profile().as_deref())
+}
+
+static CONFIG: OnceLock<ReevConfig> = OnceLock::new();
+
+/// Install `config` as the process-wide configuration
+///
+/// Returns `false` when a configuration was already installed or read.
+pub fn init(config: ReevConfig) -> bool {
+    CONFIG.set(config).is_ok()
+}
+
+/// The process-wide configuration, loaded on first use unless [`init`] ran earlier
+///
+/// A configuration that fails to load is reported and replaced by the defaults.
+pub fn get() -> &'static ReevConfig {
+    CONFIG.get_or_init(|| {
+        load().unwrap_or_else(|e| {
+            warn!("Using default configuration: {e}");
+            ReevConfig::default()
+        })
+    })
+}
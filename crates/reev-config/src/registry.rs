@@ -0,0 +1,255 @@
+//! The model registry: every agent name reev can run and the provider serving it
+//!
+//! Entries live under `[models."<name>"]` in `reev.toml`. The built-in entries below
+//! are always present; a file changes them key by key or adds new ones:
+//!
+//! ```toml
+//! [models."qwen-local"]
+//! provider = "local"
+//! base_url = "http://localhost:8080/v1"
+//! model = "qwen2.5-coder-32b"
+//! tool_calling = true
+//!
+//! [models."qwen-local".price]   # USD per million tokens, for cost reports
+//! input = 0.2
+//! cached_input = 0.05
+//! output = 0.6
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Names accepted in place of a registered model name
+pub const MODEL_ALIASES: &[(&str, &str)] = &[("glm", "glm-4.6")];
+
+/// The registered name `name` stands for, resolving [`MODEL_ALIASES`]
+pub fn canonical_model_name(name: &str) -> &str {
+    MODEL_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map_or(name, |(_, target)| target)
+}
+
+/// How reev-agent talks to a model
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    /// The built-in deterministic agent, no model involved
+    Deterministic,
+    /// The ZAI API serving the GLM models
+    Zai,
+    /// An OpenAI-compatible hosted API
+    OpenAi,
+    /// The Anthropic Messages API
+    Anthropic,
+    /// An OpenAI-compatible server on this machine, such as LM Studio
+    Local,
+    /// An Ollama server, through its native chat API
+    Ollama,
+    /// A llama.cpp server (`llama-server`)
+    #[serde(rename = "llama-cpp")]
+    LlamaCpp,
+}
+
+impl ProviderKind {
+    /// Name of the provider as written in `reev.toml`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProviderKind::Deterministic => "deterministic",
+            ProviderKind::Zai => "zai",
+            ProviderKind::OpenAi => "openai",
+            ProviderKind::Anthropic => "anthropic",
+            ProviderKind::Local => "local",
+            ProviderKind::Ollama => "ollama",
+            ProviderKind::LlamaCpp => "llama-cpp",
+        }
+    }
+}
+
+impl std::fmt::Display for ProviderKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A registered model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModelEntry {
+    /// Provider serving the model
+    pub provider: ProviderKind,
+    /// Model identifier sent to the provider, the registered name when unset
+    pub model: Option<String>,
+    /// Endpoint of the provider's API, the provider's default when unset
+    pub base_url: Option<String>,
+    /// Environment variable holding the API key; the model is unavailable without it
+    pub api_key_env: Option<String>,
+    /// The model supports tool calls
+    #[serde(default)]
+    pub tool_calling: bool,
+    /// The model supports a JSON response format
+    #[serde(default)]
+    pub json_mode: bool,
+    /// Context window in tokens; local servers are held to it
+    pub context_size: Option<u32>,
+    /// Price of the model's tokens; runs of unpriced models report no cost
+    pub price: Option<ModelPrice>,
+}
+
+/// Token prices of a model, in USD per million tokens
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModelPrice {
+    /// Price of a prompt token
+    pub input: f64,
+    /// Price of a prompt token served from the prompt cache, `input` when unset
+    pub cached_input: Option<f64>,
+    /// Price of a completion token
+    pub output: f64,
+}
+
+impl ModelPrice {
+    const fn new(input: f64, cached_input: f64, output: f64) -> Self {
+        Self {
+            input,
+            cached_input: Some(cached_input),
+            output,
+        }
+    }
+
+    /// Cost in USD of `prompt_tokens` prompt tokens, `cached_tokens` of them cached,
+    /// and `completion_tokens` completion tokens
+    pub fn cost(&self, prompt_tokens: u64, cached_tokens: u64, completion_tokens: u64) -> f64 {
+        let cached_tokens = cached_tokens.min(prompt_tokens);
+        let uncached = (prompt_tokens - cached_tokens) as f64 * self.input;
+        let cached = cached_tokens as f64 * self.cached_input.unwrap_or(self.input);
+        let completion = completion_tokens as f64 * self.output;
+        (uncached + cached + completion) / 1_000_000.0
+    }
+}
+
+impl ModelEntry {
+    fn new(provider: ProviderKind) -> Self {
+        Self {
+            provider,
+            model: None,
+            base_url: None,
+            api_key_env: None,
+            tool_calling: false,
+            json_mode: false,
+            context_size: None,
+            price: None,
+        }
+    }
+
+    fn hosted(provider: ProviderKind, base_url: &str, api_key_env: &str, context: u32) -> Self {
+        Self {
+            base_url: Some(base_url.to_string()),
+            api_key_env: Some(api_key_env.to_string()),
+            tool_calling: true,
+            json_mode: true,
+            context_size: Some(context),
+            ..Self::new(provider)
+        }
+    }
+
+    fn served(provider: ProviderKind, base_url: &str, context: u32) -> Self {
+        Self {
+            base_url: Some(base_url.to_string()),
+            tool_calling: true,
+            context_size: Some(context),
+            ..Self::new(provider)
+        }
+    }
+
+    /// Model identifier sent to the provider for the entry registered as `name`
+    pub fn model_id<'a>(&'a self, name: &'a str) -> &'a str {
+        self.model.as_deref().unwrap_or(name)
+    }
+}
+
+/// The built-in registry
+pub fn default_models() -> BTreeMap<String, ModelEntry> {
+    const ZAI_CODING_URL: &str = "https://api.z.ai/api/coding/paas/v4";
+
+    BTreeMap::from([
+        (
+            "deterministic".to_string(),
+            ModelEntry::new(ProviderKind::Deterministic),
+        ),
+        (
+            "local".to_string(),
+            ModelEntry {
+                model: Some("qwen3-coder-30b-a3b-instruct-mlx".to_string()),
+                ..ModelEntry::served(ProviderKind::Local, "http://localhost:1234/v1", 32_768)
+            },
+        ),
+        (
+            "ollama".to_string(),
+            ModelEntry {
+                model: Some("qwen3-coder:30b".to_string()),
+                ..ModelEntry::served(ProviderKind::Ollama, "http://localhost:11434", 32_768)
+            },
+        ),
+        (
+            "llama-cpp".to_string(),
+            ModelEntry::served(ProviderKind::LlamaCpp, "http://localhost:8080", 32_768),
+        ),
+        (
+            "glm-4.6".to_string(),
+            ModelEntry {
+                price: Some(ModelPrice::new(0.6, 0.11, 2.2)),
+                ..ModelEntry::hosted(ProviderKind::Zai, ZAI_CODING_URL, "ZAI_API_KEY", 200_000)
+            },
+        ),
+        (
+            "glm-4.6-coding".to_string(),
+            ModelEntry {
+                model: Some("glm-4.6".to_string()),
+                ..ModelEntry::hosted(
+                    ProviderKind::Zai,
+                    ZAI_CODING_URL,
+                    "GLM_CODING_API_KEY",
+                    200_000,
+                )
+            },
+        ),
+        (
+            "gemini-2.5-flash-lite".to_string(),
+            ModelEntry {
+                price: Some(ModelPrice::new(0.1, 0.025, 0.4)),
+                ..ModelEntry::hosted(
+                    ProviderKind::OpenAi,
+                    "https://generativelanguage.googleapis.com/v1beta/openai/",
+                    "GEMINI_API_KEY",
+                    1_048_576,
+                )
+            },
+        ),
+        (
+            "claude-sonnet-4-5".to_string(),
+            ModelEntry {
+                json_mode: false,
+                price: Some(ModelPrice::new(3.0, 0.3, 15.0)),
+                ..ModelEntry::hosted(
+                    ProviderKind::Anthropic,
+                    "https://api.anthropic.com/v1",
+                    "ANTHROPIC_API_KEY",
+                    200_000,
+                )
+            },
+        ),
+        (
+            "gpt-4o".to_string(),
+            ModelEntry {
+                price: Some(ModelPrice::new(2.5, 1.25, 10.0)),
+                ..ModelEntry::hosted(
+                    ProviderKind::OpenAi,
+                    "https://api.openai.com/v1",
+                    "OPENAI_API_KEY",
+                    128_000,
+                )
+            },
+        ),
+    ])
+}
@@ -0,0 +1,340 @@
+//! Typed sections of `reev.toml`
+//!
+//! Every field has a default, so a file only needs the values it changes.
+
+use crate::registry::{default_models, ModelEntry};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Resolved reev configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReevConfig {
+    /// LLM endpoints and credentials
+    pub llm: LlmSettings,
+    /// The reev-agent service
+    pub agent: AgentSettings,
+    /// The surfpool validator and its fault proxy
+    pub surfpool: SurfpoolSettings,
+    /// Runner service management and log locations
+    pub runner: RunnerSettings,
+    /// The results database
+    pub database: DatabaseSettings,
+    /// The Jupiter API
+    pub jupiter: JupiterSettings,
+    /// Recording and replay of LLM calls
+    pub cassette: CassetteSettings,
+    /// Limits on every agent run
+    pub budget: BudgetSettings,
+    /// The model registry, keyed by agent name
+    pub models: BTreeMap<String, ModelEntry>,
+}
+
+impl Default for ReevConfig {
+    fn default() -> Self {
+        Self {
+            llm: LlmSettings::default(),
+            agent: AgentSettings::default(),
+            surfpool: SurfpoolSettings::default(),
+            runner: RunnerSettings::default(),
+            database: DatabaseSettings::default(),
+            jupiter: JupiterSettings::default(),
+            cassette: CassetteSettings::default(),
+            budget: BudgetSettings::default(),
+            models: default_models(),
+        }
+    }
+}
+
+/// LLM endpoints and credentials
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LlmSettings {
+    /// Endpoint the runner sends generation requests to
+    pub api_url: String,
+    /// Key sent with generation requests
+    pub api_key: Option<String>,
+    /// Sampling temperature
+    pub temperature: f64,
+    /// ZAI API key, used by models with `api_key_env = "ZAI_API_KEY"`
+    pub zai_api_key: Option<String>,
+    /// GLM coding API key, used by models with `api_key_env = "GLM_CODING_API_KEY"`
+    pub glm_coding_api_key: Option<String>,
+    /// OpenAI API key, used by models with `api_key_env = "OPENAI_API_KEY"`
+    pub openai_api_key: Option<String>,
+    /// Gemini API key, used by models with `api_key_env = "GEMINI_API_KEY"`
+    pub gemini_api_key: Option<String>,
+    /// Anthropic API key, used by models with `api_key_env = "ANTHROPIC_API_KEY"`
+    pub anthropic_api_key: Option<String>,
+}
+
+impl Default for LlmSettings {
+    fn default() -> Self {
+        Self {
+            api_url: "http://localhost:9090/gen/tx".to_string(),
+            api_key: None,
+            temperature: 0.7,
+            zai_api_key: None,
+            glm_coding_api_key: None,
+            openai_api_key: None,
+            gemini_api_key: None,
+            anthropic_api_key: None,
+        }
+    }
+}
+
+/// The reev-agent service
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AgentSettings {
+    /// Host reev-agent listens on
+    pub host: String,
+    /// Port reev-agent listens on
+    pub port: u16,
+    /// Whether tool calls are written to the enhanced OpenTelemetry logs
+    pub enhanced_otel: bool,
+    /// Tool calls with rejected arguments a run may make before it fails
+    pub max_tool_retries: u32,
+}
+
+impl Default for AgentSettings {
+    fn default() -> Self {
+        Self {
+            host: "127.0.0.1".to_string(),
+            port: 9090,
+            enhanced_otel: true,
+            max_tool_retries: 2,
+        }
+    }
+}
+
+/// The surfpool validator and its fault proxy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SurfpoolSettings {
+    /// Host surfpool listens on
+    pub host: String,
+    /// Port of surfpool's RPC
+    pub rpc_port: u16,
+    /// Port of the fault-injection proxy in front of surfpool
+    pub fault_proxy_port: u16,
+}
+
+impl Default for SurfpoolSettings {
+    fn default() -> Self {
+        Self {
+            host: "127.0.0.1".to_string(),
+            rpc_port: 8899,
+            fault_proxy_port: 8890,
+        }
+    }
+}
+
+impl SurfpoolSettings {
+    /// URL of surfpool's RPC
+    pub fn rpc_url(&self) -> String {
+        format!("http://{}:{}", self.host, self.rpc_port)
+    }
+}
+
+/// Runner service management and log locations
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RunnerSettings {
+    /// Start surfpool and reev-agent when they are not running
+    pub auto_start: bool,
+    /// Log the output of managed services
+    pub verbose_logging: bool,
+    /// Pull a model missing from the Ollama server instead of failing the health check
+    pub pull_models: bool,
+    /// Directory for cached surfpool binaries
+    pub cache_dir: String,
+    /// Directory for service logs
+    pub log_dir: String,
+    /// Directory for session logs
+    pub session_log_dir: String,
+    /// Directory for flow logs
+    pub flow_log_dir: String,
+}
+
+impl Default for RunnerSettings {
+    fn default() -> Self {
+        Self {
+            auto_start: true,
+            verbose_logging: false,
+            pull_models: false,
+            cache_dir: ".surfpool/cache".to_string(),
+            log_dir: "logs".to_string(),
+            session_log_dir: "logs/sessions".to_string(),
+            flow_log_dir: "logs/flows".to_string(),
+        }
+    }
+}
+
+/// The results database
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseSettings {
+    /// Path of the results database
+    pub path: String,
+    /// Share a pool of connections between requests
+    pub enable_pooling: bool,
+    /// Largest number of pooled connections
+    pub max_connections: u32,
+}
+
+impl Default for DatabaseSettings {
+    fn default() -> Self {
+        Self {
+            path: "db/reev_results.db".to_string(),
+            enable_pooling: false,
+            max_connections: 10,
+        }
+    }
+}
+
+/// The Jupiter API
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JupiterSettings {
+    /// Base URL of the Jupiter API
+    pub api_base_url: String,
+    /// Request timeout in seconds
+    pub timeout_seconds: u64,
+    /// Retries of a failed request
+    pub max_retries: u32,
+    /// User agent sent with requests
+    pub user_agent: String,
+    /// Slippage used when none is given, in basis points
+    pub default_slippage_bps: u16,
+    /// Largest accepted slippage, in basis points
+    pub max_slippage_bps: u16,
+    /// Log requests and responses
+    pub debug: bool,
+    /// Surfpool RPC URL used for Jupiter transactions
+    pub surfpool_rpc_url: Option<String>,
+}
+
+impl Default for JupiterSettings {
+    fn default() -> Self {
+        Self {
+            api_base_url: "https://lite-api.jup.ag".to_string(),
+            timeout_seconds: 30,
+            max_retries: 3,
+            user_agent: "reev-agent/0.1.0".to_string(),
+            default_slippage_bps: 50,
+            max_slippage_bps: 1000,
+            debug: false,
+            surfpool_rpc_url: None,
+        }
+    }
+}
+
+/// What reev-agent does with the LLM calls of its agents
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CassetteMode {
+    /// Call the models
+    #[default]
+    Off,
+    /// Call the models and store each request and response in the cassette directory
+    Record,
+    /// Answer from the cassette directory without calling the models; a request
+    /// that was not recorded is an error
+    Replay,
+}
+
+/// Recording and replay of LLM calls
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CassetteSettings {
+    /// Record, replay or neither
+    pub mode: CassetteMode,
+    /// Directory of the recorded calls
+    pub dir: String,
+    /// Part of every recording's key, so one request can be recorded once per seed
+    pub seed: u64,
+}
+
+impl Default for CassetteSettings {
+    fn default() -> Self {
+        Self {
+            mode: CassetteMode::Off,
+            dir: "cassettes".to_string(),
+            seed: 0,
+        }
+    }
+}
+
+/// Limits on the agent runs of one benchmark attempt; the steps of a flow share them
+///
+/// An unset limit is no limit. A run that reaches one is stopped with what it
+/// built so far and recorded as `budget_exceeded`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BudgetSettings {
+    /// Completion calls to the model
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_turns: Option<u32>,
+    /// Tool calls the model makes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tool_calls: Option<u32>,
+    /// Prompt tokens over all completion calls
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_input_tokens: Option<u64>,
+    /// Completion tokens over all completion calls
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u64>,
+    /// Seconds from the agent receiving the first request to its last answer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_wall_time_secs: Option<u64>,
+}
+
+impl BudgetSettings {
+    /// These limits, with those left unset taken from `fallback`
+    pub fn or(self, fallback: Self) -> Self {
+        Self {
+            max_turns: self.max_turns.or(fallback.max_turns),
+            max_tool_calls: self.max_tool_calls.or(fallback.max_tool_calls),
+            max_input_tokens: self.max_input_tokens.or(fallback.max_input_tokens),
+            max_output_tokens: self.max_output_tokens.or(fallback.max_output_tokens),
+            max_wall_time_secs: self.max_wall_time_secs.or(fallback.max_wall_time_secs),
+        }
+    }
+
+    /// What is left of these limits once earlier runs have spent `spent`
+    ///
+    /// A limit used up is left at 0, which stops the next run before its first call.
+    pub fn remaining(self, spent: &BudgetSpent) -> Self {
+        Self {
+            max_turns: self.max_turns.map(|max| max.saturating_sub(spent.turns)),
+            max_tool_calls: self
+                .max_tool_calls
+                .map(|max| max.saturating_sub(spent.tool_calls)),
+            max_input_tokens: self
+                .max_input_tokens
+                .map(|max| max.saturating_sub(spent.input_tokens)),
+            max_output_tokens: self
+                .max_output_tokens
+                .map(|max| max.saturating_sub(spent.output_tokens)),
+            max_wall_time_secs: self
+                .max_wall_time_secs
+                .map(|max| max.saturating_sub(spent.wall_time_secs)),
+        }
+    }
+
+    /// Whether no limit is set
+    pub fn is_unlimited(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// What the runs of a benchmark attempt have spent against its [`BudgetSettings`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BudgetSpent {
+    pub turns: u32,
+    pub tool_calls: u32,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub wall_time_secs: u64,
+}
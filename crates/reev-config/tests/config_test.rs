@@ -0,0 +1,278 @@
+//! Tests for resolving reev.toml profiles and environment overrides
+
+use reev_config::{
+    BudgetSettings, BudgetSpent, CassetteMode, ConfigError, ProviderKind, ReevConfig,
+};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+const SOURCE: &str = r#"
+[surfpool]
+rpc_port = 9899
+
+[database]
+path = "db/base.db"
+
+[profiles.ci.database]
+path = "db/ci.db"
+
+[profiles.ci.runner]
+auto_start = false
+
+[profiles.local-llm.models.local]
+base_url = "http://localhost:8080/v1"
+"#;
+
+fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: HashMap<String, String> = vars
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    move |var| vars.get(var).cloned()
+}
+
+#[test]
+fn test_defaults_and_base_values() {
+    assert_eq!(
+        ReevConfig::from_toml("", None).unwrap(),
+        ReevConfig::default()
+    );
+
+    let config = ReevConfig::from_toml(SOURCE, None).unwrap();
+    assert_eq!(config.surfpool.rpc_port, 9899);
+    assert_eq!(config.surfpool.rpc_url(), "http://127.0.0.1:9899");
+    assert_eq!(config.database.path, "db/base.db");
+    assert!(config.runner.auto_start);
+    assert_eq!(config.agent.port, 9090);
+    println!("✅ Unset values keep their defaults");
+}
+
+#[test]
+fn test_profile_overrides_base() {
+    let config = ReevConfig::from_toml(SOURCE, Some("ci")).unwrap();
+    assert_eq!(config.database.path, "db/ci.db");
+    assert!(!config.runner.auto_start);
+    // Keys the profile does not mention come from the base tables
+    assert_eq!(config.surfpool.rpc_port, 9899);
+    assert_eq!(config.runner.log_dir, "logs");
+    assert_eq!(
+        config.models["local"].base_url.as_deref(),
+        Some("http://localhost:1234/v1")
+    );
+
+    let names = ReevConfig::profiles(SOURCE).unwrap();
+    assert_eq!(names, vec!["ci", "local-llm"]);
+
+    match ReevConfig::from_toml(SOURCE, Some("cloud")) {
+        Err(ConfigError::UnknownProfile { name, available }) => {
+            assert_eq!(name, "cloud");
+            assert_eq!(available, vec!["ci", "local-llm"]);
+        }
+        other => panic!("expected an unknown profile error, got {other:?}"),
+    }
+    println!("✅ Profiles are merged over the base values");
+}
+
+#[test]
+fn test_unknown_keys_rejected() {
+    let err = ReevConfig::from_toml("[surfpool]\nrpc_prot = 1\n", None).unwrap_err();
+    assert!(err.to_string().contains("rpc_prot"), "{err}");
+
+    let err = ReevConfig::from_toml("[profiles.ci.agent]\nport = \"x\"\n", Some("ci")).unwrap_err();
+    assert!(matches!(err, ConfigError::Parse { .. }), "{err}");
+    println!("✅ Misspelled keys and wrong types are errors");
+}
+
+#[test]
+fn test_env_overrides() {
+    let mut config = ReevConfig::from_toml(SOURCE, Some("ci")).unwrap();
+    config
+        .apply_env(env(&[
+            ("DATABASE_PATH", "/tmp/results.db"),
+            ("SURFPOOL_RPC_PORT", "7000"),
+            ("REEV_ENHANCED_OTEL", "0"),
+            ("LLM_TEMPERATURE", "0.1"),
+            ("ZAI_API_KEY", "secret"),
+            ("LOCAL_MODEL_NAME", "llama-3.1-8b"),
+            ("OLLAMA_MODEL", "llama3.1:8b"),
+            ("REEV_CASSETTE_MODE", "replay"),
+            ("REEV_MAX_TURNS", "8"),
+        ]))
+        .unwrap();
+    assert_eq!(config.database.path, "/tmp/results.db");
+    assert_eq!(config.surfpool.rpc_port, 7000);
+    assert!(!config.agent.enhanced_otel);
+    assert_eq!(config.llm.temperature, 0.1);
+    assert_eq!(config.llm.zai_api_key.as_deref(), Some("secret"));
+    assert_eq!(config.models["local"].model_id("local"), "llama-3.1-8b");
+    assert_eq!(config.models["ollama"].model_id("ollama"), "llama3.1:8b");
+    assert_eq!(config.cassette.mode, CassetteMode::Replay);
+    assert_eq!(config.budget.max_turns, Some(8));
+    assert!(!config.runner.auto_start);
+
+    let err = config
+        .apply_env(env(&[("SURFPOOL_RPC_PORT", "abc")]))
+        .unwrap_err();
+    assert!(
+        matches!(&err, ConfigError::InvalidEnv { var, .. } if var == "SURFPOOL_RPC_PORT"),
+        "{err}"
+    );
+    let err = config
+        .apply_env(env(&[("REEV_AGENT_PORT", "70000")]))
+        .unwrap_err();
+    assert!(err.to_string().contains("environment"), "{err}");
+    println!("✅ Environment variables override the file");
+}
+
+#[test]
+fn test_masked_output() {
+    let mut config = ReevConfig::default();
+    config.llm.zai_api_key = Some("secret".to_string());
+
+    let shown = config.to_toml_masked();
+    assert!(!shown.contains("secret"));
+    assert!(shown.contains("zai_api_key = \"********\""));
+    assert!(shown.contains("api_key_env = \"ZAI_API_KEY\""));
+
+    // The output is a valid configuration file itself
+    let reparsed = ReevConfig::from_toml(&shown, None).unwrap();
+    assert_eq!(reparsed.models, config.models);
+    println!("✅ API keys are masked");
+}
+
+#[test]
+fn test_model_registry() {
+    let source = r#"
+[models."glm-4.6"]
+base_url = "https://api.z.ai/api/paas/v4"
+
+[models.llama]
+provider = "local"
+base_url = "http://localhost:8080/v1"
+api_key_env = "REEV_TEST_UNSET_API_KEY"
+context_size = 8192
+"#;
+    let config = ReevConfig::from_toml(source, None).unwrap();
+
+    // Built-in entries stay registered and change only in the keys the file sets
+    let glm = config.model("glm-4.6").unwrap();
+    assert_eq!(glm.provider, ProviderKind::Zai);
+    assert_eq!(
+        glm.base_url.as_deref(),
+        Some("https://api.z.ai/api/paas/v4")
+    );
+    assert_eq!(glm.api_key_env.as_deref(), Some("ZAI_API_KEY"));
+    assert!(glm.tool_calling);
+    assert_eq!(config.model("glm").unwrap(), glm);
+    assert_eq!(
+        config.model("deterministic").unwrap().provider,
+        ProviderKind::Deterministic
+    );
+
+    let llama = config.model("llama").unwrap();
+    assert_eq!(llama.provider, ProviderKind::Local);
+    assert_eq!(llama.model_id("llama"), "llama");
+    assert_eq!(llama.context_size, Some(8192));
+    assert!(!llama.tool_calling);
+    match config.require_api_key("llama") {
+        Err(ConfigError::MissingApiKey { model, var }) => {
+            assert_eq!(model, "llama");
+            assert_eq!(var, "REEV_TEST_UNSET_API_KEY");
+        }
+        other => panic!("expected a missing key error, got {other:?}"),
+    }
+    assert_eq!(config.require_api_key("local").unwrap(), None);
+
+    match config.model("gpt-5-turbo") {
+        Err(ConfigError::UnknownModel { name, available }) => {
+            assert_eq!(name, "gpt-5-turbo");
+            assert!(available.contains(&"llama".to_string()));
+        }
+        other => panic!("expected an unknown model error, got {other:?}"),
+    }
+
+    let llama_cpp = config.model("llama-cpp").unwrap();
+    assert_eq!(llama_cpp.provider, ProviderKind::LlamaCpp);
+    assert_eq!(llama_cpp.provider.to_string(), "llama-cpp");
+
+    let err = ReevConfig::from_toml("[models.x]\nbase_url = \"http://x\"\n", None).unwrap_err();
+    assert!(err.to_string().contains("provider"), "{err}");
+    let err = ReevConfig::from_toml("[models.x]\nprovider = \"acme\"\n", None).unwrap_err();
+    assert!(matches!(err, ConfigError::Parse { .. }), "{err}");
+    println!("✅ Models are registered from the file over the built-in entries");
+}
+
+#[test]
+fn test_model_prices() {
+    let source = r#"
+[models."gpt-4o".price]
+output = 12.0
+
+[models.llama]
+provider = "local"
+price = { input = 1.0, output = 2.0 }
+"#;
+    let config = ReevConfig::from_toml(source, None).unwrap();
+
+    // A file changes the built-in price key by key
+    let gpt = config.model("gpt-4o").unwrap().price.unwrap();
+    assert_eq!(
+        (gpt.input, gpt.cached_input, gpt.output),
+        (2.5, Some(1.25), 12.0)
+    );
+    assert!((gpt.cost(1_000_000, 400_000, 100_000) - 3.2).abs() < 1e-9);
+
+    // Cached tokens cost the input price when the model sets none
+    let llama = config.model("llama").unwrap().price.unwrap();
+    assert!((llama.cost(2_000_000, 1_000_000, 500_000) - 3.0).abs() < 1e-9);
+    assert_eq!(config.model("local").unwrap().price, None);
+
+    let err = ReevConfig::from_toml("[models.gpt-4o.price]\nper_call = 1.0\n", None).unwrap_err();
+    assert!(matches!(err, ConfigError::Parse { .. }), "{err}");
+    println!("✅ Model prices come from the registry and price cached tokens apart");
+}
+
+#[test]
+fn test_budget_limits() {
+    let config =
+        ReevConfig::from_toml("[budget]\nmax_turns = 8\nmax_tool_calls = 4\n", None).unwrap();
+    let benchmark = BudgetSettings {
+        max_turns: Some(0),
+        max_output_tokens: Some(2000),
+        ..Default::default()
+    };
+
+    // A benchmark's limits replace the configured ones, even a limit of 0
+    let budget = benchmark.or(config.budget);
+    assert_eq!(budget.max_turns, Some(0));
+    assert_eq!(budget.max_tool_calls, Some(4));
+    assert_eq!(budget.max_output_tokens, Some(2000));
+    assert_eq!(budget.max_input_tokens, None);
+    assert!(BudgetSettings::default().is_unlimited());
+
+    // Later runs of an attempt get what earlier ones left
+    let left = config.budget.remaining(&BudgetSpent {
+        turns: 3,
+        tool_calls: 5,
+        input_tokens: 1000,
+        ..Default::default()
+    });
+    assert_eq!(left.max_turns, Some(5));
+    assert_eq!(left.max_tool_calls, Some(0));
+    assert_eq!(left.max_input_tokens, None);
+    println!("✅ Budget limits inherit only when unset and are shared by the runs of an attempt");
+}
+
+#[test]
+fn test_repository_config() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../reev.toml");
+    let source = std::fs::read_to_string(&path).unwrap();
+
+    let base = ReevConfig::from_toml(&source, None).unwrap();
+    assert_eq!(base, ReevConfig::default());
+    for profile in ["ci", "local-llm", "cloud"] {
+        let config = ReevConfig::from_toml(&source, Some(profile)).unwrap();
+        assert_ne!(config, base, "profile {profile} changes nothing");
+    }
+    println!("✅ reev.toml and its profiles resolve");
+}
@@ -11,6 +11,22 @@ CREATE TABLE IF NOT EXISTS benchmarks (
     created_at INTEGER DEFAULT (strftime('%s', 'now'))
 );
 
+CREATE TABLE IF NOT EXISTS evaluation_runs (
+    run_id TEXT PRIMARY KEY,
+    agent_type TEXT NOT NULL,
+    model TEXT NOT NULL,
+    temperature REAL,
+    prompt_version TEXT,
+    git_commit TEXT,
+    benchmark_hashes TEXT NOT NULL DEFAULT '{}',
+    seed INTEGER,
+    trials INTEGER NOT NULL DEFAULT 1,
+    status TEXT NOT NULL DEFAULT 'running',
+    start_time INTEGER NOT NULL,
+    end_time INTEGER,
+    created_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
 CREATE TABLE IF NOT EXISTS execution_sessions (
     session_id TEXT PRIMARY KEY,
     benchmark_id TEXT NOT NULL,
@@ -23,6 +39,9 @@ CREATE TABLE IF NOT EXISTS execution_sessions (
     final_status TEXT,
     log_file_path TEXT,
     created_at INTEGER DEFAULT (strftime('%s', 'now')),
+    run_id TEXT,
+    budget_exceeded TEXT,
+    argument_retries INTEGER NOT NULL DEFAULT 0,
     FOREIGN KEY (benchmark_id) REFERENCES benchmarks (id)
 );
 
@@ -44,6 +63,9 @@ CREATE TABLE IF NOT EXISTS agent_performance (
     execution_time_ms INTEGER,
     created_at INTEGER DEFAULT (strftime('%s', 'now')),
     prompt_md5 TEXT,
+    trial_index INTEGER NOT NULL DEFAULT 0,
+    run_id TEXT,
+    rescored INTEGER NOT NULL DEFAULT 0,
     FOREIGN KEY (session_id) REFERENCES execution_sessions (session_id),
     FOREIGN KEY (benchmark_id) REFERENCES benchmarks (id)
 );
@@ -63,6 +85,46 @@ CREATE TABLE IF NOT EXISTS session_tool_calls (
     FOREIGN KEY (session_id) REFERENCES execution_sessions (session_id) ON DELETE CASCADE
 );
 
+CREATE TABLE IF NOT EXISTS session_balance_deltas (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id TEXT NOT NULL,
+    step_index INTEGER NOT NULL,
+    account_name TEXT,
+    pubkey TEXT NOT NULL,
+    mint TEXT,
+    pre_lamports INTEGER NOT NULL,
+    post_lamports INTEGER NOT NULL,
+    pre_token_amount INTEGER,
+    post_token_amount INTEGER,
+    created_at INTEGER DEFAULT (strftime('%s', 'now')),
+    FOREIGN KEY (session_id) REFERENCES execution_sessions (session_id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS session_efficiency_metrics (
+    session_id TEXT PRIMARY KEY,
+    benchmark_id TEXT NOT NULL,
+    agent_type TEXT NOT NULL,
+    compute_units INTEGER NOT NULL DEFAULT 0,
+    fee_lamports INTEGER NOT NULL DEFAULT 0,
+    instruction_count INTEGER NOT NULL DEFAULT 0,
+    transaction_count INTEGER NOT NULL DEFAULT 0,
+    efficiency_score REAL,
+    created_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+CREATE TABLE IF NOT EXISTS session_llm_usage (
+    session_id TEXT PRIMARY KEY,
+    benchmark_id TEXT NOT NULL,
+    agent_type TEXT NOT NULL,
+    model TEXT NOT NULL,
+    run_id TEXT,
+    prompt_tokens INTEGER NOT NULL DEFAULT 0,
+    completion_tokens INTEGER NOT NULL DEFAULT 0,
+    cached_tokens INTEGER NOT NULL DEFAULT 0,
+    cost_usd REAL,
+    created_at INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
 CREATE TABLE IF NOT EXISTS schema_version (
     version TEXT PRIMARY KEY,
     applied_at INTEGER DEFAULT (strftime('%s', 'now')),
@@ -71,6 +133,7 @@ CREATE TABLE IF NOT EXISTS schema_version (
 
 -- Indexes
 CREATE INDEX IF NOT EXISTS idx_benchmarks_name ON benchmarks(benchmark_name);
+CREATE INDEX IF NOT EXISTS idx_evaluation_runs_start_time ON evaluation_runs(start_time);
 CREATE INDEX IF NOT EXISTS idx_execution_sessions_benchmark_agent ON execution_sessions(benchmark_id, agent_type);
 CREATE INDEX IF NOT EXISTS idx_execution_sessions_interface ON execution_sessions(interface);
 CREATE INDEX IF NOT EXISTS idx_execution_sessions_status ON execution_sessions(status);
@@ -79,6 +142,7 @@ CREATE INDEX IF NOT EXISTS idx_session_logs_created_at ON session_logs(created_a
 CREATE INDEX IF NOT EXISTS idx_agent_performance_session_id ON agent_performance(session_id);
 CREATE INDEX IF NOT EXISTS idx_agent_performance_prompt_md5 ON agent_performance(prompt_md5);
 CREATE INDEX IF NOT EXISTS idx_agent_performance_score ON agent_performance(score);
+CREATE INDEX IF NOT EXISTS idx_session_llm_usage_run_id ON session_llm_usage(run_id);
 CREATE INDEX IF NOT EXISTS idx_agent_performance_created_at ON agent_performance(created_at);
 
 -- Indexes for session_tool_calls table
@@ -90,5 +154,13 @@ CREATE INDEX IF NOT EXISTS idx_session_tool_calls_session_tool ON session_tool_c
 CREATE INDEX IF NOT EXISTS idx_session_tool_calls_consolidation ON session_tool_calls(session_id, tool_name, start_time);
 CREATE INDEX IF NOT EXISTS idx_session_tool_calls_updated_at ON session_tool_calls(updated_at);
 
+-- Indexes for session_balance_deltas table
+CREATE INDEX IF NOT EXISTS idx_session_balance_deltas_session_id ON session_balance_deltas(session_id);
+CREATE INDEX IF NOT EXISTS idx_session_balance_deltas_pubkey ON session_balance_deltas(pubkey);
+
+-- Indexes for session_efficiency_metrics table
+CREATE INDEX IF NOT EXISTS idx_session_efficiency_metrics_agent_type ON session_efficiency_metrics(agent_type);
+CREATE INDEX IF NOT EXISTS idx_session_efficiency_metrics_benchmark_id ON session_efficiency_metrics(benchmark_id);
+
 -- Initial data (skip auto-insertion for compatibility)
 -- INSERT OR IGNORE INTO schema_version (version, description) VALUES ('1.0', 'Phase 25: Unified logging system with session management');
@@ -33,6 +33,13 @@ impl DatabaseConfig {
         }
     }
 
+    /// Create configuration from the `[database]` section of the reev configuration
+    pub fn from_settings(settings: &reev_config::DatabaseSettings) -> Self {
+        Self::new(settings.path.clone())
+            .with_pooling(settings.enable_pooling)
+            .with_max_pool_size(settings.max_connections)
+    }
+
     /// Create configuration for local SQLite database
     pub fn local<P: Into<String>>(path: P) -> Self {
         Self::new(path)
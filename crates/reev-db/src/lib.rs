@@ -37,23 +37,29 @@
 
 pub mod config;
 pub mod error;
+pub mod matrix;
 pub mod pool;
 pub mod reader;
 pub mod shared;
+pub mod trials;
 pub mod types;
 pub mod writer;
 
 // Re-export commonly used types
 pub use config::DatabaseConfig;
 pub use error::{DatabaseError, Result};
+pub use matrix::ScoreMatrix;
 pub use pool::PooledDatabaseWriter;
 pub use reader::DatabaseReader;
+pub use trials::TrialStats;
 // Import shared types first, then legacy types for backward compatibility
 pub use shared::prelude::*;
 // Legacy types - re-export only ones not in shared module to avoid ambiguity
 pub use types::{
-    AgentPerformanceSummary, BatchError, BatchResult, DatabaseStats, DuplicateRecord,
-    PerformanceResult, QueryFilter, SyncError, SyncResult, SyncedBenchmark,
+    AgentEfficiencySummary, AgentPerformanceSummary, BatchError, BatchResult, BenchmarkCost,
+    DatabaseStats, DuplicateRecord, EvaluationRun, EvaluationRunSummary, PerformanceResult,
+    QueryFilter, RunAgentConfig, SessionEfficiency, SessionLlmUsage, SyncError, SyncResult,
+    SyncedBenchmark,
 };
 pub use writer::DatabaseWriter;
 
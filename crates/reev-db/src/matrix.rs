@@ -0,0 +1,98 @@
+//! Benchmark × agent score matrix
+//!
+//! Running the same benchmarks with several agents in one go yields a matrix with
+//! a row per benchmark and a column per agent. Each cell summarizes the agent's
+//! latest batch of trials at the benchmark, as [`TrialStats`] does.
+
+use crate::trials::TrialStats;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Outcome of one agent at one benchmark
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatrixCell {
+    /// Mean score over the trials
+    pub mean_score: f64,
+    /// Number of trials that passed
+    pub passed: usize,
+    /// Number of trials
+    pub trials: usize,
+}
+
+/// Outcomes of every agent at one benchmark
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatrixRow {
+    /// Benchmark identifier
+    pub benchmark_id: String,
+    /// One cell per agent, in the order of [`ScoreMatrix::agents`]; `None` where the
+    /// agent has no result for the benchmark
+    pub cells: Vec<Option<MatrixCell>>,
+}
+
+/// Scores of several agents over the same benchmarks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreMatrix {
+    /// Agent of each column
+    pub agents: Vec<String>,
+    /// One row per benchmark, sorted by benchmark id
+    pub rows: Vec<MatrixRow>,
+}
+
+impl ScoreMatrix {
+    /// Arrange trial statistics into a matrix with a column per agent of `agents`
+    ///
+    /// Statistics of agents not in `agents` are ignored.
+    pub fn from_stats(agents: &[String], stats: &[TrialStats]) -> Self {
+        let mut rows: BTreeMap<&str, Vec<Option<MatrixCell>>> = BTreeMap::new();
+        for stat in stats {
+            let Some(column) = agents.iter().position(|a| *a == stat.agent_type) else {
+                continue;
+            };
+            rows.entry(&stat.benchmark_id)
+                .or_insert_with(|| vec![None; agents.len()])[column] = Some(MatrixCell {
+                mean_score: stat.mean_score,
+                passed: stat.passed,
+                trials: stat.trials,
+            });
+        }
+
+        Self {
+            agents: agents.to_vec(),
+            rows: rows
+                .into_iter()
+                .map(|(benchmark_id, cells)| MatrixRow {
+                    benchmark_id: benchmark_id.to_string(),
+                    cells,
+                })
+                .collect(),
+        }
+    }
+
+    /// Mean of each agent's cell scores, `None` for an agent without results
+    pub fn agent_means(&self) -> Vec<Option<f64>> {
+        (0..self.agents.len())
+            .map(|column| {
+                let scores: Vec<f64> = self
+                    .rows
+                    .iter()
+                    .filter_map(|row| row.cells[column].as_ref())
+                    .map(|cell| cell.mean_score)
+                    .collect();
+                (!scores.is_empty()).then(|| scores.iter().sum::<f64>() / scores.len() as f64)
+            })
+            .collect()
+    }
+
+    /// Number of benchmarks each agent passed in every trial
+    pub fn agent_passes(&self) -> Vec<usize> {
+        (0..self.agents.len())
+            .map(|column| {
+                self.rows
+                    .iter()
+                    .filter_map(|row| row.cells[column].as_ref())
+                    .filter(|cell| cell.passed == cell.trials)
+                    .count()
+            })
+            .collect()
+    }
+}
@@ -94,31 +94,7 @@ impl ConnectionPool {
         })?;
 
         // Initialize schema for new connection
-        let schema_string = include_str!("../../.schema/current_schema.sql")
-            .lines()
-            .map(|line| line.trim())
-            .filter(|line| !line.is_empty() && !line.starts_with("--"))
-            .collect::<Vec<&str>>()
-            .join(" ");
-
-        let statements: Vec<&str> = schema_string
-            .split(';')
-            .map(|s| s.trim())
-            .filter(|s| !s.is_empty())
-            .collect();
-
-        for statement in statements.iter() {
-            if statement.trim().is_empty() {
-                continue;
-            }
-
-            conn.execute(statement, ()).await.map_err(|e| {
-                DatabaseError::schema_with_source(
-                    format!("Failed to execute schema statement: {statement}"),
-                    e,
-                )
-            })?;
-        }
+        crate::writer::core::initialize_schema(&conn).await?;
 
         // Add connection to pool
         let mut connections = self.connections.lock().await;
@@ -10,7 +10,7 @@ use crate::{
     types::{BenchmarkData, QueryFilter},
 };
 use std::sync::Arc;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 /// Pooled database writer that uses connection pool for concurrent operations
 pub struct PooledDatabaseWriter {
@@ -147,6 +147,27 @@ impl PooledDatabaseWriter {
         writer.complete_session(session_id, &result).await
     }
 
+    pub async fn time_out_session(&self, session_id: &str, end_time: i64) -> Result<()> {
+        let conn = self.get_connection().await?;
+        let writer =
+            crate::DatabaseWriter::from_connection(conn.connection().clone(), self.config.clone());
+        writer.time_out_session(session_id, end_time).await
+    }
+
+    pub async fn record_budget_exceeded(&self, session_id: &str, limit: &str) -> Result<()> {
+        let conn = self.get_connection().await?;
+        let writer =
+            crate::DatabaseWriter::from_connection(conn.connection().clone(), self.config.clone());
+        writer.record_budget_exceeded(session_id, limit).await
+    }
+
+    pub async fn record_argument_retries(&self, session_id: &str, retries: u32) -> Result<()> {
+        let conn = self.get_connection().await?;
+        let writer =
+            crate::DatabaseWriter::from_connection(conn.connection().clone(), self.config.clone());
+        writer.record_argument_retries(session_id, retries).await
+    }
+
     pub async fn store_complete_log(&self, session_id: &str, log_content: &str) -> Result<()> {
         let conn = self.get_connection().await?;
         let writer =
@@ -192,7 +213,7 @@ impl PooledDatabaseWriter {
         let writer = crate::DatabaseReader::from_connection(conn.connection().clone());
 
         // Build query based on filter
-        let mut query = "SELECT session_id, benchmark_id, agent_type, interface, start_time, end_time, status, final_status FROM execution_sessions".to_string();
+        let mut query = "SELECT session_id, benchmark_id, agent_type, interface, start_time, end_time, status, final_status, run_id FROM execution_sessions".to_string();
         let mut where_clauses = Vec::new();
         let mut params = Vec::new();
 
@@ -276,6 +297,7 @@ impl PooledDatabaseWriter {
                     .map_err(|_e| crate::error::DatabaseError::generic("Failed to get status"))?,
                 score: None, // Not available in this query
                 final_status: row.get(7).ok(),
+                run_id: row.get(8).ok().flatten(),
             });
         }
 
@@ -291,6 +313,51 @@ impl PooledDatabaseWriter {
         writer.get_session(session_id).await
     }
 
+    // Evaluation run operations
+    pub async fn create_run(&self, run: &crate::types::EvaluationRun) -> Result<()> {
+        let conn = self.get_connection().await?;
+        let writer =
+            crate::DatabaseWriter::from_connection(conn.connection().clone(), self.config.clone());
+        writer.create_run(run).await
+    }
+
+    pub async fn complete_run(&self, run_id: &str, status: &str, end_time: i64) -> Result<()> {
+        let conn = self.get_connection().await?;
+        let writer =
+            crate::DatabaseWriter::from_connection(conn.connection().clone(), self.config.clone());
+        writer.complete_run(run_id, status, end_time).await
+    }
+
+    pub async fn get_run(&self, run_id: &str) -> Result<Option<crate::types::EvaluationRun>> {
+        let conn = self.get_connection().await?;
+        let reader = crate::DatabaseReader::from_connection(conn.connection().clone());
+        reader.get_run(run_id).await
+    }
+
+    pub async fn list_runs(
+        &self,
+        limit: Option<u32>,
+    ) -> Result<Vec<crate::types::EvaluationRunSummary>> {
+        let conn = self.get_connection().await?;
+        let reader = crate::DatabaseReader::from_connection(conn.connection().clone());
+        reader.list_runs(limit).await
+    }
+
+    pub async fn list_run_sessions(&self, run_id: &str) -> Result<Vec<crate::types::SessionInfo>> {
+        let conn = self.get_connection().await?;
+        let reader = crate::DatabaseReader::from_connection(conn.connection().clone());
+        reader.list_run_sessions(run_id).await
+    }
+
+    pub async fn get_run_benchmark_costs(
+        &self,
+        run_id: &str,
+    ) -> Result<Vec<crate::types::BenchmarkCost>> {
+        let conn = self.get_connection().await?;
+        let reader = crate::DatabaseReader::from_connection(conn.connection().clone());
+        reader.get_run_benchmark_costs(run_id).await
+    }
+
     // Performance operations
     pub async fn insert_agent_performance(
         &self,
@@ -312,12 +379,35 @@ impl PooledDatabaseWriter {
             timestamp: performance.timestamp.clone(),
             flow_log_id: performance.flow_log_id,
             prompt_md5: performance.prompt_md5.clone(),
+            trial_index: performance.trial_index,
+            run_id: performance.run_id.clone(),
+            rescored: performance.rescored,
             additional_metrics: performance.additional_metrics.clone(),
         };
 
         writer.insert_agent_performance(&writer_performance).await
     }
 
+    pub async fn insert_session_efficiency(
+        &self,
+        efficiency: &crate::types::SessionEfficiency,
+    ) -> Result<()> {
+        let conn = self.get_connection().await?;
+        let writer =
+            crate::DatabaseWriter::from_connection(conn.connection().clone(), self.config.clone());
+        writer.insert_session_efficiency(efficiency).await
+    }
+
+    pub async fn insert_session_llm_usage(
+        &self,
+        usage: &crate::types::SessionLlmUsage,
+    ) -> Result<()> {
+        let conn = self.get_connection().await?;
+        let writer =
+            crate::DatabaseWriter::from_connection(conn.connection().clone(), self.config.clone());
+        writer.insert_session_llm_usage(usage).await
+    }
+
     pub async fn get_agent_performance(
         &self,
         filter: &QueryFilter,
@@ -351,8 +441,15 @@ impl PooledDatabaseWriter {
                 }
 
                 let mut summaries = Vec::new();
+                let conn = self.get_connection().await?;
+                let writer = crate::DatabaseWriter::from_connection(
+                    conn.connection().clone(),
+                    self.config.clone(),
+                );
 
                 for (agent_type, records) in agent_data {
+                    let trial_stats =
+                        crate::trials::latest_trial_stats(records.iter().map(Into::into), None);
                     let total_benchmarks = records.len() as i64;
                     let total_score: f64 = records.iter().map(|r| r.score).sum();
                     let average_score = if total_benchmarks > 0 {
@@ -384,9 +481,18 @@ impl PooledDatabaseWriter {
                             score: perf.score,
                             final_status: perf.final_status,
                             timestamp: perf.timestamp,
+                            trial_index: perf.trial_index,
                         })
                         .collect();
 
+                    let efficiency = writer
+                        .get_agent_efficiency(&agent_type)
+                        .await
+                        .unwrap_or_else(|e| {
+                            warn!("Failed to get efficiency for {}: {}", agent_type, e);
+                            None
+                        });
+
                     summaries.push(crate::types::AgentPerformanceSummary {
                         agent_type,
                         total_benchmarks,
@@ -395,6 +501,8 @@ impl PooledDatabaseWriter {
                         best_benchmarks: vec![],  // TODO: Calculate properly
                         worst_benchmarks: vec![], // TODO: Calculate properly
                         results,
+                        efficiency,
+                        trial_stats,
                     });
                 }
 
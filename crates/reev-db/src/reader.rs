@@ -5,7 +5,10 @@
 
 use crate::{
     error::{DatabaseError, Result},
-    types::{AgentPerformance, QueryFilter, TestResult, YmlTestResult},
+    types::{
+        AgentPerformance, EvaluationRun, EvaluationRunSummary, QueryFilter, SessionInfo,
+        TestResult, YmlTestResult,
+    },
 };
 use reev_flow::database::{DBFlowLog, DBFlowLogConverter};
 use std::collections::HashMap;
@@ -307,14 +310,19 @@ impl DatabaseReader {
     }
 
     /// Get agent performance metrics
+    ///
+    /// The `argument_retries` of each record's session are included in its
+    /// `additional_metrics`.
     pub async fn get_agent_performance(
         &self,
         filter: Option<QueryFilter>,
     ) -> Result<Vec<AgentPerformance>> {
         let mut query = "
-            SELECT id, session_id, benchmark_id, agent_type, score, final_status,
-                   execution_time_ms, created_at, prompt_md5
-            FROM agent_performance
+            SELECT ap.id, ap.session_id, ap.benchmark_id, ap.agent_type, ap.score, ap.final_status,
+                   ap.execution_time_ms, ap.created_at, ap.prompt_md5, ap.trial_index, ap.run_id,
+                   ap.rescored, es.argument_retries
+            FROM agent_performance ap
+            LEFT JOIN execution_sessions es ON es.session_id = ap.session_id
         "
         .to_string();
 
@@ -323,22 +331,27 @@ impl DatabaseReader {
 
         if let Some(f) = filter {
             if let Some(agent_type) = f.agent_type {
-                where_clauses.push("agent_type = ?");
+                where_clauses.push("ap.agent_type = ?");
                 params.push(agent_type);
             }
 
             if let Some(benchmark_id) = f.benchmark_name {
-                where_clauses.push("benchmark_id LIKE ?");
+                where_clauses.push("ap.benchmark_id LIKE ?");
                 params.push(format!("%{benchmark_id}%"));
             }
 
+            if let Some(run_id) = f.run_id {
+                where_clauses.push("ap.run_id = ?");
+                params.push(run_id);
+            }
+
             if let Some(min_score) = f.min_score {
-                where_clauses.push("score >= ?");
+                where_clauses.push("ap.score >= ?");
                 params.push(min_score.to_string());
             }
 
             if let Some(max_score) = f.max_score {
-                where_clauses.push("score <= ?");
+                where_clauses.push("ap.score <= ?");
                 params.push(max_score.to_string());
             }
 
@@ -347,13 +360,13 @@ impl DatabaseReader {
                 query.push_str(&where_clauses.join(" AND "));
             }
 
-            query.push_str(" ORDER BY created_at DESC");
+            query.push_str(" ORDER BY ap.created_at DESC, ap.id DESC");
 
             if let Some(limit) = f.limit {
                 query.push_str(&format!(" LIMIT {limit}"));
             }
         } else {
-            query.push_str(" ORDER BY created_at DESC");
+            query.push_str(" ORDER BY ap.created_at DESC, ap.id DESC");
         }
 
         let mut stmt =
@@ -435,7 +448,17 @@ impl DatabaseReader {
                     .map_err(|_| DatabaseError::generic("Failed to get created_at"))?,
                 flow_log_id: None,
                 prompt_md5: row.get(8).ok(),
-                additional_metrics: HashMap::new(),
+                trial_index: row.get::<i64>(9).unwrap_or_default() as u32,
+                run_id: row.get(10).ok().flatten(),
+                rescored: row.get::<i64>(11).unwrap_or_default() != 0,
+                additional_metrics: row
+                    .get::<Option<i64>>(12)
+                    .ok()
+                    .flatten()
+                    .map(|retries| {
+                        HashMap::from([("argument_retries".to_string(), retries as f64)])
+                    })
+                    .unwrap_or_default(),
             });
         }
 
@@ -446,6 +469,122 @@ impl DatabaseReader {
         Ok(performances)
     }
 
+    /// Get the most recent performance record per benchmark and agent
+    ///
+    /// Optionally restricted to a single agent type.
+    pub async fn get_latest_results(
+        &self,
+        agent_type: Option<&str>,
+    ) -> Result<Vec<AgentPerformance>> {
+        let filter = QueryFilter {
+            agent_type: agent_type.map(str::to_string),
+            ..Default::default()
+        };
+        self.get_latest_matching(filter).await
+    }
+
+    /// Get the most recent performance record per benchmark and agent of an evaluation run
+    pub async fn get_latest_run_results(&self, run_id: &str) -> Result<Vec<AgentPerformance>> {
+        self.get_latest_matching(QueryFilter::new().run_id(run_id))
+            .await
+    }
+
+    async fn get_latest_matching(&self, filter: QueryFilter) -> Result<Vec<AgentPerformance>> {
+        let performances = self.get_agent_performance(Some(filter)).await?;
+
+        // Records arrive newest first, so the first one seen per pair is the latest
+        let mut seen = std::collections::HashSet::new();
+        let mut latest: Vec<AgentPerformance> = performances
+            .into_iter()
+            .filter(|p| seen.insert((p.agent_type.clone(), p.benchmark_id.clone())))
+            .collect();
+        latest.sort_by(|a, b| {
+            a.agent_type
+                .cmp(&b.agent_type)
+                .then_with(|| a.benchmark_id.cmp(&b.benchmark_id))
+        });
+
+        debug!("[DB] Retrieved {} latest results", latest.len());
+        Ok(latest)
+    }
+
+    /// Get an execution session by ID
+    pub async fn get_session(&self, session_id: &str) -> Result<Option<SessionInfo>> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT session_id, benchmark_id, agent_type, interface, start_time, end_time, status, score, final_status, run_id
+                 FROM execution_sessions WHERE session_id = ?",
+                [session_id],
+            )
+            .await
+            .map_err(|e| DatabaseError::query("Failed to get session", e))?;
+
+        if let Some(row) = rows.next().await? {
+            Ok(Some(SessionInfo {
+                session_id: row.get(0)?,
+                benchmark_id: row.get(1)?,
+                agent_type: row.get(2)?,
+                interface: row.get(3)?,
+                start_time: row.get(4)?,
+                end_time: row.get(5)?,
+                status: row.get(6)?,
+                score: row.get(7)?,
+                final_status: row.get(8)?,
+                run_id: row.get(9)?,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// The budget limit that stopped a session, if one did
+    pub async fn get_budget_exceeded(&self, session_id: &str) -> Result<Option<String>> {
+        crate::writer::sessions::get_budget_exceeded(&self.conn, session_id).await
+    }
+
+    /// Get an evaluation run by ID
+    pub async fn get_run(&self, run_id: &str) -> Result<Option<EvaluationRun>> {
+        crate::writer::runs::get_run(&self.conn, run_id).await
+    }
+
+    /// List evaluation runs, newest first, with the outcome of their sessions
+    pub async fn list_runs(&self, limit: Option<u32>) -> Result<Vec<EvaluationRunSummary>> {
+        crate::writer::runs::list_runs(&self.conn, limit).await
+    }
+
+    /// List the sessions of an evaluation run, oldest first
+    pub async fn list_run_sessions(&self, run_id: &str) -> Result<Vec<SessionInfo>> {
+        crate::writer::runs::list_run_sessions(&self.conn, run_id).await
+    }
+
+    /// LLM cost of each benchmark of an evaluation run
+    pub async fn get_run_benchmark_costs(
+        &self,
+        run_id: &str,
+    ) -> Result<Vec<crate::types::BenchmarkCost>> {
+        crate::writer::usage::run_benchmark_costs(&self.conn, run_id).await
+    }
+
+    /// Get the stored log (execution trace) of a session
+    pub async fn get_session_log(&self, session_id: &str) -> Result<Option<String>> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT content FROM session_logs WHERE session_id = ?",
+                [session_id],
+            )
+            .await
+            .map_err(|e| DatabaseError::query("Failed to retrieve session log", e))?;
+
+        match rows.next().await? {
+            Some(row) => Ok(Some(row.get(0).map_err(|e| {
+                DatabaseError::generic_with_source("Failed to parse session log", e)
+            })?)),
+            None => Ok(None),
+        }
+    }
+
     /// Get YAML test results for a specific benchmark and agent
     pub async fn get_yml_test_results(
         &self,
@@ -32,6 +32,15 @@ pub struct AgentPerformance {
     pub flow_log_id: Option<i64>,
     /// Reference to the benchmark prompt
     pub prompt_md5: Option<String>,
+    /// Index of this attempt when the benchmark was run several times, from 0
+    #[serde(default)]
+    pub trial_index: u32,
+    /// Evaluation run this attempt belongs to
+    #[serde(default)]
+    pub run_id: Option<String>,
+    /// Whether the score was recomputed from the stored trace of an earlier attempt
+    #[serde(default)]
+    pub rescored: bool,
     /// Additional performance metrics
     pub additional_metrics: HashMap<String, f64>,
 }
@@ -0,0 +1,216 @@
+//! Statistics over repeated trials of a benchmark
+//!
+//! LLM agents are stochastic, so a benchmark can be run several times in one go,
+//! each attempt stored in `agent_performance` with its `trial_index`. This module
+//! summarizes such a batch: mean and spread of the score, pass@1, pass@k and a
+//! bootstrap confidence interval of the mean score.
+
+use crate::types::{AgentPerformance, PerformanceResult};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Confidence level of [`TrialStats::ci_low`] and [`TrialStats::ci_high`]
+pub const CONFIDENCE: f64 = 0.95;
+
+/// Number of resamples drawn for the bootstrap confidence interval
+pub const BOOTSTRAP_RESAMPLES: usize = 1000;
+
+/// Seed of the bootstrap resampling, fixed so reports are reproducible
+const BOOTSTRAP_SEED: u64 = 0x7265_6576;
+
+/// Score statistics of one agent over repeated trials of one benchmark
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrialStats {
+    /// Benchmark identifier
+    pub benchmark_id: String,
+    /// Agent type
+    pub agent_type: String,
+    /// Number of trials
+    pub trials: usize,
+    /// Number of trials that passed
+    pub passed: usize,
+    /// Mean score over the trials
+    pub mean_score: f64,
+    /// Sample standard deviation of the score; 0 for a single trial
+    pub std_dev: f64,
+    /// Probability that a single attempt passes
+    pub pass_at_1: f64,
+    /// The `k` of [`TrialStats::pass_at_k`]
+    pub k: usize,
+    /// Probability that at least one of `k` attempts passes
+    pub pass_at_k: f64,
+    /// Lower bound of the bootstrap confidence interval of the mean score
+    pub ci_low: f64,
+    /// Upper bound of the bootstrap confidence interval of the mean score
+    pub ci_high: f64,
+}
+
+impl TrialStats {
+    /// Summarize trial outcomes given as `(score, passed)` pairs
+    ///
+    /// `k` defaults to the number of trials and is capped at it. Returns `None` when
+    /// there are no outcomes.
+    pub fn from_outcomes(
+        benchmark_id: &str,
+        agent_type: &str,
+        outcomes: &[(f64, bool)],
+        k: Option<usize>,
+    ) -> Option<Self> {
+        if outcomes.is_empty() {
+            return None;
+        }
+        let n = outcomes.len();
+        let passed = outcomes.iter().filter(|(_, passed)| *passed).count();
+        let scores: Vec<f64> = outcomes.iter().map(|(score, _)| *score).collect();
+        let k = k.unwrap_or(n).clamp(1, n);
+        let (ci_low, ci_high) = bootstrap_mean_ci(&scores, CONFIDENCE, BOOTSTRAP_RESAMPLES);
+
+        Some(Self {
+            benchmark_id: benchmark_id.to_string(),
+            agent_type: agent_type.to_string(),
+            trials: n,
+            passed,
+            mean_score: mean(&scores),
+            std_dev: std_dev(&scores),
+            pass_at_1: pass_at_k(n, passed, 1),
+            k,
+            pass_at_k: pass_at_k(n, passed, k),
+            ci_low,
+            ci_high,
+        })
+    }
+}
+
+/// One stored attempt, as needed to group trials into batches
+#[derive(Debug, Clone, Copy)]
+pub struct TrialRecord<'a> {
+    pub agent_type: &'a str,
+    pub benchmark_id: &'a str,
+    pub score: f64,
+    pub final_status: &'a str,
+    pub trial_index: u32,
+}
+
+impl<'a> From<&'a AgentPerformance> for TrialRecord<'a> {
+    fn from(performance: &'a AgentPerformance) -> Self {
+        Self {
+            agent_type: &performance.agent_type,
+            benchmark_id: &performance.benchmark_id,
+            score: performance.score,
+            final_status: &performance.final_status,
+            trial_index: performance.trial_index,
+        }
+    }
+}
+
+impl<'a> TrialRecord<'a> {
+    /// A stored result of `agent_type`
+    pub fn from_result(agent_type: &'a str, result: &'a PerformanceResult) -> Self {
+        Self {
+            agent_type,
+            benchmark_id: &result.benchmark_id,
+            score: result.score,
+            final_status: &result.final_status,
+            trial_index: result.trial_index,
+        }
+    }
+}
+
+/// Outcomes of one batch of trials, newest first
+#[derive(Default)]
+struct Batch {
+    outcomes: Vec<(f64, bool)>,
+    /// Whether the first trial of the batch has been seen
+    complete: bool,
+}
+
+/// Statistics of the latest batch of trials per agent and benchmark
+///
+/// `records` must be ordered newest first, as stored results are returned. A batch
+/// is the run of records back to and including the newest one with `trial_index` 0.
+/// Results are sorted by agent and benchmark.
+pub fn latest_trial_stats<'a>(
+    records: impl IntoIterator<Item = TrialRecord<'a>>,
+    k: Option<usize>,
+) -> Vec<TrialStats> {
+    let mut batches: BTreeMap<(&str, &str), Batch> = BTreeMap::new();
+    for record in records {
+        let batch = batches
+            .entry((record.agent_type, record.benchmark_id))
+            .or_default();
+        if batch.complete {
+            continue;
+        }
+        batch
+            .outcomes
+            .push((record.score, is_success(record.final_status)));
+        batch.complete = record.trial_index == 0;
+    }
+
+    batches
+        .into_iter()
+        .filter_map(|((agent_type, benchmark_id), batch)| {
+            TrialStats::from_outcomes(benchmark_id, agent_type, &batch.outcomes, k)
+        })
+        .collect()
+}
+
+/// Whether a stored `final_status` counts as a pass
+pub fn is_success(final_status: &str) -> bool {
+    final_status.eq_ignore_ascii_case("succeeded") || final_status.eq_ignore_ascii_case("completed")
+}
+
+/// Unbiased pass@k estimate from `n` trials of which `c` passed
+///
+/// The probability that at least one of `k` attempts drawn without replacement from
+/// the trials passes: `1 - C(n-c, k) / C(n, k)`.
+pub fn pass_at_k(n: usize, c: usize, k: usize) -> f64 {
+    if n == 0 {
+        return 0.0;
+    }
+    if n - c < k {
+        return 1.0;
+    }
+    // C(n-c, k) / C(n, k) as a running product to avoid large binomials
+    let all_fail: f64 = (n - c + 1..=n).map(|i| 1.0 - k as f64 / i as f64).product();
+    1.0 - all_fail
+}
+
+/// Percentile bootstrap confidence interval of the mean of `scores`
+pub fn bootstrap_mean_ci(scores: &[f64], confidence: f64, resamples: usize) -> (f64, f64) {
+    match scores {
+        [] => return (0.0, 0.0),
+        [score] => return (*score, *score),
+        _ => {}
+    }
+
+    let mut rng = StdRng::seed_from_u64(BOOTSTRAP_SEED);
+    let mut means: Vec<f64> = (0..resamples.max(1))
+        .map(|_| {
+            let total: f64 = (0..scores.len())
+                .map(|_| scores[rng.gen_range(0..scores.len())])
+                .sum();
+            total / scores.len() as f64
+        })
+        .collect();
+    means.sort_by(f64::total_cmp);
+
+    let tail = (1.0 - confidence) / 2.0;
+    let at = |q: f64| means[((means.len() - 1) as f64 * q).round() as usize];
+    (at(tail), at(1.0 - tail))
+}
+
+fn mean(scores: &[f64]) -> f64 {
+    scores.iter().sum::<f64>() / scores.len() as f64
+}
+
+fn std_dev(scores: &[f64]) -> f64 {
+    if scores.len() < 2 {
+        return 0.0;
+    }
+    let mean = mean(scores);
+    let variance =
+        scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (scores.len() - 1) as f64;
+    variance.sqrt()
+}
@@ -7,7 +7,7 @@
 //! reuse across projects and eliminate duplication.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 // Re-export shared types for backward compatibility
 pub use crate::shared::flow::{DBFlowLog, ExecutionResult};
@@ -24,6 +24,9 @@ pub struct SessionInfo {
     pub status: String, // 'running', 'completed', 'failed'
     pub score: Option<f64>,
     pub final_status: Option<String>,
+    /// Evaluation run this session belongs to
+    #[serde(default)]
+    pub run_id: Option<String>,
 }
 
 /// Result of a completed session
@@ -44,6 +47,54 @@ pub struct SessionFilter {
     pub limit: Option<i32>,
 }
 
+/// Agent settings an evaluation run was made with
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunAgentConfig {
+    /// Model name, e.g. `glm-4.6`
+    pub model: String,
+    /// Sampling temperature sent to the model
+    pub temperature: Option<f64>,
+    /// Version of the agent prompts
+    pub prompt_version: Option<String>,
+}
+
+/// A set of sessions executed together, with what is needed to reproduce them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvaluationRun {
+    pub run_id: String,
+    pub agent_type: String,
+    pub agent_config: RunAgentConfig,
+    /// Commit of the repository the run was made from
+    pub git_commit: Option<String>,
+    /// SHA-256 of each benchmark file, keyed by benchmark id
+    pub benchmark_hashes: BTreeMap<String, String>,
+    pub seed: Option<u64>,
+    /// Number of attempts at each benchmark
+    pub trials: u32,
+    pub status: String,  // 'running', 'completed', 'failed'
+    pub start_time: i64, // Unix timestamp
+    pub end_time: Option<i64>,
+}
+
+/// An evaluation run with the outcome of its sessions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvaluationRunSummary {
+    #[serde(flatten)]
+    pub run: EvaluationRun,
+    /// Number of recorded attempts
+    pub total: i64,
+    /// Number of attempts that passed
+    pub passed: i64,
+    /// Average score over the attempts, if any were recorded
+    pub average_score: Option<f64>,
+    /// LLM cost of the run's priced sessions, in USD
+    #[serde(default)]
+    pub cost_usd: Option<f64>,
+    /// Score earned per USD of LLM cost
+    #[serde(default)]
+    pub score_per_dollar: Option<f64>,
+}
+
 /// Log event for structured session logging
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LogEvent {
@@ -141,6 +192,119 @@ pub struct AgentPerformanceSummary {
     pub worst_benchmarks: Vec<String>,
     /// Recent results (simplified for API)
     pub results: Vec<PerformanceResult>,
+    /// Compute unit, fee and instruction averages across recorded runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub efficiency: Option<AgentEfficiencySummary>,
+    /// Score statistics over the latest batch of trials of each benchmark
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trial_stats: Vec<crate::trials::TrialStats>,
+}
+
+/// Execution cost recorded for a single session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionEfficiency {
+    /// Session identifier
+    pub session_id: String,
+    /// Benchmark identifier
+    pub benchmark_id: String,
+    /// Agent type
+    pub agent_type: String,
+    /// Total compute units consumed
+    pub compute_units: u64,
+    /// Total fees paid, in lamports
+    pub fee_lamports: u64,
+    /// Number of instructions produced by the agent
+    pub instruction_count: u32,
+    /// Number of transactions executed
+    pub transaction_count: u32,
+    /// Efficiency score against the benchmark budget, if it declares one
+    pub efficiency_score: Option<f64>,
+}
+
+/// LLM tokens used by a session and their cost
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionLlmUsage {
+    /// Session identifier
+    pub session_id: String,
+    /// Benchmark identifier
+    pub benchmark_id: String,
+    /// Agent type
+    pub agent_type: String,
+    /// Registered model that was called
+    pub model: String,
+    /// Evaluation run the session belongs to
+    pub run_id: Option<String>,
+    /// Prompt, completion and cached tokens
+    #[serde(flatten)]
+    pub tokens: reev_flow::TokenUsage,
+    /// Cost of the tokens in USD, if the model is priced
+    pub cost_usd: Option<f64>,
+}
+
+/// LLM cost of the attempts at one benchmark in an evaluation run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkCost {
+    /// Benchmark identifier
+    pub benchmark_id: String,
+    /// Number of attempts with recorded usage
+    pub attempts: i64,
+    /// Tokens used over the attempts
+    #[serde(flatten)]
+    pub tokens: reev_flow::TokenUsage,
+    /// Cost of the attempts in USD, if the model is priced
+    pub cost_usd: Option<f64>,
+    /// Average cost of an attempt in USD
+    pub cost_per_attempt_usd: Option<f64>,
+    /// Average score of the attempts
+    pub average_score: Option<f64>,
+    /// Score earned per USD of LLM cost
+    pub score_per_dollar: Option<f64>,
+}
+
+/// Score earned per USD of `cost_usd`, unknown for unpriced or free runs
+pub fn score_per_dollar(score_sum: f64, cost_usd: Option<f64>) -> Option<f64> {
+    cost_usd
+        .filter(|cost| *cost > 0.0)
+        .map(|cost| score_sum / cost)
+}
+
+/// Per-agent efficiency averages for API responses
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentEfficiencySummary {
+    /// Number of runs with recorded efficiency metrics
+    pub runs: i64,
+    /// Average compute units per run
+    pub avg_compute_units: f64,
+    /// Average fee per run, in lamports
+    pub avg_fee_lamports: f64,
+    /// Average instruction count per run
+    pub avg_instruction_count: f64,
+    /// Average transaction count per run
+    pub avg_transaction_count: f64,
+    /// Average efficiency score over runs of budgeted benchmarks
+    pub avg_efficiency_score: Option<f64>,
+}
+
+impl AgentEfficiencySummary {
+    /// Aggregates session metrics; returns `None` when there are none
+    pub fn from_sessions(sessions: &[SessionEfficiency]) -> Option<Self> {
+        if sessions.is_empty() {
+            return None;
+        }
+        let runs = sessions.len() as f64;
+        let mean = |f: fn(&SessionEfficiency) -> f64| sessions.iter().map(f).sum::<f64>() / runs;
+        let scores: Vec<f64> = sessions.iter().filter_map(|s| s.efficiency_score).collect();
+
+        Some(Self {
+            runs: sessions.len() as i64,
+            avg_compute_units: mean(|s| s.compute_units as f64),
+            avg_fee_lamports: mean(|s| s.fee_lamports as f64),
+            avg_instruction_count: mean(|s| s.instruction_count as f64),
+            avg_transaction_count: mean(|s| s.transaction_count as f64),
+            avg_efficiency_score: (!scores.is_empty())
+                .then(|| scores.iter().sum::<f64>() / scores.len() as f64),
+        })
+    }
 }
 
 /// Simplified performance result for API
@@ -152,6 +316,8 @@ pub struct PerformanceResult {
     pub score: f64,
     pub final_status: String,
     pub timestamp: String,
+    #[serde(default)]
+    pub trial_index: u32,
 }
 
 /// Agent performance metrics
@@ -177,6 +343,15 @@ pub struct AgentPerformance {
     pub flow_log_id: Option<i64>,
     /// Reference to the benchmark prompt
     pub prompt_md5: Option<String>,
+    /// Index of this attempt when the benchmark was run several times, from 0
+    #[serde(default)]
+    pub trial_index: u32,
+    /// Evaluation run this attempt belongs to
+    #[serde(default)]
+    pub run_id: Option<String>,
+    /// Whether the score was recomputed from the stored trace of an earlier attempt
+    #[serde(default)]
+    pub rescored: bool,
     /// Additional performance metrics
     pub additional_metrics: HashMap<String, f64>,
 }
@@ -306,6 +481,8 @@ pub struct QueryFilter {
     pub date_from: Option<String>,
     /// Filter by date range (end)
     pub date_to: Option<String>,
+    /// Filter by evaluation run
+    pub run_id: Option<String>,
     /// Limit number of results
     pub limit: Option<u32>,
     /// Offset for pagination
@@ -334,6 +511,12 @@ impl QueryFilter {
         self
     }
 
+    /// Filter by evaluation run
+    pub fn run_id<S: Into<String>>(mut self, run_id: S) -> Self {
+        self.run_id = Some(run_id.into());
+        self
+    }
+
     /// Filter by score range
     pub fn score_range(mut self, min: f64, max: f64) -> Self {
         self.min_score = Some(min);
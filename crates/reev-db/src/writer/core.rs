@@ -14,6 +14,106 @@ use turso::{Builder, Connection};
 /// Current database schema loaded from external file
 const CURRENT_SCHEMA: &str = include_str!("../../.schema/current_schema.sql");
 
+/// Columns added to existing tables after their first release, as `(table, column, definition)`
+///
+/// `CREATE TABLE IF NOT EXISTS` leaves older databases untouched, so these are added
+/// with `ALTER TABLE` when missing. Each must also be present in `current_schema.sql`.
+const ADDED_COLUMNS: &[(&str, &str, &str)] = &[
+    (
+        "agent_performance",
+        "trial_index",
+        "INTEGER NOT NULL DEFAULT 0",
+    ),
+    ("execution_sessions", "run_id", "TEXT"),
+    ("agent_performance", "run_id", "TEXT"),
+    ("evaluation_runs", "trials", "INTEGER NOT NULL DEFAULT 1"),
+    (
+        "agent_performance",
+        "rescored",
+        "INTEGER NOT NULL DEFAULT 0",
+    ),
+    ("execution_sessions", "budget_exceeded", "TEXT"),
+    (
+        "execution_sessions",
+        "argument_retries",
+        "INTEGER NOT NULL DEFAULT 0",
+    ),
+];
+
+/// Indexes on [`ADDED_COLUMNS`], created once the columns exist
+const ADDED_INDEXES: &[&str] = &[
+    "CREATE INDEX IF NOT EXISTS idx_execution_sessions_run_id ON execution_sessions(run_id)",
+    "CREATE INDEX IF NOT EXISTS idx_agent_performance_run_id ON agent_performance(run_id)",
+];
+
+/// Apply the current schema to a connection, upgrading tables created by older versions
+pub(crate) async fn initialize_schema(conn: &Connection) -> Result<()> {
+    // Split schema into individual statements and filter out comments
+    let schema_string = CURRENT_SCHEMA
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with("--"))
+        .collect::<Vec<&str>>()
+        .join(" ");
+
+    let statements: Vec<&str> = schema_string
+        .split(';')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    // Execute each statement
+    for statement in statements.iter() {
+        conn.execute(statement, ()).await.map_err(|e| {
+            DatabaseError::schema_with_source(
+                format!("Failed to execute schema statement: {statement}"),
+                e,
+            )
+        })?;
+    }
+
+    for (table, column, definition) in ADDED_COLUMNS {
+        if table_has_column(conn, table, column).await? {
+            continue;
+        }
+        info!("[DB] Adding column {table}.{column}");
+        let statement = format!("ALTER TABLE {table} ADD COLUMN {column} {definition}");
+        conn.execute(&statement, ()).await.map_err(|e| {
+            DatabaseError::schema_with_source(
+                format!("Failed to execute schema statement: {statement}"),
+                e,
+            )
+        })?;
+    }
+
+    for statement in ADDED_INDEXES {
+        conn.execute(statement, ()).await.map_err(|e| {
+            DatabaseError::schema_with_source(
+                format!("Failed to execute schema statement: {statement}"),
+                e,
+            )
+        })?;
+    }
+
+    Ok(())
+}
+
+async fn table_has_column(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+    let mut rows = conn
+        .query(
+            &format!("SELECT name FROM pragma_table_info('{table}')"),
+            (),
+        )
+        .await
+        .map_err(|e| DatabaseError::query("Failed to read table columns", e))?;
+    while let Some(row) = rows.next().await? {
+        if row.get::<String>(0)? == column {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
 /// Main database writer for atomic operations with duplicate prevention
 pub struct DatabaseWriter {
     pub conn: Connection,
@@ -63,35 +163,7 @@ impl DatabaseWriter {
     /// Initialize database schema with all necessary tables and indexes
     async fn initialize_schema(&self) -> Result<()> {
         debug!("[DB] Initializing unified database schema from external file");
-
-        // Split schema into individual statements and filter out comments
-        let schema_string = CURRENT_SCHEMA
-            .lines()
-            .map(|line| line.trim())
-            .filter(|line| !line.is_empty() && !line.starts_with("--"))
-            .collect::<Vec<&str>>()
-            .join(" ");
-
-        let statements: Vec<&str> = schema_string
-            .split(';')
-            .map(|s| s.trim())
-            .filter(|s| !s.is_empty())
-            .collect();
-
-        // Execute each statement
-        for statement in statements.iter() {
-            if statement.trim().is_empty() {
-                continue;
-            }
-
-            self.conn.execute(statement, ()).await.map_err(|e| {
-                DatabaseError::schema_with_source(
-                    format!("Failed to execute schema statement: {statement}"),
-                    e,
-                )
-            })?;
-        }
-
+        initialize_schema(&self.conn).await?;
         info!("[DB] Unified database schema initialized successfully from external file");
         Ok(())
     }
@@ -5,13 +5,17 @@
 //! - Session management
 //! - Benchmark synchronization
 //! - Performance tracking
+//! - Evaluation runs
+//! - LLM usage and cost
 //! - Database monitoring
 
 pub mod benchmarks;
 pub mod core;
 pub mod monitoring;
 pub mod performance;
+pub mod runs;
 pub mod sessions;
+pub mod usage;
 
 // Re-export main DatabaseWriter for backward compatibility
 pub use core::DatabaseWriter;
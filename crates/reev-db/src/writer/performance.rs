@@ -6,7 +6,10 @@
 use crate::{
     error::{DatabaseError, Result},
     shared::performance::AgentPerformance,
-    types::{AgentPerformanceSummary, PerformanceResult},
+    trials::{latest_trial_stats, TrialRecord},
+    types::{
+        AgentEfficiencySummary, AgentPerformanceSummary, PerformanceResult, SessionEfficiency,
+    },
 };
 use std::collections::HashMap;
 use tracing::{debug, info};
@@ -33,6 +36,9 @@ impl DatabaseWriter {
             timestamp: row.get(id_offset + 7)?,
             flow_log_id: None,
             prompt_md5: row.get::<Option<String>>(id_offset + 8)?,
+            trial_index: row.get::<Option<i64>>(id_offset + 9)?.unwrap_or_default() as u32,
+            run_id: row.get::<Option<String>>(id_offset + 10)?,
+            rescored: row.get::<Option<i64>>(id_offset + 11)?.unwrap_or_default() != 0,
             additional_metrics: HashMap::new(),
         })
     }
@@ -45,7 +51,7 @@ impl DatabaseWriter {
     ) -> Result<Vec<PerformanceResult>> {
         let limit_clause = limit.map(|l| format!(" LIMIT {l}")).unwrap_or_default();
         let results_query = format!(
-            "SELECT id, session_id, benchmark_id, score, final_status, created_at
+            "SELECT id, session_id, benchmark_id, score, final_status, created_at, trial_index
              FROM agent_performance
              WHERE agent_type = ?
              ORDER BY created_at DESC{limit_clause}"
@@ -68,6 +74,7 @@ impl DatabaseWriter {
             let score: f64 = result_row.get(3)?;
             let final_status: String = result_row.get(4)?;
             let timestamp: String = result_row.get(5)?;
+            let trial_index: i64 = result_row.get(6)?;
 
             results.push(PerformanceResult {
                 id: Some(id),
@@ -76,6 +83,7 @@ impl DatabaseWriter {
                 score,
                 final_status,
                 timestamp,
+                trial_index: trial_index as u32,
             });
         }
         Ok(results)
@@ -91,9 +99,9 @@ impl DatabaseWriter {
         self.conn
             .execute(
                 "INSERT INTO agent_performance
-                 (session_id, benchmark_id, agent_type, score, final_status, execution_time_ms, created_at, prompt_md5)
-                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
-                [
+                 (session_id, benchmark_id, agent_type, score, final_status, execution_time_ms, created_at, prompt_md5, trial_index, run_id, rescored)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                (
                     performance.session_id.clone(),
                     performance.benchmark_id.clone(),
                     performance.agent_type.clone(),
@@ -102,7 +110,10 @@ impl DatabaseWriter {
                     performance.execution_time_ms.map(|t| t.to_string()).unwrap_or_default(),
                     performance.timestamp.clone(),
                     performance.prompt_md5.as_ref().unwrap_or(&String::new()).clone(),
-                ],
+                    performance.trial_index.to_string(),
+                    performance.run_id.clone(),
+                    i64::from(performance.rescored),
+                ),
             )
             .await
             .map_err(|e| DatabaseError::operation_with_source("Failed to insert agent performance", e))?;
@@ -111,6 +122,105 @@ impl DatabaseWriter {
         Ok(())
     }
 
+    /// Insert (or replace) the execution cost recorded for a session
+    pub async fn insert_session_efficiency(&self, efficiency: &SessionEfficiency) -> Result<()> {
+        debug!(
+            "[DB] Storing efficiency metrics for session: {}",
+            efficiency.session_id
+        );
+
+        // Turso does not support ON CONFLICT, so replace any previous row explicitly
+        self.conn
+            .execute(
+                "DELETE FROM session_efficiency_metrics WHERE session_id = ?",
+                [efficiency.session_id.clone()],
+            )
+            .await
+            .map_err(|e| {
+                DatabaseError::operation_with_source("Failed to replace session efficiency", e)
+            })?;
+
+        self.conn
+            .execute(
+                "INSERT INTO session_efficiency_metrics
+                 (session_id, benchmark_id, agent_type, compute_units, fee_lamports, instruction_count, transaction_count, efficiency_score)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+                (
+                    efficiency.session_id.clone(),
+                    efficiency.benchmark_id.clone(),
+                    efficiency.agent_type.clone(),
+                    efficiency.compute_units as i64,
+                    efficiency.fee_lamports as i64,
+                    efficiency.instruction_count as i64,
+                    efficiency.transaction_count as i64,
+                    efficiency.efficiency_score,
+                ),
+            )
+            .await
+            .map_err(|e| {
+                DatabaseError::operation_with_source("Failed to insert session efficiency", e)
+            })?;
+
+        Ok(())
+    }
+
+    /// Get the execution cost recorded for a session
+    pub async fn get_session_efficiency(
+        &self,
+        session_id: &str,
+    ) -> Result<Option<SessionEfficiency>> {
+        let sessions = self
+            .query_session_efficiency("WHERE session_id = ?", session_id)
+            .await?;
+        Ok(sessions.into_iter().next())
+    }
+
+    /// Get efficiency averages for an agent type, if any runs were recorded
+    pub async fn get_agent_efficiency(
+        &self,
+        agent_type: &str,
+    ) -> Result<Option<AgentEfficiencySummary>> {
+        let sessions = self
+            .query_session_efficiency("WHERE agent_type = ?", agent_type)
+            .await?;
+        Ok(AgentEfficiencySummary::from_sessions(&sessions))
+    }
+
+    /// Helper method to read efficiency rows matching a single-parameter filter
+    async fn query_session_efficiency(
+        &self,
+        where_clause: &str,
+        param: &str,
+    ) -> Result<Vec<SessionEfficiency>> {
+        let query = format!(
+            "SELECT session_id, benchmark_id, agent_type, compute_units, fee_lamports, instruction_count, transaction_count, efficiency_score
+             FROM session_efficiency_metrics
+             {where_clause}
+             ORDER BY created_at DESC"
+        );
+
+        let mut rows = self
+            .conn
+            .query(&query, [param])
+            .await
+            .map_err(|e| DatabaseError::query("Failed to query session efficiency", e))?;
+
+        let mut sessions = Vec::new();
+        while let Some(row) = rows.next().await? {
+            sessions.push(SessionEfficiency {
+                session_id: row.get(0)?,
+                benchmark_id: row.get(1)?,
+                agent_type: row.get(2)?,
+                compute_units: row.get::<i64>(3)? as u64,
+                fee_lamports: row.get::<i64>(4)? as u64,
+                instruction_count: row.get::<i64>(5)? as u32,
+                transaction_count: row.get::<i64>(6)? as u32,
+                efficiency_score: row.get::<Option<f64>>(7)?,
+            });
+        }
+        Ok(sessions)
+    }
+
     /// Get agent performance summaries
     pub async fn get_agent_performance(&self) -> Result<Vec<AgentPerformanceSummary>> {
         debug!("[DB] Getting agent performance summaries");
@@ -140,6 +250,13 @@ impl DatabaseWriter {
 
             // Get recent results for this agent type
             let results = self.get_agent_results(&agent_type, None).await?;
+            let efficiency = self.get_agent_efficiency(&agent_type).await?;
+            let trial_stats = latest_trial_stats(
+                results
+                    .iter()
+                    .map(|r| TrialRecord::from_result(&agent_type, r)),
+                None,
+            );
 
             summaries.push(AgentPerformanceSummary {
                 agent_type: agent_type.clone(),
@@ -149,6 +266,8 @@ impl DatabaseWriter {
                 best_benchmarks: vec![],  // TODO: Calculate best benchmarks
                 worst_benchmarks: vec![], // TODO: Calculate worst benchmarks
                 results,
+                efficiency,
+                trial_stats,
             });
         }
 
@@ -169,7 +288,7 @@ impl DatabaseWriter {
         let mut rows = self
             .conn
             .query(
-                "SELECT session_id, benchmark_id, agent_type, score, final_status, execution_time_ms, created_at, prompt_md5
+                "SELECT id, session_id, benchmark_id, agent_type, score, final_status, execution_time_ms, created_at, prompt_md5, trial_index, run_id, rescored
                  FROM agent_performance WHERE agent_type = ? ORDER BY created_at DESC",
                 [agent_type],
             )
@@ -202,7 +321,7 @@ impl DatabaseWriter {
         let mut rows = self
             .conn
             .query(
-                "SELECT session_id, benchmark_id, agent_type, score, final_status, execution_time_ms, created_at, prompt_md5
+                "SELECT id, session_id, benchmark_id, agent_type, score, final_status, execution_time_ms, created_at, prompt_md5, trial_index, run_id, rescored
                  FROM agent_performance WHERE benchmark_id = ? ORDER BY created_at DESC",
                 [benchmark_id],
             )
@@ -232,7 +351,7 @@ impl DatabaseWriter {
         let mut rows = self
             .conn
             .query(
-                "SELECT session_id, benchmark_id, agent_type, score, final_status, execution_time_ms, created_at, prompt_md5
+                "SELECT id, session_id, benchmark_id, agent_type, score, final_status, execution_time_ms, created_at, prompt_md5, trial_index, run_id, rescored
                  FROM agent_performance WHERE session_id = ?",
                 [session_id],
             )
@@ -280,6 +399,13 @@ impl DatabaseWriter {
 
             // Get all results for this top agent
             let results = self.get_agent_results(&agent_type, None).await?;
+            let efficiency = self.get_agent_efficiency(&agent_type).await?;
+            let trial_stats = latest_trial_stats(
+                results
+                    .iter()
+                    .map(|r| TrialRecord::from_result(&agent_type, r)),
+                None,
+            );
 
             summaries.push(AgentPerformanceSummary {
                 agent_type,
@@ -289,6 +415,8 @@ impl DatabaseWriter {
                 best_benchmarks: vec![],  // TODO: Calculate best benchmarks
                 worst_benchmarks: vec![], // TODO: Calculate worst benchmarks
                 results,
+                efficiency,
+                trial_stats,
             });
         }
 
@@ -310,7 +438,7 @@ impl DatabaseWriter {
         let mut rows = self
             .conn
             .query(
-                "SELECT session_id, benchmark_id, agent_type, score, final_status, execution_time_ms, created_at, prompt_md5
+                "SELECT id, session_id, benchmark_id, agent_type, score, final_status, execution_time_ms, created_at, prompt_md5, trial_index, run_id, rescored
                  FROM agent_performance WHERE agent_type = ? ORDER BY created_at DESC LIMIT ?",
                 [agent_type, &limit.to_string()],
             )
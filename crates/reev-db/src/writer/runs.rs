@@ -0,0 +1,270 @@
+//! Evaluation run operations
+//!
+//! An evaluation run groups the sessions and performance records produced by one
+//! invocation of the runner, together with the provenance needed to reproduce it:
+//! agent configuration, repository commit, benchmark content hashes and seed.
+
+use crate::{
+    error::{DatabaseError, Result},
+    trials::is_success,
+    types::{score_per_dollar, EvaluationRun, EvaluationRunSummary, RunAgentConfig, SessionInfo},
+};
+use std::collections::{BTreeSet, HashMap};
+use tracing::info;
+use turso::Connection;
+
+use super::core::DatabaseWriter;
+
+const RUN_COLUMNS: &str = "run_id, agent_type, model, temperature, prompt_version, git_commit,
+     benchmark_hashes, seed, status, start_time, end_time, trials";
+
+impl DatabaseWriter {
+    /// Record the start of an evaluation run
+    pub async fn create_run(&self, run: &EvaluationRun) -> Result<()> {
+        info!(
+            run_id = %run.run_id,
+            agent_type = %run.agent_type,
+            benchmarks = run.benchmark_hashes.len(),
+            "Creating evaluation run"
+        );
+
+        let benchmark_hashes = serde_json::to_string(&run.benchmark_hashes)
+            .map_err(|e| DatabaseError::serialization("Failed to serialize benchmark hashes", e))?;
+
+        self.conn
+            .execute(
+                &format!(
+                    "INSERT INTO evaluation_runs ({RUN_COLUMNS})
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+                ),
+                (
+                    run.run_id.clone(),
+                    run.agent_type.clone(),
+                    run.agent_config.model.clone(),
+                    run.agent_config.temperature,
+                    run.agent_config.prompt_version.clone(),
+                    run.git_commit.clone(),
+                    benchmark_hashes,
+                    // Stored bit-for-bit; SQLite integers are signed
+                    run.seed.map(|seed| seed as i64),
+                    run.status.clone(),
+                    run.start_time,
+                    run.end_time,
+                    i64::from(run.trials),
+                ),
+            )
+            .await
+            .map_err(|e| DatabaseError::operation_with_source("Failed to create run", e))?;
+
+        Ok(())
+    }
+
+    /// Mark an evaluation run as finished with the given status
+    pub async fn complete_run(&self, run_id: &str, status: &str, end_time: i64) -> Result<()> {
+        info!(run_id = %run_id, status = %status, "Completing evaluation run");
+
+        self.conn
+            .execute(
+                "UPDATE evaluation_runs SET status = ?, end_time = ? WHERE run_id = ?",
+                (status.to_string(), end_time, run_id.to_string()),
+            )
+            .await
+            .map_err(|e| DatabaseError::operation_with_source("Failed to complete run", e))?;
+
+        Ok(())
+    }
+
+    /// Mark a finished or interrupted evaluation run as running again
+    pub async fn reopen_run(&self, run_id: &str) -> Result<()> {
+        info!(run_id = %run_id, "Reopening evaluation run");
+
+        self.conn
+            .execute(
+                "UPDATE evaluation_runs SET status = 'running', end_time = NULL WHERE run_id = ?",
+                [run_id],
+            )
+            .await
+            .map_err(|e| DatabaseError::operation_with_source("Failed to reopen run", e))?;
+
+        Ok(())
+    }
+
+    /// Mark the sessions of a run still `running` as `interrupted`
+    ///
+    /// Such sessions were left behind by a runner that died mid-benchmark. Returns
+    /// the number of sessions updated.
+    pub async fn interrupt_run_sessions(&self, run_id: &str, end_time: i64) -> Result<u64> {
+        let updated = self
+            .conn
+            .execute(
+                "UPDATE execution_sessions SET status = 'interrupted', end_time = ?
+                 WHERE run_id = ? AND status = 'running'",
+                (end_time, run_id.to_string()),
+            )
+            .await
+            .map_err(|e| {
+                DatabaseError::operation_with_source("Failed to interrupt run sessions", e)
+            })?;
+
+        if updated > 0 {
+            info!(run_id = %run_id, sessions = updated, "Marked interrupted sessions");
+        }
+        Ok(updated)
+    }
+
+    /// Benchmark id and trial index of every attempt recorded under a run
+    pub async fn completed_attempts(&self, run_id: &str) -> Result<BTreeSet<(String, u32)>> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT benchmark_id, trial_index FROM agent_performance WHERE run_id = ?",
+                [run_id],
+            )
+            .await
+            .map_err(|e| DatabaseError::query("Failed to query completed attempts", e))?;
+
+        let mut attempts = BTreeSet::new();
+        while let Some(row) = rows.next().await? {
+            let benchmark_id: String = row.get(0)?;
+            let trial_index: i64 = row.get(1)?;
+            attempts.insert((benchmark_id, trial_index as u32));
+        }
+        Ok(attempts)
+    }
+
+    /// Get an evaluation run by ID
+    pub async fn get_run(&self, run_id: &str) -> Result<Option<EvaluationRun>> {
+        get_run(&self.conn, run_id).await
+    }
+
+    /// List evaluation runs, newest first, with the outcome of their sessions
+    pub async fn list_runs(&self, limit: Option<u32>) -> Result<Vec<EvaluationRunSummary>> {
+        list_runs(&self.conn, limit).await
+    }
+
+    /// List the sessions of an evaluation run, oldest first
+    pub async fn list_run_sessions(&self, run_id: &str) -> Result<Vec<SessionInfo>> {
+        list_run_sessions(&self.conn, run_id).await
+    }
+}
+
+pub(crate) async fn get_run(conn: &Connection, run_id: &str) -> Result<Option<EvaluationRun>> {
+    let mut rows = conn
+        .query(
+            &format!("SELECT {RUN_COLUMNS} FROM evaluation_runs WHERE run_id = ?"),
+            [run_id],
+        )
+        .await
+        .map_err(|e| DatabaseError::query("Failed to get run", e))?;
+
+    match rows.next().await? {
+        Some(row) => Ok(Some(run_from_row(&row)?)),
+        None => Ok(None),
+    }
+}
+
+pub(crate) async fn list_runs(
+    conn: &Connection,
+    limit: Option<u32>,
+) -> Result<Vec<EvaluationRunSummary>> {
+    let limit_clause = limit.map(|l| format!(" LIMIT {l}")).unwrap_or_default();
+    let mut rows = conn
+        .query(
+            &format!(
+                "SELECT {RUN_COLUMNS} FROM evaluation_runs ORDER BY start_time DESC{limit_clause}"
+            ),
+            (),
+        )
+        .await
+        .map_err(|e| DatabaseError::query("Failed to list runs", e))?;
+
+    let mut runs = Vec::new();
+    while let Some(row) = rows.next().await? {
+        runs.push(run_from_row(&row)?);
+    }
+
+    // Attempt outcomes per run: (total, passed, score sum)
+    let mut outcomes: HashMap<String, (i64, i64, f64)> = HashMap::new();
+    let mut rows = conn
+        .query(
+            "SELECT run_id, score, final_status FROM agent_performance WHERE run_id IS NOT NULL",
+            (),
+        )
+        .await
+        .map_err(|e| DatabaseError::query("Failed to query run results", e))?;
+    while let Some(row) = rows.next().await? {
+        let run_id: String = row.get(0)?;
+        let score: f64 = row.get(1)?;
+        let final_status: String = row.get(2)?;
+        let entry = outcomes.entry(run_id).or_default();
+        entry.0 += 1;
+        entry.1 += i64::from(is_success(&final_status));
+        entry.2 += score;
+    }
+    let costs = super::usage::run_costs(conn).await?;
+
+    Ok(runs
+        .into_iter()
+        .map(|run| {
+            let (total, passed, score_sum) = outcomes.get(&run.run_id).copied().unwrap_or_default();
+            let (cost_usd, priced_score_sum) = costs.get(&run.run_id).copied().unwrap_or_default();
+            EvaluationRunSummary {
+                run,
+                total,
+                passed,
+                average_score: (total > 0).then(|| score_sum / total as f64),
+                cost_usd,
+                score_per_dollar: score_per_dollar(priced_score_sum, cost_usd),
+            }
+        })
+        .collect())
+}
+
+pub(crate) async fn list_run_sessions(conn: &Connection, run_id: &str) -> Result<Vec<SessionInfo>> {
+    let mut rows = conn
+        .query(
+            "SELECT session_id, benchmark_id, agent_type, interface, start_time, end_time, status, score, final_status, run_id
+             FROM execution_sessions WHERE run_id = ? ORDER BY start_time ASC",
+            [run_id],
+        )
+        .await
+        .map_err(|e| DatabaseError::query("Failed to list run sessions", e))?;
+
+    let mut sessions = Vec::new();
+    while let Some(row) = rows.next().await? {
+        sessions.push(SessionInfo {
+            session_id: row.get(0)?,
+            benchmark_id: row.get(1)?,
+            agent_type: row.get(2)?,
+            interface: row.get(3)?,
+            start_time: row.get(4)?,
+            end_time: row.get(5)?,
+            status: row.get(6)?,
+            score: row.get(7)?,
+            final_status: row.get(8)?,
+            run_id: row.get(9)?,
+        });
+    }
+    Ok(sessions)
+}
+
+fn run_from_row(row: &turso::Row) -> Result<EvaluationRun> {
+    let benchmark_hashes: String = row.get(6)?;
+    Ok(EvaluationRun {
+        run_id: row.get(0)?,
+        agent_type: row.get(1)?,
+        agent_config: RunAgentConfig {
+            model: row.get(2)?,
+            temperature: row.get(3)?,
+            prompt_version: row.get(4)?,
+        },
+        git_commit: row.get(5)?,
+        benchmark_hashes: serde_json::from_str(&benchmark_hashes)
+            .map_err(|e| DatabaseError::serialization("Failed to parse benchmark hashes", e))?,
+        seed: row.get::<Option<i64>>(7)?.map(|seed| seed as u64),
+        status: row.get(8)?,
+        start_time: row.get(9)?,
+        end_time: row.get(10)?,
+        trials: row.get::<i64>(11)? as u32,
+    })
+}
@@ -9,6 +9,7 @@ use crate::{
 };
 use serde_json::Value;
 use tracing::info;
+use turso::Connection;
 
 use super::core::DatabaseWriter;
 
@@ -25,6 +26,20 @@ pub struct ToolCallData {
     pub error_message: Option<String>,
 }
 
+/// Pre/post balance of one account referenced by a session's transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceDeltaData {
+    pub session_id: String,
+    pub step_index: u32,
+    pub account_name: Option<String>,
+    pub pubkey: String,
+    pub mint: Option<String>,
+    pub pre_lamports: u64,
+    pub post_lamports: u64,
+    pub pre_token_amount: Option<u64>,
+    pub post_token_amount: Option<u64>,
+}
+
 impl DatabaseWriter {
     /// Create a new execution session
     pub async fn create_session(&self, session: &SessionInfo) -> Result<()> {
@@ -38,15 +53,16 @@ impl DatabaseWriter {
 
         self.conn
             .execute(
-                "INSERT INTO execution_sessions (session_id, benchmark_id, agent_type, interface, start_time, status)
-                 VALUES (?, ?, ?, ?, ?, 'running')",
-                [
+                "INSERT INTO execution_sessions (session_id, benchmark_id, agent_type, interface, start_time, status, run_id)
+                 VALUES (?, ?, ?, ?, ?, 'running', ?)",
+                (
                     session.session_id.clone(),
                     session.benchmark_id.clone(),
                     session.agent_type.clone(),
                     session.interface.clone(),
                     session.start_time.to_string(),
-                ],
+                    session.run_id.clone(),
+                ),
             )
             .await
             .map_err(|e| DatabaseError::operation_with_source("Failed to create session", e))?;
@@ -84,6 +100,107 @@ impl DatabaseWriter {
         Ok(())
     }
 
+    /// Mark a session stopped at its time limit as `timeout`, with a zero score
+    ///
+    /// Fails when the session was never created, as the timeout would be lost.
+    pub async fn time_out_session(&self, session_id: &str, end_time: i64) -> Result<()> {
+        info!(session_id = %session_id, "Marking execution session as timed out");
+
+        let updated = self
+            .conn
+            .execute(
+                "UPDATE execution_sessions
+             SET end_time = ?, status = 'timeout', score = 0, final_status = 'timed_out'
+             WHERE session_id = ?",
+                (end_time, session_id.to_string()),
+            )
+            .await
+            .map_err(|e| DatabaseError::operation_with_source("Failed to time out session", e))?;
+        if updated == 0 {
+            return Err(DatabaseError::record_not_found(
+                session_id,
+                "execution_sessions",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Record the budget limit, such as `max_turns`, that stopped a session
+    ///
+    /// The session is completed as usual with the `budget_exceeded` final status.
+    /// Fails when the session was never created, as the limit would be lost.
+    pub async fn record_budget_exceeded(&self, session_id: &str, limit: &str) -> Result<()> {
+        info!(session_id = %session_id, limit = %limit, "Recording exceeded budget of session");
+
+        let updated = self
+            .conn
+            .execute(
+                "UPDATE execution_sessions SET budget_exceeded = ? WHERE session_id = ?",
+                [limit.to_string(), session_id.to_string()],
+            )
+            .await
+            .map_err(|e| {
+                DatabaseError::operation_with_source("Failed to record exceeded budget", e)
+            })?;
+        if updated == 0 {
+            return Err(DatabaseError::record_not_found(
+                session_id,
+                "execution_sessions",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// The budget limit that stopped a session, if one did
+    pub async fn get_budget_exceeded(&self, session_id: &str) -> Result<Option<String>> {
+        get_budget_exceeded(&self.conn, session_id).await
+    }
+
+    /// Record how many tool calls of a session had their arguments rejected and retried
+    ///
+    /// Fails when the session was never created, as the count would be lost.
+    pub async fn record_argument_retries(&self, session_id: &str, retries: u32) -> Result<()> {
+        info!(session_id = %session_id, retries, "Recording argument retries of session");
+
+        let updated = self
+            .conn
+            .execute(
+                "UPDATE execution_sessions SET argument_retries = ? WHERE session_id = ?",
+                (retries, session_id.to_string()),
+            )
+            .await
+            .map_err(|e| {
+                DatabaseError::operation_with_source("Failed to record argument retries", e)
+            })?;
+        if updated == 0 {
+            return Err(DatabaseError::record_not_found(
+                session_id,
+                "execution_sessions",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Tool calls of a session whose arguments were rejected and retried
+    pub async fn get_argument_retries(&self, session_id: &str) -> Result<u32> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT argument_retries FROM execution_sessions WHERE session_id = ?",
+                [session_id],
+            )
+            .await
+            .map_err(|e| DatabaseError::query("Failed to read argument retries", e))?;
+
+        match rows.next().await? {
+            Some(row) => Ok(row.get::<u32>(0)?),
+            None => Ok(0),
+        }
+    }
+
     /// Store complete session log content
     pub async fn store_complete_log(&self, session_id: &str, log_content: &str) -> Result<()> {
         info!(
@@ -104,18 +221,34 @@ impl DatabaseWriter {
                 DatabaseError::operation_with_source("Failed to update session log path", e)
             })?;
 
-        // Store full content in session_logs table - use INSERT without OR REPLACE for compatibility
-        self.conn
+        // Store full content in session_logs table, replacing a log stored before (a
+        // flow's log is replaced by its trace). Turso supports neither OR REPLACE nor
+        // deleting from this table's created_at index, so an existing row is updated.
+        let replaced = self
+            .conn
             .execute(
-                "INSERT INTO session_logs (session_id, content, file_size) VALUES (?, ?, ?)",
-                [
-                    session_id.to_string(),
-                    log_content.to_string(),
-                    log_content.len().to_string(),
-                ],
+                "UPDATE session_logs SET content = ?, file_size = ? WHERE session_id = ?",
+                (log_content, log_content.len() as i64, session_id),
             )
             .await
-            .map_err(|e| DatabaseError::operation_with_source("Failed to store session log", e))?;
+            .map_err(|e| {
+                DatabaseError::operation_with_source("Failed to replace session log", e)
+            })?;
+        if replaced == 0 {
+            self.conn
+                .execute(
+                    "INSERT INTO session_logs (session_id, content, file_size) VALUES (?, ?, ?)",
+                    [
+                        session_id.to_string(),
+                        log_content.to_string(),
+                        log_content.len().to_string(),
+                    ],
+                )
+                .await
+                .map_err(|e| {
+                    DatabaseError::operation_with_source("Failed to store session log", e)
+                })?;
+        }
 
         info!("Session log stored successfully: {}", session_id);
         Ok(())
@@ -149,19 +282,19 @@ impl DatabaseWriter {
     /// List sessions with optional filtering
     pub async fn list_sessions(&self, filter: &SessionFilter) -> Result<Vec<SessionInfo>> {
         let query = if filter.benchmark_id.is_some() {
-            "SELECT session_id, benchmark_id, agent_type, interface, start_time, end_time, status, score, final_status
+            "SELECT session_id, benchmark_id, agent_type, interface, start_time, end_time, status, score, final_status, run_id
              FROM execution_sessions WHERE benchmark_id = ? ORDER BY start_time DESC"
         } else if filter.agent_type.is_some() {
-            "SELECT session_id, benchmark_id, agent_type, interface, start_time, end_time, status, score, final_status
+            "SELECT session_id, benchmark_id, agent_type, interface, start_time, end_time, status, score, final_status, run_id
              FROM execution_sessions WHERE agent_type = ? ORDER BY start_time DESC"
         } else if filter.interface.is_some() {
-            "SELECT session_id, benchmark_id, agent_type, interface, start_time, end_time, status, score, final_status
+            "SELECT session_id, benchmark_id, agent_type, interface, start_time, end_time, status, score, final_status, run_id
              FROM execution_sessions WHERE interface = ? ORDER BY start_time DESC"
         } else if filter.status.is_some() {
-            "SELECT session_id, benchmark_id, agent_type, interface, start_time, end_time, status, score, final_status
+            "SELECT session_id, benchmark_id, agent_type, interface, start_time, end_time, status, score, final_status, run_id
              FROM execution_sessions WHERE status = ? ORDER BY start_time DESC"
         } else {
-            "SELECT session_id, benchmark_id, agent_type, interface, start_time, end_time, status, score, final_status
+            "SELECT session_id, benchmark_id, agent_type, interface, start_time, end_time, status, score, final_status, run_id
              FROM execution_sessions ORDER BY start_time DESC"
         };
 
@@ -189,6 +322,7 @@ impl DatabaseWriter {
                     status: row.get(6)?,
                     score: row.get(7)?,
                     final_status: row.get(8)?,
+                    run_id: row.get(9)?,
                 });
             }
             results
@@ -210,6 +344,7 @@ impl DatabaseWriter {
                     status: row.get(6)?,
                     score: row.get(7)?,
                     final_status: row.get(8)?,
+                    run_id: row.get(9)?,
                 });
             }
             results
@@ -231,6 +366,7 @@ impl DatabaseWriter {
                     status: row.get(6)?,
                     score: row.get(7)?,
                     final_status: row.get(8)?,
+                    run_id: row.get(9)?,
                 });
             }
             results
@@ -252,6 +388,7 @@ impl DatabaseWriter {
                     status: row.get(6)?,
                     score: row.get(7)?,
                     final_status: row.get(8)?,
+                    run_id: row.get(9)?,
                 });
             }
             results
@@ -273,6 +410,7 @@ impl DatabaseWriter {
                     status: row.get(6)?,
                     score: row.get(7)?,
                     final_status: row.get(8)?,
+                    run_id: row.get(9)?,
                 });
             }
             results
@@ -287,7 +425,7 @@ impl DatabaseWriter {
         let mut rows = self
             .conn
             .query(
-                "SELECT session_id, benchmark_id, agent_type, interface, start_time, end_time, status, score, final_status
+                "SELECT session_id, benchmark_id, agent_type, interface, start_time, end_time, status, score, final_status, run_id
                  FROM execution_sessions WHERE session_id = ?",
                 [session_id],
             )
@@ -305,6 +443,7 @@ impl DatabaseWriter {
                 status: row.get(6)?,
                 score: row.get(7)?,
                 final_status: row.get(8)?,
+                run_id: row.get(9)?,
             }))
         } else {
             Ok(None)
@@ -324,6 +463,16 @@ impl DatabaseWriter {
             .await
             .map_err(|e| DatabaseError::operation_with_source("Failed to delete session log", e))?;
 
+        self.conn
+            .execute(
+                "DELETE FROM session_balance_deltas WHERE session_id = ?",
+                [session_id],
+            )
+            .await
+            .map_err(|e| {
+                DatabaseError::operation_with_source("Failed to delete session balance deltas", e)
+            })?;
+
         // Delete session
         self.conn
             .execute(
@@ -622,4 +771,96 @@ impl DatabaseWriter {
             "total_tools": stats.len()
         }))
     }
+
+    /// Store the pre/post balance deltas captured for a session's transactions
+    pub async fn store_balance_deltas(&self, deltas: &[BalanceDeltaData]) -> Result<()> {
+        if deltas.is_empty() {
+            return Ok(());
+        }
+
+        info!(
+            session_id = %deltas[0].session_id,
+            count = deltas.len(),
+            "Storing balance deltas"
+        );
+
+        for delta in deltas {
+            self.conn
+                .execute(
+                    "INSERT INTO session_balance_deltas
+                     (session_id, step_index, account_name, pubkey, mint, pre_lamports, post_lamports, pre_token_amount, post_token_amount)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                    (
+                        delta.session_id.clone(),
+                        delta.step_index as i64,
+                        delta.account_name.clone(),
+                        delta.pubkey.clone(),
+                        delta.mint.clone(),
+                        delta.pre_lamports as i64,
+                        delta.post_lamports as i64,
+                        delta.pre_token_amount.map(|a| a as i64),
+                        delta.post_token_amount.map(|a| a as i64),
+                    ),
+                )
+                .await
+                .map_err(|e| {
+                    DatabaseError::operation_with_source("Failed to store balance delta", e)
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Get the balance deltas recorded for a session, ordered by step
+    pub async fn get_session_balance_deltas(
+        &self,
+        session_id: &str,
+    ) -> Result<Vec<BalanceDeltaData>> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT session_id, step_index, account_name, pubkey, mint, pre_lamports, post_lamports, pre_token_amount, post_token_amount
+                 FROM session_balance_deltas
+                 WHERE session_id = ?
+                 ORDER BY step_index ASC, id ASC",
+                [session_id],
+            )
+            .await
+            .map_err(|e| DatabaseError::query("Failed to retrieve balance deltas", e))?;
+
+        let mut deltas = Vec::new();
+        while let Some(row) = rows.next().await? {
+            deltas.push(BalanceDeltaData {
+                session_id: row.get(0)?,
+                step_index: row.get::<i64>(1)? as u32,
+                account_name: row.get::<Option<String>>(2)?,
+                pubkey: row.get(3)?,
+                mint: row.get::<Option<String>>(4)?,
+                pre_lamports: row.get::<i64>(5)? as u64,
+                post_lamports: row.get::<i64>(6)? as u64,
+                pre_token_amount: row.get::<Option<i64>>(7)?.map(|a| a as u64),
+                post_token_amount: row.get::<Option<i64>>(8)?.map(|a| a as u64),
+            });
+        }
+
+        Ok(deltas)
+    }
+}
+
+pub(crate) async fn get_budget_exceeded(
+    conn: &Connection,
+    session_id: &str,
+) -> Result<Option<String>> {
+    let mut rows = conn
+        .query(
+            "SELECT budget_exceeded FROM execution_sessions WHERE session_id = ?",
+            [session_id],
+        )
+        .await
+        .map_err(|e| DatabaseError::query("Failed to read exceeded budget", e))?;
+
+    match rows.next().await? {
+        Some(row) => Ok(row.get::<Option<String>>(0)?),
+        None => Ok(None),
+    }
 }
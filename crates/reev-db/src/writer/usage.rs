@@ -0,0 +1,227 @@
+//! LLM usage operations
+//!
+//! The tokens an agent's model calls used in a session, priced with the model
+//! registry, and the cost they add up to per benchmark and per evaluation run.
+
+use crate::{
+    error::{DatabaseError, Result},
+    types::{score_per_dollar, BenchmarkCost, SessionLlmUsage},
+};
+use reev_flow::TokenUsage;
+use std::collections::{BTreeMap, HashMap};
+use tracing::{debug, warn};
+use turso::Connection;
+
+use super::core::DatabaseWriter;
+
+impl DatabaseWriter {
+    /// Insert (or replace) the LLM usage recorded for a session
+    pub async fn insert_session_llm_usage(&self, usage: &SessionLlmUsage) -> Result<()> {
+        debug!(
+            "[DB] Storing LLM usage for session: {} ({} tokens)",
+            usage.session_id,
+            usage.tokens.total_tokens()
+        );
+
+        // Turso does not support ON CONFLICT, so replace any previous row explicitly,
+        // in one transaction so a failed insert keeps the previous row
+        let transaction = self.conn.unchecked_transaction().await.map_err(|e| {
+            DatabaseError::operation_with_source("Failed to begin session LLM usage transaction", e)
+        })?;
+        match replace_session_llm_usage(&transaction, usage).await {
+            Ok(()) => transaction.commit().await.map_err(|e| {
+                DatabaseError::operation_with_source("Failed to commit session LLM usage", e)
+            }),
+            Err(e) => {
+                if let Err(rollback) = transaction.rollback().await {
+                    warn!("[DB] Failed to roll back session LLM usage: {rollback}");
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Get the LLM usage recorded for a session
+    pub async fn get_session_llm_usage(&self, session_id: &str) -> Result<Option<SessionLlmUsage>> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT session_id, benchmark_id, agent_type, model, run_id, prompt_tokens, completion_tokens, cached_tokens, cost_usd
+                 FROM session_llm_usage WHERE session_id = ?",
+                [session_id],
+            )
+            .await
+            .map_err(|e| DatabaseError::query("Failed to query session LLM usage", e))?;
+
+        match rows.next().await? {
+            Some(row) => Ok(Some(SessionLlmUsage {
+                session_id: row.get(0)?,
+                benchmark_id: row.get(1)?,
+                agent_type: row.get(2)?,
+                model: row.get(3)?,
+                run_id: row.get(4)?,
+                tokens: TokenUsage {
+                    prompt_tokens: row.get::<i64>(5)? as u64,
+                    completion_tokens: row.get::<i64>(6)? as u64,
+                    cached_tokens: row.get::<i64>(7)? as u64,
+                },
+                cost_usd: row.get::<Option<f64>>(8)?,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    /// LLM cost of each benchmark of an evaluation run
+    pub async fn get_run_benchmark_costs(&self, run_id: &str) -> Result<Vec<BenchmarkCost>> {
+        run_benchmark_costs(&self.conn, run_id).await
+    }
+}
+
+/// Delete the LLM usage of `usage`'s session and insert `usage` in its place
+async fn replace_session_llm_usage(conn: &Connection, usage: &SessionLlmUsage) -> Result<()> {
+    conn.execute(
+        "DELETE FROM session_llm_usage WHERE session_id = ?",
+        [usage.session_id.clone()],
+    )
+    .await
+    .map_err(|e| DatabaseError::operation_with_source("Failed to replace session LLM usage", e))?;
+
+    conn.execute(
+        "INSERT INTO session_llm_usage
+         (session_id, benchmark_id, agent_type, model, run_id, prompt_tokens, completion_tokens, cached_tokens, cost_usd)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        (
+            usage.session_id.clone(),
+            usage.benchmark_id.clone(),
+            usage.agent_type.clone(),
+            usage.model.clone(),
+            usage.run_id.clone(),
+            usage.tokens.prompt_tokens as i64,
+            usage.tokens.completion_tokens as i64,
+            usage.tokens.cached_tokens as i64,
+            usage.cost_usd,
+        ),
+    )
+    .await
+    .map_err(|e| DatabaseError::operation_with_source("Failed to insert session LLM usage", e))?;
+
+    Ok(())
+}
+
+/// Joins a usage row to its session's score: the completed session's score, else
+/// the attempt's own agent performance score, as flows never complete their
+/// session. Usage rows are grouped per session so it is counted once.
+const SESSION_SCORE_JOIN: &str = "LEFT JOIN execution_sessions es ON u.session_id = es.session_id
+                 LEFT JOIN agent_performance ap ON ap.session_id = u.session_id AND ap.rescored = 0";
+
+/// LLM cost of each benchmark of an evaluation run, in benchmark order
+pub(crate) async fn run_benchmark_costs(
+    conn: &Connection,
+    run_id: &str,
+) -> Result<Vec<BenchmarkCost>> {
+    let mut rows = conn
+        .query(
+            &format!(
+                "SELECT u.benchmark_id, u.prompt_tokens, u.completion_tokens, u.cached_tokens, u.cost_usd,
+                        COALESCE(MAX(es.score), MAX(ap.score))
+                 FROM session_llm_usage u
+                 {SESSION_SCORE_JOIN}
+                 WHERE u.run_id = ?
+                 GROUP BY u.session_id"
+            ),
+            [run_id],
+        )
+        .await
+        .map_err(|e| DatabaseError::query("Failed to query run LLM usage", e))?;
+
+    let mut benchmarks: BTreeMap<String, CostTally> = BTreeMap::new();
+    while let Some(row) = rows.next().await? {
+        let benchmark_id: String = row.get(0)?;
+        benchmarks.entry(benchmark_id).or_default().add(
+            TokenUsage {
+                prompt_tokens: row.get::<i64>(1)? as u64,
+                completion_tokens: row.get::<i64>(2)? as u64,
+                cached_tokens: row.get::<i64>(3)? as u64,
+            },
+            row.get::<Option<f64>>(4)?,
+            row.get::<Option<f64>>(5)?,
+        );
+    }
+
+    Ok(benchmarks
+        .into_iter()
+        .map(|(benchmark_id, tally)| {
+            let attempts = tally.attempts as f64;
+            BenchmarkCost {
+                benchmark_id,
+                attempts: tally.attempts,
+                tokens: tally.tokens,
+                cost_usd: tally.cost_usd,
+                cost_per_attempt_usd: tally.cost_usd.map(|cost| cost / attempts),
+                average_score: (tally.scored > 0).then(|| tally.score_sum / tally.scored as f64),
+                score_per_dollar: score_per_dollar(tally.priced_score_sum, tally.cost_usd),
+            }
+        })
+        .collect())
+}
+
+/// LLM cost and summed score of the priced sessions of every evaluation run with
+/// recorded usage
+pub(crate) async fn run_costs(conn: &Connection) -> Result<HashMap<String, (Option<f64>, f64)>> {
+    let mut rows = conn
+        .query(
+            &format!(
+                "SELECT u.run_id, u.cost_usd, COALESCE(MAX(es.score), MAX(ap.score))
+                 FROM session_llm_usage u
+                 {SESSION_SCORE_JOIN}
+                 WHERE u.run_id IS NOT NULL
+                 GROUP BY u.session_id"
+            ),
+            (),
+        )
+        .await
+        .map_err(|e| DatabaseError::query("Failed to query run LLM costs", e))?;
+
+    let mut runs: HashMap<String, CostTally> = HashMap::new();
+    while let Some(row) = rows.next().await? {
+        let run_id: String = row.get(0)?;
+        runs.entry(run_id).or_default().add(
+            TokenUsage::default(),
+            row.get::<Option<f64>>(1)?,
+            row.get::<Option<f64>>(2)?,
+        );
+    }
+    Ok(runs
+        .into_iter()
+        .map(|(run_id, tally)| (run_id, (tally.cost_usd, tally.priced_score_sum)))
+        .collect())
+}
+
+/// Usage, cost and score summed over sessions
+#[derive(Default)]
+struct CostTally {
+    attempts: i64,
+    tokens: TokenUsage,
+    /// Summed over the priced sessions, unknown while none is priced
+    cost_usd: Option<f64>,
+    score_sum: f64,
+    scored: i64,
+    /// Score of the priced sessions, the ones score per dollar is taken over
+    priced_score_sum: f64,
+}
+
+impl CostTally {
+    fn add(&mut self, tokens: TokenUsage, cost_usd: Option<f64>, score: Option<f64>) {
+        self.attempts += 1;
+        self.tokens += tokens;
+        let score_value = score.unwrap_or(0.0);
+        if let Some(cost) = cost_usd {
+            *self.cost_usd.get_or_insert(0.0) += cost;
+            self.priced_score_sum += score_value;
+        }
+        if score.is_some() {
+            self.score_sum += score_value;
+            self.scored += 1;
+        }
+    }
+}
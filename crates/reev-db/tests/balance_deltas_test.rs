@@ -0,0 +1,52 @@
+//! Balance Delta Storage Test
+//!
+//! Verifies that per-account pre/post balances are stored and read back per session.
+
+use reev_db::writer::sessions::BalanceDeltaData;
+use reev_db::{DatabaseConfig, DatabaseWriter};
+
+/// Test round-tripping SOL and SPL token balance deltas
+#[tokio::test]
+async fn test_store_and_get_balance_deltas() -> Result<(), Box<dyn std::error::Error>> {
+    let config = DatabaseConfig::new(":memory:");
+    let db = DatabaseWriter::new(config).await?;
+
+    let session_id = "balance-session-1";
+    let deltas = vec![
+        BalanceDeltaData {
+            session_id: session_id.to_string(),
+            step_index: 0,
+            account_name: Some("USER_WALLET_PUBKEY".to_string()),
+            pubkey: "5Q1NPFhnupvj52dAZjkWkMr4uvRMu7heuq9GBmoLJm6C".to_string(),
+            mint: None,
+            pre_lamports: 1_000_000_000,
+            post_lamports: 899_995_000,
+            pre_token_amount: None,
+            post_token_amount: None,
+        },
+        BalanceDeltaData {
+            session_id: session_id.to_string(),
+            step_index: 0,
+            account_name: None,
+            pubkey: "Df2x6LiqRXEgQvXxiWNE668tBiFfhSecYHNFuC4oB265".to_string(),
+            mint: Some("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string()),
+            pre_lamports: 2_039_280,
+            post_lamports: 2_039_280,
+            pre_token_amount: Some(50_000_000),
+            post_token_amount: Some(40_000_000),
+        },
+    ];
+
+    db.store_balance_deltas(&deltas).await?;
+    db.store_balance_deltas(&[]).await?;
+    println!("✅ Balance deltas stored");
+
+    let stored = db.get_session_balance_deltas(session_id).await?;
+    assert_eq!(stored, deltas);
+    println!("✅ Balance deltas retrieved correctly");
+
+    let other = db.get_session_balance_deltas("other-session").await?;
+    assert!(other.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,172 @@
+//! LLM Usage Storage Test
+//!
+//! Verifies that per-session token usage and cost are stored, and that runs and
+//! their benchmarks report their cost and score per dollar.
+
+use reev_db::types::{SessionInfo, SessionResult};
+use reev_db::{
+    AgentPerformance, DatabaseConfig, DatabaseWriter, EvaluationRun, RunAgentConfig,
+    SessionLlmUsage,
+};
+use reev_flow::TokenUsage;
+use std::collections::{BTreeMap, HashMap};
+use tempfile::TempDir;
+
+fn usage(session_id: &str, benchmark_id: &str, cost_usd: Option<f64>) -> SessionLlmUsage {
+    SessionLlmUsage {
+        session_id: session_id.to_string(),
+        benchmark_id: benchmark_id.to_string(),
+        agent_type: "gpt-4o".to_string(),
+        model: "gpt-4o".to_string(),
+        run_id: Some("run-1".to_string()),
+        tokens: TokenUsage {
+            prompt_tokens: 1000,
+            completion_tokens: 100,
+            cached_tokens: 400,
+        },
+        cost_usd,
+    }
+}
+
+/// Create a completed session of `run-1` with `score`
+async fn scored_session(
+    db: &DatabaseWriter,
+    session_id: &str,
+    benchmark_id: &str,
+    score: f64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    db.create_session(&SessionInfo {
+        session_id: session_id.to_string(),
+        benchmark_id: benchmark_id.to_string(),
+        agent_type: "gpt-4o".to_string(),
+        interface: "tui".to_string(),
+        start_time: 1_700_000_000,
+        end_time: None,
+        status: "running".to_string(),
+        score: None,
+        final_status: None,
+        run_id: Some("run-1".to_string()),
+    })
+    .await?;
+    db.complete_session(
+        session_id,
+        &SessionResult {
+            end_time: 1_700_000_050,
+            score,
+            final_status: "Succeeded".to_string(),
+        },
+    )
+    .await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_session_llm_usage_and_run_costs() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let config = DatabaseConfig::new(temp_dir.path().join("usage.db").to_string_lossy());
+    let db = DatabaseWriter::new(config).await?;
+
+    db.create_run(&EvaluationRun {
+        run_id: "run-1".to_string(),
+        agent_type: "gpt-4o".to_string(),
+        agent_config: RunAgentConfig {
+            model: "gpt-4o".to_string(),
+            temperature: None,
+            prompt_version: None,
+        },
+        git_commit: None,
+        benchmark_hashes: BTreeMap::new(),
+        seed: None,
+        trials: 2,
+        status: "completed".to_string(),
+        start_time: 1_700_000_000,
+        end_time: Some(1_700_000_100),
+    })
+    .await?;
+    scored_session(&db, "s1", "001", 1.0).await?;
+    scored_session(&db, "s2", "001", 0.5).await?;
+    scored_session(&db, "s3", "002", 1.0).await?;
+
+    db.insert_session_llm_usage(&usage("s1", "001", Some(0.01)))
+        .await?;
+    // Re-inserting a session replaces its previous usage
+    db.insert_session_llm_usage(&usage("s2", "001", Some(0.5)))
+        .await?;
+    db.insert_session_llm_usage(&usage("s2", "001", Some(0.02)))
+        .await?;
+    // An unpriced model has tokens but no cost
+    db.insert_session_llm_usage(&usage("s3", "002", None))
+        .await?;
+
+    assert_eq!(
+        db.get_session_llm_usage("s2").await?,
+        Some(usage("s2", "001", Some(0.02)))
+    );
+    assert!(db.get_session_llm_usage("missing").await?.is_none());
+    println!("✅ Session LLM usage stored");
+
+    let costs = db.get_run_benchmark_costs("run-1").await?;
+    assert_eq!(costs.len(), 2);
+    let priced = &costs[0];
+    assert_eq!(priced.benchmark_id, "001");
+    assert_eq!(priced.attempts, 2);
+    assert_eq!(priced.tokens.prompt_tokens, 2000);
+    assert_eq!(priced.tokens.cached_tokens, 800);
+    assert!((priced.cost_usd.unwrap() - 0.03).abs() < 1e-9);
+    assert!((priced.cost_per_attempt_usd.unwrap() - 0.015).abs() < 1e-9);
+    assert_eq!(priced.average_score, Some(0.75));
+    assert!((priced.score_per_dollar.unwrap() - 50.0).abs() < 1e-9);
+    let unpriced = &costs[1];
+    assert_eq!(unpriced.benchmark_id, "002");
+    assert_eq!(unpriced.cost_usd, None);
+    assert_eq!(unpriced.score_per_dollar, None);
+    println!("✅ Benchmark costs and score per dollar aggregated");
+
+    let runs = db.list_runs(None).await?;
+    assert_eq!(runs.len(), 1);
+    assert!((runs[0].cost_usd.unwrap() - 0.03).abs() < 1e-9);
+    // Scores of unpriced sessions earn nothing per dollar
+    assert!((runs[0].score_per_dollar.unwrap() - 50.0).abs() < 1e-6);
+    println!("✅ Run summaries report their LLM cost");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_flow_costs_use_agent_performance_score() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let config = DatabaseConfig::new(temp_dir.path().join("usage.db").to_string_lossy());
+    let db = DatabaseWriter::new(config).await?;
+
+    // Flows keep no scored session, only the agent performance of the attempt
+    for (score, rescored) in [(0.8, false), (0.2, true)] {
+        db.insert_agent_performance(&AgentPerformance {
+            id: None,
+            session_id: "flow-1".to_string(),
+            benchmark_id: "200-flow".to_string(),
+            agent_type: "gpt-4o".to_string(),
+            score,
+            final_status: "Succeeded".to_string(),
+            execution_time_ms: Some(1000),
+            timestamp: "2024-01-01T00:00:00Z".to_string(),
+            flow_log_id: None,
+            prompt_md5: None,
+            trial_index: 0,
+            additional_metrics: HashMap::new(),
+            run_id: Some("run-1".to_string()),
+            rescored,
+        })
+        .await?;
+    }
+    db.insert_session_llm_usage(&usage("flow-1", "200-flow", Some(0.02)))
+        .await?;
+
+    let costs = db.get_run_benchmark_costs("run-1").await?;
+    assert_eq!(costs.len(), 1);
+    assert_eq!(costs[0].attempts, 1);
+    assert_eq!(costs[0].average_score, Some(0.8));
+    assert!((costs[0].score_per_dollar.unwrap() - 40.0).abs() < 1e-9);
+    println!("✅ Flow costs scored from their agent performance");
+
+    Ok(())
+}
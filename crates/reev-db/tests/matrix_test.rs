@@ -0,0 +1,41 @@
+//! Score Matrix Test
+//!
+//! Verifies arranging per-agent trial statistics into a benchmark × agent matrix.
+
+use reev_db::{ScoreMatrix, TrialStats};
+
+fn stats(benchmark_id: &str, agent_type: &str, outcomes: &[(f64, bool)]) -> TrialStats {
+    TrialStats::from_outcomes(benchmark_id, agent_type, outcomes, None).unwrap()
+}
+
+#[test]
+fn test_score_matrix() {
+    let agents = vec!["deterministic".to_string(), "glm-4.6".to_string()];
+    let matrix = ScoreMatrix::from_stats(
+        &agents,
+        &[
+            stats("002-spl-transfer", "glm-4.6", &[(0.5, false), (1.0, true)]),
+            stats("001-sol-transfer", "deterministic", &[(1.0, true)]),
+            stats("001-sol-transfer", "glm-4.6", &[(1.0, true), (1.0, true)]),
+            stats("001-sol-transfer", "gpt-4o", &[(0.0, false)]),
+        ],
+    );
+
+    assert_eq!(matrix.agents, agents);
+    let benchmarks: Vec<&str> = matrix
+        .rows
+        .iter()
+        .map(|r| r.benchmark_id.as_str())
+        .collect();
+    assert_eq!(benchmarks, ["001-sol-transfer", "002-spl-transfer"]);
+
+    // The deterministic agent has no result for the second benchmark
+    assert!(matrix.rows[1].cells[0].is_none());
+    let cell = matrix.rows[1].cells[1].as_ref().unwrap();
+    assert_eq!((cell.passed, cell.trials), (1, 2));
+    assert!((cell.mean_score - 0.75).abs() < 1e-9);
+
+    assert_eq!(matrix.agent_means(), [Some(1.0), Some(0.875)]);
+    assert_eq!(matrix.agent_passes(), [1, 1]);
+    println!("✅ Score matrix arranged per benchmark and agent");
+}
@@ -40,3 +40,95 @@ async fn test_search_benchmarks() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_latest_results_and_session_log() -> Result<(), Box<dyn std::error::Error>> {
+    use reev_db::{shared::performance::AgentPerformance, types::SessionInfo};
+    use std::collections::HashMap;
+
+    let temp_dir = TempDir::new()?;
+    let db_path = temp_dir.path().join("test.db");
+    let config = DatabaseConfig::new(db_path.to_string_lossy());
+
+    let writer = DatabaseWriter::new(config.clone()).await?;
+    writer
+        .create_session(&SessionInfo {
+            session_id: "session-2".to_string(),
+            benchmark_id: "001-sol-transfer".to_string(),
+            agent_type: "deterministic".to_string(),
+            interface: "cli".to_string(),
+            start_time: 1,
+            end_time: None,
+            status: "running".to_string(),
+            score: None,
+            final_status: None,
+            run_id: None,
+        })
+        .await?;
+    writer
+        .store_complete_log("session-2", r#"{"prompt":"p","steps":[]}"#)
+        .await?;
+    writer.record_argument_retries("session-2", 2).await?;
+
+    for (session_id, agent_type, score, timestamp) in [
+        (
+            "session-1",
+            "deterministic",
+            0.5,
+            "2025-01-01T00:00:00+00:00",
+        ),
+        (
+            "session-2",
+            "deterministic",
+            1.0,
+            "2025-01-02T00:00:00+00:00",
+        ),
+        ("session-3", "glm-4.6", 0.75, "2025-01-01T00:00:00+00:00"),
+    ] {
+        writer
+            .insert_agent_performance(&AgentPerformance {
+                id: None,
+                session_id: session_id.to_string(),
+                benchmark_id: "001-sol-transfer".to_string(),
+                agent_type: agent_type.to_string(),
+                score,
+                final_status: "succeeded".to_string(),
+                execution_time_ms: Some(1000),
+                timestamp: timestamp.to_string(),
+                flow_log_id: None,
+                prompt_md5: None,
+                trial_index: 0,
+                additional_metrics: HashMap::new(),
+                run_id: None,
+                rescored: false,
+            })
+            .await?;
+    }
+
+    let reader = DatabaseReader::from_config(config).await?;
+
+    let latest = reader.get_latest_results(None).await?;
+    assert_eq!(latest.len(), 2);
+    assert_eq!(latest[0].agent_type, "deterministic");
+    assert_eq!(latest[0].session_id, "session-2");
+    assert_eq!(latest[1].agent_type, "glm-4.6");
+    assert_eq!(
+        latest[0].additional_metrics.get("argument_retries"),
+        Some(&2.0)
+    );
+    assert!(latest[1].additional_metrics.is_empty());
+
+    let deterministic = reader.get_latest_results(Some("deterministic")).await?;
+    assert_eq!(deterministic.len(), 1);
+    assert_eq!(deterministic[0].score, 1.0);
+    println!("✅ Latest results keep one record per benchmark and agent");
+
+    let session = reader.get_session("session-2").await?.expect("session");
+    assert_eq!(session.benchmark_id, "001-sol-transfer");
+    assert!(reader.get_session("missing").await?.is_none());
+    assert!(reader.get_session_log("session-2").await?.is_some());
+    assert!(reader.get_session_log("missing").await?.is_none());
+    println!("✅ Sessions and session logs are readable");
+
+    Ok(())
+}
@@ -0,0 +1,259 @@
+//! Evaluation Runs Test
+//!
+//! Verifies that evaluation runs are stored with their provenance, that sessions and
+//! performance records link to them, and that `run_id` is added to older databases.
+
+use reev_db::shared::performance::AgentPerformance;
+use reev_db::types::SessionInfo;
+use reev_db::{
+    DatabaseConfig, DatabaseReader, DatabaseWriter, EvaluationRun, QueryFilter, RunAgentConfig,
+};
+use std::collections::{BTreeMap, HashMap};
+use tempfile::TempDir;
+
+fn evaluation_run(run_id: &str, start_time: i64) -> EvaluationRun {
+    EvaluationRun {
+        run_id: run_id.to_string(),
+        agent_type: "glm-4.6".to_string(),
+        agent_config: RunAgentConfig {
+            model: "glm-4.6".to_string(),
+            temperature: Some(0.2),
+            prompt_version: Some("v2".to_string()),
+        },
+        git_commit: Some("0123456789abcdef".to_string()),
+        benchmark_hashes: BTreeMap::from([
+            ("001".to_string(), "aa".repeat(32)),
+            ("002".to_string(), "bb".repeat(32)),
+        ]),
+        seed: Some(u64::MAX),
+        trials: 3,
+        status: "running".to_string(),
+        start_time,
+        end_time: None,
+    }
+}
+
+fn session(session_id: &str, benchmark_id: &str, run_id: Option<&str>) -> SessionInfo {
+    SessionInfo {
+        session_id: session_id.to_string(),
+        benchmark_id: benchmark_id.to_string(),
+        agent_type: "glm-4.6".to_string(),
+        interface: "tui".to_string(),
+        start_time: 1_700_000_000,
+        end_time: None,
+        status: "running".to_string(),
+        score: None,
+        final_status: None,
+        run_id: run_id.map(str::to_string),
+    }
+}
+
+fn performance(
+    session_id: &str,
+    benchmark_id: &str,
+    score: f64,
+    run_id: Option<&str>,
+) -> AgentPerformance {
+    AgentPerformance {
+        id: None,
+        session_id: session_id.to_string(),
+        benchmark_id: benchmark_id.to_string(),
+        agent_type: "glm-4.6".to_string(),
+        score,
+        final_status: if score >= 0.75 { "succeeded" } else { "failed" }.to_string(),
+        execution_time_ms: Some(1000),
+        timestamp: "2025-01-01T00:00:00Z".to_string(),
+        flow_log_id: None,
+        prompt_md5: None,
+        trial_index: 0,
+        run_id: run_id.map(str::to_string),
+        rescored: false,
+        additional_metrics: HashMap::new(),
+    }
+}
+
+#[tokio::test]
+async fn test_run_lifecycle() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let config = DatabaseConfig::new(temp_dir.path().join("runs.db").to_string_lossy());
+    let db = DatabaseWriter::new(config).await?;
+
+    let run = evaluation_run("run-1", 1_700_000_000);
+    db.create_run(&run).await?;
+    assert_eq!(db.get_run("run-1").await?, Some(run.clone()));
+    assert_eq!(db.get_run("missing").await?, None);
+
+    db.complete_run("run-1", "completed", 1_700_000_100).await?;
+    let stored = db.get_run("run-1").await?.unwrap();
+    assert_eq!(stored.status, "completed");
+    assert_eq!(stored.end_time, Some(1_700_000_100));
+    assert_eq!(stored.seed, Some(u64::MAX));
+    assert_eq!(stored.trials, 3);
+    assert_eq!(stored.benchmark_hashes, run.benchmark_hashes);
+    println!("✅ Run stored with its provenance");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sessions_and_results_link_to_run() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let config = DatabaseConfig::new(temp_dir.path().join("runs.db").to_string_lossy());
+    let db = DatabaseWriter::new(config.clone()).await?;
+
+    db.create_run(&evaluation_run("old-run", 1_700_000_000))
+        .await?;
+    db.create_run(&evaluation_run("new-run", 1_700_000_500))
+        .await?;
+
+    db.create_session(&session("s1", "001", Some("new-run")))
+        .await?;
+    db.create_session(&session("s2", "002", Some("new-run")))
+        .await?;
+    db.create_session(&session("s3", "001", Some("old-run")))
+        .await?;
+    db.create_session(&session("s4", "001", None)).await?;
+
+    db.insert_agent_performance(&performance("s1", "001", 1.0, Some("new-run")))
+        .await?;
+    db.insert_agent_performance(&performance("s2", "002", 0.5, Some("new-run")))
+        .await?;
+    db.insert_agent_performance(&performance("s3", "001", 0.0, Some("old-run")))
+        .await?;
+    db.insert_agent_performance(&performance("s4", "001", 1.0, None))
+        .await?;
+
+    let sessions = db.list_run_sessions("new-run").await?;
+    let ids: Vec<&str> = sessions.iter().map(|s| s.session_id.as_str()).collect();
+    assert_eq!(ids, vec!["s1", "s2"]);
+    assert_eq!(sessions[0].run_id.as_deref(), Some("new-run"));
+
+    let runs = db.list_runs(None).await?;
+    assert_eq!(runs.len(), 2);
+    assert_eq!(runs[0].run.run_id, "new-run");
+    assert_eq!((runs[0].total, runs[0].passed), (2, 1));
+    assert_eq!(runs[0].average_score, Some(0.75));
+    assert_eq!((runs[1].total, runs[1].passed), (1, 0));
+    assert_eq!(db.list_runs(Some(1)).await?.len(), 1);
+    println!("✅ Runs summarize their sessions");
+
+    let reader = DatabaseReader::from_config(config).await?;
+    let filtered = reader
+        .get_agent_performance(Some(QueryFilter::new().run_id("old-run")))
+        .await?;
+    assert_eq!(filtered.len(), 1);
+    assert_eq!(filtered[0].session_id, "s3");
+    assert_eq!(filtered[0].run_id.as_deref(), Some("old-run"));
+
+    let mut latest = reader.get_latest_run_results("new-run").await?;
+    latest.sort_by(|a, b| a.benchmark_id.cmp(&b.benchmark_id));
+    let sessions: Vec<&str> = latest.iter().map(|p| p.session_id.as_str()).collect();
+    assert_eq!(sessions, vec!["s1", "s2"]);
+    assert_eq!(reader.list_runs(None).await?.len(), 2);
+    println!("✅ Results filtered by run");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_resume_bookkeeping() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let config = DatabaseConfig::new(temp_dir.path().join("runs.db").to_string_lossy());
+    let db = DatabaseWriter::new(config).await?;
+
+    db.create_run(&evaluation_run("run-1", 1_700_000_000))
+        .await?;
+    db.create_session(&session("done", "001", Some("run-1")))
+        .await?;
+    db.complete_session(
+        "done",
+        &reev_db::types::SessionResult {
+            end_time: 1_700_000_050,
+            score: 1.0,
+            final_status: "Succeeded".to_string(),
+        },
+    )
+    .await?;
+    db.insert_agent_performance(&performance("done", "001", 1.0, Some("run-1")))
+        .await?;
+    // Left behind by a runner that died mid-benchmark
+    db.create_session(&session("partial", "002", Some("run-1")))
+        .await?;
+    db.create_session(&session("other", "002", None)).await?;
+    db.complete_run("run-1", "failed", 1_700_000_100).await?;
+
+    assert_eq!(db.interrupt_run_sessions("run-1", 1_700_000_200).await?, 1);
+    let partial = db.get_session("partial").await?.unwrap();
+    assert_eq!(partial.status, "interrupted");
+    assert_eq!(partial.end_time, Some(1_700_000_200));
+    assert_eq!(db.get_session("done").await?.unwrap().status, "Succeeded");
+    assert_eq!(db.get_session("other").await?.unwrap().status, "running");
+    assert_eq!(db.interrupt_run_sessions("run-1", 1_700_000_300).await?, 0);
+
+    let completed = db.completed_attempts("run-1").await?;
+    assert_eq!(
+        completed.into_iter().collect::<Vec<_>>(),
+        vec![("001".to_string(), 0)]
+    );
+
+    db.reopen_run("run-1").await?;
+    let run = db.get_run("run-1").await?.unwrap();
+    assert_eq!(run.status, "running");
+    assert_eq!(run.end_time, None);
+    println!("✅ Interrupted run prepared for resuming");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_run_id_added_to_existing_database() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let path = temp_dir.path().join("old.db");
+
+    // A database created before evaluation runs existed
+    {
+        let db = turso::Builder::new_local(&path.to_string_lossy())
+            .build()
+            .await?;
+        let conn = db.connect()?;
+        conn.execute(
+            "CREATE TABLE agent_performance (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id TEXT NOT NULL,
+                benchmark_id TEXT NOT NULL,
+                agent_type TEXT NOT NULL,
+                score REAL NOT NULL,
+                final_status TEXT NOT NULL,
+                execution_time_ms INTEGER,
+                created_at INTEGER DEFAULT (strftime('%s', 'now')),
+                prompt_md5 TEXT
+            )",
+            (),
+        )
+        .await?;
+        conn.execute(
+            "INSERT INTO agent_performance (session_id, benchmark_id, agent_type, score, final_status, created_at)
+             VALUES ('old', '001', 'glm-4.6', 1.0, 'succeeded', '2024-01-01T00:00:00Z')",
+            (),
+        )
+        .await?;
+    }
+
+    let config = DatabaseConfig::new(path.to_string_lossy());
+    let db = DatabaseWriter::new(config.clone()).await?;
+    db.create_run(&evaluation_run("run-1", 1_700_000_000))
+        .await?;
+    db.insert_agent_performance(&performance("new", "001", 0.5, Some("run-1")))
+        .await?;
+
+    let reader = DatabaseReader::from_config(config).await?;
+    let stored = reader.get_agent_performance(None).await?;
+    assert_eq!(stored.len(), 2);
+    assert_eq!(stored[0].run_id.as_deref(), Some("run-1"));
+    assert_eq!(stored[1].run_id, None);
+    let runs = reader.list_runs(None).await?;
+    assert_eq!((runs[0].total, runs[0].passed), (1, 0));
+    println!("✅ run_id column added to existing database");
+
+    Ok(())
+}
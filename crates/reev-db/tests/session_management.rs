@@ -33,6 +33,7 @@ async fn test_session_lifecycle() -> Result<(), Box<dyn std::error::Error>> {
         status: "running".to_string(),
         score: None,
         final_status: None,
+        run_id: None,
     };
 
     // Create session
@@ -87,6 +88,15 @@ async fn test_session_lifecycle() -> Result<(), Box<dyn std::error::Error>> {
     assert!(retrieved_log.contains("test tool"));
     println!("✅ Log retrieved successfully");
 
+    // Storing the log again, e.g. a flow's trace after its flow log, replaces it
+    db.store_complete_log(&session_id, r#"{"prompt":"p","steps":[]}"#)
+        .await?;
+    assert_eq!(
+        db.get_session_log(&session_id).await?,
+        r#"{"prompt":"p","steps":[]}"#
+    );
+    println!("✅ Log replaced successfully");
+
     // Test Web interface session
     let web_session_id = uuid::Uuid::new_v4().to_string();
     let web_session = SessionInfo {
@@ -99,6 +109,7 @@ async fn test_session_lifecycle() -> Result<(), Box<dyn std::error::Error>> {
         status: "running".to_string(),
         score: None,
         final_status: None,
+        run_id: None,
     };
 
     db.create_session(&web_session).await?;
@@ -161,6 +172,7 @@ async fn test_interface_consistency() -> Result<(), Box<dyn std::error::Error>>
         status: "running".to_string(),
         score: None,
         final_status: None,
+        run_id: None,
     };
 
     // Create Web session (identical except interface)
@@ -174,6 +186,7 @@ async fn test_interface_consistency() -> Result<(), Box<dyn std::error::Error>>
         status: "running".to_string(),
         score: None,
         final_status: None,
+        run_id: None,
     };
 
     db.create_session(&tui_session).await?;
@@ -230,3 +243,120 @@ async fn test_interface_consistency() -> Result<(), Box<dyn std::error::Error>>
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_session_timeout() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let db_path = temp_dir.path().join("test_sessions.db");
+    let config = DatabaseConfig::new(db_path.to_string_lossy());
+    let db = DatabaseWriter::new(config).await?;
+
+    let session = SessionInfo {
+        session_id: "slow".to_string(),
+        benchmark_id: "test-benchmark".to_string(),
+        agent_type: "test-agent".to_string(),
+        interface: "tui".to_string(),
+        start_time: 1_700_000_000,
+        end_time: None,
+        status: "running".to_string(),
+        score: None,
+        final_status: None,
+        run_id: None,
+    };
+    db.create_session(&session).await?;
+
+    db.time_out_session("slow", 1_700_000_030).await?;
+    let stored = db.get_session("slow").await?.unwrap();
+    assert_eq!(stored.status, "timeout");
+    assert_eq!(stored.final_status.as_deref(), Some("timed_out"));
+    assert_eq!(stored.score, Some(0.0));
+    assert_eq!(stored.end_time, Some(1_700_000_030));
+    println!("✅ Session marked as timed out");
+
+    assert!(db.time_out_session("missing", 1_700_000_060).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_session_budget_exceeded() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let db_path = temp_dir.path().join("test_sessions.db");
+    let config = DatabaseConfig::new(db_path.to_string_lossy());
+    let db = DatabaseWriter::new(config).await?;
+
+    let session = SessionInfo {
+        session_id: "looping".to_string(),
+        benchmark_id: "test-benchmark".to_string(),
+        agent_type: "test-agent".to_string(),
+        interface: "tui".to_string(),
+        start_time: 1_700_000_000,
+        end_time: None,
+        status: "running".to_string(),
+        score: None,
+        final_status: None,
+        run_id: None,
+    };
+    db.create_session(&session).await?;
+    assert_eq!(db.get_budget_exceeded("looping").await?, None);
+
+    db.complete_session(
+        "looping",
+        &SessionResult {
+            end_time: 1_700_000_030,
+            score: 0.5,
+            final_status: "budget_exceeded".to_string(),
+        },
+    )
+    .await?;
+    db.record_budget_exceeded("looping", "max_turns").await?;
+
+    let stored = db.get_session("looping").await?.unwrap();
+    assert_eq!(stored.final_status.as_deref(), Some("budget_exceeded"));
+    assert_eq!(stored.score, Some(0.5));
+    assert_eq!(
+        db.get_budget_exceeded("looping").await?.as_deref(),
+        Some("max_turns")
+    );
+    println!("✅ Session stopped at its budget records the limit");
+
+    // The limit of a session that was never created would be lost
+    assert!(db
+        .record_budget_exceeded("missing", "max_turns")
+        .await
+        .is_err());
+    println!("✅ Exceeded budget of an unknown session is an error");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_session_argument_retries() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let db_path = temp_dir.path().join("test_sessions.db");
+    let config = DatabaseConfig::new(db_path.to_string_lossy());
+    let db = DatabaseWriter::new(config).await?;
+
+    let session = SessionInfo {
+        session_id: "corrected".to_string(),
+        benchmark_id: "test-benchmark".to_string(),
+        agent_type: "test-agent".to_string(),
+        interface: "cli".to_string(),
+        start_time: 1_700_000_000,
+        end_time: None,
+        status: "running".to_string(),
+        score: None,
+        final_status: None,
+        run_id: None,
+    };
+    db.create_session(&session).await?;
+    assert_eq!(db.get_argument_retries("corrected").await?, 0);
+
+    db.record_argument_retries("corrected", 3).await?;
+    assert_eq!(db.get_argument_retries("corrected").await?, 3);
+    assert_eq!(db.get_argument_retries("missing").await?, 0);
+    assert!(db.record_argument_retries("missing", 1).await.is_err());
+    println!("✅ Session records its argument retries");
+
+    Ok(())
+}
@@ -0,0 +1,193 @@
+//! Repeated Trials Test
+//!
+//! Verifies pass@k, the bootstrap confidence interval, grouping stored records into
+//! the latest batch of trials, and that `trial_index` is stored and added to older
+//! databases.
+
+use reev_db::shared::performance::AgentPerformance;
+use reev_db::trials::{bootstrap_mean_ci, latest_trial_stats, pass_at_k, TrialRecord};
+use reev_db::{DatabaseConfig, DatabaseReader, DatabaseWriter, TrialStats};
+use std::collections::HashMap;
+use tempfile::TempDir;
+
+fn performance(benchmark_id: &str, score: f64, trial_index: u32) -> AgentPerformance {
+    AgentPerformance {
+        id: None,
+        session_id: format!("{benchmark_id}-{trial_index}"),
+        benchmark_id: benchmark_id.to_string(),
+        agent_type: "glm-4.6".to_string(),
+        score,
+        final_status: if score >= 0.75 { "succeeded" } else { "failed" }.to_string(),
+        execution_time_ms: Some(1000),
+        timestamp: format!("2025-01-01T00:00:0{trial_index}Z"),
+        flow_log_id: None,
+        prompt_md5: None,
+        trial_index,
+        additional_metrics: HashMap::new(),
+        run_id: None,
+        rescored: false,
+    }
+}
+
+#[test]
+fn test_pass_at_k() {
+    assert_eq!(pass_at_k(5, 0, 1), 0.0);
+    assert_eq!(pass_at_k(5, 5, 1), 1.0);
+    assert!((pass_at_k(5, 2, 1) - 0.4).abs() < 1e-9);
+    // 1 - C(3,2)/C(5,2) = 1 - 3/10
+    assert!((pass_at_k(5, 2, 2) - 0.7).abs() < 1e-9);
+    // Fewer failures than k attempts: one of them must pass
+    assert_eq!(pass_at_k(5, 2, 4), 1.0);
+    assert_eq!(pass_at_k(0, 0, 1), 0.0);
+}
+
+#[test]
+fn test_trial_stats_from_outcomes() {
+    let outcomes = [(1.0, true), (0.5, false), (1.0, true), (0.5, false)];
+    let stats = TrialStats::from_outcomes("001", "glm-4.6", &outcomes, None).unwrap();
+    assert_eq!(stats.trials, 4);
+    assert_eq!(stats.passed, 2);
+    assert_eq!(stats.mean_score, 0.75);
+    assert!((stats.std_dev - (1.0f64 / 12.0).sqrt()).abs() < 1e-9);
+    assert_eq!(stats.pass_at_1, 0.5);
+    assert_eq!(stats.k, 4);
+    assert_eq!(stats.pass_at_k, 1.0);
+    assert!(stats.ci_low >= 0.5 && stats.ci_low < stats.mean_score);
+    assert!(stats.ci_high <= 1.0 && stats.ci_high > stats.mean_score);
+
+    // k is capped at the number of trials
+    let stats = TrialStats::from_outcomes("001", "glm-4.6", &outcomes, Some(10)).unwrap();
+    assert_eq!(stats.k, 4);
+    assert!(TrialStats::from_outcomes("001", "glm-4.6", &[], None).is_none());
+
+    // A single trial has no spread
+    let stats = TrialStats::from_outcomes("001", "glm-4.6", &[(0.8, true)], None).unwrap();
+    assert_eq!(stats.std_dev, 0.0);
+    assert_eq!((stats.ci_low, stats.ci_high), (0.8, 0.8));
+}
+
+#[test]
+fn test_bootstrap_is_reproducible() {
+    let scores = [0.2, 0.4, 0.9, 1.0, 0.7];
+    let ci = bootstrap_mean_ci(&scores, 0.95, 500);
+    assert_eq!(ci, bootstrap_mean_ci(&scores, 0.95, 500));
+    assert!(ci.0 <= ci.1);
+    assert_eq!(bootstrap_mean_ci(&[1.0, 1.0, 1.0], 0.95, 100), (1.0, 1.0));
+}
+
+#[test]
+fn test_latest_trial_stats_uses_latest_batch() {
+    let agent = "glm-4.6".to_string();
+    let newest_first = [
+        // Latest batch of three trials
+        (2u32, 1.0, "succeeded"),
+        (1, 0.0, "failed"),
+        (0, 1.0, "succeeded"),
+        // An older single run
+        (0, 0.0, "failed"),
+    ];
+    let performances: Vec<_> = newest_first
+        .iter()
+        .map(
+            |(trial_index, score, status)| reev_db::types::AgentPerformance {
+                id: None,
+                session_id: String::new(),
+                benchmark_id: "001".to_string(),
+                agent_type: agent.clone(),
+                score: *score,
+                final_status: status.to_string(),
+                execution_time_ms: None,
+                timestamp: String::new(),
+                flow_log_id: None,
+                prompt_md5: None,
+                trial_index: *trial_index,
+                additional_metrics: HashMap::new(),
+                run_id: None,
+                rescored: false,
+            },
+        )
+        .collect();
+
+    let stats = latest_trial_stats(performances.iter().map(TrialRecord::from), None);
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].trials, 3);
+    assert_eq!(stats[0].passed, 2);
+    assert!((stats[0].pass_at_1 - 2.0 / 3.0).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_trial_index_stored() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let config = DatabaseConfig::new(temp_dir.path().join("trials.db").to_string_lossy());
+    let db = DatabaseWriter::new(config.clone()).await?;
+
+    for (trial_index, score) in [0.5, 1.0, 1.0].into_iter().enumerate() {
+        db.insert_agent_performance(&performance("001", score, trial_index as u32))
+            .await?;
+    }
+
+    let reader = DatabaseReader::from_config(config).await?;
+    let stored = reader.get_agent_performance(None).await?;
+    let mut indexes: Vec<u32> = stored.iter().map(|p| p.trial_index).collect();
+    indexes.sort();
+    assert_eq!(indexes, vec![0, 1, 2]);
+
+    let summaries = db.get_agent_performance().await?;
+    assert_eq!(summaries.len(), 1);
+    let stats = &summaries[0].trial_stats;
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].trials, 3);
+    assert_eq!(stats[0].passed, 2);
+    println!("✅ Trial statistics included in performance summary");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_trial_index_added_to_existing_database() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = TempDir::new()?;
+    let path = temp_dir.path().join("old.db");
+
+    // A database created before trial_index existed
+    {
+        let db = turso::Builder::new_local(&path.to_string_lossy())
+            .build()
+            .await?;
+        let conn = db.connect()?;
+        conn.execute(
+            "CREATE TABLE agent_performance (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id TEXT NOT NULL,
+                benchmark_id TEXT NOT NULL,
+                agent_type TEXT NOT NULL,
+                score REAL NOT NULL,
+                final_status TEXT NOT NULL,
+                execution_time_ms INTEGER,
+                created_at INTEGER DEFAULT (strftime('%s', 'now')),
+                prompt_md5 TEXT
+            )",
+            (),
+        )
+        .await?;
+        conn.execute(
+            "INSERT INTO agent_performance (session_id, benchmark_id, agent_type, score, final_status, created_at)
+             VALUES ('old', '001', 'glm-4.6', 1.0, 'succeeded', '2024-01-01T00:00:00Z')",
+            (),
+        )
+        .await?;
+    }
+
+    let config = DatabaseConfig::new(path.to_string_lossy());
+    let db = DatabaseWriter::new(config.clone()).await?;
+    db.insert_agent_performance(&performance("001", 0.5, 1))
+        .await?;
+
+    let reader = DatabaseReader::from_config(config).await?;
+    let stored = reader.get_agent_performance(None).await?;
+    assert_eq!(stored.len(), 2);
+    assert_eq!(stored[0].trial_index, 1);
+    assert_eq!(stored[1].trial_index, 0);
+    println!("✅ trial_index column added to existing database");
+
+    Ok(())
+}
@@ -285,6 +285,7 @@ impl EnhancedOtelLogger {
             "successful_calls": calls.iter().filter(|c| matches!(c.status, ToolExecutionStatus::Success)).count(),
             "failed_calls": calls.iter().filter(|c| matches!(c.status, ToolExecutionStatus::Error)).count(),
             "timeout_calls": calls.iter().filter(|c| matches!(c.status, ToolExecutionStatus::Timeout)).count(),
+            "argument_retries": calls.iter().filter(|c| c.metadata.get("argument_retry").is_some()).count(),
             "average_execution_time_ms": average_execution_time_ms,
             "tools_used": tools_used
         });
@@ -358,6 +359,11 @@ pub fn init_enhanced_otel_logging_with_session(session_id: String) -> Result<Str
     Ok(log_file)
 }
 
+/// Whether tool calls are written to the enhanced otel logs (`agent.enhanced_otel`)
+pub fn enhanced_otel_enabled() -> bool {
+    reev_config::get().agent.enhanced_otel
+}
+
 /// Get the global enhanced otel logger
 pub fn get_enhanced_otel_logger() -> Result<&'static EnhancedOtelLogger> {
     ENHANCED_OTEL_LOGGER
@@ -471,7 +477,7 @@ macro_rules! log_enhanced_tool_error_update {
 macro_rules! log_tool_call {
     ($tool_name:expr, $args:expr) => {
         // Enhanced otel logging is enabled by default (can be disabled with REEV_ENHANCED_OTEL=0)
-        if std::env::var("REEV_ENHANCED_OTEL").unwrap_or_else(|_| "1".to_string()) != "0" {
+        if $crate::enhanced_otel::enhanced_otel_enabled() {
             // Also log to enhanced file-based system
             let input_params = serde_json::to_value($args)
                 .unwrap_or_else(|_| serde_json::Value::Object(Default::default()));
@@ -511,7 +517,7 @@ macro_rules! log_tool_call {
 macro_rules! log_tool_completion {
     ($tool_name:expr, $execution_time_ms:expr, $result:expr, $success:expr) => {
         // Enhanced otel logging is enabled by default (can be disabled with REEV_ENHANCED_OTEL=0)
-        if std::env::var("REEV_ENHANCED_OTEL").unwrap_or_else(|_| "1".to_string()) != "0" {
+        if $crate::enhanced_otel::enhanced_otel_enabled() {
             // Also log to enhanced file-based system
             let input_params = serde_json::json!({}); // Will be populated from earlier call
             if $success {
@@ -31,6 +31,7 @@ pub trait DatabaseWriter: Send + Sync {
 /// Agent performance data for database storage
 #[derive(Debug, Clone)]
 pub struct AgentPerformanceData {
+    pub session_id: String,
     pub benchmark_id: String,
     pub agent_type: String,
     pub score: f64,
@@ -39,6 +40,10 @@ pub struct AgentPerformanceData {
     pub timestamp: String,
     pub flow_log_id: Option<i64>,
     pub prompt_md5: Option<String>,
+    /// Index of this attempt when the benchmark is run several times, from 0
+    pub trial_index: u32,
+    /// Evaluation run the attempt belongs to
+    pub run_id: Option<String>,
 }
 
 /// Main flow logger interface
@@ -50,6 +55,8 @@ pub struct FlowLogger {
     events: Vec<FlowEvent>,
     output_path: PathBuf,
     database: Option<Arc<dyn DatabaseWriter>>,
+    trial_index: u32,
+    run_id: Option<String>,
 }
 
 impl FlowLogger {
@@ -73,6 +80,8 @@ impl FlowLogger {
             events: Vec::new(),
             output_path,
             database: None,
+            trial_index: 0,
+            run_id: None,
         }
     }
 
@@ -100,6 +109,8 @@ impl FlowLogger {
             events: Vec::new(),
             output_path,
             database: None,
+            trial_index: 0,
+            run_id: None,
         }
     }
 
@@ -128,6 +139,8 @@ impl FlowLogger {
             events: Vec::new(),
             output_path,
             database: Some(database),
+            trial_index: 0,
+            run_id: None,
         }
     }
 
@@ -160,6 +173,8 @@ impl FlowLogger {
             events: Vec::new(),
             output_path,
             database: Some(database),
+            trial_index: 0,
+            run_id: None,
         }
     }
 
@@ -169,6 +184,18 @@ impl FlowLogger {
         self
     }
 
+    /// Set the trial index recorded with the agent performance
+    pub fn with_trial_index(mut self, trial_index: u32) -> Self {
+        self.trial_index = trial_index;
+        self
+    }
+
+    /// Set the evaluation run recorded with the agent performance
+    pub fn with_run_id(mut self, run_id: Option<String>) -> Self {
+        self.run_id = run_id;
+        self
+    }
+
     /// Log an LLM request event
     pub fn log_llm_request(&mut self, content: LlmRequestContent, depth: u32) {
         let event = FlowEvent {
@@ -322,6 +349,7 @@ impl FlowLogger {
                     );
 
                     let performance_data = AgentPerformanceData {
+                        session_id: self.session_id.clone(),
                         benchmark_id: flow_log.benchmark_id.clone(),
                         agent_type: flow_log.agent_type.clone(),
                         score,
@@ -330,6 +358,8 @@ impl FlowLogger {
                         timestamp,
                         flow_log_id: Some(flow_log_id),
                         prompt_md5: prompt_md5.clone(),
+                        trial_index: self.trial_index,
+                        run_id: self.run_id.clone(),
                     };
 
                     if let Err(e) = database.insert_agent_performance(&performance_data).await {
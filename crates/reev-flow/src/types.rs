@@ -83,6 +83,33 @@ pub struct LlmRequestContent {
     pub request_id: String,
 }
 
+/// Tokens consumed by LLM calls, as reported by the provider
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TokenUsage {
+    /// Prompt tokens, cached ones included
+    pub prompt_tokens: u64,
+    /// Completion tokens
+    pub completion_tokens: u64,
+    /// Prompt tokens served from the provider's prompt cache
+    pub cached_tokens: u64,
+}
+
+impl TokenUsage {
+    /// Prompt and completion tokens together
+    pub fn total_tokens(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+}
+
+impl std::ops::AddAssign for TokenUsage {
+    fn add_assign(&mut self, other: Self) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.completion_tokens += other.completion_tokens;
+        self.cached_tokens += other.cached_tokens;
+    }
+}
+
 /// Tool call content
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolCallContent {
@@ -314,9 +314,7 @@ impl FlowUtils {
 
     /// Get the default flow log output path
     pub fn get_default_flow_log_path() -> std::path::PathBuf {
-        std::env::var("REEV_FLOW_LOG_PATH")
-            .map(std::path::PathBuf::from)
-            .unwrap_or_else(|_| std::path::PathBuf::from("logs/flows"))
+        std::path::PathBuf::from(&reev_config::get().runner.flow_log_dir)
     }
 }
 
@@ -99,6 +99,12 @@ pub struct AgentObservation {
     pub account_states: HashMap<String, Value>,
     /// A map of account placeholder names to their actual public keys.
     pub key_map: HashMap<String, String>,
+    /// Pre/post balance changes for every account referenced by the last transaction.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub balance_deltas: Vec<crate::balance_delta::AccountBalanceDelta>,
+    /// Compute units, fee and instruction count of the last executed transaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_transaction_metrics: Option<crate::efficiency::TransactionMetrics>,
 }
 
 #[async_trait]
@@ -118,6 +124,27 @@ pub trait Agent {
     fn get_tool_calls(&self) -> Vec<crate::session_logger::ToolCallInfo> {
         Vec::new() // Default implementation returns empty vector
     }
+
+    /// The budget limit, such as `max_turns`, that stopped one of the agent's runs
+    fn budget_exceeded(&self) -> Option<String> {
+        None
+    }
+
+    /// The LLM tokens the agent's runs used, if it called a model that reported them
+    fn token_usage(&self) -> Option<reev_flow::TokenUsage> {
+        None
+    }
+
+    /// Tool calls of the agent's runs whose arguments were rejected and retried
+    fn argument_retries(&self) -> u32 {
+        0
+    }
+
+    /// Abort work the agent still does for an action the caller stopped waiting
+    /// for; returns whether there was any
+    async fn cancel(&mut self) -> Result<bool> {
+        Ok(false)
+    }
 }
 
 /// Structs for deserializing the third-party LLM's JSON response.
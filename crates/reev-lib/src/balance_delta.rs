@@ -0,0 +1,310 @@
+//! Pre/Post Balance Deltas
+//!
+//! Captures lamport and SPL token balances for every account referenced by a
+//! transaction, immediately before and after it is executed, and turns the two
+//! snapshots into per-account deltas. The deltas travel with the
+//! `AgentObservation` so they end up in the execution trace, the rendered
+//! result tree, the API transaction logs and the database.
+
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{program_pack::Pack, pubkey::Pubkey};
+use spl_token::state::Account as SplTokenAccount;
+use std::collections::HashMap;
+use tracing::warn;
+
+/// Number of accounts requested per `getMultipleAccounts` call (RPC limit).
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// Balance of a single account at one point in time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BalanceSnapshot {
+    /// Lamports held by the account (0 if it does not exist).
+    pub lamports: u64,
+    /// The mint, if the account is an SPL token account.
+    pub mint: Option<Pubkey>,
+    /// The raw token amount, if the account is an SPL token account.
+    pub token_amount: Option<u64>,
+}
+
+/// The change in balance of a single account across one transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountBalanceDelta {
+    /// The `key_map` placeholder for this account, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The base58 public key of the account.
+    pub pubkey: String,
+    /// Lamports before the transaction.
+    pub pre_lamports: u64,
+    /// Lamports after the transaction.
+    pub post_lamports: u64,
+    /// The token mint, for SPL token accounts (before or after the transaction).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mint: Option<String>,
+    /// Raw token amount before the transaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_token_amount: Option<u64>,
+    /// Raw token amount after the transaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_token_amount: Option<u64>,
+}
+
+impl AccountBalanceDelta {
+    /// Builds a delta from two snapshots of the same account.
+    pub fn from_snapshots(
+        pubkey: &Pubkey,
+        name: Option<String>,
+        pre: &BalanceSnapshot,
+        post: &BalanceSnapshot,
+    ) -> Self {
+        Self {
+            name,
+            pubkey: pubkey.to_string(),
+            pre_lamports: pre.lamports,
+            post_lamports: post.lamports,
+            mint: post.mint.or(pre.mint).map(|m| m.to_string()),
+            pre_token_amount: pre.token_amount,
+            post_token_amount: post.token_amount,
+        }
+    }
+
+    /// Signed lamport change (`post - pre`).
+    pub fn lamports_change(&self) -> i128 {
+        self.post_lamports as i128 - self.pre_lamports as i128
+    }
+
+    /// Signed raw token change (`post - pre`), if either side is a token account.
+    pub fn token_change(&self) -> Option<i128> {
+        if self.pre_token_amount.is_none() && self.post_token_amount.is_none() {
+            return None;
+        }
+        Some(
+            self.post_token_amount.unwrap_or(0) as i128
+                - self.pre_token_amount.unwrap_or(0) as i128,
+        )
+    }
+
+    /// Whether anything about this account's balance changed.
+    pub fn has_change(&self) -> bool {
+        self.lamports_change() != 0 || self.token_change().is_some_and(|c| c != 0)
+    }
+
+    /// A short label for display: the placeholder name if known, otherwise the pubkey.
+    pub fn label(&self) -> String {
+        match &self.name {
+            Some(name) => format!("{name} ({})", short_pubkey(&self.pubkey)),
+            None => self.pubkey.clone(),
+        }
+    }
+
+    /// Formats the lamport change, e.g. `1.000000000 → 0.899995000 SOL (-0.100005000)`.
+    pub fn format_sol_change(&self) -> String {
+        format!(
+            "{} → {} SOL ({})",
+            format_sol(self.pre_lamports as i128),
+            format_sol(self.post_lamports as i128),
+            format_signed_sol(self.lamports_change())
+        )
+    }
+
+    /// Formats the raw token change, e.g. `50000000 → 40000000 (-10000000) [EPjF…Dt1v]`.
+    pub fn format_token_change(&self) -> Option<String> {
+        let change = self.token_change()?;
+        let mint = self
+            .mint
+            .as_deref()
+            .map(|m| format!(" [{}]", short_pubkey(m)))
+            .unwrap_or_default();
+        Some(format!(
+            "{} → {} ({change:+}){mint}",
+            self.pre_token_amount.unwrap_or(0),
+            self.post_token_amount.unwrap_or(0)
+        ))
+    }
+}
+
+/// Fetches balance snapshots for `pubkeys`, in the same order.
+///
+/// Accounts that do not exist are reported with zero lamports. If the RPC call
+/// itself fails the error is logged and zeroed snapshots are returned so that a
+/// failed balance lookup never fails the step.
+pub fn snapshot_balances(rpc_client: &RpcClient, pubkeys: &[Pubkey]) -> Vec<BalanceSnapshot> {
+    let mut snapshots = Vec::with_capacity(pubkeys.len());
+    for chunk in pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        match rpc_client.get_multiple_accounts(chunk) {
+            Ok(accounts) => {
+                snapshots.extend(accounts.into_iter().map(|account| match account {
+                    Some(account) => {
+                        let token = (account.owner == spl_token::ID
+                            && account.data.len() == SplTokenAccount::LEN)
+                            .then(|| SplTokenAccount::unpack(&account.data).ok())
+                            .flatten();
+                        BalanceSnapshot {
+                            lamports: account.lamports,
+                            mint: token.map(|t| t.mint),
+                            token_amount: token.map(|t| t.amount),
+                        }
+                    }
+                    None => BalanceSnapshot::default(),
+                }));
+            }
+            Err(e) => {
+                warn!("[balance_delta] Failed to fetch account balances: {e}");
+                snapshots.extend(std::iter::repeat_n(BalanceSnapshot::default(), chunk.len()));
+            }
+        }
+    }
+    snapshots
+}
+
+/// Pairs up pre/post snapshots into deltas, naming accounts via `pubkey_map`.
+pub fn compute_balance_deltas(
+    pubkeys: &[Pubkey],
+    pre: &[BalanceSnapshot],
+    post: &[BalanceSnapshot],
+    pubkey_map: &HashMap<String, Pubkey>,
+) -> Vec<AccountBalanceDelta> {
+    let names: HashMap<&Pubkey, &String> = pubkey_map.iter().map(|(k, v)| (v, k)).collect();
+    pubkeys
+        .iter()
+        .zip(pre.iter().zip(post.iter()))
+        .map(|(pubkey, (pre, post))| {
+            AccountBalanceDelta::from_snapshots(
+                pubkey,
+                names.get(pubkey).map(|n| n.to_string()),
+                pre,
+                post,
+            )
+        })
+        .collect()
+}
+
+/// Renders deltas as a fixed-width text table, one row per account.
+pub fn format_balance_delta_table(deltas: &[AccountBalanceDelta]) -> String {
+    let rows: Vec<[String; 4]> = deltas
+        .iter()
+        .map(|d| {
+            [
+                d.label(),
+                format_signed_sol(d.lamports_change()),
+                d.token_change()
+                    .map(|c| format!("{c:+}"))
+                    .unwrap_or_else(|| "-".to_string()),
+                d.mint
+                    .as_deref()
+                    .map(short_pubkey)
+                    .unwrap_or_else(|| "-".to_string()),
+            ]
+        })
+        .collect();
+
+    let header = ["Account", "Δ SOL", "Δ Token", "Mint"];
+    let mut widths = header.map(|h| h.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: [&str; 4]| {
+        cells
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| {
+                let pad = width.saturating_sub(cell.chars().count());
+                format!("{cell}{}", " ".repeat(pad))
+            })
+            .collect::<Vec<_>>()
+            .join(" │ ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut lines = vec![format_row(header)];
+    lines.push(
+        widths
+            .iter()
+            .map(|w| "─".repeat(*w))
+            .collect::<Vec<_>>()
+            .join("─┼─"),
+    );
+    for row in &rows {
+        lines.push(format_row([&row[0], &row[1], &row[2], &row[3]]));
+    }
+    lines.join("\n")
+}
+
+fn format_sol(lamports: i128) -> String {
+    let sign = if lamports < 0 { "-" } else { "" };
+    let abs = lamports.unsigned_abs();
+    format!("{sign}{}.{:09}", abs / 1_000_000_000, abs % 1_000_000_000)
+}
+
+fn format_signed_sol(lamports: i128) -> String {
+    if lamports > 0 {
+        format!("+{}", format_sol(lamports))
+    } else {
+        format_sol(lamports)
+    }
+}
+
+fn short_pubkey(pubkey: &str) -> String {
+    if pubkey.len() <= 8 {
+        return pubkey.to_string();
+    }
+    format!("{}…{}", &pubkey[..4], &pubkey[pubkey.len() - 4..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delta_changes_and_table() {
+        let pubkey = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let pre = BalanceSnapshot {
+            lamports: 2_000_000_000,
+            mint: Some(mint),
+            token_amount: Some(50),
+        };
+        let post = BalanceSnapshot {
+            lamports: 1_899_995_000,
+            mint: Some(mint),
+            token_amount: Some(40),
+        };
+        let map = HashMap::from([("USER_USDC_ATA".to_string(), pubkey)]);
+
+        let deltas = compute_balance_deltas(&[pubkey], &[pre], &[post], &map);
+        assert_eq!(deltas.len(), 1);
+        let delta = &deltas[0];
+        assert_eq!(delta.name.as_deref(), Some("USER_USDC_ATA"));
+        assert_eq!(delta.lamports_change(), -100_005_000);
+        assert_eq!(delta.token_change(), Some(-10));
+        assert!(delta.has_change());
+        assert_eq!(
+            delta.format_sol_change(),
+            "2.000000000 → 1.899995000 SOL (-0.100005000)"
+        );
+
+        let table = format_balance_delta_table(&deltas);
+        assert!(table.starts_with("Account"));
+        assert!(table.contains("USER_USDC_ATA"));
+        assert!(table.contains("-0.100005000"));
+        assert!(table.contains("-10"));
+    }
+
+    #[test]
+    fn test_unchanged_system_account() {
+        let snapshot = BalanceSnapshot {
+            lamports: 1,
+            ..Default::default()
+        };
+        let delta =
+            AccountBalanceDelta::from_snapshots(&Pubkey::new_unique(), None, &snapshot, &snapshot);
+        assert_eq!(delta.token_change(), None);
+        assert!(!delta.has_change());
+        assert!(delta.format_token_change().is_none());
+    }
+}
@@ -23,10 +23,147 @@ pub struct TestCase {
     /// Optional flow definition for multi-step benchmarks.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub flow: Option<Vec<FlowStep>>,
+    /// Optional failure injection applied to RPC traffic while the agent acts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub faults: Option<crate::faults::FaultConfig>,
+    /// Optional time limit in seconds of a whole attempt at the benchmark, all
+    /// flow steps included.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u32>,
+    /// Optional limits on the agent runs of an attempt, flow steps included,
+    /// replacing those of the `[budget]` configuration that it sets.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<reev_config::BudgetSettings>,
     /// The ground truth assertions and expected outcomes for this benchmark.
     pub ground_truth: GroundTruth,
 }
 
+impl TestCase {
+    /// Time limit of a whole attempt, all flow steps included: the benchmark's
+    /// own, else `default`.
+    pub fn attempt_timeout(
+        &self,
+        default: Option<std::time::Duration>,
+    ) -> Option<std::time::Duration> {
+        self.timeout
+            .map(|secs| std::time::Duration::from_secs(u64::from(secs)))
+            .or(default)
+    }
+
+    /// Limits shared by the agent runs of an attempt: the benchmark's own, else
+    /// those of `global`.
+    pub fn run_budget(&self, global: reev_config::BudgetSettings) -> reev_config::BudgetSettings {
+        self.budget.unwrap_or_default().or(global)
+    }
+
+    /// Checks the benchmark for mistakes that deserialization alone does not catch.
+    ///
+    /// Returns a human-readable description of every problem found; an empty list
+    /// means the benchmark is valid.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if self.id.trim().is_empty() {
+            problems.push("id must not be empty".to_string());
+        }
+        if self.prompt.trim().is_empty() {
+            problems.push("prompt must not be empty".to_string());
+        }
+
+        let mut pubkeys = std::collections::HashSet::new();
+        for item in &self.initial_state {
+            if !pubkeys.insert(item.pubkey.as_str()) {
+                problems.push(format!("initial_state lists '{}' twice", item.pubkey));
+            }
+            if item.nonce.is_some() && item.multisig.is_some() {
+                problems.push(format!(
+                    "initial_state '{}' cannot be both a nonce and a multisig",
+                    item.pubkey
+                ));
+            }
+            if let Some(multisig) = &item.multisig {
+                let n = multisig.signers.len();
+                if multisig.m == 0 || multisig.m as usize > n || n > 11 {
+                    problems.push(format!(
+                        "multisig '{}' requires 1 <= m <= signers <= 11, got {}-of-{n}",
+                        item.pubkey, multisig.m
+                    ));
+                }
+            }
+        }
+        if !pubkeys.contains("USER_WALLET_PUBKEY") {
+            problems.push("initial_state must contain USER_WALLET_PUBKEY (fee payer)".to_string());
+        }
+
+        if self.timeout == Some(0) {
+            problems.push("timeout must be at least 1 second".to_string());
+        }
+
+        if let Some(flow) = &self.flow {
+            for (i, step) in flow.iter().enumerate() {
+                if step.step as usize != i + 1 {
+                    problems.push(format!(
+                        "flow step {} is out of order (expected step {})",
+                        step.step,
+                        i + 1
+                    ));
+                }
+                if step.prompt.trim().is_empty() {
+                    problems.push(format!("flow step {} has an empty prompt", step.step));
+                }
+                if step.timeout == Some(0) {
+                    problems.push(format!("flow step {} has a zero timeout", step.step));
+                }
+            }
+        }
+
+        for assertion in &self.ground_truth.final_state_assertions {
+            if assertion.weight() < 0.0 {
+                problems.push(format!(
+                    "assertion on '{}' has a negative weight",
+                    assertion.pubkey()
+                ));
+            }
+            if let StateAssertion::NonceAdvanced { pubkey, .. } = assertion {
+                let is_nonce = self
+                    .initial_state
+                    .iter()
+                    .any(|item| &item.pubkey == pubkey && item.nonce.is_some());
+                if !is_nonce {
+                    problems.push(format!(
+                        "NonceAdvanced assertion on '{pubkey}' has no matching nonce account"
+                    ));
+                }
+            }
+        }
+
+        if let Some(efficiency) = &self.ground_truth.efficiency {
+            if !(0.0..=1.0).contains(&efficiency.weight) {
+                problems.push("efficiency.weight must be between 0 and 1".to_string());
+            }
+        }
+
+        if let Some(faults) = &self.faults {
+            if faults.max_attempts == 0 {
+                problems.push("faults.max_attempts must be at least 1".to_string());
+            }
+            if !(0.0..=1.0).contains(&faults.recovery_weight) {
+                problems.push("faults.recovery_weight must be between 0 and 1".to_string());
+            }
+            for rule in &faults.rules {
+                if !(0.0..=1.0).contains(&rule.probability) {
+                    problems.push(format!(
+                        "fault rule {:?} has probability {} outside 0-1",
+                        rule.kind, rule.probability
+                    ));
+                }
+            }
+        }
+
+        problems
+    }
+}
+
 /// Defines the initial state of a single on-chain account for a benchmark.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -40,6 +177,14 @@ pub struct InitialStateItem {
     /// Optional data for the account, typically used for SPL token accounts.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub data: Option<SplAccountData>,
+    /// Optional durable nonce configuration; the account is created as an initialized
+    /// nonce account owned by the System Program.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<NonceAccountData>,
+    /// Optional SPL multisig configuration; the account is created as an initialized
+    /// SPL Token multisig.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multisig: Option<MultisigAccountData>,
 }
 
 /// Represents the data field for an SPL token account.
@@ -54,6 +199,24 @@ pub struct SplAccountData {
     pub amount: String,
 }
 
+/// Represents the configuration of a durable nonce account.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct NonceAccountData {
+    /// The placeholder or pubkey allowed to advance the nonce.
+    pub authority: String,
+}
+
+/// Represents the configuration of an SPL Token multisig account.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct MultisigAccountData {
+    /// The number of signers required to authorize an instruction.
+    pub m: u8,
+    /// The placeholders or pubkeys of all valid signers.
+    pub signers: Vec<String>,
+}
+
 /// The set of ground truth conditions and expected outcomes for a benchmark.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -78,6 +241,27 @@ pub struct GroundTruth {
     /// Useful for API-based benchmarks where the agent returns data instead of instructions.
     #[serde(default)]
     pub skip_instruction_validation: bool,
+
+    /// Optional compute unit, fee and instruction budgets.
+    /// When present, efficiency against these budgets contributes to the final score.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub efficiency: Option<crate::efficiency::EfficiencyBudget>,
+}
+
+impl GroundTruth {
+    /// The ground truth of one flow step: the expected instructions of `step`,
+    /// with the flow's assertions.
+    pub fn for_flow_step(&self, step: u32) -> GroundTruth {
+        GroundTruth {
+            expected_instructions: self
+                .expected_instructions
+                .iter()
+                .filter(|instruction| instruction.step.unwrap_or(1) == step)
+                .cloned()
+                .collect(),
+            ..self.clone()
+        }
+    }
 }
 
 /// Provides a default value for `transaction_status` for backward compatibility.
@@ -103,7 +287,8 @@ pub struct FlowStep {
     /// Whether this step is critical for flow success
     #[serde(default)]
     pub critical: bool,
-    /// Timeout for this step in seconds
+    /// Timeout for this step in seconds, within what is left of the benchmark's
+    /// `timeout`
     #[serde(default)]
     pub timeout: Option<u32>,
     /// Dependencies on previous steps
@@ -152,6 +337,14 @@ pub enum StateAssertion {
         #[serde(default = "default_weight")]
         weight: f64,
     },
+    /// Asserts that the stored blockhash of a durable nonce account changed.
+    NonceAdvanced {
+        /// The pubkey of the nonce account to check.
+        pubkey: String,
+        /// The weight of this assertion for scoring. Defaults to 1.0.
+        #[serde(default = "default_weight")]
+        weight: f64,
+    },
 }
 
 impl StateAssertion {
@@ -160,6 +353,7 @@ impl StateAssertion {
             StateAssertion::SolBalance { pubkey, .. } => pubkey,
             StateAssertion::SolBalanceChange { pubkey, .. } => pubkey,
             StateAssertion::TokenAccountBalance { pubkey, .. } => pubkey,
+            StateAssertion::NonceAdvanced { pubkey, .. } => pubkey,
         }
     }
 
@@ -168,6 +362,7 @@ impl StateAssertion {
             StateAssertion::SolBalance { weight, .. } => *weight,
             StateAssertion::SolBalanceChange { weight, .. } => *weight,
             StateAssertion::TokenAccountBalance { weight, .. } => *weight,
+            StateAssertion::NonceAdvanced { weight, .. } => *weight,
         }
     }
 
@@ -212,6 +407,9 @@ pub struct BenchmarkInstruction {
     /// The weight for correct instruction data.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub data_weight: Option<f64>,
+    /// The flow step that sends this instruction; the first when not given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step: Option<u32>,
 }
 
 /// A serializable representation of an `AccountMeta` for use in benchmarks.
@@ -203,6 +203,11 @@ pub mod llm {
             .and_then(|s| f32::from_str(&s).ok())
             .unwrap_or(0.7)
     }
+
+    /// Get the version label of the agent prompts, recorded with evaluation runs
+    pub fn prompt_version() -> Option<String> {
+        env::var("REEV_PROMPT_VERSION").ok()
+    }
 }
 
 /// Load and validate all configuration
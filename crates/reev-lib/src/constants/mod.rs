@@ -28,7 +28,7 @@ pub use amounts::{
 pub use env::{
     agents::{default_agent, enable_mock, mock_param},
     database::{database_path, enable_connection_pooling, max_connections},
-    llm::{google_api_key, local_server_url, max_turns, temperature},
+    llm::{google_api_key, local_server_url, max_turns, prompt_version, temperature},
     load_config,
     logging::{enable_debug, rust_log_filter},
     network::{reev_agent_host, reev_agent_port, surfpool_host, surfpool_port},
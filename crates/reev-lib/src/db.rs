@@ -33,7 +33,8 @@ pub use reev_db::{
 };
 
 // Re-export session types from types module
-pub use reev_db::types::{SessionInfo, SessionResult};
+pub use reev_db::types::{SessionEfficiency, SessionInfo, SessionLlmUsage, SessionResult};
+pub use reev_db::writer::sessions::BalanceDeltaData;
 
 // Re-export shared types for clarity
 pub use reev_db::shared::flow::DBFlowLog as SharedFlowLog;
@@ -52,6 +53,12 @@ pub struct AgentPerformanceData {
     pub timestamp: String,
     pub flow_log_id: Option<i64>,
     pub prompt_md5: Option<String>,
+    /// Index of this attempt when the benchmark is run several times, from 0
+    #[serde(default)]
+    pub trial_index: u32,
+    /// Evaluation run the attempt belongs to
+    #[serde(default)]
+    pub run_id: Option<String>,
 }
 
 // Removed conflicting DbAgentPerformance conversion - using shared AgentPerformance instead
@@ -69,11 +76,84 @@ impl From<AgentPerformanceData> for SharedPerformanceMetrics {
             timestamp: data.timestamp,
             flow_log_id: data.flow_log_id,
             prompt_md5: data.prompt_md5,
+            trial_index: data.trial_index,
+            run_id: data.run_id,
+            rescored: false,
             additional_metrics: std::collections::HashMap::new(),
         }
     }
 }
 
+/// Flattens the balance deltas captured in every trace step into rows for storage
+pub fn balance_deltas_from_trace(
+    session_id: &str,
+    trace: &crate::trace::ExecutionTrace,
+) -> Vec<BalanceDeltaData> {
+    trace
+        .steps
+        .iter()
+        .enumerate()
+        .flat_map(|(step_index, step)| {
+            step.observation
+                .balance_deltas
+                .iter()
+                .map(move |delta| BalanceDeltaData {
+                    session_id: session_id.to_string(),
+                    step_index: step_index as u32,
+                    account_name: delta.name.clone(),
+                    pubkey: delta.pubkey.clone(),
+                    mint: delta.mint.clone(),
+                    pre_lamports: delta.pre_lamports,
+                    post_lamports: delta.post_lamports,
+                    pre_token_amount: delta.pre_token_amount,
+                    post_token_amount: delta.post_token_amount,
+                })
+        })
+        .collect()
+}
+
+/// Builds the efficiency record for a session from aggregated run metrics
+pub fn session_efficiency(
+    session_id: &str,
+    test_case: &crate::benchmark::TestCase,
+    agent_type: &str,
+    metrics: &crate::efficiency::EfficiencyMetrics,
+) -> SessionEfficiency {
+    SessionEfficiency {
+        session_id: session_id.to_string(),
+        benchmark_id: test_case.id.clone(),
+        agent_type: agent_type.to_string(),
+        compute_units: metrics.compute_units,
+        fee_lamports: metrics.fee_lamports,
+        instruction_count: metrics.instruction_count,
+        transaction_count: metrics.transaction_count,
+        efficiency_score: test_case
+            .ground_truth
+            .efficiency
+            .as_ref()
+            .and_then(|budget| budget.score(metrics)),
+    }
+}
+
+/// Builds the LLM usage record for a session from the usage its agent reported
+pub fn session_llm_usage(
+    session_id: &str,
+    test_case: &crate::benchmark::TestCase,
+    agent_type: &str,
+    run_id: &str,
+    usage: &crate::results::LlmUsage,
+) -> SessionLlmUsage {
+    SessionLlmUsage {
+        session_id: session_id.to_string(),
+        benchmark_id: test_case.id.clone(),
+        agent_type: agent_type.to_string(),
+        model: usage.model.clone(),
+        run_id: Some(run_id.to_string()),
+        tokens: usage.tokens,
+        cost_usd: usage.cost_usd,
+    }
+}
+
 // Additional reev-lib specific database extensions can be added here if needed
 
 // Wrapper struct to implement reev-flow DatabaseWriter trait for reev-db DatabaseWriter
@@ -148,11 +228,8 @@ impl reev_flow::logger::DatabaseWriter for FlowDatabaseWriter {
         performance: &reev_flow::logger::AgentPerformanceData,
     ) -> reev_flow::error::FlowResult<i64> {
         // Convert from reev-flow AgentPerformanceData to reev-lib AgentPerformanceData
-        // Generate session_id from benchmark and agent info since reev-flow doesn't track sessions
-        let generated_session_id =
-            format!("{}_{}", performance.benchmark_id, performance.agent_type);
         let lib_performance = AgentPerformanceData {
-            session_id: generated_session_id,
+            session_id: performance.session_id.clone(),
             benchmark_id: performance.benchmark_id.clone(),
             agent_type: performance.agent_type.clone(),
             score: performance.score,
@@ -161,6 +238,8 @@ impl reev_flow::logger::DatabaseWriter for FlowDatabaseWriter {
             timestamp: performance.timestamp.clone(),
             flow_log_id: performance.flow_log_id,
             prompt_md5: performance.prompt_md5.clone(),
+            trial_index: performance.trial_index,
+            run_id: performance.run_id.clone(),
         };
 
         // Convert to DbAgentPerformance
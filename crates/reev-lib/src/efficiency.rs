@@ -0,0 +1,237 @@
+//! Execution Efficiency Metrics
+//!
+//! Two agents that both solve a benchmark are not necessarily equally good: one
+//! may burn five times the compute units or pad its transaction with pointless
+//! instructions. This module records what a run actually cost (compute units,
+//! fees, instruction and transaction counts) and, when a benchmark declares an
+//! `efficiency` budget in its ground truth, turns it into a score component.
+//!
+//! ## Budget Scoring
+//!
+//! Each configured budget contributes `min(1, budget / actual)`; the efficiency
+//! score is the mean over all configured budgets. It is blended into the final
+//! score with [`EfficiencyBudget::weight`] (10% by default).
+
+use crate::{agent::AgentAction, trace::ExecutionTrace};
+use serde::{Deserialize, Serialize};
+
+/// Default share of the final score given to efficiency when a budget is set.
+const DEFAULT_EFFICIENCY_WEIGHT: f64 = 0.1;
+
+fn default_efficiency_weight() -> f64 {
+    DEFAULT_EFFICIENCY_WEIGHT
+}
+
+/// Cost of a single executed transaction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionMetrics {
+    /// Compute units consumed by the transaction.
+    pub compute_units: u64,
+    /// Fee paid for the transaction, in lamports.
+    pub fee_lamports: u64,
+    /// Number of instructions in the transaction.
+    pub instruction_count: u32,
+}
+
+/// Aggregated cost of a whole benchmark run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EfficiencyMetrics {
+    /// Total compute units consumed across all transactions.
+    pub compute_units: u64,
+    /// Total fees paid, in lamports.
+    pub fee_lamports: u64,
+    /// Total number of instructions the agent produced.
+    pub instruction_count: u32,
+    /// Number of transactions that were executed.
+    pub transaction_count: u32,
+}
+
+impl EfficiencyMetrics {
+    /// Adds a single executed transaction to the totals.
+    pub fn add_transaction(&mut self, metrics: &TransactionMetrics) {
+        self.compute_units += metrics.compute_units;
+        self.fee_lamports += metrics.fee_lamports;
+        self.instruction_count += metrics.instruction_count;
+        self.transaction_count += 1;
+    }
+
+    /// Aggregates the metrics of every step in an execution trace.
+    ///
+    /// Steps that did not execute a transaction still count their instructions.
+    pub fn from_trace(trace: &ExecutionTrace) -> Self {
+        let mut totals = Self::default();
+        for step in &trace.steps {
+            match &step.observation.last_transaction_metrics {
+                Some(metrics) => totals.add_transaction(metrics),
+                None => totals.instruction_count += step.action.len() as u32,
+            }
+        }
+        totals
+    }
+
+    /// Builds metrics for a single-step run from its actions and final observation.
+    pub fn from_final_step(
+        actions: &[AgentAction],
+        final_metrics: Option<&TransactionMetrics>,
+    ) -> Self {
+        let mut totals = Self::default();
+        match final_metrics {
+            Some(metrics) => totals.add_transaction(metrics),
+            None => totals.instruction_count = actions.len() as u32,
+        }
+        totals
+    }
+}
+
+/// Optional per-benchmark budgets declared under `ground_truth.efficiency`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct EfficiencyBudget {
+    /// Maximum compute units before the score starts to drop.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_compute_units: Option<u64>,
+    /// Maximum total fee in lamports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fee_lamports: Option<u64>,
+    /// Maximum number of instructions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_instructions: Option<u32>,
+    /// Maximum number of transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_transactions: Option<u32>,
+    /// Share of the final score given to efficiency (0-1).
+    #[serde(default = "default_efficiency_weight")]
+    pub weight: f64,
+}
+
+impl Default for EfficiencyBudget {
+    fn default() -> Self {
+        Self {
+            max_compute_units: None,
+            max_fee_lamports: None,
+            max_instructions: None,
+            max_transactions: None,
+            weight: DEFAULT_EFFICIENCY_WEIGHT,
+        }
+    }
+}
+
+impl EfficiencyBudget {
+    /// Scores `metrics` against the configured budgets (0-1).
+    ///
+    /// Returns `None` when no budget is configured.
+    pub fn score(&self, metrics: &EfficiencyMetrics) -> Option<f64> {
+        let components: Vec<f64> = [
+            self.max_compute_units.map(|b| (b, metrics.compute_units)),
+            self.max_fee_lamports.map(|b| (b, metrics.fee_lamports)),
+            self.max_instructions
+                .map(|b| (b as u64, metrics.instruction_count as u64)),
+            self.max_transactions
+                .map(|b| (b as u64, metrics.transaction_count as u64)),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|(budget, actual)| budget_component(budget, actual))
+        .collect();
+
+        if components.is_empty() {
+            return None;
+        }
+        Some(components.iter().sum::<f64>() / components.len() as f64)
+    }
+
+    /// Describes each budget that `metrics` exceeded.
+    pub fn overruns(&self, metrics: &EfficiencyMetrics) -> Vec<String> {
+        let mut overruns = Vec::new();
+        if let Some(budget) = self
+            .max_compute_units
+            .filter(|b| metrics.compute_units > *b)
+        {
+            overruns.push(format!(
+                "Compute units {} exceeded budget {budget}",
+                metrics.compute_units
+            ));
+        }
+        if let Some(budget) = self.max_fee_lamports.filter(|b| metrics.fee_lamports > *b) {
+            overruns.push(format!(
+                "Fee {} lamports exceeded budget {budget}",
+                metrics.fee_lamports
+            ));
+        }
+        if let Some(budget) = self
+            .max_instructions
+            .filter(|b| metrics.instruction_count > *b)
+        {
+            overruns.push(format!(
+                "Instruction count {} exceeded budget {budget}",
+                metrics.instruction_count
+            ));
+        }
+        if let Some(budget) = self
+            .max_transactions
+            .filter(|b| metrics.transaction_count > *b)
+        {
+            overruns.push(format!(
+                "Transaction count {} exceeded budget {budget}",
+                metrics.transaction_count
+            ));
+        }
+        overruns
+    }
+
+    /// The blend weight, clamped to 0-1.
+    pub fn clamped_weight(&self) -> f64 {
+        self.weight.clamp(0.0, 1.0)
+    }
+}
+
+/// `1.0` when within budget, otherwise `budget / actual`.
+fn budget_component(budget: u64, actual: u64) -> f64 {
+    if actual <= budget {
+        1.0
+    } else {
+        budget as f64 / actual as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_score() {
+        let budget = EfficiencyBudget {
+            max_compute_units: Some(1_000),
+            max_instructions: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(budget.weight, DEFAULT_EFFICIENCY_WEIGHT);
+
+        let within = EfficiencyMetrics {
+            compute_units: 800,
+            instruction_count: 2,
+            transaction_count: 1,
+            ..Default::default()
+        };
+        assert_eq!(budget.score(&within), Some(1.0));
+        assert!(budget.overruns(&within).is_empty());
+
+        let over = EfficiencyMetrics {
+            compute_units: 2_000,
+            instruction_count: 4,
+            transaction_count: 1,
+            ..Default::default()
+        };
+        assert_eq!(budget.score(&over), Some(0.5));
+        assert_eq!(budget.overruns(&over).len(), 2);
+
+        assert_eq!(EfficiencyBudget::default().score(&over), None);
+    }
+
+    #[test]
+    fn test_budget_yaml_defaults_weight() {
+        let budget: EfficiencyBudget = serde_yaml::from_str("max_compute_units: 5000").unwrap();
+        assert_eq!(budget.max_compute_units, Some(5000));
+        assert_eq!(budget.weight, DEFAULT_EFFICIENCY_WEIGHT);
+    }
+}
@@ -0,0 +1,344 @@
+//! Fault Injection
+//!
+//! Surfpool always answers cleanly, which says nothing about how an agent copes
+//! with a real, unreliable cluster. A benchmark can declare a `faults:` section
+//! that the runner's failure-injection proxy (sitting between the environment and
+//! surfpool) applies to JSON-RPC traffic while the agent is acting:
+//!
+//! ```yaml
+//! faults:
+//!   seed: 7
+//!   max_attempts: 3
+//!   rules:
+//!     - type: BlockhashNotFound
+//!       max_injections: 1
+//!     - type: Latency
+//!       latency_ms: 250
+//!       probability: 0.5
+//! ```
+//!
+//! ## Recovery Scoring
+//!
+//! Every episode step in which a fault was injected counts as a faulted step. The
+//! agent gets up to `max_attempts` tries at it, seeing the failed observation in
+//! between. The recovery score is the share of faulted steps that eventually
+//! succeeded and is blended into the final score with `recovery_weight`.
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+fn default_max_attempts() -> u32 {
+    3
+}
+
+fn default_recovery_weight() -> f64 {
+    0.2
+}
+
+fn default_probability() -> f64 {
+    1.0
+}
+
+/// The `faults:` section of a benchmark.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct FaultConfig {
+    /// Seed for the injection RNG; runs with the same seed inject the same faults.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// How many times the agent may attempt a step that failed because of a fault.
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Share of the final score given to recovery (0-1).
+    #[serde(default = "default_recovery_weight")]
+    pub recovery_weight: f64,
+    /// The faults to inject.
+    pub rules: Vec<FaultRule>,
+}
+
+/// A single fault and the requests it applies to.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FaultRule {
+    /// What to inject.
+    #[serde(flatten)]
+    pub kind: FaultKind,
+    /// JSON-RPC methods this rule applies to. Empty means the kind's defaults.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub methods: Vec<String>,
+    /// Chance (0-1) of injecting on each matching request.
+    #[serde(default = "default_probability")]
+    pub probability: f64,
+    /// Number of matching requests to let through before injecting.
+    #[serde(default)]
+    pub skip: u32,
+    /// Stop injecting after this many faults.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_injections: Option<u32>,
+}
+
+/// The kinds of fault the proxy can inject.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum FaultKind {
+    /// Delay the request before forwarding it.
+    Latency { latency_ms: u64 },
+    /// Drop the request without forwarding it; the client sees a transport error.
+    Drop,
+    /// Fail transaction submission with `BlockhashNotFound`.
+    BlockhashNotFound,
+    /// Fail transaction submission with `AccountInUse`.
+    AccountInUse,
+}
+
+/// Methods that transaction faults apply to when a rule lists none.
+const TRANSACTION_METHODS: &[&str] = &["sendTransaction", "simulateTransaction"];
+
+impl FaultKind {
+    /// Whether this kind applies to `method` when the rule does not list methods.
+    fn applies_by_default(&self, method: &str) -> bool {
+        match self {
+            FaultKind::Latency { .. } | FaultKind::Drop => true,
+            FaultKind::BlockhashNotFound | FaultKind::AccountInUse => {
+                TRANSACTION_METHODS.contains(&method)
+            }
+        }
+    }
+}
+
+impl FaultRule {
+    /// Whether this rule applies to a JSON-RPC `method`.
+    pub fn applies_to(&self, method: &str) -> bool {
+        if self.methods.is_empty() {
+            self.kind.applies_by_default(method)
+        } else {
+            self.methods.iter().any(|m| m == method)
+        }
+    }
+}
+
+/// A transaction error the proxy should answer with instead of forwarding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InjectedError {
+    BlockhashNotFound,
+    AccountInUse,
+}
+
+impl InjectedError {
+    /// The `TransactionError` variant name, as it appears in RPC responses.
+    pub fn transaction_error(&self) -> &'static str {
+        match self {
+            InjectedError::BlockhashNotFound => "BlockhashNotFound",
+            InjectedError::AccountInUse => "AccountInUse",
+        }
+    }
+
+    /// The human-readable message Solana uses for this error.
+    pub fn message(&self) -> &'static str {
+        match self {
+            InjectedError::BlockhashNotFound => "Blockhash not found",
+            InjectedError::AccountInUse => "Account in use",
+        }
+    }
+}
+
+/// What the proxy should do with a single request.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FaultDecision {
+    /// Total delay before answering.
+    pub latency: Duration,
+    /// Drop the request instead of forwarding it.
+    pub drop: bool,
+    /// Answer with this error instead of forwarding.
+    pub error: Option<InjectedError>,
+}
+
+impl FaultDecision {
+    /// Whether any fault is injected for this request.
+    pub fn is_fault(&self) -> bool {
+        !self.latency.is_zero() || self.drop || self.error.is_some()
+    }
+}
+
+/// Applies a [`FaultConfig`] to a stream of JSON-RPC requests.
+#[derive(Debug)]
+pub struct FaultInjector {
+    config: FaultConfig,
+    rng: StdRng,
+    matched: Vec<u32>,
+    injected: Vec<u32>,
+}
+
+impl FaultInjector {
+    pub fn new(config: FaultConfig) -> Self {
+        let rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let rules = config.rules.len();
+        Self {
+            config,
+            rng,
+            matched: vec![0; rules],
+            injected: vec![0; rules],
+        }
+    }
+
+    /// Decides which faults to inject for a request to `method`.
+    ///
+    /// Latency from several rules adds up; the first drop or error wins.
+    pub fn decide(&mut self, method: &str) -> FaultDecision {
+        let mut decision = FaultDecision::default();
+        for (i, rule) in self.config.rules.iter().enumerate() {
+            if !rule.applies_to(method) {
+                continue;
+            }
+            self.matched[i] += 1;
+            if self.matched[i] <= rule.skip
+                || rule
+                    .max_injections
+                    .is_some_and(|max| self.injected[i] >= max)
+            {
+                continue;
+            }
+
+            let terminal = decision.drop || decision.error.is_some();
+            if terminal && !matches!(rule.kind, FaultKind::Latency { .. }) {
+                continue;
+            }
+            if !self.rng.gen_bool(rule.probability.clamp(0.0, 1.0)) {
+                continue;
+            }
+
+            self.injected[i] += 1;
+            match rule.kind {
+                FaultKind::Latency { latency_ms } => {
+                    decision.latency += Duration::from_millis(latency_ms)
+                }
+                FaultKind::Drop => decision.drop = true,
+                FaultKind::BlockhashNotFound => {
+                    decision.error = Some(InjectedError::BlockhashNotFound)
+                }
+                FaultKind::AccountInUse => decision.error = Some(InjectedError::AccountInUse),
+            }
+        }
+        decision
+    }
+
+    /// Total number of faults injected so far.
+    pub fn injected_count(&self) -> u32 {
+        self.injected.iter().sum()
+    }
+}
+
+/// How an agent coped with injected faults over an episode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecoveryStats {
+    /// Number of faults injected across the episode.
+    pub injected_faults: u32,
+    /// Steps in which at least one fault was injected.
+    pub faulted_steps: u32,
+    /// Faulted steps that eventually succeeded.
+    pub recovered_steps: u32,
+    /// Extra attempts made after a faulted failure.
+    pub retries: u32,
+}
+
+impl RecoveryStats {
+    /// Records the outcome of one episode step.
+    pub fn record_step(&mut self, injected_faults: u32, attempts: u32, succeeded: bool) {
+        if injected_faults == 0 {
+            return;
+        }
+        self.injected_faults += injected_faults;
+        self.faulted_steps += 1;
+        self.retries += attempts.saturating_sub(1);
+        if succeeded {
+            self.recovered_steps += 1;
+        }
+    }
+
+    /// Share of faulted steps that recovered, or `None` if nothing was injected.
+    pub fn score(&self) -> Option<f64> {
+        (self.faulted_steps > 0).then(|| self.recovered_steps as f64 / self.faulted_steps as f64)
+    }
+}
+
+impl FaultConfig {
+    /// Blends the recovery score into `score`; unchanged if no fault hit the episode.
+    pub fn blend_recovery(&self, score: f64, stats: &RecoveryStats) -> f64 {
+        match stats.score() {
+            Some(recovery) => {
+                let weight = self.recovery_weight.clamp(0.0, 1.0);
+                (score * (1.0 - weight) + recovery * weight).clamp(0.0, 1.0)
+            }
+            None => score,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const YAML: &str = r#"
+seed: 7
+rules:
+  - type: BlockhashNotFound
+    max_injections: 1
+  - type: Latency
+    latency_ms: 250
+    methods: ["getLatestBlockhash"]
+  - type: Drop
+    skip: 1
+    probability: 0.0
+"#;
+
+    #[test]
+    fn test_fault_config_yaml() {
+        let config: FaultConfig = serde_yaml::from_str(YAML).unwrap();
+        assert_eq!(config.seed, Some(7));
+        assert_eq!(config.max_attempts, 3);
+        assert_eq!(config.rules.len(), 3);
+        assert_eq!(config.rules[0].kind, FaultKind::BlockhashNotFound);
+        assert_eq!(config.rules[0].probability, 1.0);
+        assert_eq!(config.rules[1].kind, FaultKind::Latency { latency_ms: 250 });
+        assert!(config.rules[0].applies_to("sendTransaction"));
+        assert!(!config.rules[0].applies_to("getBalance"));
+        assert!(config.rules[1].applies_to("getLatestBlockhash"));
+        assert!(!config.rules[1].applies_to("sendTransaction"));
+    }
+
+    #[test]
+    fn test_injector_decisions() {
+        let config: FaultConfig = serde_yaml::from_str(YAML).unwrap();
+        let mut injector = FaultInjector::new(config);
+
+        let first = injector.decide("simulateTransaction");
+        assert_eq!(first.error, Some(InjectedError::BlockhashNotFound));
+        assert!(!first.drop);
+
+        // max_injections reached; the drop rule never fires at probability 0
+        let second = injector.decide("simulateTransaction");
+        assert!(!second.is_fault());
+
+        let latency = injector.decide("getLatestBlockhash");
+        assert_eq!(latency.latency, Duration::from_millis(250));
+        assert_eq!(injector.injected_count(), 2);
+    }
+
+    #[test]
+    fn test_recovery_score_blend() {
+        let config: FaultConfig = serde_yaml::from_str(YAML).unwrap();
+        let mut stats = RecoveryStats::default();
+        assert_eq!(config.blend_recovery(0.8, &stats), 0.8);
+
+        stats.record_step(0, 1, true);
+        stats.record_step(2, 2, true);
+        stats.record_step(1, 3, false);
+        assert_eq!(stats.faulted_steps, 2);
+        assert_eq!(stats.retries, 3);
+        assert_eq!(stats.score(), Some(0.5));
+        assert!((config.blend_recovery(1.0, &stats) - 0.9).abs() < 1e-9);
+    }
+}
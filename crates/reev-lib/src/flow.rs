@@ -37,11 +37,8 @@ pub fn create_flow_logger(
     agent_type: String,
     output_path: Option<std::path::PathBuf>,
 ) -> FlowLogger {
-    let output_path = output_path.unwrap_or_else(|| {
-        std::env::var("REEV_FLOW_LOG_PATH")
-            .map(PathBuf::from)
-            .unwrap_or_else(|_| PathBuf::from("logs/flows"))
-    });
+    let output_path =
+        output_path.unwrap_or_else(|| PathBuf::from(&reev_config::get().runner.flow_log_dir));
 
     FlowLogger::new(benchmark_id, agent_type, output_path)
 }
@@ -53,20 +50,14 @@ pub fn create_session_logger(
     agent_type: String,
     log_dir: Option<PathBuf>,
 ) -> Result<SessionFileLogger> {
-    let log_dir = log_dir.unwrap_or_else(|| {
-        std::env::var("REEV_SESSION_LOG_PATH")
-            .map(PathBuf::from)
-            .unwrap_or_else(|_| PathBuf::from("logs/sessions"))
-    });
+    let log_dir = log_dir.unwrap_or_else(get_default_session_log_path);
 
     SessionFileLogger::new(session_id, benchmark_id, agent_type, &log_dir)
 }
 
 /// Get default session log path
 pub fn get_default_session_log_path() -> PathBuf {
-    std::env::var("REEV_SESSION_LOG_PATH")
-        .map(PathBuf::from)
-        .unwrap_or_else(|_| PathBuf::from("logs/sessions"))
+    PathBuf::from(&reev_config::get().runner.session_log_dir)
 }
 
 /// Quick render function for the most common use case
@@ -1,16 +1,20 @@
 pub mod actions;
 pub mod agent;
+pub mod balance_delta;
 pub mod balance_validation;
 pub mod benchmark;
 pub mod constants;
 pub mod db;
+pub mod efficiency;
 pub mod env;
+pub mod faults;
 pub mod flow;
 pub mod instruction_score;
 pub mod llm_agent; // Temporarily restored for runner compatibility
 pub mod mock;
 pub mod otel_extraction;
 pub mod parsing;
+pub mod program_logs;
 
 pub mod results;
 pub mod score;
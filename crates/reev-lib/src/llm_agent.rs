@@ -1,11 +1,13 @@
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Instant;
 
 use crate::agent::{Agent, AgentAction, AgentObservation, RawInstruction};
 use crate::flow::{FlowLogger, LlmRequestContent, ToolCallContent, ToolResultStatus};
 use crate::parsing::ResponseParser;
 use anyhow::Context;
 use async_trait::async_trait;
+use reev_config::ProviderKind;
 use reqwest::Client;
 use serde_json::json;
 use tracing::{debug, info, instrument, warn};
@@ -21,13 +23,24 @@ pub struct LlmAgent {
     current_depth: u32,
     is_glm: bool,
     session_id: Option<String>,
+    is_deterministic: bool,
+    ground_truth: Option<crate::benchmark::GroundTruth>,
+    budget: Option<reev_config::BudgetSettings>,
+    /// What the agent's runs have spent against `budget`, and when the first started
+    budget_spent: reev_config::BudgetSpent,
+    budget_started: Option<Instant>,
+    budget_exceeded: Option<String>,
+    token_usage: Option<reev_flow::TokenUsage>,
+    argument_retries: u32,
+    seed: Option<u64>,
 }
 
 impl LlmAgent {
     /// Creates a new `LlmAgent`.
     ///
     /// It initializes a `reqwest` client for making API calls.
-    /// API configuration is loaded from environment variables.
+    /// API configuration is read from the `[llm]` section of the reev configuration,
+    /// and `agent_name` must be registered in its `[models]` table.
     pub fn new(agent_name: &str) -> anyhow::Result<Self> {
         Self::new_with_flow_logging(agent_name, None)
     }
@@ -39,80 +52,30 @@ impl LlmAgent {
     ) -> anyhow::Result<Self> {
         info!("[LlmAgent] Initializing agent: '{agent_name}'");
 
-        // Check for GLM Coding environment variables
-        let glm_api_key = std::env::var("GLM_CODING_API_KEY").ok();
-        let glm_api_url = std::env::var("GLM_CODING_API_URL").ok();
-
-        // Check if GLM environment is set (for determining parsing mode)
-        let _glm_env_available = glm_api_key.is_some() && glm_api_url.is_some();
-
-        let (api_url, api_key, model_name, agent_name_for_type, is_glm) = match (
-            glm_api_key,
-            glm_api_url,
-        ) {
-            (Some(key), Some(url))
-                if !key.is_empty() && !url.is_empty() && agent_name != "local" =>
-            {
-                info!("[LlmAgent] GLM Coding environment detected, routing through reev-agent");
-                // Route GLM through reev-agent instead of direct API calls
-                let final_url = if agent_name == "deterministic" {
-                    "http://localhost:9090/gen/tx?mock=true".to_string()
-                } else {
-                    "http://localhost:9090/gen/tx".to_string()
-                };
-                // Preserve the original model name instead of hardcoding
-                let model_name = agent_name.to_string();
-                // GLM parsing should only be used for models starting with 'glm'
-                let is_glm = agent_name.starts_with("glm");
-                (final_url, None, model_name, agent_name.to_string(), is_glm)
-            }
-            (Some(_), None) => {
-                anyhow::bail!("GLM_CODING_API_KEY is set but GLM_CODING_API_URL is missing. Please set both GLM_CODING_API_KEY and GLM_CODING_API_URL for GLM Coding 4.6 support.");
-            }
-            (None, Some(_)) => {
-                anyhow::bail!("GLM_CODING_API_URL is set but GLM_CODING_API_KEY is missing. Please set both GLM_CODING_API_KEY and GLM_CODING_API_URL for GLM Coding 4.6 support.");
-            }
-            _ => {
-                info!("[LlmAgent] GLM environment variables not found, using default LLM configuration");
-
-                // Load base API URL from environment variables, falling back to a default.
-                let base_url = std::env::var("LLM_API_URL")
-                    .unwrap_or_else(|_| "http://localhost:9090/gen/tx".to_string());
-                info!("[LlmAgent] Using base URL: {base_url}");
-
-                // Append `?mock=true` if the deterministic agent is selected.
-                let api_url = if agent_name == "deterministic" {
-                    format!("{base_url}?mock=true")
-                } else {
-                    base_url
-                };
+        let config = reev_config::get();
+        let llm = &config.llm;
+        // Unregistered models fail here rather than with the first request
+        let entry = config.model(agent_name)?;
 
-                // Pass through agent names directly - 'local' should remain 'local' for actual local models
-                // Add support for "glm" alias to "glm-4.6"
-                let model_name = match agent_name {
-                    "glm" => "glm-4.6",
-                    _ => agent_name,
-                }
-                .to_string();
+        // Every agent is served by reev-agent at `llm.api_url`, which routes by model name.
+        // Append `?mock=true` if the deterministic agent is selected.
+        let api_url = if entry.provider == ProviderKind::Deterministic {
+            format!("{}?mock=true", llm.api_url)
+        } else {
+            llm.api_url.clone()
+        };
 
-                // Load API key from environment variables if it exists.
-                let api_key = match std::env::var("LLM_API_KEY") {
-                    Ok(key) if !key.is_empty() => {
-                        info!("[LlmAgent] Using LLM_API_KEY from environment.");
-                        Some(key)
-                    }
-                    _ => {
-                        info!("[LlmAgent] WARNING: LLM_API_KEY environment variable not set or is empty.");
-                        None
-                    }
-                };
+        // Pass through agent names directly - 'local' should remain 'local' for actual local models
+        // Aliases such as "glm" resolve to their registered name
+        let model_name = reev_config::canonical_model_name(agent_name).to_string();
 
-                // GLM parsing should only be used for models starting with 'glm'
-                let is_glm = agent_name.starts_with("glm");
+        let api_key = llm.api_key.clone().filter(|key| !key.is_empty());
+        if api_key.is_none() {
+            info!("[LlmAgent] WARNING: LLM_API_KEY (llm.api_key) not set or is empty.");
+        }
 
-                (api_url, api_key, model_name, agent_name.to_string(), is_glm)
-            }
-        };
+        // GLM parsing should only be used for models served by the ZAI API
+        let is_glm = entry.provider == ProviderKind::Zai;
 
         info!("[LlmAgent] Final API URL for agent '{agent_name}': {api_url}");
         info!("[LlmAgent] Model name being sent in payload: '{model_name}'");
@@ -125,11 +88,20 @@ impl LlmAgent {
             api_url,
             api_key,
             model_name,
-            agent_type: agent_name_for_type,
+            agent_type: agent_name.to_string(),
             flow_logger,
             current_depth: 0,
             is_glm,
             session_id: None,
+            is_deterministic: entry.provider == ProviderKind::Deterministic,
+            ground_truth: None,
+            budget: None,
+            budget_spent: Default::default(),
+            budget_started: None,
+            budget_exceeded: None,
+            token_usage: None,
+            argument_retries: 0,
+            seed: None,
         })
     }
 
@@ -142,6 +114,34 @@ impl LlmAgent {
     pub fn set_session_id(&mut self, session_id: String) {
         self.session_id = Some(session_id);
     }
+
+    /// Give the deterministic agent the benchmark's ground truth, from which it
+    /// builds benchmarks that have no coding agent. Other agents never receive it.
+    pub fn set_ground_truth(&mut self, ground_truth: &crate::benchmark::GroundTruth) {
+        if self.is_deterministic {
+            self.ground_truth = Some(ground_truth.clone());
+        }
+    }
+
+    /// Set the limits the agent's runs share; each request is sent what is left
+    pub fn set_budget(&mut self, budget: reev_config::BudgetSettings) {
+        self.budget = Some(budget);
+    }
+
+    /// What is left of the budget for the next run, the wall time counted from the first
+    fn remaining_budget(&mut self) -> Option<reev_config::BudgetSettings> {
+        let started = *self.budget_started.get_or_insert_with(Instant::now);
+        let spent = reev_config::BudgetSpent {
+            wall_time_secs: started.elapsed().as_secs(),
+            ..self.budget_spent
+        };
+        self.budget.map(|budget| budget.remaining(&spent))
+    }
+
+    /// Seed the provider's sampling of every request, for a reproducible attempt
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = Some(seed);
+    }
 }
 
 #[async_trait]
@@ -169,9 +169,7 @@ impl Agent for LlmAgent {
         // Initialize flow logger if not already done and logging is enabled
         // Flow logging is always enabled
         if self.flow_logger.is_none() {
-            let output_path =
-                std::env::var("REEV_FLOW_LOG_PATH").unwrap_or_else(|_| "logs/flows".to_string());
-            let path = PathBuf::from(output_path);
+            let path = PathBuf::from(&reev_config::get().runner.flow_log_dir);
             std::fs::create_dir_all(&path)?;
 
             // Use session_id if available, otherwise fallback to id (benchmark_id)
@@ -217,6 +215,7 @@ impl Agent for LlmAgent {
         let available_tools = self.determine_available_tools(id, &context_prompt);
 
         // 3. Create the final JSON payload for the API.
+        let budget = self.remaining_budget();
         let request_payload = if self.is_glm {
             // GLM routes through reev-agent, use reev-agent format
 
@@ -259,6 +258,8 @@ impl Agent for LlmAgent {
                 "allowed_tools": available_tools,
                 "account_states": observation.account_states,
                 "key_map": observation.key_map,
+                "budget": budget,
+                "seed": self.seed,
             });
 
             // Add session_id if available
@@ -293,7 +294,12 @@ impl Agent for LlmAgent {
                 "model_name": self.model_name,
                 "account_states": observation.account_states,
                 "key_map": observation.key_map,
+                "budget": budget,
+                "seed": self.seed,
             });
+            if let Some(ground_truth) = &self.ground_truth {
+                payload["ground_truth"] = serde_json::to_value(ground_truth)?;
+            }
 
             // Add session_id if available
             if let Some(ref session_id) = self.session_id {
@@ -428,6 +434,41 @@ impl Agent for LlmAgent {
 
         let parser = ResponseParser::new(self.is_glm);
         let llm_response = parser.parse_with_fallback(&llm_response_text);
+        let raw_response = serde_json::from_str::<serde_json::Value>(&llm_response_text).ok();
+        if let Some(limit) = raw_response
+            .as_ref()
+            .and_then(|response| response["budget_exceeded"].as_str().map(str::to_string))
+        {
+            warn!("[LlmAgent] The agent was stopped at its {limit} budget");
+            self.budget_exceeded.get_or_insert(limit);
+        }
+        if let Some(spent) = raw_response
+            .as_ref()
+            .and_then(|response| response.get("budget_usage"))
+        {
+            let count = |field: &str| spent[field].as_u64().unwrap_or_default();
+            self.budget_spent.turns += count("turns") as u32;
+            self.budget_spent.tool_calls += count("tool_calls") as u32;
+            self.budget_spent.input_tokens += count("prompt_tokens");
+            self.budget_spent.output_tokens += count("completion_tokens");
+        }
+        if let Some(retries) = raw_response
+            .as_ref()
+            .and_then(|response| response["argument_retries"].as_u64())
+            .filter(|retries| *retries > 0)
+        {
+            info!("[LlmAgent] The agent retried {retries} rejected tool calls");
+            self.argument_retries += retries as u32;
+        }
+        if let Some(usage) = raw_response.and_then(|response| {
+            serde_json::from_value::<reev_flow::TokenUsage>(response.get("usage")?.clone()).ok()
+        }) {
+            info!(
+                "[LlmAgent] The agent used {} prompt ({} cached) and {} completion tokens",
+                usage.prompt_tokens, usage.cached_tokens, usage.completion_tokens
+            );
+            *self.token_usage.get_or_insert_default() += usage;
+        }
 
         debug!("[LlmAgent] Parsed LlmResponse: {:?}", llm_response);
 
@@ -556,6 +597,37 @@ impl Agent for LlmAgent {
 
         Ok(actions)
     }
+
+    fn budget_exceeded(&self) -> Option<String> {
+        self.budget_exceeded.clone()
+    }
+
+    fn token_usage(&self) -> Option<reev_flow::TokenUsage> {
+        self.token_usage
+    }
+
+    fn argument_retries(&self) -> u32 {
+        self.argument_retries
+    }
+
+    /// Ask reev-agent to abort the run of this agent's session
+    async fn cancel(&mut self) -> anyhow::Result<bool> {
+        let Some(session_id) = &self.session_id else {
+            return Ok(false);
+        };
+        let mut url = reqwest::Url::parse(&self.api_url).context("Invalid agent API URL")?;
+        url.set_query(None);
+        url.set_path(&format!("/sessions/{session_id}/cancel"));
+        let response = self
+            .client
+            .post(url)
+            .send()
+            .await
+            .context("Failed to send cancel request to reev-agent")?;
+        let cancelled = response.status().is_success();
+        info!("[LlmAgent] Cancel of session {session_id} in reev-agent: {cancelled}");
+        Ok(cancelled)
+    }
 }
 
 impl LlmAgent {
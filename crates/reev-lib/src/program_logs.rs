@@ -0,0 +1,444 @@
+//! Structured Program Log Decoding
+//!
+//! Solana returns transaction logs as flat strings. This module turns them into
+//! a typed invocation tree so every consumer (API, TUI, runner) can display the
+//! same information without re-parsing log lines itself.
+//!
+//! Recognised log lines:
+//!
+//! - `Program <id> invoke [<depth>]` - opens a new invocation
+//! - `Program <id> consumed <n> of <budget> compute units`
+//! - `Program <id> success` / `Program <id> failed: <error>` - closes it
+//! - `Program log: ...`, `Program data: ...`, `Program return: <id> <data>`
+//!
+//! Anything else (e.g. `Log truncated`) is kept verbatim in
+//! [`ProgramLogTree::unparsed`].
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Outcome of a single program invocation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum InvocationStatus {
+    /// The program logged `success`.
+    Success,
+    /// The program logged `failed: <error>`.
+    Failed { error: String },
+    /// The logs ended (or were truncated) before the invocation closed.
+    Incomplete,
+}
+
+/// A single program invocation, with the CPIs it made as children.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProgramInvocation {
+    /// The invoked program's base58 id.
+    pub program_id: String,
+    /// Invocation depth as reported by the runtime (1 = top-level instruction).
+    pub depth: usize,
+    /// Compute units consumed, including any CPIs made by this invocation.
+    pub compute_units: Option<u64>,
+    /// The compute budget that was available when the invocation started.
+    pub compute_budget: Option<u64>,
+    /// Whether the invocation succeeded, failed or never closed.
+    pub status: InvocationStatus,
+    /// The instruction name from `Program log: Instruction: <name>`, if logged.
+    pub instruction: Option<String>,
+    /// Other `Program log:` messages, in order.
+    pub logs: Vec<String>,
+    /// Base64 payloads of `Program data:` events, in order.
+    pub data_events: Vec<String>,
+    /// Base64 payload of `Program return:`, if any.
+    pub return_data: Option<String>,
+    /// Cross-program invocations made by this program.
+    pub children: Vec<ProgramInvocation>,
+}
+
+impl ProgramInvocation {
+    fn new(program_id: String, depth: usize) -> Self {
+        Self {
+            program_id,
+            depth,
+            compute_units: None,
+            compute_budget: None,
+            status: InvocationStatus::Incomplete,
+            instruction: None,
+            logs: Vec::new(),
+            data_events: Vec::new(),
+            return_data: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// Compute units spent in this program itself, excluding its CPIs.
+    pub fn exclusive_compute_units(&self) -> Option<u64> {
+        let total = self.compute_units?;
+        let children: u64 = self
+            .children
+            .iter()
+            .filter_map(|child| child.compute_units)
+            .sum();
+        Some(total.saturating_sub(children))
+    }
+
+    /// Whether the invocation logged `success`.
+    pub fn is_success(&self) -> bool {
+        self.status == InvocationStatus::Success
+    }
+
+    /// The error this invocation failed with, if any.
+    pub fn error(&self) -> Option<&str> {
+        match &self.status {
+            InvocationStatus::Failed { error } => Some(error),
+            _ => None,
+        }
+    }
+
+    /// Human-readable program name (see [`program_name`]).
+    pub fn program_name(&self) -> String {
+        program_name(&self.program_id)
+    }
+
+    fn visit<'a>(&'a self, out: &mut Vec<&'a ProgramInvocation>) {
+        out.push(self);
+        for child in &self.children {
+            child.visit(out);
+        }
+    }
+}
+
+/// Aggregated compute-unit usage for one program across a transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProgramComputeUnits {
+    /// The program's base58 id.
+    pub program_id: String,
+    /// Human-readable program name.
+    pub program_name: String,
+    /// Number of times the program was invoked (top-level or via CPI).
+    pub invocations: usize,
+    /// Compute units spent in the program itself, excluding CPIs it made.
+    pub compute_units: u64,
+}
+
+/// A parsed set of program logs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProgramLogTree {
+    /// Top-level invocations (one per executed instruction), in order.
+    pub invocations: Vec<ProgramInvocation>,
+    /// Log lines that did not belong to any recognised pattern or invocation.
+    pub unparsed: Vec<String>,
+}
+
+impl ProgramLogTree {
+    /// Parses raw Solana program logs into an invocation tree.
+    pub fn parse<S: AsRef<str>>(logs: &[S]) -> Self {
+        let mut tree = Self::default();
+        let mut stack: Vec<ProgramInvocation> = Vec::new();
+
+        for line in logs {
+            let line = line.as_ref().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(message) = line.strip_prefix("Program log: ") {
+                match stack.last_mut() {
+                    Some(current) => match message.strip_prefix("Instruction: ") {
+                        Some(name) if current.instruction.is_none() => {
+                            current.instruction = Some(name.trim().to_string())
+                        }
+                        _ => current.logs.push(message.to_string()),
+                    },
+                    None => tree.unparsed.push(line.to_string()),
+                }
+            } else if let Some(data) = line.strip_prefix("Program data: ") {
+                match stack.last_mut() {
+                    Some(current) => current.data_events.push(data.trim().to_string()),
+                    None => tree.unparsed.push(line.to_string()),
+                }
+            } else if let Some(rest) = line.strip_prefix("Program return: ") {
+                let data = rest.split_once(' ').map(|(_, data)| data).unwrap_or("");
+                match stack.last_mut() {
+                    Some(current) => current.return_data = Some(data.trim().to_string()),
+                    None => tree.unparsed.push(line.to_string()),
+                }
+            } else if let Some(rest) = line.strip_prefix("Program ") {
+                let (program_id, event) = rest.split_once(' ').unwrap_or((rest, ""));
+                if let Some(depth) = parse_invoke(event) {
+                    // A new invoke at a depth we already have open means the
+                    // previous invocation never closed (e.g. truncated logs).
+                    while stack.last().is_some_and(|open| open.depth >= depth) {
+                        close_invocation(&mut stack, &mut tree.invocations);
+                    }
+                    stack.push(ProgramInvocation::new(program_id.to_string(), depth));
+                } else if let Some((consumed, budget)) = parse_consumed(event) {
+                    match stack
+                        .iter_mut()
+                        .rev()
+                        .find(|open| open.program_id == program_id)
+                    {
+                        Some(current) => {
+                            current.compute_units = Some(consumed);
+                            current.compute_budget = Some(budget);
+                        }
+                        None => tree.unparsed.push(line.to_string()),
+                    }
+                } else if event == "success" || event.starts_with("failed") {
+                    let status = match event.strip_prefix("failed") {
+                        Some(error) => InvocationStatus::Failed {
+                            error: error.trim_start_matches(':').trim().to_string(),
+                        },
+                        None => InvocationStatus::Success,
+                    };
+                    if stack.iter().any(|open| open.program_id == program_id) {
+                        while let Some(open) = stack.last_mut() {
+                            if open.program_id == program_id {
+                                open.status = status;
+                                close_invocation(&mut stack, &mut tree.invocations);
+                                break;
+                            }
+                            close_invocation(&mut stack, &mut tree.invocations);
+                        }
+                    } else {
+                        tree.unparsed.push(line.to_string());
+                    }
+                } else {
+                    match stack.last_mut() {
+                        Some(current) => current.logs.push(line.to_string()),
+                        None => tree.unparsed.push(line.to_string()),
+                    }
+                }
+            } else {
+                tree.unparsed.push(line.to_string());
+            }
+        }
+
+        while !stack.is_empty() {
+            close_invocation(&mut stack, &mut tree.invocations);
+        }
+        tree
+    }
+
+    /// Whether no invocations were recognised.
+    pub fn is_empty(&self) -> bool {
+        self.invocations.is_empty()
+    }
+
+    /// All invocations, depth-first in execution order.
+    pub fn iter(&self) -> impl Iterator<Item = &ProgramInvocation> {
+        let mut all = Vec::new();
+        for invocation in &self.invocations {
+            invocation.visit(&mut all);
+        }
+        all.into_iter()
+    }
+
+    /// Total compute units consumed by all top-level instructions.
+    pub fn total_compute_units(&self) -> u64 {
+        self.invocations
+            .iter()
+            .filter_map(|invocation| invocation.compute_units)
+            .sum()
+    }
+
+    /// Errors returned by failed invocations, innermost first as logged.
+    pub fn errors(&self) -> Vec<(&str, &str)> {
+        self.iter()
+            .filter_map(|invocation| {
+                invocation
+                    .error()
+                    .map(|error| (invocation.program_id.as_str(), error))
+            })
+            .collect()
+    }
+
+    /// Compute units spent per program (excluding CPIs), highest first.
+    pub fn compute_units_by_program(&self) -> Vec<ProgramComputeUnits> {
+        let mut order: Vec<&str> = Vec::new();
+        let mut totals: HashMap<&str, (usize, u64)> = HashMap::new();
+        for invocation in self.iter() {
+            let entry = totals.entry(&invocation.program_id).or_insert_with(|| {
+                order.push(&invocation.program_id);
+                (0, 0)
+            });
+            entry.0 += 1;
+            entry.1 += invocation.exclusive_compute_units().unwrap_or(0);
+        }
+
+        let mut breakdown: Vec<ProgramComputeUnits> = order
+            .into_iter()
+            .map(|program_id| {
+                let (invocations, compute_units) = totals[program_id];
+                ProgramComputeUnits {
+                    program_id: program_id.to_string(),
+                    program_name: program_name(program_id),
+                    invocations,
+                    compute_units,
+                }
+            })
+            .collect();
+        breakdown.sort_by_key(|program| std::cmp::Reverse(program.compute_units));
+        breakdown
+    }
+
+    /// Renders the tree as indented plain text, one invocation per line.
+    pub fn render_text(&self, show_cu: bool) -> String {
+        let mut lines = Vec::new();
+        for invocation in &self.invocations {
+            render_invocation_text(invocation, 0, show_cu, &mut lines);
+        }
+        lines.extend(self.unparsed.iter().cloned());
+        lines.join("\n")
+    }
+}
+
+/// Returns a human-readable name for well-known programs, or a short id otherwise.
+pub fn program_name(program_id: &str) -> String {
+    match program_id {
+        "11111111111111111111111111111111" => "System".to_string(),
+        "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA" => "SPL Token".to_string(),
+        "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb" => "SPL Token-2022".to_string(),
+        "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL" => "Associated Token".to_string(),
+        "ComputeBudget111111111111111111111111111111" => "Compute Budget".to_string(),
+        "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4" => "Jupiter Router".to_string(),
+        "jup3YeL8QhtSx1e253b2FDvsMNC87fDrgQZivbrndc9" => "Jupiter Lend".to_string(),
+        "TessVdML9pBGgG9yGks7o4HewRaXVAMuoVj4x83GLQH" => "Tessellate".to_string(),
+        "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM" => "Serum DEX".to_string(),
+        "9H6tua7jkLhdm3w8BvgpTn5LZNU7g4ZynDmCiNN3q6Rp" => "Raydium DEX".to_string(),
+        "SysvarRent111111111111111111111111111111111" => "Sysvar Rent".to_string(),
+        "SysvarC1ock11111111111111111111111111111111" => "Sysvar Clock".to_string(),
+        _ => format!("Program {}", &program_id[..program_id.len().min(8)]),
+    }
+}
+
+fn parse_invoke(event: &str) -> Option<usize> {
+    event
+        .strip_prefix("invoke [")?
+        .strip_suffix(']')?
+        .parse()
+        .ok()
+}
+
+fn parse_consumed(event: &str) -> Option<(u64, u64)> {
+    let rest = event.strip_prefix("consumed ")?;
+    let (consumed, rest) = rest.split_once(" of ")?;
+    let budget = rest.strip_suffix(" compute units")?;
+    Some((consumed.parse().ok()?, budget.parse().ok()?))
+}
+
+/// Pops the innermost open invocation and attaches it to its parent (or the roots).
+fn close_invocation(stack: &mut Vec<ProgramInvocation>, roots: &mut Vec<ProgramInvocation>) {
+    if let Some(invocation) = stack.pop() {
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(invocation),
+            None => roots.push(invocation),
+        }
+    }
+}
+
+fn render_invocation_text(
+    invocation: &ProgramInvocation,
+    indent: usize,
+    show_cu: bool,
+    lines: &mut Vec<String>,
+) {
+    let pad = "  ".repeat(indent);
+    let status = match &invocation.status {
+        InvocationStatus::Success => "✅".to_string(),
+        InvocationStatus::Failed { error } => format!("❌ {error}"),
+        InvocationStatus::Incomplete => "⏳".to_string(),
+    };
+    let instruction = invocation
+        .instruction
+        .as_deref()
+        .map(|name| format!(" {name}"))
+        .unwrap_or_default();
+    let cu = match (show_cu, invocation.compute_units) {
+        (true, Some(cu)) => format!(" ({cu} CU)"),
+        _ => String::new(),
+    };
+    lines.push(format!(
+        "{pad}{}{instruction}{cu} {status}",
+        invocation.program_name()
+    ));
+    for message in &invocation.logs {
+        lines.push(format!("{pad}  📝 {message}"));
+    }
+    for data in &invocation.data_events {
+        lines.push(format!("{pad}  📡 {data}"));
+    }
+    for child in &invocation.children {
+        render_invocation_text(child, indent + 1, show_cu, lines);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ATA: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+    const TOKEN: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+    #[test]
+    fn test_parse_nested_invocations() {
+        let logs = vec![
+            format!("Program {ATA} invoke [1]"),
+            "Program log: CreateIdempotent".to_string(),
+            format!("Program {TOKEN} invoke [2]"),
+            "Program log: Instruction: GetAccountDataSize".to_string(),
+            format!("Program {TOKEN} consumed 1569 of 997595 compute units"),
+            format!("Program return: {TOKEN} pQAAAAAAAAA="),
+            format!("Program {TOKEN} success"),
+            "Program data: ZXZlbnQ=".to_string(),
+            format!("Program {ATA} consumed 5000 of 1000000 compute units"),
+            format!("Program {ATA} success"),
+        ];
+
+        let tree = ProgramLogTree::parse(&logs);
+        assert_eq!(tree.invocations.len(), 1);
+        assert!(tree.unparsed.is_empty());
+
+        let ata = &tree.invocations[0];
+        assert_eq!(ata.program_id, ATA);
+        assert_eq!(ata.depth, 1);
+        assert!(ata.is_success());
+        assert_eq!(ata.logs, vec!["CreateIdempotent".to_string()]);
+        assert_eq!(ata.data_events, vec!["ZXZlbnQ=".to_string()]);
+        assert_eq!(ata.compute_units, Some(5000));
+        assert_eq!(ata.exclusive_compute_units(), Some(3431));
+
+        let token = &ata.children[0];
+        assert_eq!(token.depth, 2);
+        assert_eq!(token.instruction.as_deref(), Some("GetAccountDataSize"));
+        assert_eq!(token.return_data.as_deref(), Some("pQAAAAAAAAA="));
+
+        assert_eq!(tree.total_compute_units(), 5000);
+        let breakdown = tree.compute_units_by_program();
+        assert_eq!(breakdown[0].program_id, ATA);
+        assert_eq!(breakdown[0].compute_units, 3431);
+        assert_eq!(breakdown[1].compute_units, 1569);
+    }
+
+    #[test]
+    fn test_parse_failure_and_truncation() {
+        let logs = vec![
+            format!("Program {TOKEN} invoke [1]"),
+            "Program log: Instruction: Transfer".to_string(),
+            "Program log: Error: insufficient funds".to_string(),
+            format!("Program {TOKEN} consumed 4381 of 200000 compute units"),
+            format!("Program {TOKEN} failed: custom program error: 0x1"),
+            format!("Program {ATA} invoke [1]"),
+            "Log truncated".to_string(),
+        ];
+
+        let tree = ProgramLogTree::parse(&logs);
+        assert_eq!(tree.invocations.len(), 2);
+        assert_eq!(
+            tree.invocations[0].error(),
+            Some("custom program error: 0x1")
+        );
+        assert_eq!(tree.invocations[1].status, InvocationStatus::Incomplete);
+        assert_eq!(tree.errors(), vec![(TOKEN, "custom program error: 0x1")]);
+        assert!(tree.render_text(true).contains("(4381 CU) ❌"));
+    }
+}
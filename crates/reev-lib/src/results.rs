@@ -1,4 +1,5 @@
-use crate::{benchmark::TestCase, trace::ExecutionTrace};
+use crate::{benchmark::TestCase, flow::ScoringBreakdown, trace::ExecutionTrace};
+use reev_flow::TokenUsage;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
 
@@ -7,6 +8,10 @@ use std::fmt::{self, Display};
 pub enum FinalStatus {
     Succeeded,
     Failed,
+    /// The agent or the environment did not finish a step within its time limit.
+    TimedOut,
+    /// The agent was stopped at a turn, tool call, token or wall time limit.
+    BudgetExceeded,
 }
 
 impl Display for FinalStatus {
@@ -14,6 +19,8 @@ impl Display for FinalStatus {
         match self {
             FinalStatus::Succeeded => write!(f, "succeeded"),
             FinalStatus::Failed => write!(f, "failed"),
+            FinalStatus::TimedOut => write!(f, "timed_out"),
+            FinalStatus::BudgetExceeded => write!(f, "budget_exceeded"),
         }
     }
 }
@@ -33,6 +40,50 @@ pub struct TestResult {
     pub score: f64,
     /// The complete, step-by-step record of the agent's actions and the environment's responses.
     pub trace: ExecutionTrace,
+    /// How the score was reached and the issues that lowered it, if the runner recorded it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scoring_breakdown: Option<ScoringBreakdown>,
+    /// Index of this attempt when the benchmark is run several times, from 0.
+    #[serde(default)]
+    pub trial_index: u32,
+    /// The evaluation run this attempt was recorded under, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+    /// The LLM tokens the attempt used and their cost, if its agent called a model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub llm_usage: Option<LlmUsage>,
+}
+
+/// The LLM tokens an attempt used and what they cost.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LlmUsage {
+    /// The registered model that was called.
+    pub model: String,
+    #[serde(flatten)]
+    pub tokens: TokenUsage,
+    /// Cost of the tokens in USD, if the model has a price in the registry.
+    pub cost_usd: Option<f64>,
+}
+
+impl LlmUsage {
+    /// Prices `tokens` with the registry entry of `model`.
+    pub fn priced(model: &str, tokens: TokenUsage) -> Self {
+        let price = reev_config::get()
+            .model(model)
+            .ok()
+            .and_then(|entry| entry.price);
+        Self {
+            model: reev_config::canonical_model_name(model).to_string(),
+            tokens,
+            cost_usd: price.map(|price| {
+                price.cost(
+                    tokens.prompt_tokens,
+                    tokens.cached_tokens,
+                    tokens.completion_tokens,
+                )
+            }),
+        }
+    }
 }
 
 impl TestResult {
@@ -49,6 +100,34 @@ impl TestResult {
             final_status,
             score,
             trace,
+            scoring_breakdown: None,
+            trial_index: 0,
+            run_id: None,
+            llm_usage: None,
         }
     }
+
+    /// Attaches the scoring breakdown of the run.
+    pub fn with_scoring_breakdown(mut self, scoring_breakdown: ScoringBreakdown) -> Self {
+        self.scoring_breakdown = Some(scoring_breakdown);
+        self
+    }
+
+    /// Sets the trial index of the run.
+    pub fn with_trial_index(mut self, trial_index: u32) -> Self {
+        self.trial_index = trial_index;
+        self
+    }
+
+    /// Sets the evaluation run the attempt was recorded under.
+    pub fn with_run_id(mut self, run_id: impl Into<String>) -> Self {
+        self.run_id = Some(run_id.into());
+        self
+    }
+
+    /// Attaches the LLM usage of the run, if its agent reported any.
+    pub fn with_llm_usage(mut self, llm_usage: Option<LlmUsage>) -> Self {
+        self.llm_usage = llm_usage;
+        self
+    }
 }
@@ -28,6 +28,21 @@
 //! - Full score (1.0) awarded if API calls succeed
 //! - Focuses on end results rather than instruction structure
 //!
+//! ### Efficiency Budgets
+//! Benchmarks may declare `ground_truth.efficiency` budgets (compute units, fees,
+//! instruction and transaction counts). When present, the efficiency score is
+//! blended into the final score with the budget's weight:
+//! ```text
+//! Final Score = Base Score × (1 - weight) + Efficiency Score × weight
+//! ```
+//! A failed execution earns no efficiency credit.
+//!
+//! ### Durable Nonce Assertions
+//! `NonceAdvanced` assertions compare a nonce account's stored blockhash before and
+//! after execution. The on-chain score is scaled by the weighted share of nonces that
+//! were advanced, so a transaction that succeeds without using its durable nonce does
+//! not earn full on-chain credit.
+//!
 //! ### Flow Benchmarks
 //! Multi-step workflows are scored per-step with:
 //! - Individual step scores contributing to overall flow score
@@ -36,7 +51,8 @@
 
 use crate::{
     agent::{AgentAction, AgentObservation},
-    benchmark::TestCase,
+    benchmark::{StateAssertion, TestCase},
+    efficiency::EfficiencyMetrics,
     flow::ScoringBreakdown,
     instruction_score::calculate_instruction_score,
 };
@@ -56,6 +72,12 @@ const INSTRUCTION_SCORE_WEIGHT: f64 = 0.75;
 /// that might be beyond agent control.
 const ONCHAIN_SCORE_WEIGHT: f64 = 0.25;
 
+/// Highest score of a run stopped at its turn, tool call, token or wall time budget
+///
+/// A stopped run is scored on what it did, but kept below the success thresholds
+/// of single-step (75%) and flow (60%) benchmarks, so it never passes.
+pub const BUDGET_EXCEEDED_MAX_SCORE: f64 = 0.5;
+
 /// Calculates the final, comprehensive score for a test case.
 ///
 /// This function implements the core two-tiered scoring algorithm that combines
@@ -106,11 +128,13 @@ const ONCHAIN_SCORE_WEIGHT: f64 = 0.25;
 ///     prompt: "Test prompt".to_string(),
 ///     initial_state: vec![],
 ///     flow: None,
+///     faults: None,
 ///     ground_truth: GroundTruth {
 ///         transaction_status: "Success".to_string(),
 ///         final_state_assertions: vec![],
 ///         expected_instructions: vec![],
 ///         skip_instruction_validation: false,
+///         efficiency: None,
 ///     },
 /// };
 /// let agent_actions = vec![];
@@ -120,6 +144,8 @@ const ONCHAIN_SCORE_WEIGHT: f64 = 0.25;
 ///     last_transaction_logs: vec![],
 ///     account_states: HashMap::new(),
 ///     key_map: HashMap::new(),
+///     balance_deltas: vec![],
+///     last_transaction_metrics: None,
 /// };
 /// let final_state = initial_state.clone();
 ///
@@ -136,6 +162,31 @@ pub fn calculate_final_score(
     actions: &[AgentAction],
     initial_observation: &AgentObservation,
     final_observation: &AgentObservation,
+) -> f64 {
+    let metrics = EfficiencyMetrics::from_final_step(
+        actions,
+        final_observation.last_transaction_metrics.as_ref(),
+    );
+    calculate_final_score_with_metrics(
+        test_case,
+        actions,
+        initial_observation,
+        final_observation,
+        &metrics,
+    )
+}
+
+/// Calculates the final score using efficiency metrics aggregated over a whole run.
+///
+/// Identical to [`calculate_final_score`] except that the efficiency component (if
+/// the benchmark declares a budget) is computed from `metrics` rather than from the
+/// final observation alone. Use this when the run spans multiple transactions.
+pub fn calculate_final_score_with_metrics(
+    test_case: &TestCase,
+    actions: &[AgentAction],
+    initial_observation: &AgentObservation,
+    final_observation: &AgentObservation,
+    metrics: &EfficiencyMetrics,
 ) -> f64 {
     info!(
         "[SCORE] ==> Starting final score calculation for benchmark: {}",
@@ -151,10 +202,11 @@ pub fn calculate_final_score(
     };
 
     // Calculate on-chain execution score
-    let onchain_score = calculate_onchain_score(
-        final_observation,
-        test_case.ground_truth.skip_instruction_validation,
-    );
+    let onchain_score =
+        calculate_onchain_score(
+            final_observation,
+            test_case.ground_truth.skip_instruction_validation,
+        ) * nonce_assertion_score(test_case, initial_observation, final_observation).unwrap_or(1.0);
 
     // Apply scoring formula based on benchmark type
     let final_score = if test_case.ground_truth.skip_instruction_validation {
@@ -168,6 +220,7 @@ pub fn calculate_final_score(
         // Standard benchmarks: weighted combination
         (instruction_score * INSTRUCTION_SCORE_WEIGHT) + (onchain_score * ONCHAIN_SCORE_WEIGHT)
     };
+    let final_score = apply_efficiency(test_case, final_score, onchain_score, metrics);
 
     info!(
         instruction_score,
@@ -188,6 +241,28 @@ pub fn calculate_detailed_score(
     actions: &[AgentAction],
     initial_observation: &AgentObservation,
     final_observation: &AgentObservation,
+) -> ScoringBreakdown {
+    let metrics = EfficiencyMetrics::from_final_step(
+        actions,
+        final_observation.last_transaction_metrics.as_ref(),
+    );
+    calculate_detailed_score_with_metrics(
+        test_case,
+        actions,
+        initial_observation,
+        final_observation,
+        &metrics,
+    )
+}
+
+/// Calculates the detailed scoring breakdown using efficiency metrics aggregated over a
+/// whole run, matching [`calculate_final_score_with_metrics`].
+pub fn calculate_detailed_score_with_metrics(
+    test_case: &TestCase,
+    actions: &[AgentAction],
+    initial_observation: &AgentObservation,
+    final_observation: &AgentObservation,
+    metrics: &EfficiencyMetrics,
 ) -> ScoringBreakdown {
     let instruction_score = if test_case.ground_truth.skip_instruction_validation {
         1.0
@@ -195,16 +270,18 @@ pub fn calculate_detailed_score(
         calculate_instruction_score(test_case, actions, &initial_observation.key_map)
     };
 
-    let onchain_score = calculate_onchain_score(
-        final_observation,
-        test_case.ground_truth.skip_instruction_validation,
-    );
+    let onchain_score =
+        calculate_onchain_score(
+            final_observation,
+            test_case.ground_truth.skip_instruction_validation,
+        ) * nonce_assertion_score(test_case, initial_observation, final_observation).unwrap_or(1.0);
 
     let final_score = if test_case.ground_truth.skip_instruction_validation {
         1.0
     } else {
         (instruction_score * INSTRUCTION_SCORE_WEIGHT) + (onchain_score * ONCHAIN_SCORE_WEIGHT)
     };
+    let final_score = apply_efficiency(test_case, final_score, onchain_score, metrics);
 
     let mut issues = Vec::new();
     let mut mismatches = Vec::new();
@@ -230,6 +307,16 @@ pub fn calculate_detailed_score(
         }
     }
 
+    // Analyze durable nonce assertions
+    for pubkey in unadvanced_nonces(test_case, initial_observation, final_observation) {
+        issues.push(format!("Durable nonce {pubkey} was not advanced"));
+    }
+
+    // Analyze efficiency budget overruns
+    if let Some(budget) = &test_case.ground_truth.efficiency {
+        issues.extend(budget.overruns(metrics));
+    }
+
     ScoringBreakdown {
         instruction_score,
         onchain_score,
@@ -239,6 +326,110 @@ pub fn calculate_detailed_score(
     }
 }
 
+/// Blends the efficiency score into `base_score` if the benchmark declares a budget.
+fn apply_efficiency(
+    test_case: &TestCase,
+    base_score: f64,
+    onchain_score: f64,
+    metrics: &EfficiencyMetrics,
+) -> f64 {
+    let Some(budget) = &test_case.ground_truth.efficiency else {
+        return base_score;
+    };
+    let Some(efficiency_score) = budget.score(metrics) else {
+        return base_score;
+    };
+
+    // A failed execution is not "efficient", no matter how cheap it was
+    let efficiency_score = if onchain_score > 0.0 && base_score > 0.0 {
+        efficiency_score
+    } else {
+        0.0
+    };
+    let weight = budget.clamped_weight();
+
+    info!(
+        efficiency_score,
+        weight,
+        compute_units = metrics.compute_units,
+        fee_lamports = metrics.fee_lamports,
+        instruction_count = metrics.instruction_count,
+        transaction_count = metrics.transaction_count,
+        "[SCORE] Applied efficiency budget"
+    );
+
+    base_score * (1.0 - weight) + efficiency_score * weight
+}
+
+/// Weighted share of `NonceAdvanced` assertions that hold, or `None` if there are none.
+fn nonce_assertion_score(
+    test_case: &TestCase,
+    initial_observation: &AgentObservation,
+    final_observation: &AgentObservation,
+) -> Option<f64> {
+    let assertions: Vec<(&str, f64)> = test_case
+        .ground_truth
+        .final_state_assertions
+        .iter()
+        .filter_map(|assertion| match assertion {
+            StateAssertion::NonceAdvanced { pubkey, weight } => Some((pubkey.as_str(), *weight)),
+            _ => None,
+        })
+        .collect();
+    let total_weight: f64 = assertions.iter().map(|(_, weight)| weight).sum();
+    if total_weight <= 0.0 {
+        return None;
+    }
+
+    let advanced_weight: f64 = assertions
+        .iter()
+        .filter(|(pubkey, _)| nonce_advanced(pubkey, initial_observation, final_observation))
+        .map(|(_, weight)| weight)
+        .sum();
+    debug!(advanced_weight, total_weight, "Durable nonce assertions");
+    Some(advanced_weight / total_weight)
+}
+
+/// Placeholders of `NonceAdvanced` assertions that do not hold.
+fn unadvanced_nonces<'a>(
+    test_case: &'a TestCase,
+    initial_observation: &AgentObservation,
+    final_observation: &AgentObservation,
+) -> Vec<&'a str> {
+    test_case
+        .ground_truth
+        .final_state_assertions
+        .iter()
+        .filter_map(|assertion| match assertion {
+            StateAssertion::NonceAdvanced { pubkey, .. }
+                if !nonce_advanced(pubkey, initial_observation, final_observation) =>
+            {
+                Some(pubkey.as_str())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether the stored blockhash of nonce account `pubkey` changed between observations.
+fn nonce_advanced(
+    pubkey: &str,
+    initial_observation: &AgentObservation,
+    final_observation: &AgentObservation,
+) -> bool {
+    let nonce = |obs: &AgentObservation| {
+        obs.account_states
+            .get(pubkey)
+            .and_then(|state| state.get("nonce"))
+            .and_then(|nonce| nonce.as_str())
+            .map(str::to_string)
+    };
+    match (nonce(initial_observation), nonce(final_observation)) {
+        (Some(before), Some(after)) => before != after,
+        _ => false,
+    }
+}
+
 /// Calculates a binary score based on the transaction's on-chain execution status.
 fn calculate_onchain_score(
     final_observation: &AgentObservation,
@@ -27,10 +27,14 @@ pub struct SolanaEnv {
 
 impl SolanaEnv {
     pub fn new() -> Result<Self> {
-        let rpc_client = RpcClient::new_with_commitment(
-            LOCAL_SURFPOOL_RPC_URL.to_string(),
-            CommitmentConfig::confirmed(),
-        );
+        Self::with_rpc_url(LOCAL_SURFPOOL_RPC_URL)
+    }
+
+    /// Creates an environment that talks to surfpool through `rpc_url`, e.g. a
+    /// failure-injection proxy in front of it.
+    pub fn with_rpc_url(rpc_url: &str) -> Result<Self> {
+        let rpc_client =
+            RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed());
 
         Ok(Self {
             rpc_client,
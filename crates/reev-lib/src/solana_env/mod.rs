@@ -1,4 +1,5 @@
 pub mod environment;
 pub mod observation;
 pub mod reset;
+pub mod signers;
 pub mod step;
@@ -1,7 +1,7 @@
 use crate::{
     agent::AgentObservation,
     benchmark::{AddressDerivation, GroundTruth},
-    solana_env::environment::SolanaEnv,
+    solana_env::{environment::SolanaEnv, signers},
 };
 use anyhow::Result;
 use serde_json::json;
@@ -88,6 +88,7 @@ pub(crate) fn get_observation(
                     }
                 }
             }
+            signers::insert_signer_state(&mut state, &account);
             account_states.insert(name.clone(), state);
         } else {
             // Account doesn't exist on-chain (0 lamports), include it from initial_state
@@ -118,5 +119,7 @@ pub(crate) fn get_observation(
         last_transaction_status: last_tx_status.to_string(),
         last_transaction_error: last_tx_error,
         last_transaction_logs: last_tx_logs,
+        balance_deltas: Vec::new(),
+        last_transaction_metrics: None,
     })
 }
@@ -1,6 +1,6 @@
 use crate::{
     agent::AgentObservation,
-    solana_env::{environment::SolanaEnv, observation},
+    solana_env::{environment::SolanaEnv, observation, signers},
     test_scenarios,
 };
 use anyhow::{Context, Result};
@@ -27,7 +27,7 @@ pub(crate) async fn handle_reset(
         if i == 9 {
             anyhow::bail!(
                 "Could not connect to `surfpool` validator at {}",
-                "http://127.0.0.1:8899"
+                env.rpc_client.url()
             );
         }
         thread::sleep(Duration::from_secs(1));
@@ -129,7 +129,11 @@ pub(crate) async fn handle_reset(
         info!("Fee payer funded.");
     }
 
-    // 7. Get the initial observation before any complex scenario setup.
+    // 7. Create durable nonce and multisig accounts so their state is observed.
+    signers::setup_signer_accounts(env, &test_case)
+        .context("Failed to set up nonce and multisig accounts")?;
+
+    // 8. Get the initial observation before any complex scenario setup.
     let mut initial_observation = observation::get_observation(
         env,
         &test_case.ground_truth,
@@ -138,14 +142,14 @@ pub(crate) async fn handle_reset(
         vec![],
     )?;
 
-    // 8. Delegate complex SPL setup to the centralized scenario handler.
+    // 9. Delegate complex SPL setup to the centralized scenario handler.
     // This function handles ATA derivation and uses RPC cheat codes to fund accounts,
     // mutating the environment and observation maps with the real derived addresses.
     test_scenarios::setup_spl_scenario(env, &test_case, &mut initial_observation)
         .await
         .context("Failed to set up SPL scenario")?;
 
-    // 9. Log final balances for debugging
+    // 10. Log final balances for debugging
     if let Some(fee_payer_pubkey) = &env.fee_payer {
         if let Ok(pubkey) = Pubkey::from_str(fee_payer_pubkey) {
             if let Ok(balance) = env.rpc_client.get_balance(&pubkey) {
@@ -0,0 +1,335 @@
+//! Durable nonce and multisig signer support.
+//!
+//! Benchmarks can seed durable nonce accounts (`nonce:` on an initial state item)
+//! and SPL Token multisig authorities (`multisig:`). This module creates them during
+//! `reset` and decides how signers apply during `step`:
+//!
+//! - A transaction whose first instruction is `AdvanceNonceAccount` is signed against
+//!   the nonce's stored blockhash instead of the latest one, exactly as the runtime
+//!   treats durable transactions. The nonce authority must sign it.
+//! - Nonce and multisig accounts never sign for themselves. Their keypairs are
+//!   discarded once the accounts exist, so a multisig-owned account can only be
+//!   moved by the subset of signers the agent lists in its instruction.
+
+use crate::{
+    benchmark::{InitialStateItem, TestCase},
+    solana_env::environment::SolanaEnv,
+};
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use solana_client::nonce_utils;
+use solana_program::program_pack::Pack;
+use solana_sdk::{
+    account::Account,
+    hash::Hash,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+use solana_system_interface::instruction as system_instruction;
+use spl_token::state::Multisig;
+use std::str::FromStr;
+use tracing::info;
+
+/// Size of a durable nonce account (`nonce::state::Versions`).
+const NONCE_ACCOUNT_SIZE: usize = 80;
+
+/// Extra lamports given to the setup payer to cover transaction fees.
+const SETUP_FEE_BUFFER: u64 = 10_000_000;
+
+/// Creates every nonce and multisig account declared in the initial state.
+///
+/// Accounts are paid for by a throwaway setup payer so that the fee payer's balance
+/// (and any assertion on it) is unaffected. Multisig signer placeholders that are not
+/// accounts in the initial state get fresh keypairs.
+pub(crate) fn setup_signer_accounts(env: &mut SolanaEnv, test_case: &TestCase) -> Result<()> {
+    let items: Vec<&InitialStateItem> = test_case
+        .initial_state
+        .iter()
+        .filter(|item| item.nonce.is_some() || item.multisig.is_some())
+        .collect();
+    if items.is_empty() {
+        return Ok(());
+    }
+
+    let nonce_rent = env
+        .rpc_client
+        .get_minimum_balance_for_rent_exemption(NONCE_ACCOUNT_SIZE)?;
+    let multisig_rent = env
+        .rpc_client
+        .get_minimum_balance_for_rent_exemption(Multisig::LEN)?;
+    let funding: u64 = items
+        .iter()
+        .map(|item| {
+            let rent = if item.nonce.is_some() {
+                nonce_rent
+            } else {
+                multisig_rent
+            };
+            item.lamports.max(rent)
+        })
+        .sum();
+
+    let payer = Keypair::new();
+    let sig = env
+        .rpc_client
+        .request_airdrop(&payer.pubkey(), funding + SETUP_FEE_BUFFER)?;
+    env.rpc_client
+        .confirm_transaction(&sig)
+        .context("Failed to confirm signer setup airdrop")?;
+
+    for item in items {
+        let account = env.keypair_map.remove(&item.pubkey).with_context(|| {
+            format!(
+                "Nonce and multisig accounts must use a placeholder, got '{}'",
+                item.pubkey
+            )
+        })?;
+
+        if let Some(nonce) = &item.nonce {
+            let authority = resolve_pubkey(env, &nonce.authority)?;
+            let instructions = system_instruction::create_nonce_account(
+                &payer.pubkey(),
+                &account.pubkey(),
+                &authority,
+                item.lamports.max(nonce_rent),
+            );
+            send_setup_transaction(env, &instructions, &[&payer, &account])
+                .with_context(|| format!("Failed to create nonce account '{}'", item.pubkey))?;
+            info!(
+                "[signers] Created nonce account '{}' ({}) with authority {}",
+                item.pubkey,
+                account.pubkey(),
+                authority
+            );
+        } else if let Some(multisig) = &item.multisig {
+            let signers = multisig
+                .signers
+                .iter()
+                .map(|signer| resolve_or_generate(env, signer))
+                .collect::<Result<Vec<Pubkey>>>()?;
+            let signer_refs: Vec<&Pubkey> = signers.iter().collect();
+            let instructions = vec![
+                system_instruction::create_account(
+                    &payer.pubkey(),
+                    &account.pubkey(),
+                    item.lamports.max(multisig_rent),
+                    Multisig::LEN as u64,
+                    &spl_token::id(),
+                ),
+                spl_token::instruction::initialize_multisig(
+                    &spl_token::id(),
+                    &account.pubkey(),
+                    &signer_refs,
+                    multisig.m,
+                )?,
+            ];
+            send_setup_transaction(env, &instructions, &[&payer, &account])
+                .with_context(|| format!("Failed to create multisig '{}'", item.pubkey))?;
+            info!(
+                "[signers] Created {}-of-{} multisig '{}' ({})",
+                multisig.m,
+                signers.len(),
+                item.pubkey,
+                account.pubkey()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Collects the keypairs for every signer the instructions require, fee payer first.
+///
+/// Signers without a known keypair are skipped; signing then fails with a missing
+/// signature, which is reported back to the agent.
+pub(crate) fn collect_signers<'a>(
+    env: &'a SolanaEnv,
+    instructions: &[Instruction],
+) -> Result<Vec<&'a Keypair>> {
+    let mut signers = vec![env.get_fee_payer_keypair()?];
+    for acc in instructions
+        .iter()
+        .flat_map(|ix| &ix.accounts)
+        .filter(|acc| acc.is_signer)
+    {
+        if let Some(keypair) = env
+            .keypair_map
+            .values()
+            .find(|kp| kp.pubkey() == acc.pubkey)
+        {
+            if !signers.iter().any(|s| s.pubkey() == keypair.pubkey()) {
+                signers.push(keypair);
+            }
+        }
+    }
+    Ok(signers)
+}
+
+/// Whether `instruction` is a System Program `AdvanceNonceAccount`.
+pub(crate) fn is_advance_nonce(instruction: &Instruction) -> bool {
+    instruction.program_id == solana_system_interface::program::id()
+        && instruction.data
+            == system_instruction::advance_nonce_account(&Pubkey::default(), &Pubkey::default())
+                .data
+}
+
+/// Returns the stored blockhash to sign with if the transaction is a durable one.
+///
+/// As in the runtime, a transaction is durable only when its first instruction
+/// advances a nonce account.
+pub(crate) fn durable_nonce_blockhash(
+    env: &SolanaEnv,
+    instructions: &[Instruction],
+) -> Result<Option<Hash>> {
+    let Some(nonce_pubkey) = instructions
+        .first()
+        .filter(|ix| is_advance_nonce(ix))
+        .and_then(|ix| ix.accounts.first())
+        .map(|acc| acc.pubkey)
+    else {
+        return Ok(None);
+    };
+
+    let account = nonce_utils::get_account_with_commitment(
+        &env.rpc_client,
+        &nonce_pubkey,
+        env.rpc_client.commitment(),
+    )
+    .with_context(|| format!("Failed to fetch nonce account {nonce_pubkey}"))?;
+    let data = nonce_utils::data_from_account(&account)
+        .with_context(|| format!("Account {nonce_pubkey} is not an initialized nonce"))?;
+    info!(
+        "[signers] Durable transaction using nonce {} (blockhash {})",
+        nonce_pubkey,
+        data.blockhash()
+    );
+    Ok(Some(data.blockhash()))
+}
+
+/// Adds durable nonce or multisig details to an observed account state.
+pub(crate) fn insert_signer_state(state: &mut Value, account: &Account) {
+    let Some(obj) = state.as_object_mut() else {
+        return;
+    };
+    if account.owner == solana_system_interface::program::id() {
+        if let Ok(data) = nonce_utils::data_from_account(account) {
+            obj.insert("nonce".to_string(), json!(data.blockhash().to_string()));
+            obj.insert(
+                "nonce_authority".to_string(),
+                json!(data.authority.to_string()),
+            );
+        }
+    } else if account.owner == spl_token::ID && account.data.len() == Multisig::LEN {
+        if let Ok(multisig) = Multisig::unpack(&account.data) {
+            let signers: Vec<String> = multisig.signers[..multisig.n as usize]
+                .iter()
+                .map(|s| s.to_string())
+                .collect();
+            obj.insert("multisig_m".to_string(), json!(multisig.m));
+            obj.insert("multisig_signers".to_string(), json!(signers));
+        }
+    }
+}
+
+/// Resolves a placeholder or literal pubkey.
+fn resolve_pubkey(env: &SolanaEnv, name: &str) -> Result<Pubkey> {
+    match env.pubkey_map.get(name) {
+        Some(pubkey) => Ok(*pubkey),
+        None => Pubkey::from_str(name)
+            .with_context(|| format!("Unknown placeholder or invalid pubkey: {name}")),
+    }
+}
+
+/// Resolves a signer, generating a keypair for placeholders seen for the first time.
+fn resolve_or_generate(env: &mut SolanaEnv, name: &str) -> Result<Pubkey> {
+    if env.pubkey_map.contains_key(name) || Pubkey::from_str(name).is_ok() {
+        return resolve_pubkey(env, name);
+    }
+    let keypair = Keypair::new();
+    let pubkey = keypair.pubkey();
+    env.pubkey_map.insert(name.to_string(), pubkey);
+    env.keypair_map.insert(name.to_string(), keypair);
+    info!("[signers] Generated new address for signer placeholder '{name}': {pubkey}");
+    Ok(pubkey)
+}
+
+fn send_setup_transaction(
+    env: &SolanaEnv,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<()> {
+    let blockhash = env.rpc_client.get_latest_blockhash()?;
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&signers[0].pubkey()),
+        signers,
+        blockhash,
+    );
+    env.rpc_client.send_and_confirm_transaction(&transaction)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benchmark::StateAssertion;
+
+    #[test]
+    fn test_is_advance_nonce() {
+        let nonce = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        assert!(is_advance_nonce(
+            &system_instruction::advance_nonce_account(&nonce, &authority)
+        ));
+        assert!(!is_advance_nonce(&system_instruction::transfer(
+            &authority, &nonce, 1
+        )));
+    }
+
+    #[test]
+    fn test_signer_accounts_yaml() {
+        let test_case: TestCase = serde_yaml::from_str(
+            r#"
+id: nonce-multisig
+description: Durable nonce and multisig
+tags: []
+initial_state:
+  - pubkey: USER_WALLET_PUBKEY
+    owner: "11111111111111111111111111111111"
+    lamports: 1000000000
+  - pubkey: NONCE_ACCOUNT
+    owner: "11111111111111111111111111111111"
+    lamports: 1500000
+    nonce:
+      authority: USER_WALLET_PUBKEY
+  - pubkey: TREASURY_MULTISIG
+    owner: TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA
+    lamports: 0
+    multisig:
+      m: 2
+      signers: [SIGNER_A, SIGNER_B, SIGNER_C]
+prompt: Advance the nonce
+ground_truth:
+  final_state_assertions:
+    - type: NonceAdvanced
+      pubkey: NONCE_ACCOUNT
+"#,
+        )
+        .unwrap();
+
+        let nonce = test_case.initial_state[1].nonce.as_ref().unwrap();
+        assert_eq!(nonce.authority, "USER_WALLET_PUBKEY");
+        let multisig = test_case.initial_state[2].multisig.as_ref().unwrap();
+        assert_eq!(multisig.m, 2);
+        assert_eq!(multisig.signers.len(), 3);
+        assert_eq!(
+            test_case.ground_truth.final_state_assertions[0],
+            StateAssertion::NonceAdvanced {
+                pubkey: "NONCE_ACCOUNT".to_string(),
+                weight: 1.0,
+            }
+        );
+    }
+}
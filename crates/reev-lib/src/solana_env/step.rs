@@ -1,15 +1,17 @@
 use crate::{
     agent::{AgentAction, AgentObservation},
+    balance_delta::{self, AccountBalanceDelta},
     benchmark::GroundTruth,
+    efficiency::TransactionMetrics,
     env::Step,
-    solana_env::{environment::SolanaEnv, observation},
+    solana_env::{environment::SolanaEnv, observation, signers},
 };
 use anyhow::Result;
 use serde_json::json;
 use solana_client::rpc_config::RpcSimulateTransactionConfig;
 use solana_sdk::{instruction::Instruction, signature::Signer, transaction::Transaction};
 
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 pub(crate) fn handle_step(
     env: &mut SolanaEnv,
@@ -20,6 +22,8 @@ pub(crate) fn handle_step(
     let mut tx_status = "Failure";
     let mut tx_error: Option<String> = None;
     let mut tx_logs: Vec<String> = Vec::new();
+    let mut balance_deltas: Vec<AccountBalanceDelta> = Vec::new();
+    let mut tx_metrics: Option<TransactionMetrics> = None;
     let mut info = json!({});
     let mut reward = 0.0;
 
@@ -80,24 +84,7 @@ pub(crate) fn handle_step(
         // --- 4. Build and simulate the transaction for non-API benchmarks ---
         let instructions: Vec<Instruction> = actions.into_iter().map(|a| a.0).collect();
         let fee_payer_keypair = env.get_fee_payer_keypair()?;
-        let mut signers = vec![fee_payer_keypair];
-
-        // Collect signers from all instructions
-        for instruction in &instructions {
-            for acc in &instruction.accounts {
-                if acc.is_signer {
-                    if let Some(keypair) = env
-                        .keypair_map
-                        .values()
-                        .find(|kp| kp.pubkey() == acc.pubkey)
-                    {
-                        if !signers.iter().any(|s| s.pubkey() == keypair.pubkey()) {
-                            signers.push(keypair);
-                        }
-                    }
-                }
-            }
-        }
+        let signers = signers::collect_signers(env, &instructions)?;
 
         let mut transaction =
             Transaction::new_with_payer(&instructions, Some(&fee_payer_keypair.pubkey()));
@@ -128,6 +115,9 @@ pub(crate) fn handle_step(
                 "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA" => {
                     info!("  -> SPL Token program instruction detected");
                 }
+                "11111111111111111111111111111111" if signers::is_advance_nonce(instruction) => {
+                    info!("  -> Durable nonce advance instruction detected");
+                }
                 "11111111111111111111111111111111" => {
                     info!("  -> System program instruction detected");
                 }
@@ -159,32 +149,73 @@ pub(crate) fn handle_step(
         } else {
             // --- 6. If simulation succeeds, execute the transaction ---
             info!("Transaction simulation successful. Executing transaction...");
-            let latest_blockhash = env.rpc_client.get_latest_blockhash()?;
-            transaction.sign(&signers, latest_blockhash);
-
-            // Execute the transaction
-            match env.rpc_client.send_and_confirm_transaction(&transaction) {
-                Ok(sig) => {
-                    info!("Transaction executed successfully: {}", sig.to_string());
-                    tx_status = "Success";
-                    reward = 1.0;
-                    // Use simulation logs since get_transaction might fail
-                    tx_logs = sim_logs.clone();
-                }
-                Err(e) => {
-                    let error_string =
-                        format!("Transaction execution failed after successful simulation: {e}");
-                    error!("{}", error_string);
-                    tx_error = Some(error_string.clone());
-                    tx_logs = sim_logs; // On execution failure, we only have simulation logs
-                    info = json!({ "error": error_string });
+            // Durable transactions must be signed against the nonce's stored blockhash
+            let blockhash = match signers::durable_nonce_blockhash(env, &instructions)? {
+                Some(nonce_blockhash) => nonce_blockhash,
+                None => env.rpc_client.get_latest_blockhash()?,
+            };
+            if let Err(e) = transaction.try_sign(&signers, blockhash) {
+                let error_string = format!("Transaction signing failed: {e}");
+                error!("{}", error_string);
+                tx_error = Some(error_string.clone());
+                tx_logs = sim_logs;
+                info = json!({ "error": error_string });
+            } else {
+                // Snapshot balances of every referenced account around execution
+                let account_keys = transaction.message.account_keys.clone();
+                let pre_balances = balance_delta::snapshot_balances(&env.rpc_client, &account_keys);
+
+                // Execute the transaction
+                let send_result = env.rpc_client.send_and_confirm_transaction(&transaction);
+
+                let post_balances =
+                    balance_delta::snapshot_balances(&env.rpc_client, &account_keys);
+                balance_deltas = balance_delta::compute_balance_deltas(
+                    &account_keys,
+                    &pre_balances,
+                    &post_balances,
+                    &env.pubkey_map,
+                );
+
+                match send_result {
+                    Ok(sig) => {
+                        info!("Transaction executed successfully: {}", sig.to_string());
+                        tx_status = "Success";
+                        reward = 1.0;
+                        // Use simulation logs since get_transaction might fail
+                        tx_logs = sim_logs.clone();
+
+                        let fee_lamports = env
+                            .rpc_client
+                            .get_fee_for_message(&transaction.message)
+                            .unwrap_or_else(|e| {
+                                warn!("Failed to fetch fee for executed transaction: {e}");
+                                0
+                            });
+                        tx_metrics = Some(TransactionMetrics {
+                            compute_units: sim_result.value.units_consumed.unwrap_or(0),
+                            fee_lamports,
+                            instruction_count: transaction.message.instructions.len() as u32,
+                        });
+                    }
+                    Err(e) => {
+                        let error_string = format!(
+                            "Transaction execution failed after successful simulation: {e}"
+                        );
+                        error!("{}", error_string);
+                        tx_error = Some(error_string.clone());
+                        tx_logs = sim_logs; // On execution failure, we only have simulation logs
+                        info = json!({ "error": error_string });
+                    }
                 }
             }
         }
     }
 
     // --- 7. Get the final observation AFTER the transaction has settled ---
-    let obs = observation::get_observation(env, ground_truth, tx_status, tx_error, tx_logs)?;
+    let mut obs = observation::get_observation(env, ground_truth, tx_status, tx_error, tx_logs)?;
+    obs.balance_deltas = balance_deltas;
+    obs.last_transaction_metrics = tx_metrics;
 
     // --- 8. Return the final step result ---
     Ok(Step {
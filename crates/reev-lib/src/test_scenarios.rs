@@ -24,7 +24,7 @@
 use crate::{
     agent::AgentObservation,
     benchmark::{InitialStateItem, TestCase},
-    solana_env::environment::SolanaEnv,
+    solana_env::{environment::SolanaEnv, signers},
 };
 use anyhow::{Context, Result};
 use jup_sdk::surfpool::SurfpoolClient;
@@ -151,6 +151,7 @@ pub async fn setup_spl_scenario(
                     }
                 }
             }
+            signers::insert_signer_state(&mut state, &account);
             observation
                 .account_states
                 .insert(placeholder.clone(), state);
@@ -1,4 +1,7 @@
-use crate::agent::{AgentAction, AgentObservation};
+use crate::{
+    agent::{AgentAction, AgentObservation},
+    faults::RecoveryStats,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -10,6 +13,14 @@ pub struct ExecutionTrace {
     pub prompt: String,
     /// The sequence of steps taken by the agent.
     pub steps: Vec<TraceStep>,
+    /// The environment state after `reset`, before the agent acted.
+    ///
+    /// Stored so a trace can be re-scored later without re-running the benchmark.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_observation: Option<Box<AgentObservation>>,
+    /// How the agent coped with injected faults, for benchmarks that inject them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recovery: Option<RecoveryStats>,
 }
 
 impl ExecutionTrace {
@@ -18,6 +29,8 @@ impl ExecutionTrace {
         Self {
             prompt,
             steps: Vec::new(),
+            initial_observation: None,
+            recovery: None,
         }
     }
 
@@ -0,0 +1,35 @@
+//! # Reev Mock LLM
+//!
+//! An OpenAI-compatible chat completions server that plays back scripted
+//! conversations instead of running a model, so the agents' real tool-calling
+//! loop (tools, multi-turn execution and response parsing) can be tested without
+//! a paid model. Besides well-formed tool calls and answers, a script can send
+//! tool arguments that are not valid JSON, refusals, answers cut off at the token
+//! limit, HTTP errors and answers delayed like those of a slow model.
+//!
+//! ```no_run
+//! use reev_mock_llm::{Conversation, MockLlmServer, Script, Turn};
+//! use serde_json::json;
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let script = Script::new().conversation(Conversation::new(
+//!     "sol-transfer",
+//!     vec![
+//!         Turn::tool_call("sol_transfer", json!({"amount": 100000000})),
+//!         Turn::text(r#"{"transactions": {{tool_result}}, "summary": "Sent"}"#),
+//!     ],
+//! ));
+//! let server = MockLlmServer::start(script).await?;
+//! // Point an OpenAI client, or the `local` model of reev.toml, at server.base_url()
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The `reev-mock-llm` binary serves a YAML script for runs of the whole stack:
+//! `reev-mock-llm --script script.yml --port 1234` answers for `--agent local`.
+
+pub mod script;
+mod server;
+
+pub use script::{Conversation, Script, ScriptError, ScriptedCall, Turn};
+pub use server::{MockLlmServer, MOCK_MODEL};
@@ -0,0 +1,39 @@
+use std::{net::SocketAddr, path::PathBuf};
+
+use anyhow::Result;
+use clap::Parser;
+use reev_mock_llm::{MockLlmServer, Script};
+
+/// Serve scripted conversations on an OpenAI-compatible chat completions API
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Cli {
+    /// YAML script of the conversations to play back
+    #[arg(short, long)]
+    script: PathBuf,
+
+    /// Port to listen on; 1234 is the `local` model's default
+    #[arg(short, long, default_value_t = 1234)]
+    port: u16,
+
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1")]
+    host: std::net::IpAddr,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| "info".into()),
+        )
+        .try_init();
+
+    let cli = Cli::parse();
+    let script = Script::load(&cli.script)?;
+    let server = MockLlmServer::start_on(SocketAddr::new(cli.host, cli.port), script).await?;
+    println!("Mock LLM listening at {}", server.base_url());
+
+    tokio::signal::ctrl_c().await?;
+    Ok(())
+}
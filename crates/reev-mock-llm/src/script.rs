@@ -0,0 +1,299 @@
+//! Scripted conversations played back by the mock server
+//!
+//! A script is a list of conversations, each a list of turns. A request is
+//! answered from the first conversation whose `matches` text appears in one of
+//! its user messages (a conversation without `matches` takes any request), with
+//! the turn given by the number of assistant messages already in the request.
+//! Requests carry the whole history, so the server keeps no state between them.
+//!
+//! ```yaml
+//! conversations:
+//!   - name: sol-transfer
+//!     matches: "0.1 SOL"
+//!     turns:
+//!       - type: tool_calls
+//!         calls:
+//!           - name: sol_transfer
+//!             arguments: {user_pubkey: USER_WALLET_PUBKEY, amount: 100000000}
+//!       - type: text
+//!         content: '{"transactions": {{tool_result}}, "summary": "Sent 0.1 SOL"}'
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+/// Placeholder in a `text` turn replaced by the content of the request's last tool result
+pub const TOOL_RESULT_PLACEHOLDER: &str = "{{tool_result}}";
+
+#[derive(Debug, Error)]
+pub enum ScriptError {
+    #[error("Failed to read script {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Invalid script: {0}")]
+    Parse(#[from] serde_yaml::Error),
+
+    #[error("No scripted conversation matches the request; its first user message is: {0}")]
+    NoConversation(String),
+
+    #[error("Conversation '{name}' has {turns} turns; the request asks for turn {turn}")]
+    Exhausted {
+        name: String,
+        turns: usize,
+        turn: usize,
+    },
+}
+
+/// Conversations the server plays back
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Script {
+    pub conversations: Vec<Conversation>,
+}
+
+/// One scripted conversation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Conversation {
+    pub name: String,
+    /// Text a user message of the request must contain
+    #[serde(default)]
+    pub matches: Option<String>,
+    pub turns: Vec<Turn>,
+}
+
+/// The assistant's answer to one request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Turn {
+    /// Call tools with well-formed arguments
+    ToolCalls { calls: Vec<ScriptedCall> },
+    /// Answer with text; `{{tool_result}}` is replaced by the last tool result
+    Text { content: String },
+    /// Call a tool with arguments that are not valid JSON
+    MalformedToolCall { name: String, arguments: String },
+    /// Decline the request in the `refusal` field, as OpenAI models do
+    Refusal { refusal: String },
+    /// Answer with `unit` repeated `repeat` times, cut off at the token limit
+    LongText { unit: String, repeat: usize },
+    /// Fail the request with an HTTP status, such as 429 or 500
+    Error { status: u16, message: String },
+    /// Answer with `turn` after `delay_ms` milliseconds, as a slow model would
+    Delayed { delay_ms: u64, turn: Box<Turn> },
+}
+
+/// A tool call of a `tool_calls` turn
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScriptedCall {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: Value,
+}
+
+impl Script {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a conversation, tried after the ones already added
+    pub fn conversation(mut self, conversation: Conversation) -> Self {
+        self.conversations.push(conversation);
+        self
+    }
+
+    pub fn from_yaml(source: &str) -> Result<Self, ScriptError> {
+        Ok(serde_yaml::from_str(source)?)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ScriptError> {
+        let source = std::fs::read_to_string(path).map_err(|source| ScriptError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::from_yaml(&source)
+    }
+
+    /// The conversation answering the chat `messages` and the turn they are at
+    pub fn turn_for(&self, messages: &[Value]) -> Result<(&Conversation, &Turn), ScriptError> {
+        let user_texts: Vec<String> = messages
+            .iter()
+            .filter(|message| message["role"] == "user")
+            .map(|message| content_text(&message["content"]))
+            .collect();
+
+        let conversation = self
+            .conversations
+            .iter()
+            .find(|conversation| match &conversation.matches {
+                Some(text) => user_texts.iter().any(|user| user.contains(text.as_str())),
+                None => true,
+            })
+            .ok_or_else(|| {
+                let first = user_texts.first().map(String::as_str).unwrap_or_default();
+                ScriptError::NoConversation(first.chars().take(200).collect())
+            })?;
+
+        let turn = messages
+            .iter()
+            .filter(|message| message["role"] == "assistant")
+            .count();
+        let scripted = conversation
+            .turns
+            .get(turn)
+            .ok_or_else(|| ScriptError::Exhausted {
+                name: conversation.name.clone(),
+                turns: conversation.turns.len(),
+                turn: turn + 1,
+            })?;
+        Ok((conversation, scripted))
+    }
+}
+
+impl Conversation {
+    pub fn new(name: &str, turns: Vec<Turn>) -> Self {
+        Self {
+            name: name.to_string(),
+            matches: None,
+            turns,
+        }
+    }
+
+    /// Only answer requests with a user message containing `text`
+    pub fn matching(mut self, text: &str) -> Self {
+        self.matches = Some(text.to_string());
+        self
+    }
+}
+
+impl Turn {
+    /// A turn calling the single tool `name`
+    pub fn tool_call(name: &str, arguments: Value) -> Self {
+        Self::ToolCalls {
+            calls: vec![ScriptedCall {
+                name: name.to_string(),
+                arguments,
+            }],
+        }
+    }
+
+    pub fn text(content: &str) -> Self {
+        Self::Text {
+            content: content.to_string(),
+        }
+    }
+
+    /// `turn`, answered after `delay_ms` milliseconds
+    pub fn delayed(delay_ms: u64, turn: Turn) -> Self {
+        Self::Delayed {
+            delay_ms,
+            turn: Box::new(turn),
+        }
+    }
+}
+
+/// The text of a message content, given as a string or a list of parts
+pub(crate) fn content_text(content: &Value) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|part| part["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const SCRIPT: &str = r#"
+conversations:
+  - name: transfer
+    matches: "0.1 SOL"
+    turns:
+      - type: tool_calls
+        calls:
+          - name: sol_transfer
+            arguments: {amount: 100000000}
+      - type: text
+        content: "{{tool_result}}"
+  - name: fallback
+    turns:
+      - type: refusal
+        refusal: "I can't help with that."
+"#;
+
+    #[test]
+    fn test_turn_selection() {
+        let script = Script::from_yaml(SCRIPT).unwrap();
+        let user = json!({"role": "user", "content": [{"type": "text", "text": "Send 0.1 SOL"}]});
+
+        let (conversation, turn) = script.turn_for(&[user.clone()]).unwrap();
+        assert_eq!(conversation.name, "transfer");
+        assert_eq!(
+            turn,
+            &Turn::tool_call("sol_transfer", json!({"amount": 100000000}))
+        );
+
+        let history = [
+            user.clone(),
+            json!({"role": "assistant", "content": null, "tool_calls": []}),
+            json!({"role": "tool", "content": "[]"}),
+        ];
+        let (_, turn) = script.turn_for(&history).unwrap();
+        assert_eq!(turn, &Turn::text("{{tool_result}}"));
+
+        let (conversation, _) = script
+            .turn_for(&[json!({"role": "user", "content": "Swap USDC"})])
+            .unwrap();
+        assert_eq!(conversation.name, "fallback");
+
+        let mut history = history.to_vec();
+        history.push(json!({"role": "assistant", "content": "done"}));
+        let err = script.turn_for(&history).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                ScriptError::Exhausted {
+                    turns: 2,
+                    turn: 3,
+                    ..
+                }
+            ),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_delayed_turn() {
+        let turn: Turn = serde_yaml::from_str(
+            "type: delayed\ndelay_ms: 500\nturn:\n  type: text\n  content: done\n",
+        )
+        .unwrap();
+        assert_eq!(turn, Turn::delayed(500, Turn::text("done")));
+    }
+
+    #[test]
+    fn test_invalid_scripts() {
+        let err =
+            Script::from_yaml("conversations:\n  - name: a\n    turns:\n      - type: shout\n")
+                .unwrap_err();
+        assert!(err.to_string().contains("shout"), "{err}");
+
+        let script = Script::new().conversation(Conversation::new("a", vec![]).matching("SOL"));
+        let err = script
+            .turn_for(&[json!({"role": "user", "content": "Swap USDC"})])
+            .unwrap_err();
+        assert!(matches!(err, ScriptError::NoConversation(ref text) if text == "Swap USDC"));
+    }
+}
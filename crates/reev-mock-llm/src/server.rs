@@ -0,0 +1,243 @@
+//! The OpenAI-compatible HTTP server
+
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use axum::{
+    extract::State,
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tracing::{info, warn};
+
+use crate::script::{content_text, Script, Turn, TOOL_RESULT_PLACEHOLDER};
+
+/// Model name listed by `/v1/models`; requests may name any model
+pub const MOCK_MODEL: &str = "reev-mock";
+
+/// Rough size of a token, for the usage reported with each answer
+const CHARS_PER_TOKEN: usize = 4;
+
+#[derive(Clone)]
+struct ServerState {
+    script: Arc<Script>,
+    requests: Arc<Mutex<Vec<Value>>>,
+}
+
+/// A running mock server
+///
+/// The server runs until the runtime it was started on shuts down, or for the
+/// life of the process when started with [`MockLlmServer::spawn`].
+#[derive(Clone)]
+pub struct MockLlmServer {
+    addr: SocketAddr,
+    requests: Arc<Mutex<Vec<Value>>>,
+}
+
+impl MockLlmServer {
+    /// Serve `script` on a free port of 127.0.0.1
+    pub async fn start(script: Script) -> Result<Self> {
+        Self::start_on(SocketAddr::from(([127, 0, 0, 1], 0)), script).await
+    }
+
+    /// Serve `script` on `addr`
+    pub async fn start_on(addr: SocketAddr, script: Script) -> Result<Self> {
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to bind the mock LLM server to {addr}"))?;
+        let addr = listener.local_addr()?;
+        let state = ServerState {
+            script: Arc::new(script),
+            requests: Arc::default(),
+        };
+        let requests = state.requests.clone();
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, router(state)).await {
+                warn!("[MockLlm] Server stopped: {e}");
+            }
+        });
+        info!("[MockLlm] Serving scripted conversations at http://{addr}/v1");
+        Ok(Self { addr, requests })
+    }
+
+    /// Serve `script` on a thread of its own, so the server outlives the caller's
+    /// runtime (each `#[tokio::test]` has one)
+    pub fn spawn(script: Script) -> Result<Self> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let runtime = match tokio::runtime::Runtime::new() {
+                Ok(runtime) => runtime,
+                Err(e) => return sender.send(Err(e.into())).unwrap_or_default(),
+            };
+            runtime.block_on(async move {
+                let started = Self::start(script).await;
+                let failed = started.is_err();
+                let _ = sender.send(started);
+                if !failed {
+                    std::future::pending::<()>().await;
+                }
+            });
+        });
+        receiver
+            .recv()
+            .context("The mock LLM server thread exited before starting")?
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Base URL for OpenAI clients, ending in `/v1`
+    pub fn base_url(&self) -> String {
+        format!("http://{}/v1", self.addr)
+    }
+
+    /// The bodies of the chat completion requests received so far
+    pub fn requests(&self) -> Vec<Value> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+fn router(state: ServerState) -> Router {
+    Router::new()
+        .route("/v1/chat/completions", post(chat_completions))
+        .route("/chat/completions", post(chat_completions))
+        .route("/v1/models", get(models))
+        .route("/health", get(|| async { "OK" }))
+        .with_state(state)
+}
+
+async fn models() -> Json<Value> {
+    Json(json!({
+        "object": "list",
+        "data": [{"id": MOCK_MODEL, "object": "model", "owned_by": "reev"}]
+    }))
+}
+
+async fn chat_completions(
+    State(state): State<ServerState>,
+    Json(request): Json<Value>,
+) -> (StatusCode, Json<Value>) {
+    state.requests.lock().unwrap().push(request.clone());
+    let messages = request["messages"].as_array().cloned().unwrap_or_default();
+
+    let (conversation, turn) = match state.script.turn_for(&messages) {
+        Ok(found) => found,
+        Err(e) => {
+            warn!("[MockLlm] {e}");
+            return error(
+                StatusCode::BAD_REQUEST,
+                "invalid_request_error",
+                &e.to_string(),
+            );
+        }
+    };
+    let turn_number = messages.iter().filter(|m| m["role"] == "assistant").count() + 1;
+    info!(
+        "[MockLlm] Conversation '{}' turn {turn_number}",
+        conversation.name
+    );
+
+    let mut turn = turn;
+    while let Turn::Delayed {
+        delay_ms,
+        turn: delayed,
+    } = turn
+    {
+        tokio::time::sleep(Duration::from_millis(*delay_ms)).await;
+        turn = delayed;
+    }
+
+    let (message, finish_reason) = match turn {
+        Turn::ToolCalls { calls } => {
+            let tool_calls: Vec<Value> = calls
+                .iter()
+                .enumerate()
+                .map(|(i, call)| tool_call(turn_number, i, &call.name, call.arguments.to_string()))
+                .collect();
+            (
+                json!({"role": "assistant", "content": null, "tool_calls": tool_calls}),
+                "tool_calls",
+            )
+        }
+        Turn::Text { content } => {
+            let tool_result = messages
+                .iter()
+                .rev()
+                .find(|message| message["role"] == "tool")
+                .map(|message| content_text(&message["content"]))
+                .map(|text| match serde_json::from_str(&text) {
+                    // Tool outputs that are strings arrive JSON-encoded
+                    Ok(Value::String(output)) => output,
+                    _ => text,
+                })
+                .unwrap_or_default();
+            let content = content.replace(TOOL_RESULT_PLACEHOLDER, &tool_result);
+            (json!({"role": "assistant", "content": content}), "stop")
+        }
+        Turn::MalformedToolCall { name, arguments } => (
+            json!({
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [tool_call(turn_number, 0, name, arguments.clone())]
+            }),
+            "tool_calls",
+        ),
+        Turn::Refusal { refusal } => (
+            json!({"role": "assistant", "content": null, "refusal": refusal}),
+            "stop",
+        ),
+        Turn::LongText { unit, repeat } => (
+            json!({"role": "assistant", "content": unit.repeat(*repeat)}),
+            "length",
+        ),
+        Turn::Error { status, message } => {
+            let status = StatusCode::from_u16(*status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            return error(status, "mock_error", message);
+        }
+        Turn::Delayed { .. } => unreachable!("delays are waited out above"),
+    };
+
+    let prompt_tokens = request["messages"].to_string().len() / CHARS_PER_TOKEN;
+    let completion_tokens = message.to_string().len() / CHARS_PER_TOKEN;
+    let response = json!({
+        "id": format!("chatcmpl-mock-{turn_number}"),
+        "object": "chat.completion",
+        "created": 0,
+        "model": request["model"].as_str().unwrap_or(MOCK_MODEL),
+        "choices": [{
+            "index": 0,
+            "message": message,
+            "logprobs": null,
+            "finish_reason": finish_reason
+        }],
+        "usage": {
+            "prompt_tokens": prompt_tokens,
+            "completion_tokens": completion_tokens,
+            "total_tokens": prompt_tokens + completion_tokens
+        }
+    });
+    (StatusCode::OK, Json(response))
+}
+
+fn tool_call(turn_number: usize, index: usize, name: &str, arguments: String) -> Value {
+    json!({
+        "id": format!("call_{turn_number}_{index}"),
+        "type": "function",
+        "function": {"name": name, "arguments": arguments}
+    })
+}
+
+fn error(status: StatusCode, kind: &str, message: &str) -> (StatusCode, Json<Value>) {
+    (
+        status,
+        Json(json!({"error": {"message": message, "type": kind, "code": status.as_u16()}})),
+    )
+}
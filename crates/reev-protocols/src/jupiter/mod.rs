@@ -3,21 +3,6 @@
 //! This module provides real API integration with Jupiter's various services
 //! including swaps, lending, and earning operations.
 
-// Inline config helpers
-fn get_env_string(key: &str, default: &str) -> String {
-    std::env::var(key).unwrap_or_else(|_| default.to_string())
-}
-
-fn get_env_var<T>(key: &str, default: T) -> T
-where
-    T: std::str::FromStr,
-    T::Err: std::fmt::Debug,
-{
-    std::env::var(key)
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(default)
-}
 use anyhow::Result;
 use reqwest::Client;
 use serde_json::Value;
@@ -76,23 +61,26 @@ impl Default for JupiterConfig {
     }
 }
 
-impl JupiterConfig {
-    /// Load configuration from environment variables with dotenvy support
-    pub fn from_env() -> Self {
-        // Load .env file if it exists
-        let _ = dotenvy::dotenv();
-
+impl From<&reev_config::JupiterSettings> for JupiterConfig {
+    fn from(settings: &reev_config::JupiterSettings) -> Self {
         Self {
-            api_base_url: get_env_string("JUPITER_API_BASE_URL", "https://lite-api.jup.ag"),
-            timeout: Duration::from_secs(get_env_var("JUPITER_TIMEOUT_SECONDS", 30)),
-            max_retries: get_env_var("JUPITER_MAX_RETRIES", 3),
-            user_agent: get_env_string("JUPITER_USER_AGENT", "reev-agent/0.1.0"),
-            default_slippage_bps: get_env_var("JUPITER_DEFAULT_SLIPPAGE_BPS", 50),
-            max_slippage_bps: get_env_var("JUPITER_MAX_SLIPPAGE_BPS", 1000),
-            debug_logging: get_env_var("JUPITER_DEBUG", false),
-            surfpool_rpc_url: std::env::var("JUPITER_SURFPOOL_RPC_URL").ok(),
+            api_base_url: settings.api_base_url.clone(),
+            timeout: Duration::from_secs(settings.timeout_seconds),
+            max_retries: settings.max_retries,
+            user_agent: settings.user_agent.clone(),
+            default_slippage_bps: settings.default_slippage_bps,
+            max_slippage_bps: settings.max_slippage_bps,
+            debug_logging: settings.debug,
+            surfpool_rpc_url: settings.surfpool_rpc_url.clone(),
         }
     }
+}
+
+impl JupiterConfig {
+    /// Configuration from the `[jupiter]` section of the reev configuration
+    pub fn from_config() -> Self {
+        Self::from(&reev_config::get().jupiter)
+    }
 
     /// Validate configuration values
     pub fn validate(&self) -> Result<()> {
@@ -209,7 +197,7 @@ pub fn init_jupiter_config(config: JupiterConfig) {
 
 /// Get global Jupiter configuration
 pub fn get_jupiter_config() -> &'static JupiterConfig {
-    JUPITER_CONFIG.get_or_init(JupiterConfig::from_env)
+    JUPITER_CONFIG.get_or_init(JupiterConfig::from_config)
 }
 
 /// Execute HTTP request with retry logic
@@ -298,9 +286,7 @@ pub async fn query_token_balance(token_account: &str) -> Result<u64> {
         .and_then(|v| v.get("amount"))
         .and_then(|v| v.as_str())
         .and_then(|s| s.parse::<u64>().ok())
-        .ok_or_else(|| {
-            anyhow::anyhow!("Failed to parse token balance from response: {result}")
-        })?;
+        .ok_or_else(|| anyhow::anyhow!("Failed to parse token balance from response: {result}"))?;
 
     tracing::debug!(
         "[Jupiter] Queried token balance for {}: {}",
@@ -0,0 +1,252 @@
+//! `compare`: diff two sets of results
+//!
+//! Each side is an agent type, whose latest results are read from the database, an
+//! evaluation run id, whose results are read the same way, or a JSON file written by
+//! `export`.
+
+use crate::cli::{
+    DbArgs,
+    export::{ResultExport, ResultRecord},
+    format_score, format_table,
+};
+use anyhow::Result;
+use clap::Args;
+use std::{collections::BTreeMap, path::Path};
+
+#[derive(Args, Debug, Clone)]
+pub struct CompareArgs {
+    /// Baseline: an agent type, an evaluation run id or an exported results .json file
+    pub baseline: String,
+
+    /// Candidate: an agent type, an evaluation run id or an exported results .json file
+    pub candidate: String,
+
+    #[command(flatten)]
+    pub db: DbArgs,
+}
+
+/// Score change of one benchmark present on both sides
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkDelta {
+    pub benchmark_id: String,
+    pub baseline_score: f64,
+    pub candidate_score: f64,
+    pub baseline_passed: bool,
+    pub candidate_passed: bool,
+}
+
+impl BenchmarkDelta {
+    pub fn delta(&self) -> f64 {
+        self.candidate_score - self.baseline_score
+    }
+
+    /// Passed in the baseline but fails in the candidate
+    pub fn newly_failing(&self) -> bool {
+        self.baseline_passed && !self.candidate_passed
+    }
+}
+
+/// Result of comparing a candidate against a baseline
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Comparison {
+    /// Benchmarks present on both sides, sorted by id
+    pub deltas: Vec<BenchmarkDelta>,
+    /// Benchmarks only in the baseline
+    pub missing: Vec<String>,
+    /// Benchmarks only in the candidate
+    pub added: Vec<String>,
+}
+
+impl Comparison {
+    pub fn newly_failing(&self) -> Vec<&BenchmarkDelta> {
+        self.deltas.iter().filter(|d| d.newly_failing()).collect()
+    }
+
+    pub fn baseline_mean(&self) -> f64 {
+        mean(self.deltas.iter().map(|d| d.baseline_score))
+    }
+
+    pub fn candidate_mean(&self) -> f64 {
+        mean(self.deltas.iter().map(|d| d.candidate_score))
+    }
+
+    /// Change of the mean score over the benchmarks present on both sides
+    pub fn mean_delta(&self) -> f64 {
+        self.candidate_mean() - self.baseline_mean()
+    }
+
+    /// Every way the candidate exceeds `thresholds`; empty when the gate passes
+    pub fn violations(&self, thresholds: &Thresholds) -> Vec<String> {
+        let mut violations = Vec::new();
+
+        for delta in &self.deltas {
+            let drop = -delta.delta();
+            if drop > thresholds.max_benchmark_drop + DROP_EPSILON {
+                violations.push(format!(
+                    "{} dropped {:.1} points (limit {:.1})",
+                    delta.benchmark_id,
+                    drop * 100.0,
+                    thresholds.max_benchmark_drop * 100.0
+                ));
+            }
+            if delta.newly_failing() && !thresholds.allow_new_failures {
+                violations.push(format!("{} is newly failing", delta.benchmark_id));
+            }
+        }
+
+        let mean_drop = -self.mean_delta();
+        if mean_drop > thresholds.max_mean_drop + DROP_EPSILON {
+            violations.push(format!(
+                "Mean score dropped {:.1} points (limit {:.1})",
+                mean_drop * 100.0,
+                thresholds.max_mean_drop * 100.0
+            ));
+        }
+
+        if !self.missing.is_empty() && !thresholds.allow_missing {
+            violations.push(format!(
+                "Missing from candidate: {}",
+                self.missing.join(", ")
+            ));
+        }
+
+        violations
+    }
+}
+
+/// Limits a candidate must stay within to pass a regression gate
+#[derive(Debug, Clone, PartialEq)]
+pub struct Thresholds {
+    /// Largest allowed score drop of any single benchmark (0-1)
+    pub max_benchmark_drop: f64,
+    /// Largest allowed drop of the mean score (0-1)
+    pub max_mean_drop: f64,
+    /// Whether benchmarks that passed in the baseline may fail in the candidate
+    pub allow_new_failures: bool,
+    /// Whether baseline benchmarks may be missing from the candidate
+    pub allow_missing: bool,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            max_benchmark_drop: 0.1,
+            max_mean_drop: 0.02,
+            allow_new_failures: false,
+            allow_missing: false,
+        }
+    }
+}
+
+/// Tolerance for floating point noise when comparing drops against thresholds
+const DROP_EPSILON: f64 = 1e-9;
+
+fn mean(scores: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = scores.fold((0.0, 0usize), |(sum, count), s| (sum + s, count + 1));
+    if count == 0 { 0.0 } else { sum / count as f64 }
+}
+
+/// Compare results by benchmark id
+///
+/// Each side should hold at most one record per benchmark; if it holds more, the
+/// last one wins.
+pub fn compare_results(baseline: &[ResultRecord], candidate: &[ResultRecord]) -> Comparison {
+    let by_id = |records: &[ResultRecord]| -> BTreeMap<String, ResultRecord> {
+        records
+            .iter()
+            .map(|r| (r.benchmark_id.clone(), r.clone()))
+            .collect()
+    };
+    let baseline = by_id(baseline);
+    let candidate = by_id(candidate);
+
+    let mut comparison = Comparison::default();
+    for (id, base) in &baseline {
+        match candidate.get(id) {
+            Some(cand) => comparison.deltas.push(BenchmarkDelta {
+                benchmark_id: id.clone(),
+                baseline_score: base.score,
+                candidate_score: cand.score,
+                baseline_passed: base.passed(),
+                candidate_passed: cand.passed(),
+            }),
+            None => comparison.missing.push(id.clone()),
+        }
+    }
+    comparison.added = candidate
+        .keys()
+        .filter(|id| !baseline.contains_key(*id))
+        .cloned()
+        .collect();
+    comparison
+}
+
+/// Load one side of a comparison
+pub async fn load_side(side: &str, db: &DbArgs) -> Result<Vec<ResultRecord>> {
+    let path = Path::new(side);
+    if path.extension().and_then(|e| e.to_str()) == Some("json") {
+        return Ok(ResultExport::load(path)?.records);
+    }
+
+    let reader = db.open_reader().await?;
+    let performances = if reader.get_run(side).await?.is_some() {
+        reader.get_latest_run_results(side).await?
+    } else {
+        reader.get_latest_results(Some(side)).await?
+    };
+    let records: Vec<ResultRecord> = performances.into_iter().map(Into::into).collect();
+    if records.is_empty() {
+        anyhow::bail!("No stored results for agent or run '{side}'");
+    }
+    Ok(records)
+}
+
+pub async fn execute(args: CompareArgs) -> Result<()> {
+    let baseline = load_side(&args.baseline, &args.db).await?;
+    let candidate = load_side(&args.candidate, &args.db).await?;
+    let comparison = compare_results(&baseline, &candidate);
+    print_comparison(&comparison, &args.baseline, &args.candidate);
+    Ok(())
+}
+
+/// Print the per-benchmark deltas and the aggregate change
+pub fn print_comparison(comparison: &Comparison, baseline: &str, candidate: &str) {
+    let rows: Vec<Vec<String>> = comparison
+        .deltas
+        .iter()
+        .map(|d| {
+            vec![
+                d.benchmark_id.clone(),
+                format_score(d.baseline_score),
+                format_score(d.candidate_score),
+                format!("{:+.1}", d.delta() * 100.0),
+                if d.newly_failing() {
+                    "newly failing".to_string()
+                } else {
+                    String::new()
+                },
+            ]
+        })
+        .collect();
+    println!(
+        "{}",
+        format_table(&["BENCHMARK", baseline, candidate, "DELTA", ""], &rows)
+    );
+
+    println!(
+        "\nMean score: {} -> {} ({:+.1})",
+        format_score(comparison.baseline_mean()),
+        format_score(comparison.candidate_mean()),
+        comparison.mean_delta() * 100.0
+    );
+    let newly_failing = comparison.newly_failing();
+    if !newly_failing.is_empty() {
+        println!("Newly failing: {}", newly_failing.len());
+    }
+    if !comparison.missing.is_empty() {
+        println!("Only in baseline: {}", comparison.missing.join(", "));
+    }
+    if !comparison.added.is_empty() {
+        println!("Only in candidate: {}", comparison.added.join(", "));
+    }
+}
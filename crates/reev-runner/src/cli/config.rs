@@ -0,0 +1,69 @@
+//! `config`: inspect the resolved configuration
+//!
+//! The runner, reev-agent and the API server share one configuration resolved from
+//! `reev.toml`, the profile selected with `--profile` (or `REEV_PROFILE`) and
+//! environment overrides. `config show` prints the values in effect, with API keys
+//! masked, so a profile can be checked before running anything with it.
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use reev_config::ReevConfig;
+use std::path::Path;
+
+#[derive(Args, Debug, Clone)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ConfigCommand {
+    /// Print the resolved configuration with API keys masked
+    Show,
+}
+
+/// Render `config` as TOML, preceded by comments naming where it came from
+pub fn format_config(
+    config: &ReevConfig,
+    path: &Path,
+    profile: Option<&str>,
+    available: &[String],
+) -> String {
+    let file = if path.exists() {
+        path.display().to_string()
+    } else {
+        format!("{} (not found, using defaults)", path.display())
+    };
+    let available = if available.is_empty() {
+        "none".to_string()
+    } else {
+        available.join(", ")
+    };
+    format!(
+        "# Configuration file: {file}\n# Profile: {} (available: {available})\n\n{}",
+        profile.unwrap_or("-"),
+        config.to_toml_masked()
+    )
+}
+
+pub fn execute(args: ConfigArgs) -> Result<()> {
+    match args.command {
+        ConfigCommand::Show => {
+            let path = reev_config::config_path();
+            let available = match std::fs::read_to_string(&path) {
+                Ok(source) => ReevConfig::profiles(&source)?,
+                Err(_) => Vec::new(),
+            };
+            print!(
+                "{}",
+                format_config(
+                    reev_config::get(),
+                    &path,
+                    reev_config::selected_profile().as_deref(),
+                    &available
+                )
+            );
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! `export`: write stored results as JSON
+//!
+//! The exported file is also accepted by `compare`, so results from another
+//! machine or an older database can be compared against the current ones.
+
+use crate::cli::DbArgs;
+use anyhow::{Context, Result};
+use clap::Args;
+use reev_db::types::AgentPerformance;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path, path::PathBuf};
+
+#[derive(Args, Debug, Clone)]
+pub struct ExportArgs {
+    /// Only export results of this agent
+    #[arg(long)]
+    pub agent: Option<String>,
+
+    /// Only export results recorded under this evaluation run
+    #[arg(long)]
+    pub run: Option<String>,
+
+    /// Export every stored record instead of the latest one per benchmark and agent
+    #[arg(long)]
+    pub all: bool,
+
+    /// Write to this file instead of stdout
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub db: DbArgs,
+}
+
+/// One stored benchmark result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResultRecord {
+    pub benchmark_id: String,
+    pub agent_type: String,
+    pub session_id: String,
+    pub score: f64,
+    pub final_status: String,
+    pub timestamp: String,
+    pub execution_time_ms: Option<i64>,
+    #[serde(default)]
+    pub trial_index: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+    /// Whether the score was recomputed from a stored trace by `rescore`
+    #[serde(default)]
+    pub rescored: bool,
+    /// Tool calls whose arguments were rejected and retried by the model
+    #[serde(default)]
+    pub argument_retries: u32,
+}
+
+impl ResultRecord {
+    pub fn passed(&self) -> bool {
+        self.final_status.eq_ignore_ascii_case("succeeded")
+    }
+}
+
+impl From<AgentPerformance> for ResultRecord {
+    fn from(performance: AgentPerformance) -> Self {
+        Self {
+            benchmark_id: performance.benchmark_id,
+            agent_type: performance.agent_type,
+            session_id: performance.session_id,
+            score: performance.score,
+            final_status: performance.final_status,
+            timestamp: performance.timestamp,
+            execution_time_ms: performance.execution_time_ms,
+            trial_index: performance.trial_index,
+            run_id: performance.run_id,
+            rescored: performance.rescored,
+            argument_retries: performance
+                .additional_metrics
+                .get("argument_retries")
+                .map_or(0, |retries| *retries as u32),
+        }
+    }
+}
+
+/// The exported results file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultExport {
+    pub exported_at: String,
+    pub records: Vec<ResultRecord>,
+}
+
+impl ResultExport {
+    pub fn new(records: Vec<ResultRecord>) -> Self {
+        Self {
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            records,
+        }
+    }
+
+    /// Read a file previously written by `export`
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read results file '{}'", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse results file '{}'", path.display()))
+    }
+}
+
+pub async fn execute(args: ExportArgs) -> Result<()> {
+    let reader = args.db.open_reader().await?;
+    let performances = if args.all {
+        let filter = reev_db::QueryFilter {
+            agent_type: args.agent.clone(),
+            run_id: args.run.clone(),
+            ..Default::default()
+        };
+        reader.get_agent_performance(Some(filter)).await?
+    } else if let Some(run_id) = &args.run {
+        let mut latest = reader.get_latest_run_results(run_id).await?;
+        if let Some(agent) = &args.agent {
+            latest.retain(|p| &p.agent_type == agent);
+        }
+        latest
+    } else {
+        reader.get_latest_results(args.agent.as_deref()).await?
+    };
+
+    let export = ResultExport::new(performances.into_iter().map(Into::into).collect());
+    let json = serde_json::to_string_pretty(&export)?;
+
+    match &args.output {
+        Some(path) => {
+            fs::write(path, json)
+                .with_context(|| format!("Failed to write '{}'", path.display()))?;
+            println!(
+                "Exported {} result(s) to {}",
+                export.records.len(),
+                path.display()
+            );
+        }
+        None => println!("{json}"),
+    }
+    Ok(())
+}
@@ -0,0 +1,75 @@
+//! `gate`: fail when a candidate regresses against a baseline
+//!
+//! Both sides are loaded like `compare` does: an agent type reads that agent's latest
+//! `agent_performance` results, an evaluation run id the results recorded under that
+//! run, and a `.json` path reads a file written by `export`. The
+//! command prints the comparison and exits non-zero if any threshold is exceeded, so it
+//! can guard a CI job directly.
+
+use crate::cli::{
+    DbArgs,
+    compare::{Thresholds, compare_results, load_side, print_comparison},
+};
+use anyhow::Result;
+use clap::Args;
+
+#[derive(Args, Debug, Clone)]
+pub struct GateArgs {
+    /// Baseline: an agent type, an evaluation run id or an exported results .json file
+    pub baseline: String,
+
+    /// Candidate: an agent type, an evaluation run id or an exported results .json file
+    pub candidate: String,
+
+    /// Largest allowed score drop of any single benchmark (0-1)
+    #[arg(long, default_value_t = Thresholds::default().max_benchmark_drop)]
+    pub max_benchmark_drop: f64,
+
+    /// Largest allowed drop of the mean score (0-1)
+    #[arg(long, default_value_t = Thresholds::default().max_mean_drop)]
+    pub max_mean_drop: f64,
+
+    /// Do not fail when a benchmark that passed in the baseline fails in the candidate
+    #[arg(long)]
+    pub allow_new_failures: bool,
+
+    /// Do not fail when baseline benchmarks are missing from the candidate
+    #[arg(long)]
+    pub allow_missing: bool,
+
+    #[command(flatten)]
+    pub db: DbArgs,
+}
+
+impl GateArgs {
+    pub fn thresholds(&self) -> Thresholds {
+        Thresholds {
+            max_benchmark_drop: self.max_benchmark_drop,
+            max_mean_drop: self.max_mean_drop,
+            allow_new_failures: self.allow_new_failures,
+            allow_missing: self.allow_missing,
+        }
+    }
+}
+
+pub async fn execute(args: GateArgs) -> Result<()> {
+    let baseline = load_side(&args.baseline, &args.db).await?;
+    let candidate = load_side(&args.candidate, &args.db).await?;
+    let comparison = compare_results(&baseline, &candidate);
+    print_comparison(&comparison, &args.baseline, &args.candidate);
+
+    let violations = comparison.violations(&args.thresholds());
+    if violations.is_empty() {
+        println!("\n✅ No regression against {}", args.baseline);
+        return Ok(());
+    }
+
+    println!("\n❌ Regression against {}:", args.baseline);
+    for violation in &violations {
+        println!("   - {violation}");
+    }
+    anyhow::bail!(
+        "Regression gate failed with {} violation(s)",
+        violations.len()
+    )
+}
@@ -0,0 +1,68 @@
+//! `list`: show the available benchmarks
+
+use crate::{
+    cli::{DEFAULT_BENCHMARKS_DIR, format_table},
+    selection::{BenchmarkSelection, load_suites, suites_dir},
+};
+use anyhow::Result;
+use clap::Args;
+use std::path::PathBuf;
+
+#[derive(Args, Debug, Clone)]
+pub struct ListArgs {
+    /// Benchmark file or directory
+    #[arg(default_value = DEFAULT_BENCHMARKS_DIR)]
+    pub path: PathBuf,
+
+    #[command(flatten)]
+    pub selection: BenchmarkSelection,
+
+    /// List the suites defined for the benchmarks instead
+    #[arg(long, conflicts_with = "suite")]
+    pub suites: bool,
+}
+
+pub fn execute(args: ListArgs) -> Result<()> {
+    if args.suites {
+        return list_suites(&args);
+    }
+
+    let benchmarks = args.selection.resolve(&args.path)?;
+
+    let rows: Vec<Vec<String>> = benchmarks
+        .iter()
+        .map(|benchmark| {
+            let test_case = &benchmark.test_case;
+            let kind = match &test_case.flow {
+                Some(steps) => format!("flow ({} steps)", steps.len()),
+                None => "single".to_string(),
+            };
+            vec![
+                test_case.id.clone(),
+                kind,
+                test_case.tags.join(","),
+                test_case.description.clone(),
+            ]
+        })
+        .collect();
+
+    println!(
+        "{}",
+        format_table(&["ID", "TYPE", "TAGS", "DESCRIPTION"], &rows)
+    );
+    println!("\n{} benchmark(s)", rows.len());
+    Ok(())
+}
+
+fn list_suites(args: &ListArgs) -> Result<()> {
+    let mut rows = Vec::new();
+    for suite in load_suites(&suites_dir(&args.path))? {
+        let count = suite.selection.resolve(&args.path)?.len();
+        rows.push(vec![suite.name, count.to_string(), suite.description]);
+    }
+    println!(
+        "{}",
+        format_table(&["SUITE", "BENCHMARKS", "DESCRIPTION"], &rows)
+    );
+    Ok(())
+}
@@ -0,0 +1,207 @@
+//! Command-line interface for reev-runner
+//!
+//! The runner is organized as subcommands that all build on the same library
+//! pieces: benchmark files are discovered through [`crate::selection`], benchmarks
+//! are executed with [`crate::run_benchmarks`]-style functions and stored results
+//! are read back through [`reev_db::DatabaseReader`].
+//!
+//! - [`run`]: Run benchmarks, optionally filtered
+//! - [`list`]: List benchmarks and their tags
+//! - [`validate`]: Check benchmark files for mistakes
+//! - [`report`]: Show the latest results per agent
+//! - [`compare`]: Diff the latest results of two agents or exported result files
+//! - [`gate`]: Fail when a candidate regresses against a baseline
+//! - [`runs`]: List evaluation runs or show one run's provenance and sessions
+//! - [`replay`]: Re-score a stored session trace against the current benchmark file
+//! - [`rescore`]: Store new scores for stored traces against the current benchmark files
+//! - [`export`]: Export stored results as JSON
+//! - [`config`]: Print the resolved configuration
+//!
+//! Running without a subcommand (`reev-runner <PATH> --agent <AGENT>`) is the same
+//! as `reev-runner run <PATH> --agent <AGENT>`. Log output goes to stderr so that
+//! command output on stdout can be piped.
+//!
+//! Every subcommand reads the configuration of `reev.toml`; `--profile <NAME>`
+//! selects one of its profiles for the runner and the services it starts.
+
+use anyhow::{Context, Result};
+use clap::{Args, CommandFactory, Parser, Subcommand};
+use reev_db::{DatabaseConfig, DatabaseReader, DatabaseWriter};
+use std::path::{Path, PathBuf};
+
+pub mod compare;
+pub mod config;
+pub mod export;
+pub mod gate;
+pub mod list;
+pub mod replay;
+pub mod report;
+pub mod rescore;
+pub mod run;
+pub mod runs;
+pub mod validate;
+
+/// Default directory holding benchmark files
+pub const DEFAULT_BENCHMARKS_DIR: &str = "benchmarks";
+
+/// A command-line runner for the Reev evaluation framework.
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Configuration profile of reev.toml to use [env: REEV_PROFILE]
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+
+    /// Arguments for running benchmarks when no subcommand is given
+    #[command(flatten)]
+    pub run: run::RunArgs,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Run benchmarks
+    Run(run::RunArgs),
+    /// List benchmarks and their tags
+    List(list::ListArgs),
+    /// Check benchmark files for mistakes
+    Validate(validate::ValidateArgs),
+    /// Show the latest results per agent
+    Report(report::ReportArgs),
+    /// Compare the latest results of two agents or exported result files
+    Compare(compare::CompareArgs),
+    /// Fail when a candidate regresses against a baseline
+    Gate(gate::GateArgs),
+    /// List evaluation runs or show one run's provenance and sessions
+    Runs(runs::RunsArgs),
+    /// Re-score a stored session trace against the current benchmark file
+    Replay(replay::ReplayArgs),
+    /// Store new scores for stored traces against the current benchmark files
+    Rescore(rescore::RescoreArgs),
+    /// Export stored results as JSON
+    Export(export::ExportArgs),
+    /// Inspect the resolved configuration
+    Config(config::ConfigArgs),
+}
+
+/// Location of the results database
+#[derive(Args, Debug, Clone)]
+pub struct DbArgs {
+    /// Path to the results database [default: `database.path` of the configuration]
+    #[arg(long)]
+    pub db: Option<PathBuf>,
+}
+
+impl DbArgs {
+    /// The results database given with `--db`, or the configured one
+    pub fn path(&self) -> PathBuf {
+        self.db.clone().unwrap_or_else(default_db_path)
+    }
+
+    /// Open a read-only view of the results database
+    pub async fn open_reader(&self) -> Result<DatabaseReader> {
+        open_reader(&self.path()).await
+    }
+
+    /// Open the existing results database for writing
+    pub async fn open_writer(&self) -> Result<DatabaseWriter> {
+        let path = self.path();
+        if !path.exists() {
+            anyhow::bail!(
+                "Results database not found at '{}'; run some benchmarks first",
+                path.display()
+            );
+        }
+        DatabaseWriter::new(DatabaseConfig::new(path.to_string_lossy()))
+            .await
+            .with_context(|| format!("Failed to open results database '{}'", path.display()))
+    }
+}
+
+/// Location of the results database in the configuration
+pub fn default_db_path() -> PathBuf {
+    PathBuf::from(&reev_config::get().database.path)
+}
+
+/// Open a reader on an existing results database
+pub async fn open_reader(path: &Path) -> Result<DatabaseReader> {
+    if !path.exists() {
+        anyhow::bail!(
+            "Results database not found at '{}'; run some benchmarks first",
+            path.display()
+        );
+    }
+    DatabaseReader::from_config(DatabaseConfig::new(path.to_string_lossy()))
+        .await
+        .with_context(|| format!("Failed to open results database '{}'", path.display()))
+}
+
+impl Cli {
+    /// Execute the selected subcommand
+    pub async fn execute(self) -> Result<()> {
+        if let Some(profile) = &self.profile {
+            // Exported so the services the runner starts use the same profile
+            unsafe {
+                std::env::set_var(reev_config::PROFILE_VAR, profile);
+            }
+        }
+        reev_config::init(reev_config::load()?);
+
+        match self.command {
+            Some(Command::Run(args)) => run::execute(args).await,
+            Some(Command::List(args)) => list::execute(args),
+            Some(Command::Validate(args)) => validate::execute(args),
+            Some(Command::Report(args)) => report::execute(args).await,
+            Some(Command::Compare(args)) => compare::execute(args).await,
+            Some(Command::Gate(args)) => gate::execute(args).await,
+            Some(Command::Runs(args)) => runs::execute(args).await,
+            Some(Command::Replay(args)) => replay::execute(args).await,
+            Some(Command::Rescore(args)) => rescore::execute(args).await,
+            Some(Command::Export(args)) => export::execute(args).await,
+            Some(Command::Config(args)) => config::execute(args),
+            None if self.run.path.is_none() => {
+                Self::command().print_help()?;
+                Ok(())
+            }
+            None => run::execute(self.run).await,
+        }
+    }
+}
+
+/// Format rows as a left-aligned, space-separated table with a header line
+pub fn format_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            if let Some(width) = widths.get_mut(i) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+    }
+
+    let format_row = |cells: Vec<&str>| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let separators: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+    let mut lines = vec![format_row(headers.to_vec())];
+    lines.push(format_row(separators.iter().map(String::as_str).collect()));
+    for row in rows {
+        lines.push(format_row(row.iter().map(String::as_str).collect()));
+    }
+    lines.join("\n")
+}
+
+/// Format a 0-1 score as a percentage
+pub fn format_score(score: f64) -> String {
+    format!("{:.1}%", score * 100.0)
+}
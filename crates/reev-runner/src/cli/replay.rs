@@ -0,0 +1,129 @@
+//! `replay`: re-score a stored session against the current benchmark file
+//!
+//! The stored execution trace is rendered and scored again with the benchmark's
+//! current ground truth, without starting an agent or surfpool. Nothing is
+//! written back to the database; `rescore` stores new scores for many sessions.
+
+use crate::{
+    cli::{DEFAULT_BENCHMARKS_DIR, DbArgs, format_score},
+    renderer, scored_outcome,
+    selection::load_benchmarks,
+};
+use anyhow::{Context, Result};
+use clap::Args;
+use reev_lib::{
+    agent::AgentObservation, benchmark::TestCase, efficiency::EfficiencyMetrics,
+    results::TestResult, score::calculate_final_score_with_metrics, trace::ExecutionTrace,
+};
+use std::path::PathBuf;
+
+#[derive(Args, Debug, Clone)]
+pub struct ReplayArgs {
+    /// Session to replay
+    pub session_id: String,
+
+    /// Benchmark file or directory to look the session's benchmark up in
+    #[arg(long, default_value = DEFAULT_BENCHMARKS_DIR)]
+    pub benchmarks: PathBuf,
+
+    #[command(flatten)]
+    pub db: DbArgs,
+}
+
+/// Score a stored trace against `test_case`
+///
+/// Traces recorded before the initial observation was stored fall back to the
+/// first step's observation, which still carries the placeholder key map but
+/// cannot show changes made by that step (e.g. an advanced nonce). The stored
+/// fault recovery is blended in as the runner does for benchmarks with `faults`.
+pub fn rescore_trace(test_case: &TestCase, trace: &ExecutionTrace) -> Result<f64> {
+    let final_observation = trace
+        .steps
+        .last()
+        .map(|step| &step.observation)
+        .context("Trace has no steps")?;
+    let initial_observation: &AgentObservation = trace
+        .initial_observation
+        .as_deref()
+        .unwrap_or(&trace.steps[0].observation);
+
+    // Flows are scored on every step's actions, single benchmarks on the last attempt
+    let actions: Vec<_> = if test_case.flow.is_some() {
+        trace
+            .steps
+            .iter()
+            .flat_map(|step| step.action.clone())
+            .collect()
+    } else {
+        trace
+            .steps
+            .last()
+            .map(|s| s.action.clone())
+            .unwrap_or_default()
+    };
+
+    let metrics = EfficiencyMetrics::from_trace(trace);
+    let score = calculate_final_score_with_metrics(
+        test_case,
+        &actions,
+        initial_observation,
+        final_observation,
+        &metrics,
+    );
+    Ok(match (&test_case.faults, &trace.recovery) {
+        (Some(faults), Some(recovery)) => faults.blend_recovery(score, recovery),
+        _ => score,
+    })
+}
+
+pub async fn execute(args: ReplayArgs) -> Result<()> {
+    let reader = args.db.open_reader().await?;
+    let session = reader
+        .get_session(&args.session_id)
+        .await?
+        .with_context(|| format!("Session '{}' not found", args.session_id))?;
+    let log = reader
+        .get_session_log(&args.session_id)
+        .await?
+        .with_context(|| format!("Session '{}' has no stored trace", args.session_id))?;
+    let trace: ExecutionTrace =
+        serde_json::from_str(&log).context("Stored session log is not an execution trace")?;
+
+    let benchmark = load_benchmarks(&args.benchmarks)?
+        .into_iter()
+        .find(|b| b.test_case.id == session.benchmark_id)
+        .with_context(|| {
+            format!(
+                "Benchmark '{}' not found in '{}'",
+                session.benchmark_id,
+                args.benchmarks.display()
+            )
+        })?;
+    let test_case = benchmark.test_case;
+
+    let score = rescore_trace(&test_case, &trace)?;
+    let budget_exceeded = reader.get_budget_exceeded(&args.session_id).await?;
+    let (score, final_status) = scored_outcome(&test_case, score, budget_exceeded.is_some());
+
+    if trace.initial_observation.is_none() {
+        println!("⚠️  Trace has no initial observation; using the first step's observation");
+    }
+    if let Some(limit) = &budget_exceeded {
+        println!("⚠️  Agent stopped at its {limit} budget; the score is capped");
+    }
+    let result = TestResult::new(&test_case, final_status, score, trace);
+    println!("{}", renderer::render_result_as_tree(&result));
+    println!(
+        "Session {} ({}, agent {})",
+        session.session_id, session.benchmark_id, session.agent_type
+    );
+    println!(
+        "Stored score: {}",
+        session
+            .score
+            .map(format_score)
+            .unwrap_or_else(|| "-".to_string())
+    );
+    println!("Re-scored:    {}", format_score(score));
+    Ok(())
+}
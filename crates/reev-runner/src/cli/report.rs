@@ -0,0 +1,249 @@
+//! `report`: show the latest stored result of every benchmark
+//!
+//! `RETRIES` counts the tool calls of a result whose arguments were rejected and
+//! retried by the model.
+//!
+//! With `--trials`, shows statistics over the latest batch of repeated trials
+//! (`run --trials N`) instead, and with `--matrix` a benchmark × agent table of
+//! their mean scores.
+
+use crate::cli::{DbArgs, export::ResultRecord, format_score, format_table};
+use anyhow::Result;
+use clap::Args;
+use reev_db::{
+    QueryFilter, ScoreMatrix, TrialStats,
+    matrix::MatrixCell,
+    trials::{CONFIDENCE, latest_trial_stats},
+};
+use std::collections::BTreeMap;
+
+#[derive(Args, Debug, Clone)]
+pub struct ReportArgs {
+    /// Only report results of this agent
+    #[arg(long)]
+    pub agent: Option<String>,
+
+    /// Show mean, std-dev, pass@1, pass@k and confidence intervals over the latest trials
+    #[arg(long)]
+    pub trials: bool,
+
+    /// The k of pass@k; defaults to the number of trials
+    #[arg(long, requires = "trials")]
+    pub k: Option<usize>,
+
+    /// Show a benchmark × agent table of mean scores over the latest trials
+    #[arg(long, conflicts_with = "trials")]
+    pub matrix: bool,
+
+    #[command(flatten)]
+    pub db: DbArgs,
+}
+
+/// Aggregate results of one agent
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgentSummary {
+    pub agent_type: String,
+    pub benchmarks: usize,
+    pub passed: usize,
+    pub mean_score: f64,
+}
+
+/// Summarize records per agent, sorted by agent name
+pub fn summarize(records: &[ResultRecord]) -> Vec<AgentSummary> {
+    let mut by_agent: BTreeMap<&str, Vec<&ResultRecord>> = BTreeMap::new();
+    for record in records {
+        by_agent.entry(&record.agent_type).or_default().push(record);
+    }
+
+    by_agent
+        .into_iter()
+        .map(|(agent_type, records)| AgentSummary {
+            agent_type: agent_type.to_string(),
+            benchmarks: records.len(),
+            passed: records.iter().filter(|r| r.passed()).count(),
+            mean_score: records.iter().map(|r| r.score).sum::<f64>() / records.len() as f64,
+        })
+        .collect()
+}
+
+/// Render trial statistics as a table
+pub fn format_trial_stats(stats: &[TrialStats]) -> String {
+    let rows: Vec<Vec<String>> = stats
+        .iter()
+        .map(|s| {
+            vec![
+                s.agent_type.clone(),
+                s.benchmark_id.clone(),
+                format!("{}/{}", s.passed, s.trials),
+                format_score(s.mean_score),
+                format!("{:.1}", s.std_dev * 100.0),
+                format_score(s.pass_at_1),
+                format_score(s.pass_at_k),
+                format!("{} - {}", format_score(s.ci_low), format_score(s.ci_high)),
+            ]
+        })
+        .collect();
+    let k_header = match stats.first() {
+        Some(first) if stats.iter().all(|s| s.k == first.k) => format!("PASS@{}", first.k),
+        _ => "PASS@K".to_string(),
+    };
+    let ci_header = format!("{:.0}% CI", CONFIDENCE * 100.0);
+    format_table(
+        &[
+            "AGENT",
+            "BENCHMARK",
+            "PASSED",
+            "MEAN",
+            "STD",
+            "PASS@1",
+            &k_header,
+            &ci_header,
+        ],
+        &rows,
+    )
+}
+
+/// Render a score matrix with a column per agent and a mean and pass count per agent
+///
+/// A cell shows the mean score and how many trials passed; `-` marks a benchmark
+/// the agent has no result for.
+pub fn format_matrix(matrix: &ScoreMatrix) -> String {
+    let mut rows: Vec<Vec<String>> = matrix
+        .rows
+        .iter()
+        .map(|row| {
+            std::iter::once(row.benchmark_id.clone())
+                .chain(row.cells.iter().map(|cell| match cell {
+                    Some(cell) => format_cell(cell),
+                    None => "-".to_string(),
+                }))
+                .collect()
+        })
+        .collect();
+    rows.push(
+        std::iter::once("MEAN".to_string())
+            .chain(
+                matrix
+                    .agent_means()
+                    .into_iter()
+                    .map(|mean| mean.map_or("-".to_string(), format_score)),
+            )
+            .collect(),
+    );
+    rows.push(
+        std::iter::once("PASSED".to_string())
+            .chain(
+                matrix
+                    .agent_passes()
+                    .into_iter()
+                    .zip(matrix.agent_means())
+                    .map(|(passed, mean)| match mean {
+                        Some(_) => format!("{passed}/{}", matrix.rows.len()),
+                        None => "-".to_string(),
+                    }),
+            )
+            .collect(),
+    );
+
+    let headers: Vec<&str> = std::iter::once("BENCHMARK")
+        .chain(matrix.agents.iter().map(String::as_str))
+        .collect();
+    format_table(&headers, &rows)
+}
+
+fn format_cell(cell: &MatrixCell) -> String {
+    format!(
+        "{} ({}/{})",
+        format_score(cell.mean_score),
+        cell.passed,
+        cell.trials
+    )
+}
+
+pub async fn execute(args: ReportArgs) -> Result<()> {
+    let reader = args.db.open_reader().await?;
+
+    if args.matrix {
+        let filter = QueryFilter {
+            agent_type: args.agent.clone(),
+            ..Default::default()
+        };
+        let records = reader.get_agent_performance(Some(filter)).await?;
+        if records.is_empty() {
+            println!("No results found");
+        } else {
+            let stats = latest_trial_stats(records.iter().map(Into::into), None);
+            let mut agents: Vec<String> = stats.iter().map(|s| s.agent_type.clone()).collect();
+            agents.dedup();
+            println!(
+                "{}",
+                format_matrix(&ScoreMatrix::from_stats(&agents, &stats))
+            );
+        }
+        return Ok(());
+    }
+
+    if args.trials {
+        let filter = QueryFilter {
+            agent_type: args.agent.clone(),
+            ..Default::default()
+        };
+        let records = reader.get_agent_performance(Some(filter)).await?;
+        if records.is_empty() {
+            println!("No results found");
+        } else {
+            let stats = latest_trial_stats(records.iter().map(Into::into), args.k);
+            println!("{}", format_trial_stats(&stats));
+        }
+        return Ok(());
+    }
+    let records: Vec<ResultRecord> = reader
+        .get_latest_results(args.agent.as_deref())
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    if records.is_empty() {
+        println!("No results found");
+        return Ok(());
+    }
+
+    let rows: Vec<Vec<String>> = records
+        .iter()
+        .map(|r| {
+            vec![
+                r.agent_type.clone(),
+                r.benchmark_id.clone(),
+                format_score(r.score),
+                r.final_status.clone(),
+                r.argument_retries.to_string(),
+                r.timestamp.clone(),
+            ]
+        })
+        .collect();
+    println!(
+        "{}",
+        format_table(
+            &["AGENT", "BENCHMARK", "SCORE", "STATUS", "RETRIES", "WHEN"],
+            &rows
+        )
+    );
+
+    let summary_rows: Vec<Vec<String>> = summarize(&records)
+        .into_iter()
+        .map(|s| {
+            vec![
+                s.agent_type,
+                s.benchmarks.to_string(),
+                s.passed.to_string(),
+                format_score(s.mean_score),
+            ]
+        })
+        .collect();
+    println!(
+        "\n{}",
+        format_table(&["AGENT", "BENCHMARKS", "PASSED", "MEAN"], &summary_rows)
+    );
+    Ok(())
+}
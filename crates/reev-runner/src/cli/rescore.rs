@@ -0,0 +1,263 @@
+//! `rescore`: score stored traces again against the current benchmark files
+//!
+//! When a benchmark's expected instructions, assertions or weights are fixed, the
+//! stored execution traces are scored again instead of re-running every agent. No
+//! agent or surfpool is started. The new scores are stored as results marked
+//! `rescored`, under a new evaluation run per agent, so `compare` can diff them
+//! against the results they came from.
+
+use crate::{
+    cli::{DEFAULT_BENCHMARKS_DIR, DbArgs, format_score, format_table, replay},
+    evaluation::{RUN_COMPLETED, new_evaluation_run},
+    scored_outcome,
+    selection::{BenchmarkFile, load_benchmarks},
+};
+use anyhow::{Context, Result};
+use clap::Args;
+use reev_db::{DatabaseReader, DatabaseWriter, QueryFilter, types::AgentPerformance};
+use reev_lib::{results::FinalStatus, trace::ExecutionTrace};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
+
+#[derive(Args, Debug, Clone)]
+pub struct RescoreArgs {
+    /// Re-score every attempt of this evaluation run instead of the latest result
+    /// per benchmark and agent
+    #[arg(long)]
+    pub run: Option<String>,
+
+    /// Only re-score results of this agent
+    #[arg(long)]
+    pub agent: Option<String>,
+
+    /// Benchmark file or directory holding the current benchmarks
+    #[arg(long, default_value = DEFAULT_BENCHMARKS_DIR)]
+    pub benchmarks: PathBuf,
+
+    /// Show the new scores without storing them
+    #[arg(long)]
+    pub dry_run: bool,
+
+    #[command(flatten)]
+    pub db: DbArgs,
+}
+
+/// A stored result scored again against the current benchmark file
+#[derive(Debug, Clone)]
+pub struct Rescored {
+    /// The stored result whose trace was scored
+    pub original: AgentPerformance,
+    /// The benchmark file scored against
+    pub path: PathBuf,
+    pub score: f64,
+    pub final_status: FinalStatus,
+}
+
+impl Rescored {
+    /// The result record storing the new score as attempt `trial_index` of
+    /// evaluation run `run_id`
+    pub fn to_performance(&self, run_id: &str, trial_index: u32) -> reev_db::AgentPerformance {
+        let original = &self.original;
+        reev_db::AgentPerformance {
+            id: None,
+            session_id: original.session_id.clone(),
+            benchmark_id: original.benchmark_id.clone(),
+            agent_type: original.agent_type.clone(),
+            score: self.score,
+            final_status: self.final_status.to_string(),
+            execution_time_ms: original.execution_time_ms,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            flow_log_id: None,
+            prompt_md5: original.prompt_md5.clone(),
+            trial_index,
+            run_id: Some(run_id.to_string()),
+            rescored: true,
+            additional_metrics: HashMap::new(),
+        }
+    }
+}
+
+/// Score the stored trace of each of `records` again against `benchmarks`
+///
+/// Results without a stored trace, or whose benchmark is no longer found, are
+/// returned separately with the reason they were skipped.
+pub async fn rescore_results(
+    reader: &DatabaseReader,
+    benchmarks: &[BenchmarkFile],
+    records: Vec<AgentPerformance>,
+) -> Result<(Vec<Rescored>, Vec<(AgentPerformance, String)>)> {
+    let mut rescored = Vec::new();
+    let mut skipped = Vec::new();
+
+    for record in records {
+        let Some(benchmark) = benchmarks
+            .iter()
+            .find(|b| b.test_case.id == record.benchmark_id)
+        else {
+            skipped.push((record, "benchmark not found".to_string()));
+            continue;
+        };
+        let Some(log) = reader.get_session_log(&record.session_id).await? else {
+            skipped.push((record, "no stored trace".to_string()));
+            continue;
+        };
+        let trace = match serde_json::from_str::<ExecutionTrace>(&log) {
+            Ok(trace) if !trace.steps.is_empty() => trace,
+            Ok(_) => {
+                skipped.push((record, "trace has no steps".to_string()));
+                continue;
+            }
+            Err(_) => {
+                skipped.push((record, "stored log is not an execution trace".to_string()));
+                continue;
+            }
+        };
+
+        let score = replay::rescore_trace(&benchmark.test_case, &trace)?;
+        let budget_exceeded = reader.get_budget_exceeded(&record.session_id).await?;
+        let (score, final_status) =
+            scored_outcome(&benchmark.test_case, score, budget_exceeded.is_some());
+        rescored.push(Rescored {
+            final_status,
+            path: benchmark.path.clone(),
+            score,
+            original: record,
+        });
+    }
+    Ok((rescored, skipped))
+}
+
+/// Store re-scored results under a new completed evaluation run per agent
+///
+/// The runs record the current benchmark hashes and commit. A run re-scored as a
+/// whole keeps its agent settings, seed and trial indexes; otherwise each result
+/// is stored as a single attempt. Returns the ids of the new runs.
+pub async fn record_rescored(
+    db: &DatabaseWriter,
+    rescored: &[Rescored],
+    source_run: Option<&str>,
+) -> Result<Vec<String>> {
+    let mut by_agent: BTreeMap<&str, Vec<&Rescored>> = BTreeMap::new();
+    for result in rescored {
+        by_agent
+            .entry(&result.original.agent_type)
+            .or_default()
+            .push(result);
+    }
+    let source = match source_run {
+        Some(run_id) => db.get_run(run_id).await?,
+        None => None,
+    };
+
+    let mut run_ids = Vec::new();
+    for (agent, mut results) in by_agent {
+        // Oldest first, so trial 0 of each batch is stored first as `run` does
+        results.sort_by(|a, b| {
+            (&a.original.benchmark_id, a.original.trial_index)
+                .cmp(&(&b.original.benchmark_id, b.original.trial_index))
+        });
+        let mut paths: Vec<PathBuf> = results.iter().map(|r| r.path.clone()).collect();
+        paths.dedup();
+        let trial_index = |result: &Rescored| match source_run {
+            Some(_) => result.original.trial_index,
+            None => 0,
+        };
+        let trials = results
+            .iter()
+            .map(|r| trial_index(r) + 1)
+            .max()
+            .unwrap_or(1);
+
+        let mut run = new_evaluation_run(agent, &paths, trials, None)?;
+        if let Some(source) = &source {
+            run.agent_config = source.agent_config.clone();
+            run.seed = source.seed;
+        }
+        db.create_run(&run)
+            .await
+            .context("Failed to record re-scoring run")?;
+        for result in results {
+            db.insert_agent_performance(&result.to_performance(&run.run_id, trial_index(result)))
+                .await
+                .context("Failed to store re-scored result")?;
+        }
+        db.complete_run(&run.run_id, RUN_COMPLETED, chrono::Utc::now().timestamp())
+            .await?;
+        run_ids.push(run.run_id);
+    }
+    Ok(run_ids)
+}
+
+/// Render re-scored results with their stored and new scores
+pub fn format_rescored(rescored: &[Rescored]) -> String {
+    let rows: Vec<Vec<String>> = rescored
+        .iter()
+        .map(|result| {
+            let original = &result.original;
+            vec![
+                original.benchmark_id.clone(),
+                original.agent_type.clone(),
+                original.trial_index.to_string(),
+                format_score(original.score),
+                format_score(result.score),
+                format!("{:+.1}", (result.score - original.score) * 100.0),
+                result.final_status.to_string(),
+            ]
+        })
+        .collect();
+    format_table(
+        &[
+            "BENCHMARK",
+            "AGENT",
+            "TRIAL",
+            "STORED",
+            "RESCORED",
+            "DELTA",
+            "STATUS",
+        ],
+        &rows,
+    )
+}
+
+pub async fn execute(args: RescoreArgs) -> Result<()> {
+    let reader = args.db.open_reader().await?;
+    let records = match &args.run {
+        Some(run_id) => {
+            if reader.get_run(run_id).await?.is_none() {
+                anyhow::bail!("No evaluation run '{run_id}'");
+            }
+            let filter = QueryFilter {
+                agent_type: args.agent.clone(),
+                run_id: Some(run_id.clone()),
+                ..Default::default()
+            };
+            reader.get_agent_performance(Some(filter)).await?
+        }
+        None => reader.get_latest_results(args.agent.as_deref()).await?,
+    };
+    let benchmarks = load_benchmarks(&args.benchmarks)?;
+    let (rescored, skipped) = rescore_results(&reader, &benchmarks, records).await?;
+
+    for (record, reason) in &skipped {
+        println!(
+            "Skipped {} ({}, session {}): {reason}",
+            record.benchmark_id, record.agent_type, record.session_id
+        );
+    }
+    if rescored.is_empty() {
+        println!("No stored traces to re-score");
+        return Ok(());
+    }
+    println!("{}", format_rescored(&rescored));
+
+    if args.dry_run {
+        return Ok(());
+    }
+    let db = args.db.open_writer().await?;
+    for run_id in record_rescored(&db, &rescored, args.run.as_deref()).await? {
+        println!("Re-scored results recorded as evaluation run {run_id}");
+    }
+    Ok(())
+}
@@ -0,0 +1,350 @@
+//! `run`: execute benchmarks with an agent
+//!
+//! With `--agents a,b,c`, runs the benchmarks with each agent in one go and prints
+//! a benchmark × agent score matrix (see [`crate::matrix`]).
+
+use crate::{
+    RunOptions,
+    cli::{
+        DEFAULT_BENCHMARKS_DIR, default_db_path, format_score, open_reader,
+        report::{format_matrix, format_trial_stats},
+    },
+    evaluation::resume_benchmarks,
+    matrix::{ProviderLimit, ProviderPermits},
+    output::{OutputTarget, write_matrix_outputs, write_outputs},
+    renderer,
+    selection::{BenchmarkSelection, Suite, suites_dir},
+};
+use anyhow::{Context, Result};
+use clap::Args;
+use reev_db::{ScoreMatrix, TrialStats};
+use reev_lib::results::{FinalStatus, TestResult};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tracing::info;
+
+#[derive(Args, Debug, Clone)]
+pub struct RunArgs {
+    /// Path to a specific benchmark YAML file, a directory containing multiple benchmarks, or a flow log file.
+    pub path: Option<PathBuf>,
+
+    /// The agent to run the benchmarks with.
+    /// Any model of the registry in reev.toml, such as 'deterministic', 'local' or 'glm-4.6'.
+    #[arg(long, default_value = "deterministic")]
+    pub agent: String,
+
+    /// Run the benchmarks with each of these agents (comma-separated) on one set of
+    /// dependencies and print a benchmark × agent score matrix
+    #[arg(long, value_delimiter = ',', conflicts_with_all = ["agent", "resume"])]
+    pub agents: Vec<String>,
+
+    /// Attempts of one provider's agents that may run at once in a matrix run, as
+    /// `<PROVIDER>=<N>` with provider zai, openai, anthropic, local or deterministic
+    /// (repeatable; default 1 per provider)
+    #[arg(
+        long = "max-concurrency",
+        value_name = "PROVIDER=N",
+        requires = "agents"
+    )]
+    pub max_concurrency: Vec<ProviderLimit>,
+
+    #[command(flatten)]
+    pub selection: BenchmarkSelection,
+
+    /// Run each benchmark this many times, each in a fresh session, and report
+    /// mean, spread, pass@1, pass@k and a confidence interval per benchmark
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub trials: u32,
+
+    /// Seed of the run, recorded with it: trial N sends seed + N as the provider's
+    /// sampling seed with the configured temperature, and seeds fault injection
+    /// of benchmarks whose `faults:` sets no seed
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Continue an interrupted evaluation run, running only the attempts it has
+    /// not recorded yet, with the run's own agent and trial count
+    #[arg(
+        long,
+        value_name = "RUN_ID",
+        conflicts_with_all = ["agent", "trials", "seed", "filters", "tags", "ids", "suite"]
+    )]
+    pub resume: Option<String>,
+
+    /// Time limit in seconds of each attempt, all flow steps included, for
+    /// benchmarks that set no `timeout:` of their own
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub timeout: Option<u64>,
+
+    /// Also write results as `junit=<path>`, `json=<path>` or `md=<path>` (repeatable)
+    #[arg(long = "output", value_name = "FORMAT=PATH")]
+    pub outputs: Vec<OutputTarget>,
+
+    /// Render flow log as ASCII tree (only works with .yml flow files)
+    #[arg(long)]
+    pub render_flow: bool,
+
+    /// Use shared surfpool instances instead of creating fresh ones
+    #[arg(long)]
+    pub shared_surfpool: bool,
+}
+
+impl RunArgs {
+    /// The benchmark path, defaulting to the benchmarks directory
+    pub fn path(&self) -> PathBuf {
+        self.path
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_BENCHMARKS_DIR))
+    }
+}
+
+pub async fn execute(args: RunArgs) -> Result<()> {
+    let path = args.path();
+
+    if args.render_flow {
+        if path.extension().and_then(|s| s.to_str()) != Some("yml") {
+            anyhow::bail!("--render-flow requires a .yml flow log file");
+        }
+        info!("Rendering flow log as ASCII tree: '{}'", path.display());
+        let tree_output = reev_lib::flow::render_flow_file_as_ascii_tree(&path)
+            .map_err(|e| anyhow::anyhow!("Error rendering flow log: {e}"))?;
+        println!("\n{tree_output}");
+        return Ok(());
+    }
+
+    if let Some(run_id) = &args.resume {
+        return resume(&args, run_id, &path).await;
+    }
+
+    // Load the suite up front so a typo fails before any dependency starts
+    let suite = match &args.selection.suite {
+        Some(name) => Some(Suite::load(&suites_dir(&path), name)?),
+        None => None,
+    };
+
+    // Look the agents up in the model registry for the same reason
+    let agents = if args.agents.is_empty() {
+        std::slice::from_ref(&args.agent)
+    } else {
+        &args.agents
+    };
+    for agent in agents {
+        reev_config::get().model(agent)?;
+    }
+
+    if !args.agents.is_empty() {
+        return matrix(&args, &path, suite.as_ref()).await;
+    }
+
+    info!(
+        "Running benchmarks at: '{}' with agent: '{}'",
+        path.display(),
+        args.agent
+    );
+
+    let results =
+        crate::run_selected_benchmarks(&path, &args.selection, &args.agent, &run_options(&args))
+            .await?;
+
+    if results.is_empty() && !args.selection.is_empty() {
+        info!("No benchmarks matched selection: {:?}", args.selection);
+    }
+
+    report_results(&results, &args.agent, args.trials, &args.outputs)?;
+
+    if let Some(suite) = suite {
+        print_suite_score(&suite, &results, None);
+    }
+
+    Ok(())
+}
+
+/// Options of a new run from the command line
+fn run_options(args: &RunArgs) -> RunOptions {
+    RunOptions {
+        shared_surfpool: args.shared_surfpool,
+        kill_api: true,
+        trials: args.trials,
+        seed: args.seed,
+        timeout: args.timeout.map(Duration::from_secs),
+        ..Default::default()
+    }
+}
+
+/// Run the selected benchmarks with every agent of `--agents` and print the score matrix
+///
+/// Fails after reporting if the run of any agent failed.
+async fn matrix(args: &RunArgs, path: &Path, suite: Option<&Suite>) -> Result<()> {
+    info!(
+        "Running benchmarks at: '{}' with agents: {}",
+        path.display(),
+        args.agents.join(", ")
+    );
+    let benchmark_paths = args
+        .selection
+        .resolve(path)?
+        .into_iter()
+        .map(|benchmark| benchmark.path)
+        .collect();
+    let permits = ProviderPermits::new(&args.agents, &args.max_concurrency);
+    let outcomes =
+        crate::run_agent_matrix(benchmark_paths, &args.agents, &run_options(args), &permits)
+            .await?;
+    if outcomes.is_empty() {
+        info!("No benchmarks matched selection: {:?}", args.selection);
+        return Ok(());
+    }
+
+    let mut finished: Vec<(&str, &[TestResult])> = vec![];
+    let mut failed = vec![];
+    for outcome in &outcomes {
+        match &outcome.results {
+            Ok(results) => {
+                render_trees(results);
+                finished.push((&outcome.agent, results));
+            }
+            Err(e) => failed.push(format!("{}: {e:#}", outcome.agent)),
+        }
+    }
+    let stats: Vec<TrialStats> = finished
+        .iter()
+        .flat_map(|(agent, results)| trial_stats(results, agent))
+        .collect();
+    let matrix = ScoreMatrix::from_stats(&args.agents, &stats);
+
+    write_matrix_outputs(&finished, &matrix, &args.outputs)?;
+    log_outputs(&args.outputs);
+
+    if args.trials > 1 {
+        println!("{}", format_trial_stats(&stats));
+    }
+    println!("{}", format_matrix(&matrix));
+    if let Some(suite) = suite {
+        for (agent, results) in &finished {
+            print_suite_score(suite, results, Some(agent));
+        }
+    }
+    for outcome in &outcomes {
+        println!("Evaluation run of {}: {}", outcome.agent, outcome.run_id);
+    }
+
+    if !failed.is_empty() {
+        anyhow::bail!(
+            "{} of {} agent runs failed:\n{}",
+            failed.len(),
+            outcomes.len(),
+            failed.join("\n")
+        );
+    }
+    Ok(())
+}
+
+/// Print the suite's aggregate score over `results`, of `agent` in a matrix run
+fn print_suite_score(suite: &Suite, results: &[TestResult], agent: Option<&str>) {
+    let Some(score) = suite.aggregate_score(results) else {
+        return;
+    };
+    let passed = results
+        .iter()
+        .filter(|r| r.final_status == FinalStatus::Succeeded)
+        .count();
+    let label = match agent {
+        Some(agent) => format!("Suite '{}' ({agent})", suite.name),
+        None => format!("Suite '{}'", suite.name),
+    };
+    println!(
+        "{label}: {}/{} passed, aggregate score {}",
+        passed,
+        results.len(),
+        format_score(score)
+    );
+}
+
+/// Continue evaluation run `run_id` with the benchmark files found at `path`
+async fn resume(args: &RunArgs, run_id: &str, path: &Path) -> Result<()> {
+    let run = open_reader(&default_db_path())
+        .await?
+        .get_run(run_id)
+        .await?
+        .with_context(|| format!("No evaluation run '{run_id}'"))?;
+    let benchmark_paths = resume_benchmarks(&run, path)?;
+    info!(
+        "Resuming run {} of {} benchmark(s) with agent: '{}'",
+        run_id,
+        benchmark_paths.len(),
+        run.agent_type
+    );
+
+    let options = RunOptions {
+        shared_surfpool: args.shared_surfpool,
+        kill_api: true,
+        resume: Some(run_id.to_string()),
+        timeout: args.timeout.map(Duration::from_secs),
+        ..Default::default()
+    };
+    let results = crate::run_benchmark_paths(benchmark_paths, &run.agent_type, &options).await?;
+    if results.is_empty() {
+        println!("Evaluation run {run_id} had no attempts left to run");
+        return Ok(());
+    }
+    report_results(&results, &run.agent_type, run.trials, &args.outputs)
+}
+
+/// Render results, write the requested outputs and print the trial statistics
+fn report_results(
+    results: &[TestResult],
+    agent: &str,
+    trials: u32,
+    outputs: &[OutputTarget],
+) -> Result<()> {
+    render_trees(results);
+
+    write_outputs(results, agent, outputs)?;
+    log_outputs(outputs);
+
+    if trials > 1 {
+        println!("{}", format_trial_stats(&trial_stats(results, agent)));
+    }
+
+    if let Some(run_id) = results.first().and_then(|r| r.run_id.as_deref()) {
+        println!("Evaluation run: {run_id}");
+    }
+    Ok(())
+}
+
+/// Log each result as an ASCII tree
+fn render_trees(results: &[TestResult]) {
+    for result in results {
+        let tree_output = renderer::render_result_as_tree(result);
+        info!("\n{tree_output}");
+    }
+}
+
+fn log_outputs(outputs: &[OutputTarget]) {
+    for target in outputs {
+        info!(
+            "Wrote {:?} output to {}",
+            target.format,
+            target.path.display()
+        );
+    }
+}
+
+/// Statistics of each benchmark over the trials of a run
+///
+/// Expects the results of one benchmark to be consecutive, as
+/// [`crate::run_benchmark_paths`] returns them.
+pub fn trial_stats(results: &[TestResult], agent: &str) -> Vec<TrialStats> {
+    results
+        .chunk_by(|a, b| a.id == b.id)
+        .filter_map(|trials| {
+            let outcomes: Vec<(f64, bool)> = trials
+                .iter()
+                .map(|r| (r.score, r.final_status == FinalStatus::Succeeded))
+                .collect();
+            TrialStats::from_outcomes(&trials[0].id, agent, &outcomes, None)
+        })
+        .collect()
+}
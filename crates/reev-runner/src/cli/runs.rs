@@ -0,0 +1,157 @@
+//! `runs`: list evaluation runs or show one of them
+//!
+//! Every `run` is recorded as an evaluation run grouping its sessions. Without an
+//! argument the latest runs are listed; given a run id, its provenance (agent
+//! settings, commit, seed and benchmark hashes) and sessions are shown.
+
+use crate::cli::{DbArgs, format_score, format_table};
+use anyhow::Result;
+use clap::Args;
+use reev_db::{EvaluationRun, EvaluationRunSummary, types::SessionInfo};
+
+#[derive(Args, Debug, Clone)]
+pub struct RunsArgs {
+    /// Show this run's provenance and sessions instead of listing runs
+    pub run_id: Option<String>,
+
+    /// Number of runs to list
+    #[arg(long, default_value_t = 20)]
+    pub limit: u32,
+
+    #[command(flatten)]
+    pub db: DbArgs,
+}
+
+/// Render runs as a table, in the given order
+pub fn format_runs(runs: &[EvaluationRunSummary]) -> String {
+    let rows: Vec<Vec<String>> = runs
+        .iter()
+        .map(|summary| {
+            let run = &summary.run;
+            vec![
+                run.run_id.clone(),
+                format_time(run.start_time),
+                run.agent_type.clone(),
+                run.status.clone(),
+                format!("{}/{}", summary.passed, summary.total),
+                summary
+                    .average_score
+                    .map(format_score)
+                    .unwrap_or_else(|| "-".to_string()),
+                summary
+                    .cost_usd
+                    .map(|cost| format!("${cost:.4}"))
+                    .unwrap_or_else(|| "-".to_string()),
+                run.git_commit
+                    .as_deref()
+                    .map(short_commit)
+                    .unwrap_or("-")
+                    .to_string(),
+            ]
+        })
+        .collect();
+    format_table(
+        &[
+            "RUN", "STARTED", "AGENT", "STATUS", "PASSED", "SCORE", "COST", "COMMIT",
+        ],
+        &rows,
+    )
+}
+
+/// Render a run's provenance followed by its sessions
+pub fn format_run(run: &EvaluationRun, sessions: &[SessionInfo]) -> String {
+    let config = &run.agent_config;
+    let optional = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+    let mut lines = vec![
+        format!("Run:             {}", run.run_id),
+        format!("Status:          {}", run.status),
+        format!("Started:         {}", format_time(run.start_time)),
+        format!(
+            "Finished:        {}",
+            optional(run.end_time.map(format_time))
+        ),
+        format!("Agent:           {}", run.agent_type),
+        format!("Model:           {}", config.model),
+        format!(
+            "Temperature:     {}",
+            optional(config.temperature.map(|t| t.to_string()))
+        ),
+        format!(
+            "Prompt version:  {}",
+            optional(config.prompt_version.clone())
+        ),
+        format!("Commit:          {}", optional(run.git_commit.clone())),
+        format!(
+            "Seed:            {}",
+            optional(run.seed.map(|s| s.to_string()))
+        ),
+        format!("Trials:          {}", run.trials),
+        String::new(),
+    ];
+
+    let hashes: Vec<Vec<String>> = run
+        .benchmark_hashes
+        .iter()
+        .map(|(benchmark_id, hash)| vec![benchmark_id.clone(), hash.clone()])
+        .collect();
+    lines.push(format_table(&["BENCHMARK", "SHA-256"], &hashes));
+    lines.push(String::new());
+
+    if sessions.is_empty() {
+        lines.push("No sessions recorded".to_string());
+    } else {
+        let rows: Vec<Vec<String>> = sessions
+            .iter()
+            .map(|session| {
+                vec![
+                    session.session_id.clone(),
+                    session.benchmark_id.clone(),
+                    session.interface.clone(),
+                    session.status.clone(),
+                    session
+                        .score
+                        .map(format_score)
+                        .unwrap_or_else(|| "-".to_string()),
+                ]
+            })
+            .collect();
+        lines.push(format_table(
+            &["SESSION", "BENCHMARK", "INTERFACE", "STATUS", "SCORE"],
+            &rows,
+        ));
+    }
+    lines.join("\n")
+}
+
+fn format_time(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+fn short_commit(commit: &str) -> &str {
+    commit.get(..8).unwrap_or(commit)
+}
+
+pub async fn execute(args: RunsArgs) -> Result<()> {
+    let reader = args.db.open_reader().await?;
+
+    match &args.run_id {
+        Some(run_id) => {
+            let Some(run) = reader.get_run(run_id).await? else {
+                anyhow::bail!("No evaluation run '{run_id}'");
+            };
+            let sessions = reader.list_run_sessions(run_id).await?;
+            println!("{}", format_run(&run, &sessions));
+        }
+        None => {
+            let runs = reader.list_runs(Some(args.limit)).await?;
+            if runs.is_empty() {
+                println!("No evaluation runs found");
+            } else {
+                println!("{}", format_runs(&runs));
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,93 @@
+//! `validate`: check benchmark files without running them
+//!
+//! Every file is parsed on its own so a broken file is reported alongside the
+//! others instead of aborting the whole check. Parsed benchmarks are then checked
+//! with [`reev_lib::benchmark::TestCase::validate`] and for ids shared across files.
+
+use crate::{
+    cli::DEFAULT_BENCHMARKS_DIR,
+    selection::{BenchmarkFile, discover_benchmarks},
+};
+use anyhow::Result;
+use clap::Args;
+use std::{collections::HashMap, path::PathBuf};
+
+#[derive(Args, Debug, Clone)]
+pub struct ValidateArgs {
+    /// Benchmark file or directory
+    #[arg(default_value = DEFAULT_BENCHMARKS_DIR)]
+    pub path: PathBuf,
+}
+
+/// Problems found in a single benchmark file
+#[derive(Debug, Clone)]
+pub struct ValidationReport {
+    pub path: PathBuf,
+    pub problems: Vec<String>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Validate every benchmark file found at `path`
+pub fn validate_benchmarks(path: &std::path::Path) -> Result<Vec<ValidationReport>> {
+    let mut reports = Vec::new();
+    let mut ids: HashMap<String, Vec<usize>> = HashMap::new();
+
+    for path in discover_benchmarks(path)? {
+        let problems = match BenchmarkFile::load(&path) {
+            Ok(benchmark) => {
+                ids.entry(benchmark.test_case.id.clone())
+                    .or_default()
+                    .push(reports.len());
+                benchmark.test_case.validate()
+            }
+            Err(e) => vec![format!("{e:#}")],
+        };
+        reports.push(ValidationReport { path, problems });
+    }
+
+    for (id, indices) in ids.iter().filter(|(_, indices)| indices.len() > 1) {
+        for &index in indices {
+            let others: Vec<String> = indices
+                .iter()
+                .filter(|&&other| other != index)
+                .map(|&other| reports[other].path.display().to_string())
+                .collect();
+            reports[index]
+                .problems
+                .push(format!("id '{id}' is also used by {}", others.join(", ")));
+        }
+    }
+
+    Ok(reports)
+}
+
+pub fn execute(args: ValidateArgs) -> Result<()> {
+    let reports = validate_benchmarks(&args.path)?;
+
+    for report in &reports {
+        if report.is_valid() {
+            println!("✅ {}", report.path.display());
+        } else {
+            println!("❌ {}", report.path.display());
+            for problem in &report.problems {
+                println!("   - {problem}");
+            }
+        }
+    }
+
+    let invalid = reports.iter().filter(|r| !r.is_valid()).count();
+    println!(
+        "\n{} benchmark(s) checked, {} invalid",
+        reports.len(),
+        invalid
+    );
+    if invalid > 0 {
+        anyhow::bail!("{invalid} benchmark(s) failed validation");
+    }
+    Ok(())
+}
@@ -2,6 +2,7 @@
 
 use super::{HealthCheckConfig, HealthCheckResult, HealthError, ServiceHealth};
 use anyhow::Result;
+use reev_config::{ModelEntry, ProviderKind};
 use reqwest::Client;
 use std::time::{Duration, Instant};
 use tokio::time::timeout;
@@ -42,6 +43,158 @@ impl HealthChecker {
         self.check_rpc_endpoint(&service_name, rpc_url).await
     }
 
+    /// Check that the local server of model `name` is up and able to serve it
+    ///
+    /// An Ollama server must have the model pulled; with `pull` a missing model is
+    /// pulled first. A llama.cpp server must have loaded its model with a context
+    /// at least as large as the model's `context_size`. Models of other providers
+    /// are reported healthy without a check.
+    pub async fn check_local_model(
+        &self,
+        name: &str,
+        entry: &ModelEntry,
+        pull: bool,
+    ) -> HealthCheckResult {
+        let start_time = Instant::now();
+        let base_url = entry.base_url.as_deref().unwrap_or_default();
+        let base_url = base_url.trim_end_matches('/');
+        let model = entry.model_id(name);
+
+        debug!(name, base_url, provider = %entry.provider, "Performing model health check");
+
+        let result = match entry.provider {
+            ProviderKind::Ollama => self.check_ollama_model(base_url, model, pull).await,
+            ProviderKind::LlamaCpp => {
+                let result = timeout(
+                    self.config.timeout,
+                    self.check_llama_cpp_context(base_url, entry.context_size),
+                )
+                .await;
+                result.unwrap_or_else(|_| {
+                    Err(anyhow::anyhow!(
+                        "timeout after {}ms",
+                        self.config.timeout.as_millis()
+                    ))
+                })
+            }
+            _ => Ok(()),
+        };
+
+        let response_time = start_time.elapsed();
+        let service_name = format!("{} model {name}", entry.provider);
+        match result {
+            Ok(()) => {
+                info!(
+                    name,
+                    response_time_ms = response_time.as_millis(),
+                    "Model health check passed"
+                );
+                HealthCheckResult::new(service_name)
+                    .with_status(ServiceHealth::Healthy)
+                    .with_response_time(response_time)
+            }
+            Err(e) => {
+                let error_msg = e.to_string();
+                warn!(name, error = %error_msg, "Model health check failed");
+                HealthCheckResult::new(service_name)
+                    .with_status(ServiceHealth::Unhealthy(error_msg.clone()))
+                    .with_response_time(response_time)
+                    .with_details(error_msg)
+            }
+        }
+    }
+
+    /// Check that `model` is pulled on the Ollama server, pulling it when `pull` is set
+    async fn check_ollama_model(&self, base_url: &str, model: &str, pull: bool) -> Result<()> {
+        let tags: serde_json::Value = timeout(self.config.timeout, async {
+            let response = self
+                .client
+                .get(format!("{base_url}/api/tags"))
+                .send()
+                .await?
+                .error_for_status()?;
+            anyhow::Ok(response.json().await?)
+        })
+        .await
+        .map_err(|_| anyhow::anyhow!("Ollama at {base_url} did not answer"))?
+        .map_err(|e| anyhow::anyhow!("Ollama at {base_url} is not reachable: {e}"))?;
+
+        // Ollama lists models with their tag, `latest` when none was given
+        let wanted = if model.contains(':') {
+            model.to_string()
+        } else {
+            format!("{model}:latest")
+        };
+        let pulled = tags["models"].as_array().is_some_and(|models| {
+            models
+                .iter()
+                .filter_map(|m| m["name"].as_str())
+                .any(|name| name == model || name == wanted)
+        });
+        if pulled {
+            return Ok(());
+        }
+        if !pull {
+            anyhow::bail!(
+                "model {model} is not pulled on Ollama at {base_url}; run `ollama pull {model}` or set runner.pull_models"
+            );
+        }
+
+        info!(model, "Pulling model on Ollama, this may take a while");
+        // Downloads take far longer than a health check, so no timeout applies here
+        let status: serde_json::Value = self
+            .client
+            .post(format!("{base_url}/api/pull"))
+            .timeout(Duration::from_secs(24 * 60 * 60))
+            .json(&serde_json::json!({"model": model, "stream": false}))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        match status["status"].as_str() {
+            Some("success") => Ok(()),
+            _ => Err(anyhow::anyhow!("pulling {model} failed: {status}")),
+        }
+    }
+
+    /// Check that the llama.cpp server has loaded its model with a large enough context
+    async fn check_llama_cpp_context(
+        &self,
+        base_url: &str,
+        context_size: Option<u32>,
+    ) -> Result<()> {
+        // 503 while the model is still loading
+        self.client
+            .get(format!("{base_url}/health"))
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("llama.cpp at {base_url} is not reachable: {e}"))?
+            .error_for_status()
+            .map_err(|e| anyhow::anyhow!("llama.cpp at {base_url} is not ready: {e}"))?;
+
+        let Some(context_size) = context_size else {
+            return Ok(());
+        };
+        let props: serde_json::Value = self
+            .client
+            .get(format!("{base_url}/props"))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let n_ctx = props["default_generation_settings"]["n_ctx"]
+            .as_u64()
+            .or_else(|| props["n_ctx"].as_u64());
+        match n_ctx {
+            Some(n_ctx) if n_ctx < u64::from(context_size) => Err(anyhow::anyhow!(
+                "llama.cpp at {base_url} runs with a {n_ctx}-token context, smaller than the configured context_size of {context_size}; start llama-server with `-c {context_size}`"
+            )),
+            _ => Ok(()),
+        }
+    }
+
     /// Generic HTTP endpoint health check
     pub async fn check_http_endpoint(&self, service_name: &str, url: &str) -> HealthCheckResult {
         let start_time = Instant::now();
@@ -30,6 +30,9 @@ pub struct DependencyConfig {
     pub reev_agent_port: u16,
     pub surfpool_rpc_port: u16,
 
+    /// Port for the failure-injection proxy in front of surfpool
+    pub fault_proxy_port: u16,
+
     /// Timeout for health checks
     pub health_check_timeout: Duration,
 
@@ -44,6 +47,9 @@ pub struct DependencyConfig {
 
     /// Base directory for service logs
     pub log_dir: String,
+
+    /// Whether to pull a model missing from the Ollama server
+    pub pull_models: bool,
 }
 
 impl Default for DependencyConfig {
@@ -56,11 +62,13 @@ impl Default for DependencyConfig {
             shared_instances: true,
             reev_agent_port: 9090,
             surfpool_rpc_port: 8899,
+            fault_proxy_port: 8890,
             health_check_timeout: Duration::from_secs(5),
             startup_timeout: Duration::from_secs(60),
             verbose_logging: false,
             cache_dir: ".surfpool/cache".to_string(),
             log_dir: "logs".to_string(),
+            pull_models: false,
             agent_type: None,
             benchmark_id: None,
         }
@@ -68,40 +76,20 @@ impl Default for DependencyConfig {
 }
 
 impl DependencyConfig {
-    /// Load configuration from environment variables with defaults
-    pub fn from_env() -> Self {
-        let mut config = Self::default();
-
-        // Override with environment variables if present
-        if let Ok(port) = std::env::var("REEV_AGENT_PORT") {
-            if let Ok(port) = port.parse() {
-                config.reev_agent_port = port;
-            }
-        }
-
-        if let Ok(port) = std::env::var("SURFPOOL_RPC_PORT") {
-            if let Ok(port) = port.parse() {
-                config.surfpool_rpc_port = port;
-            }
-        }
-
-        if let Ok(val) = std::env::var("REEV_AUTO_START") {
-            config.auto_start = val.to_lowercase() != "false" && val != "0";
-        }
-
-        if let Ok(val) = std::env::var("REEV_VERBOSE_LOGGING") {
-            config.verbose_logging = val.to_lowercase() != "false" && val != "0";
-        }
-
-        if let Ok(path) = std::env::var("REEV_CACHE_DIR") {
-            config.cache_dir = path;
-        }
-
-        if let Ok(path) = std::env::var("REEV_LOG_DIR") {
-            config.log_dir = path;
+    /// Configuration from the `[agent]`, `[surfpool]` and `[runner]` sections of the
+    /// reev configuration
+    pub fn from_config(config: &reev_config::ReevConfig) -> Self {
+        Self {
+            auto_start: config.runner.auto_start,
+            reev_agent_port: config.agent.port,
+            surfpool_rpc_port: config.surfpool.rpc_port,
+            fault_proxy_port: config.surfpool.fault_proxy_port,
+            verbose_logging: config.runner.verbose_logging,
+            cache_dir: config.runner.cache_dir.clone(),
+            log_dir: config.runner.log_dir.clone(),
+            pull_models: config.runner.pull_models,
+            ..Self::default()
         }
-
-        config
     }
 
     /// Get port for a specific dependency type
@@ -132,6 +120,17 @@ impl DependencyConfig {
             });
         }
 
+        if self.fault_proxy_port == self.reev_agent_port
+            || self.fault_proxy_port == self.surfpool_rpc_port
+        {
+            return Err(super::DependencyError::ConfigError {
+                message: format!(
+                    "Port conflict: fault_proxy_port ({}) must differ from reev_agent_port and surfpool_rpc_port",
+                    self.fault_proxy_port
+                ),
+            });
+        }
+
         // Check cache directory path
         if self.cache_dir.is_empty() {
             return Err(super::DependencyError::ConfigError {
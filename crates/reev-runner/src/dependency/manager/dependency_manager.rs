@@ -9,7 +9,9 @@ use super::{DependencyConfig, DependencyError, DependencyService, DependencyType
 use crate::dependency::binary::BinaryManager;
 use crate::dependency::health::{HealthChecker, ServiceHealth};
 use crate::dependency::process::{ProcessConfig, ProcessGuard, ProcessManager, ProcessUtils};
+use crate::dependency::proxy::{FaultProxy, FaultProxyHandle};
 use anyhow::{Context, Result};
+use reev_config::ProviderKind;
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
@@ -40,6 +42,9 @@ pub struct DependencyManager {
 
     /// Whether dependencies have been initialized
     initialized: Arc<RwLock<bool>>,
+
+    /// Failure-injection proxy, started on demand for benchmarks with faults
+    fault_proxy: Option<FaultProxyHandle>,
 }
 
 impl DependencyManager {
@@ -72,6 +77,7 @@ impl DependencyManager {
             services: Arc::new(RwLock::new(HashMap::new())),
             processes: Arc::new(RwLock::new(HashMap::new())),
             initialized: Arc::new(RwLock::new(false)),
+            fault_proxy: None,
         })
     }
 
@@ -209,7 +215,17 @@ impl DependencyManager {
         .with_stderr(log_file)
         .with_startup_timeout(self.config.startup_timeout)
         .with_health_check(format!("http://localhost:{port}/health"))
-        .with_health_check_interval(Duration::from_secs(2));
+        .with_health_check_interval(Duration::from_secs(2))
+        .with_env_var("REEV_AGENT_PORT".to_string(), port.to_string());
+
+        // Route the agent's own surfpool traffic through the fault proxy when it runs
+        let process_config = match &self.fault_proxy {
+            Some(proxy) => process_config.with_env_var(
+                "JUPITER_SURFPOOL_RPC_URL".to_string(),
+                proxy.url().to_string(),
+            ),
+            None => process_config,
+        };
 
         // Start the process
         debug!("Starting reev-agent process...");
@@ -459,9 +475,67 @@ impl DependencyManager {
             reev_agent: reev_agent_url,
             surfpool_rpc: surfpool_rpc_url,
             surfpool_ws: surfpool_ws_url,
+            fault_proxy: self.fault_proxy.as_ref().map(|p| p.url().to_string()),
         })
     }
 
+    /// Start the failure-injection proxy in front of surfpool, or reuse the running one
+    pub async fn ensure_fault_proxy(&mut self) -> Result<FaultProxyHandle> {
+        if let Some(proxy) = &self.fault_proxy {
+            return Ok(proxy.clone());
+        }
+
+        let surfpool_url = self.get_dependency_urls().await?.surfpool_rpc;
+        let port = self.config.fault_proxy_port;
+        debug!(port, upstream = %surfpool_url, "Starting fault proxy");
+
+        let proxy = FaultProxy::start(port, surfpool_url).await.map_err(|e| {
+            DependencyError::StartError {
+                service: "fault-proxy".to_string(),
+                source: e,
+            }
+        })?;
+        self.fault_proxy = Some(proxy.clone());
+
+        info!(port, "fault proxy is ready");
+        Ok(proxy)
+    }
+
+    /// Check that the model behind `agent_name` can be served
+    ///
+    /// Models on a local Ollama or llama.cpp server are checked, and pulled when
+    /// [`DependencyConfig::pull_models`] is set; other models need no service.
+    pub async fn ensure_model(&self, agent_name: &str) -> Result<()> {
+        let name = reev_config::canonical_model_name(agent_name);
+        let entry = reev_config::get().model(name)?;
+        if !matches!(
+            entry.provider,
+            ProviderKind::Ollama | ProviderKind::LlamaCpp
+        ) {
+            return Ok(());
+        }
+
+        let result = self
+            .health_checker
+            .check_local_model(name, entry, self.config.pull_models)
+            .await;
+        match result.status {
+            ServiceHealth::Unhealthy(reason) => Err(DependencyError::HealthCheckError {
+                service: result.service_name,
+                reason,
+            }
+            .into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Stop the failure-injection proxy if it is running
+    pub async fn stop_fault_proxy(&mut self) {
+        if let Some(proxy) = self.fault_proxy.take() {
+            proxy.shutdown().await;
+        }
+    }
+
     /// Get health status of all dependencies
     pub async fn get_health_status(&self) -> HashMap<String, ServiceHealth> {
         let services = self.services.read().await;
@@ -484,6 +558,8 @@ impl DependencyManager {
         // No monitoring to stop - services are shut down directly
         debug!("Health checking stopped (continuous monitoring was not used)");
 
+        self.stop_fault_proxy().await;
+
         // Shutdown processes
         let mut processes = self.processes.write().await;
         for (dependency_type, process_guard) in processes.drain() {
@@ -512,6 +588,8 @@ impl DependencyManager {
         // No monitoring to stop - services are shut down directly
         debug!("Health checking force stopped (continuous monitoring was not used)");
 
+        self.stop_fault_proxy().await;
+
         // Force shutdown processes
         let mut processes = self.processes.write().await;
         for (dependency_type, process_guard) in processes.drain() {
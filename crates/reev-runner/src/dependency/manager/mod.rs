@@ -24,6 +24,8 @@ pub struct DependencyUrls {
     pub reev_agent: String,
     pub surfpool_rpc: String,
     pub surfpool_ws: Option<String>,
+    /// Failure-injection proxy in front of surfpool, if started
+    pub fault_proxy: Option<String>,
 }
 
 // Re-export ServiceHealth from health module to avoid conflicts
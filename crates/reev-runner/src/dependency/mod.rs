@@ -21,6 +21,7 @@
 //! - [`binary`]: Binary download, caching, and management
 //! - [`health`]: Health checking and monitoring
 //! - [`process`]: Process management and lifecycle
+//! - [`proxy`]: Failure-injection JSON-RPC proxy in front of surfpool
 //!
 //! # Example Usage
 //!
@@ -30,7 +31,7 @@
 //!
 //! #[tokio::main]
 //! async fn main() -> anyhow::Result<()> {
-//!     let config = DependencyConfig::from_env();
+//!     let config = DependencyConfig::from_config(reev_config::get());
 //!     let mut manager = DependencyManager::new(config)?;
 //!
 //!     // Ensure all dependencies are running
@@ -51,6 +52,7 @@ pub mod binary;
 pub mod health;
 pub mod manager;
 pub mod process;
+pub mod proxy;
 
 // re-export main types for convenience
 pub use binary::{BinaryAcquisitionResult, BinaryManager, Platform, Version};
@@ -60,13 +62,14 @@ pub use manager::{
     ServiceHealth,
 };
 pub use process::{LifecycleManager, ProcessConfig, ProcessGuard, ProcessManager};
+pub use proxy::{FaultProxy, FaultProxyHandle};
 
 use anyhow::Result;
 use tracing::{debug, info, warn};
 
 /// Initialize dependency management system with default configuration
 pub async fn init_default() -> Result<DependencyManager> {
-    let config = DependencyConfig::from_env();
+    let config = DependencyConfig::from_config(reev_config::get());
     init_with_config(config).await
 }
 
@@ -99,7 +102,7 @@ pub async fn check_dependencies() -> Result<bool> {
 pub async fn cleanup_existing() -> Result<()> {
     warn!("Cleaning up existing dependency processes");
 
-    let config = DependencyConfig::from_env();
+    let config = DependencyConfig::from_config(reev_config::get());
     let mut manager = DependencyManager::new(config)?;
 
     // Force cleanup any existing processes
@@ -0,0 +1,247 @@
+//! JSON-RPC failure-injection proxy
+//!
+//! Forwards every request to surfpool unchanged while disarmed. Once armed with a
+//! [`FaultConfig`], each request is run through a [`FaultInjector`] which may
+//! delay it, drop it (answering `503 Service Unavailable` without forwarding) or
+//! answer transaction submissions with `BlockhashNotFound` / `AccountInUse`
+//! errors shaped exactly like surfpool's own.
+
+use anyhow::{Context, Result};
+use axum::{
+    Json, Router,
+    body::Bytes,
+    extract::State,
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::post,
+};
+use reev_lib::faults::{FaultConfig, FaultDecision, FaultInjector, InjectedError};
+use serde_json::{Value, json};
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicU32, Ordering},
+};
+use tokio::{net::TcpListener, sync::oneshot, task::JoinHandle};
+use tracing::{debug, info, warn};
+
+/// JSON-RPC error code surfpool uses for failed preflight simulation
+const PREFLIGHT_FAILURE_CODE: i64 = -32002;
+
+/// Shared state between the proxy server and its handle
+struct ProxyState {
+    upstream: String,
+    client: reqwest::Client,
+    injector: Mutex<Option<FaultInjector>>,
+    injected: AtomicU32,
+}
+
+impl ProxyState {
+    fn decide(&self, method: &str) -> FaultDecision {
+        let mut injector = self.injector.lock().unwrap_or_else(|e| e.into_inner());
+        let decision = match injector.as_mut() {
+            Some(injector) => injector.decide(method),
+            None => FaultDecision::default(),
+        };
+        if decision.is_fault() {
+            self.injected.fetch_add(1, Ordering::SeqCst);
+        }
+        decision
+    }
+}
+
+/// Failure-injection proxy server
+pub struct FaultProxy;
+
+impl FaultProxy {
+    /// Start the proxy on `port`, forwarding to `upstream`
+    pub async fn start(port: u16, upstream: String) -> Result<FaultProxyHandle> {
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .await
+            .with_context(|| format!("Failed to bind fault proxy on port {port}"))?;
+        let addr = listener.local_addr()?;
+
+        let state = Arc::new(ProxyState {
+            upstream,
+            client: reqwest::Client::new(),
+            injector: Mutex::new(None),
+            injected: AtomicU32::new(0),
+        });
+        let app = Router::new()
+            .route("/", post(handle_rpc))
+            .with_state(Arc::clone(&state));
+
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let task = tokio::spawn(async move {
+            let server = axum::serve(listener, app).with_graceful_shutdown(async {
+                let _ = shutdown_rx.await;
+            });
+            if let Err(e) = server.await {
+                warn!(error = %e, "Fault proxy stopped with error");
+            }
+        });
+
+        info!(%addr, upstream = %state.upstream, "Fault proxy started");
+        Ok(FaultProxyHandle {
+            url: format!("http://{addr}"),
+            state,
+            shutdown: Arc::new(Mutex::new(Some(shutdown_tx))),
+            task: Arc::new(Mutex::new(Some(task))),
+        })
+    }
+}
+
+/// Handle to a running fault proxy
+#[derive(Clone)]
+pub struct FaultProxyHandle {
+    url: String,
+    state: Arc<ProxyState>,
+    shutdown: Arc<Mutex<Option<oneshot::Sender<()>>>>,
+    task: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+impl FaultProxyHandle {
+    /// URL clients should use instead of surfpool's
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Start injecting faults according to `config`
+    pub fn arm(&self, config: FaultConfig) {
+        debug!(rules = config.rules.len(), "Arming fault proxy");
+        *self
+            .state
+            .injector
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = Some(FaultInjector::new(config));
+    }
+
+    /// Stop injecting faults; requests are forwarded untouched
+    pub fn disarm(&self) {
+        debug!("Disarming fault proxy");
+        *self
+            .state
+            .injector
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    /// Number of requests that had a fault injected since the proxy started
+    pub fn injected_count(&self) -> u32 {
+        self.state.injected.load(Ordering::SeqCst)
+    }
+
+    /// Stop the proxy server
+    pub async fn shutdown(&self) {
+        if let Some(tx) = self
+            .shutdown
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+        {
+            let _ = tx.send(());
+        }
+        let task = self.task.lock().unwrap_or_else(|e| e.into_inner()).take();
+        if let Some(task) = task {
+            let _ = task.await;
+        }
+        debug!("Fault proxy stopped");
+    }
+}
+
+async fn handle_rpc(State(state): State<Arc<ProxyState>>, body: Bytes) -> Response {
+    let request: Option<Value> = serde_json::from_slice(&body).ok();
+    // Batches are forwarded as-is; only single requests are subject to faults
+    let method = request
+        .as_ref()
+        .and_then(|r| r.get("method"))
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let id = request
+        .as_ref()
+        .and_then(|r| r.get("id"))
+        .cloned()
+        .unwrap_or(Value::Null);
+
+    let decision = state.decide(method);
+    if !decision.latency.is_zero() {
+        debug!(method, latency = ?decision.latency, "Injecting latency");
+        tokio::time::sleep(decision.latency).await;
+    }
+    if decision.drop {
+        debug!(method, "Dropping request");
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "request dropped by fault proxy",
+        )
+            .into_response();
+    }
+    if let Some(error) = decision.error {
+        debug!(
+            method,
+            error = error.transaction_error(),
+            "Injecting RPC error"
+        );
+        return Json(injected_error_response(id, method, error)).into_response();
+    }
+
+    forward(&state, body).await
+}
+
+async fn forward(state: &ProxyState, body: Bytes) -> Response {
+    let upstream = state
+        .client
+        .post(&state.upstream)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(body)
+        .send()
+        .await;
+
+    match upstream {
+        Ok(response) => {
+            let status = response.status();
+            match response.bytes().await {
+                Ok(bytes) => (
+                    StatusCode::from_u16(status.as_u16()).unwrap_or(StatusCode::BAD_GATEWAY),
+                    [(header::CONTENT_TYPE, "application/json")],
+                    bytes,
+                )
+                    .into_response(),
+                Err(e) => (StatusCode::BAD_GATEWAY, e.to_string()).into_response(),
+            }
+        }
+        Err(e) => {
+            warn!(error = %e, "Fault proxy failed to reach upstream");
+            (StatusCode::BAD_GATEWAY, e.to_string()).into_response()
+        }
+    }
+}
+
+/// Builds the response surfpool would give for a transaction failing with `error`
+fn injected_error_response(id: Value, method: &str, error: InjectedError) -> Value {
+    let simulation = json!({
+        "err": error.transaction_error(),
+        "logs": [],
+        "accounts": null,
+        "unitsConsumed": 0,
+        "returnData": null,
+    });
+
+    if method == "simulateTransaction" {
+        // Simulation reports transaction errors in the result, not as an RPC error
+        return json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": { "context": { "slot": 0 }, "value": simulation },
+        });
+    }
+
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {
+            "code": PREFLIGHT_FAILURE_CODE,
+            "message": format!("Transaction simulation failed: {}", error.message()),
+            "data": simulation,
+        },
+    })
+}
@@ -0,0 +1,9 @@
+//! Failure-injection proxy for dependency management
+//!
+//! This module provides a JSON-RPC proxy that sits between the environment and
+//! surfpool and injects latency, dropped requests and transaction errors
+//! according to a benchmark's `faults:` section.
+
+pub mod fault_proxy;
+
+pub use fault_proxy::{FaultProxy, FaultProxyHandle};
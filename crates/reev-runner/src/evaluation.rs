@@ -0,0 +1,115 @@
+//! Evaluation run provenance
+//!
+//! Every invocation of the runner is recorded as an [`EvaluationRun`] grouping the
+//! sessions it produced. The run keeps what is needed to tell runs apart and repeat
+//! them: the agent settings, the repository commit, a hash of each benchmark file
+//! and the seed. A run interrupted midway can be resumed from what it recorded.
+
+use crate::selection::discover_benchmarks;
+use anyhow::{Context, Result};
+use reev_db::{EvaluationRun, RunAgentConfig};
+use reev_lib::benchmark::TestCase;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Status of a run still in progress
+pub const RUN_RUNNING: &str = "running";
+/// Status of a run whose benchmarks all finished
+pub const RUN_COMPLETED: &str = "completed";
+/// Status of a run aborted by an error
+pub const RUN_FAILED: &str = "failed";
+
+/// Describe a new run of `trials` attempts at each of `benchmark_paths` with
+/// `agent_name`, starting now
+pub fn new_evaluation_run(
+    agent_name: &str,
+    benchmark_paths: &[PathBuf],
+    trials: u32,
+    seed: Option<u64>,
+) -> Result<EvaluationRun> {
+    Ok(EvaluationRun {
+        run_id: uuid::Uuid::new_v4().to_string(),
+        agent_type: agent_name.to_string(),
+        agent_config: agent_config(agent_name),
+        git_commit: git_commit(),
+        benchmark_hashes: benchmark_hashes(benchmark_paths)?,
+        seed,
+        trials,
+        status: RUN_RUNNING.to_string(),
+        start_time: chrono::Utc::now().timestamp(),
+        end_time: None,
+    })
+}
+
+/// The agent settings in effect for this process
+pub fn agent_config(agent_name: &str) -> RunAgentConfig {
+    RunAgentConfig {
+        model: agent_name.to_string(),
+        temperature: Some(f64::from(reev_lib::constants::temperature())),
+        prompt_version: reev_lib::constants::prompt_version(),
+    }
+}
+
+/// SHA-256 of each benchmark file, keyed by benchmark id
+pub fn benchmark_hashes(benchmark_paths: &[PathBuf]) -> Result<BTreeMap<String, String>> {
+    benchmark_paths
+        .iter()
+        .map(|path| benchmark_hash(path))
+        .collect()
+}
+
+/// Benchmark id and SHA-256 of one benchmark file
+fn benchmark_hash(path: &Path) -> Result<(String, String)> {
+    let content =
+        fs::read(path).with_context(|| format!("Failed to read benchmark: {}", path.display()))?;
+    let test_case: TestCase = serde_yaml::from_slice(&content)
+        .with_context(|| format!("Failed to parse benchmark: {}", path.display()))?;
+    Ok((test_case.id, format!("{:x}", Sha256::digest(&content))))
+}
+
+/// Locate the benchmark files of `run` under `path` to resume it
+///
+/// Fails if a benchmark of the run is missing or its file changed since the run
+/// started, as resuming would then mix results of different benchmarks.
+pub fn resume_benchmarks(run: &EvaluationRun, path: &Path) -> Result<Vec<PathBuf>> {
+    let mut found: BTreeMap<String, (PathBuf, String)> = BTreeMap::new();
+    for benchmark_path in discover_benchmarks(path)? {
+        let (id, hash) = benchmark_hash(&benchmark_path)?;
+        found.insert(id, (benchmark_path, hash));
+    }
+
+    run.benchmark_hashes
+        .iter()
+        .map(|(id, hash)| match found.remove(id) {
+            Some((benchmark_path, current)) if current == *hash => Ok(benchmark_path),
+            Some((benchmark_path, _)) => anyhow::bail!(
+                "Benchmark '{id}' ({}) changed since run {} started; start a new run instead",
+                benchmark_path.display(),
+                run.run_id
+            ),
+            None => anyhow::bail!(
+                "Benchmark '{id}' of run {} not found in '{}'",
+                run.run_id,
+                path.display()
+            ),
+        })
+        .collect()
+}
+
+/// Commit of the working directory's repository, if it is one
+pub fn git_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|commit| !commit.is_empty())
+}
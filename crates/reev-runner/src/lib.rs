@@ -1,30 +1,48 @@
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Context, Result};
 
 use reev_lib::{
-    agent::{Agent, AgentObservation},
-    benchmark::{FlowStep, TestCase},
+    agent::{Agent, AgentAction, AgentObservation},
+    benchmark::{FlowStep, GroundTruth, TestCase},
     db::{DatabaseConfig, DatabaseWriter, FlowDatabaseWriter},
-    env::GymEnv,
+    efficiency::EfficiencyMetrics,
+    env::{GymEnv, Step},
+    faults::{FaultConfig, RecoveryStats},
     flow::{ExecutionResult, FlowLogger, create_session_logger},
     llm_agent::LlmAgent,
-    results::{FinalStatus, TestResult},
-    score::calculate_final_score,
+    results::{FinalStatus, LlmUsage, TestResult},
+    score::{
+        BUDGET_EXCEEDED_MAX_SCORE, calculate_detailed_score_with_metrics,
+        calculate_final_score_with_metrics,
+    },
     server_utils::{kill_existing_reev_agent, kill_existing_surfpool},
     solana_env::environment::SolanaEnv,
     trace::ExecutionTrace,
 };
 use std::{
-    fs,
+    collections::BTreeSet,
+    fmt, fs,
+    future::Future,
     path::{Path, PathBuf},
     sync::Arc,
-    time::SystemTime,
+    time::{Duration, Instant, SystemTime},
 };
+use tokio::sync::{Mutex, Semaphore};
 use tracing::{debug, info, instrument, warn};
 
-use crate::dependency::{DependencyConfig, DependencyManager};
+use crate::{
+    dependency::{DependencyConfig, DependencyManager, FaultProxyHandle},
+    evaluation::{RUN_COMPLETED, RUN_FAILED, new_evaluation_run},
+    matrix::ProviderPermits,
+    selection::{BenchmarkSelection, discover_benchmarks},
+};
 
+pub mod cli;
 pub mod dependency;
+pub mod evaluation;
+pub mod matrix;
+pub mod output;
 pub mod renderer;
+pub mod selection;
 
 #[allow(dead_code)]
 const AGENT_PORT: u16 = 9090;
@@ -42,6 +60,59 @@ impl Drop for DependencyManagerGuard {
     }
 }
 
+/// A benchmark's `faults:` section together with the proxy that applies it
+#[derive(Clone, Copy)]
+struct FaultInjection<'a> {
+    config: &'a FaultConfig,
+    proxy: &'a FaultProxyHandle,
+    /// The attempt's seed, used when the benchmark's `faults:` sets none
+    seed: Option<u64>,
+}
+
+impl<'a> FaultInjection<'a> {
+    fn new(
+        test_case: &'a TestCase,
+        proxy: Option<&'a FaultProxyHandle>,
+        seed: Option<u64>,
+    ) -> Option<Self> {
+        Some(Self {
+            config: test_case.faults.as_ref()?,
+            proxy: proxy?,
+            seed,
+        })
+    }
+
+    /// Arm the proxy for an episode; injection stops when the guard is dropped
+    fn arm(&self) -> ArmedFaults<'a> {
+        let mut config = self.config.clone();
+        config.seed = config.seed.or(self.seed);
+        self.proxy.arm(config);
+        ArmedFaults(self.proxy)
+    }
+}
+
+/// RAII guard that disarms the fault proxy at the end of an episode
+struct ArmedFaults<'a>(&'a FaultProxyHandle);
+
+impl Drop for ArmedFaults<'_> {
+    fn drop(&mut self) {
+        self.0.disarm();
+    }
+}
+
+/// The Solana environment of an attempt, shared with the blocking threads its
+/// steps run on (see [`step_env`])
+type SharedEnv = Arc<Mutex<SolanaEnv>>;
+
+/// Create the Solana environment, routed through the fault proxy when injecting faults
+fn create_env(faults: Option<FaultInjection<'_>>) -> Result<SharedEnv> {
+    let env = match faults {
+        Some(faults) => SolanaEnv::with_rpc_url(faults.proxy.url()),
+        None => SolanaEnv::new(),
+    }?;
+    Ok(Arc::new(Mutex::new(env)))
+}
+
 /// Initialize dependencies with custom configuration
 async fn init_dependencies_with_config(config: DependencyConfig) -> Result<DependencyManagerGuard> {
     debug!("Initializing dependency management...");
@@ -88,6 +159,112 @@ async fn init_dependencies_with_config(config: DependencyConfig) -> Result<Depen
     Ok(guard)
 }
 
+/// How a set of benchmarks is run
+#[derive(Debug, Clone)]
+pub struct RunOptions {
+    /// Reuse existing service instances instead of creating fresh ones
+    pub shared_surfpool: bool,
+    /// Kill existing API processes before starting
+    pub kill_api: bool,
+    /// Number of times each benchmark is run, each in a fresh session
+    pub trials: u32,
+    /// Record results under this existing evaluation run instead of creating one.
+    /// The caller is then responsible for completing the run.
+    pub run_id: Option<String>,
+    /// Seed of the run's provider sampling and fault injection, recorded with a
+    /// newly created evaluation run
+    pub seed: Option<u64>,
+    /// Continue this interrupted evaluation run instead of starting one: attempts
+    /// it already recorded are skipped, its sessions left `running` are marked
+    /// `interrupted`, and its own trial count applies
+    pub resume: Option<String>,
+    /// Time limit of each attempt, all flow steps included, in benchmarks that
+    /// set no `timeout` of their own
+    pub timeout: Option<Duration>,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            shared_surfpool: false,
+            kill_api: false,
+            trials: 1,
+            run_id: None,
+            seed: None,
+            resume: None,
+            timeout: None,
+        }
+    }
+}
+
+/// The evaluation run attempts are recorded under
+struct RunRecord {
+    run_id: String,
+    /// Whether this invocation completes the run when done
+    owned: bool,
+    trials: u32,
+    /// Attempts recorded before a resume, as benchmark id and trial index
+    completed: BTreeSet<(String, u32)>,
+    /// Attempt time limit applied where the benchmark sets none
+    timeout: Option<Duration>,
+    /// Seed the attempts' seeds are derived from
+    seed: Option<u64>,
+}
+
+/// Identity of one attempt at a benchmark
+#[derive(Debug, Clone, Copy)]
+struct Attempt<'a> {
+    session_id: &'a str,
+    trial_index: u32,
+    run_id: &'a str,
+    /// The run's seed offset by the trial index, so trials differ but each can be
+    /// reproduced
+    seed: Option<u64>,
+}
+
+/// End of an attempt limited by its benchmark's `timeout` or `run --timeout`
+#[derive(Debug, Clone, Copy)]
+struct Deadline {
+    at: Instant,
+    limit: Duration,
+}
+
+impl Deadline {
+    /// A deadline `limit` from now
+    fn after(limit: Option<Duration>) -> Option<Self> {
+        limit.map(|limit| Self {
+            at: Instant::now() + limit,
+            limit,
+        })
+    }
+}
+
+/// An agent step that did not finish within its time limit
+#[derive(Debug)]
+struct StepTimedOut {
+    /// Flow step number, `None` for a single-step benchmark
+    step: Option<u32>,
+    limit: Duration,
+    /// Whether the limit was the whole attempt's rather than the flow step's own
+    attempt: bool,
+}
+
+impl fmt::Display for StepTimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = self.limit.as_secs();
+        match (self.attempt, self.step) {
+            (true, Some(step)) => {
+                write!(f, "Benchmark timed out after {secs}s in flow step {step}")
+            }
+            (true, None) => write!(f, "Benchmark timed out after {secs}s"),
+            (false, Some(step)) => write!(f, "Flow step {step} timed out after {secs}s"),
+            (false, None) => write!(f, "Step timed out after {secs}s"),
+        }
+    }
+}
+
+impl std::error::Error for StepTimedOut {}
+
 /// Runs all benchmarks found at given path and returns results.
 /// If shared_surfpool is true, reuses existing service instances.
 /// If false, creates fresh instances for each run.
@@ -99,448 +276,519 @@ pub async fn run_benchmarks(
     kill_api: bool,
 ) -> Result<Vec<TestResult>> {
     let benchmark_paths = discover_benchmarks(&path)?;
-    if benchmark_paths.is_empty() {
-        return Ok(vec![]);
-    }
-
-    // Kill any existing API processes only if explicitly requested
-    if kill_api {
-        reev_lib::server_utils::kill_existing_api(3001).await?;
-    }
-
-    // Clean up any stale database WAL files that might cause lock issues
-    cleanup_stale_database_files().await?;
-
-    // Initialize dependency management system based on shared_surfpool flag
-    if shared_surfpool {
-        info!("🔴 Using shared surfpool mode - reusing existing instances...");
-    } else {
-        info!("✨ Using fresh surfpool mode - creating new instances...");
-    }
-
-    let mut dependency_guard = init_dependencies_with_config(DependencyConfig {
-        shared_instances: shared_surfpool,
-        agent_type: Some(agent_name.to_string()),
+    let options = RunOptions {
+        shared_surfpool,
+        kill_api,
         ..Default::default()
-    })
-    .await
-    .context("Failed to initialize dependencies")?;
-    info!("Dependency initialization completed successfully");
-
-    info!("Initializing database...");
-    let db_config = DatabaseConfig::new("db/reev_results.db");
-    let db_writer = DatabaseWriter::new(db_config).await?;
-
-    // Sync benchmarks to database before wrapping
-    info!("Syncing benchmarks to database...");
-    match db_writer.sync_benchmarks_from_dir("benchmarks").await {
-        Ok(sync_result) => {
-            info!(
-                "✅ Successfully synced {} benchmarks to database (new: {}, updated: {})",
-                sync_result.processed_count, sync_result.new_count, sync_result.updated_count
-            );
-        }
-        Err(e) => {
-            warn!(
-                "⚠️ Failed to sync benchmarks to database: {}. Continuing without sync...",
-                e
-            );
-        }
-    }
+    };
+    run_benchmark_paths(benchmark_paths, agent_name, &options).await
+}
 
-    let db = Arc::new(FlowDatabaseWriter::new(db_writer));
-    info!("Database initialization completed");
+/// Runs the benchmarks at `path` that match `selection`.
+pub async fn run_selected_benchmarks(
+    path: &Path,
+    selection: &BenchmarkSelection,
+    agent_name: &str,
+    options: &RunOptions,
+) -> Result<Vec<TestResult>> {
+    let benchmark_paths = selection
+        .resolve(path)?
+        .into_iter()
+        .map(|benchmark| benchmark.path)
+        .collect();
+    run_benchmark_paths(benchmark_paths, agent_name, options).await
+}
 
-    let mut results = vec![];
+/// Results of one agent of a matrix run
+#[derive(Debug)]
+pub struct AgentResults {
+    pub agent: String,
+    /// Evaluation run the agent's attempts were recorded under
+    pub run_id: String,
+    /// The agent's results, or the error that stopped its run
+    pub results: Result<Vec<TestResult>>,
+}
 
-    for path in benchmark_paths {
-        info!(path = %path.display(), "Running benchmark");
-        info!("Loading benchmark configuration...");
-        let f = fs::File::open(&path)?;
-        let test_case: TestCase = serde_yaml::from_reader(f)?;
-        info!(id = %test_case.id, "Loaded test case");
+/// Dependencies and database shared by every attempt of an invocation
+struct Harness {
+    dependencies: tokio::sync::Mutex<DependencyManagerGuard>,
+    db: Arc<FlowDatabaseWriter>,
+    /// Held through each episode with injected faults, as the fault proxy applies
+    /// the faults of one benchmark at a time
+    fault_lock: tokio::sync::Mutex<()>,
+}
 
-        // Start reev-agent for this specific benchmark
-        info!(
-            "Starting reev-agent for benchmark: {} with agent: {}",
-            test_case.id, agent_name
-        );
-        dependency_guard
-            .manager
-            .update_config_and_restart_agent(
-                Some(agent_name.to_string()),
-                Some(test_case.id.clone()),
-            )
-            .await
-            .context("Failed to start reev-agent for benchmark")?;
-
-        // Check if this is a flow benchmark
-        if let Some(flow_steps) = &test_case.flow {
-            info!(
-                benchmark_id = %test_case.id,
-                steps_count = %flow_steps.len(),
-                "Detected flow benchmark, executing step-by-step"
-            );
+impl Harness {
+    /// Start surfpool and open the results database
+    ///
+    /// `agent_name` only names the reev-agent log files; `None` when several agents
+    /// share the reev-agent.
+    async fn start(options: &RunOptions, agent_name: Option<&str>) -> Result<Self> {
+        let RunOptions {
+            shared_surfpool,
+            kill_api,
+            ..
+        } = *options;
+
+        // Kill any existing API processes only if explicitly requested
+        if kill_api {
+            reev_lib::server_utils::kill_existing_api(3001).await?;
+        }
 
-            // Generate session_id for flow benchmark
-            let session_id = uuid::Uuid::new_v4().to_string();
+        // Clean up any stale database WAL files that might cause lock issues
+        cleanup_stale_database_files().await?;
 
-            let result = run_flow_benchmark(
-                &test_case,
-                flow_steps,
-                agent_name,
-                &path.display().to_string(),
-                Arc::clone(&db),
-                &session_id,
-            )
-            .await?;
-            results.push(result);
+        // Initialize dependency management system based on shared_surfpool flag
+        if shared_surfpool {
+            info!("🔴 Using shared surfpool mode - reusing existing instances...");
+        } else {
+            info!("✨ Using fresh surfpool mode - creating new instances...");
+        }
 
-            // Stop reev-agent after flow benchmark completion
-            info!("Stopping reev-agent after flow benchmark: {}", test_case.id);
-            if let Err(e) = dependency_guard.manager.stop_reev_agent().await {
+        let dependency_guard = init_dependencies_with_config(DependencyConfig {
+            shared_instances: shared_surfpool,
+            agent_type: agent_name.map(str::to_string),
+            ..DependencyConfig::from_config(reev_config::get())
+        })
+        .await
+        .context("Failed to initialize dependencies")?;
+        info!("Dependency initialization completed successfully");
+
+        info!("Initializing database...");
+        let db_config = DatabaseConfig::from_settings(&reev_config::get().database);
+        let db_writer = DatabaseWriter::new(db_config).await?;
+
+        // Sync benchmarks to database before wrapping
+        info!("Syncing benchmarks to database...");
+        match db_writer.sync_benchmarks_from_dir("benchmarks").await {
+            Ok(sync_result) => {
+                info!(
+                    "✅ Successfully synced {} benchmarks to database (new: {}, updated: {})",
+                    sync_result.processed_count, sync_result.new_count, sync_result.updated_count
+                );
+            }
+            Err(e) => {
                 warn!(
-                    benchmark_id = %test_case.id,
-                    error = %e,
-                    "Failed to stop reev-agent gracefully after flow benchmark"
+                    "⚠️ Failed to sync benchmarks to database: {}. Continuing without sync...",
+                    e
                 );
             }
-
-            continue;
         }
 
-        // Initialize unified session logging if enabled
-        let session_id = uuid::Uuid::new_v4().to_string();
-        // Flow logging is always enabled
-        let log_path =
-            std::env::var("REEV_SESSION_LOG_PATH").unwrap_or_else(|_| "logs/sessions".to_string());
-        let path = PathBuf::from(log_path);
-        std::fs::create_dir_all(&path)?;
+        let db = Arc::new(FlowDatabaseWriter::new(db_writer));
+        info!("Database initialization completed");
 
-        let session_logger = Some(create_session_logger(
-            session_id.clone(),
-            test_case.id.clone(),
-            agent_name.to_string(),
-            Some(path),
-        )?);
-
-        // Create session in database
-        let start_time = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
-
-        let session_info = reev_lib::db::SessionInfo {
-            session_id: session_id.clone(),
-            benchmark_id: test_case.id.clone(),
-            agent_type: agent_name.to_string(),
-            interface: "tui".to_string(),
-            start_time,
-            end_time: None,
-            status: "running".to_string(),
-            score: None,
-            final_status: None,
-        };
+        Ok(Self {
+            dependencies: tokio::sync::Mutex::new(dependency_guard),
+            db,
+            fault_lock: tokio::sync::Mutex::new(()),
+        })
+    }
 
-        if let Err(e) = db.create_session(&session_info).await {
-            warn!(
-                benchmark_id = %test_case.id,
-                session_id = %session_id,
-                error = %e,
-                "Failed to create session in database"
-            );
+    /// Check that the models of `agents` can be served before any benchmark runs
+    async fn ensure_models(&self, agents: &[&str]) -> Result<()> {
+        let dependencies = self.dependencies.lock().await;
+        for agent in agents {
+            dependencies
+                .manager
+                .ensure_model(agent)
+                .await
+                .with_context(|| format!("Model of agent '{agent}' is not available"))?;
+        }
+        Ok(())
+    }
+
+    /// Close the database connection properly to prevent lock issues
+    async fn close(self) {
+        info!("Closing database connection...");
+        if let Err(e) = self.db.close().await {
+            warn!(error = %e, "Failed to close database connection gracefully");
         } else {
-            info!(
-                benchmark_id = %test_case.id,
-                session_id = %session_id,
-                "Created session in database"
-            );
+            info!("Database connection closed successfully");
         }
+    }
+}
+
+/// Runs the given benchmark files with a single set of dependencies.
+///
+/// All attempts are recorded under one evaluation run, created here unless
+/// [`RunOptions::run_id`] names an existing one. With more than one trial, results
+/// of the same benchmark are consecutive and ordered by their `trial_index`.
+pub async fn run_benchmark_paths(
+    benchmark_paths: Vec<PathBuf>,
+    agent_name: &str,
+    options: &RunOptions,
+) -> Result<Vec<TestResult>> {
+    if benchmark_paths.is_empty() {
+        return Ok(vec![]);
+    }
+    let harness = Harness::start(options, Some(agent_name)).await?;
+    harness.ensure_models(&[agent_name]).await?;
+
+    let run = open_run(&harness.db, &benchmark_paths, agent_name, options).await?;
+    info!(run_id = %run.run_id, "Recording results under evaluation run");
+
+    let outcome = run_attempts(&benchmark_paths, agent_name, &run, &harness, None).await;
+    finish_run(&harness.db, &run, outcome.is_ok()).await;
+
+    info!("All benchmarks finished.");
+    harness.close().await;
+    outcome
+}
 
-        let mut llm_agent = LlmAgent::new_with_flow_logging(agent_name, None)?;
-        info!("[Runner] Setting session_id on LlmAgent: {}", session_id);
-        llm_agent.set_session_id(session_id.clone());
-        info!("[Runner] Session_id set successfully");
-        let mut agent = Box::new(llm_agent) as Box<dyn Agent + Send>;
-        let mut env = SolanaEnv::new().context("Failed to create Solana environment")?;
+/// Runs the given benchmark files with each of `agents` on one set of dependencies.
+///
+/// Every agent is recorded under an evaluation run of its own and runs its
+/// attempts in order, while agents run concurrently: each attempt first takes a
+/// permit of its provider from `permits`. One reev-agent serves all agents, as the
+/// model is chosen per request. Results are returned in the order of `agents`; an
+/// error in one agent's run does not stop the others.
+pub async fn run_agent_matrix(
+    benchmark_paths: Vec<PathBuf>,
+    agents: &[String],
+    options: &RunOptions,
+    permits: &ProviderPermits,
+) -> Result<Vec<AgentResults>> {
+    if options.run_id.is_some() || options.resume.is_some() {
+        anyhow::bail!("A matrix run records each agent under a new evaluation run");
+    }
+    if benchmark_paths.is_empty() || agents.is_empty() {
+        return Ok(vec![]);
+    }
+    let harness = Arc::new(Harness::start(options, None).await?);
+    let agent_names: Vec<&str> = agents.iter().map(String::as_str).collect();
+    harness.ensure_models(&agent_names).await?;
+    harness
+        .dependencies
+        .lock()
+        .await
+        .manager
+        .update_config_and_restart_agent(None, None)
+        .await
+        .context("Failed to start reev-agent for the matrix run")?;
+    for (provider, limit) in permits.limits() {
+        info!(provider, limit, "Provider concurrency limit");
+    }
 
-        let options = serde_json::to_value(&test_case)
-            .context("Failed to serialize test case for env options")?;
-        let initial_observation = env.reset(None, Some(options)).await?;
+    let mut runs = Vec::with_capacity(agents.len());
+    for agent in agents {
+        let run = open_run(&harness.db, &benchmark_paths, agent, options).await?;
+        info!(agent = %agent, run_id = %run.run_id, "Recording agent results under evaluation run");
+        runs.push(Arc::new(run));
+    }
 
-        let (final_observation, trace, actions) =
-            match run_evaluation_loop(&mut env, agent.as_mut(), &test_case, &initial_observation)
+    // Each agent on its own task, so a blocked attempt holds up no other agent
+    let tasks: Vec<_> = agents
+        .iter()
+        .zip(&runs)
+        .map(|(agent, run)| {
+            let benchmark_paths = benchmark_paths.clone();
+            let agent = agent.clone();
+            let run = Arc::clone(run);
+            let harness = Arc::clone(&harness);
+            let agent_permits = permits.for_agent(&agent);
+            tokio::spawn(async move {
+                run_attempts(
+                    &benchmark_paths,
+                    &agent,
+                    &run,
+                    &harness,
+                    Some(&agent_permits),
+                )
                 .await
-            {
-                Ok(result) => result,
-                Err(e) => {
-                    // Ensure session is marked as failed even if evaluation loop fails
-                    let error_session_result = reev_lib::db::SessionResult {
-                        end_time: std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap()
-                            .as_secs() as i64,
-                        score: 0.0,
-                        final_status: FinalStatus::Failed.to_string(),
-                    };
-
-                    if let Err(db_err) = db
-                        .complete_session(&session_id, &error_session_result)
-                        .await
-                    {
-                        warn!(
-                            benchmark_id = %test_case.id,
-                            session_id = %session_id,
-                            error = %db_err,
-                            "Failed to complete session after evaluation failure"
-                        );
-                    }
+            })
+        })
+        .collect();
 
-                    return Err(e).context(format!(
-                        "Evaluation loop failed for benchmark: {}",
-                        test_case.id
-                    ));
-                }
-            };
+    let mut results = Vec::with_capacity(agents.len());
+    for ((agent, run), task) in agents.iter().zip(runs).zip(tasks) {
+        let outcome = task
+            .await
+            .unwrap_or_else(|e| Err(anyhow::anyhow!("Agent run panicked: {e}")));
+        finish_run(&harness.db, &run, outcome.is_ok()).await;
+        if let Err(e) = &outcome {
+            warn!(agent = %agent, run_id = %run.run_id, error = %e, "Agent run failed");
+        }
+        results.push(AgentResults {
+            agent: agent.clone(),
+            run_id: run.run_id.clone(),
+            results: outcome,
+        });
+    }
 
-        // Use the new comprehensive scoring function from reev-lib.
-        // Use the new comprehensive scoring function.
-        let score = calculate_final_score(
-            &test_case,
-            &actions,
-            &initial_observation,
-            &final_observation,
-        );
+    if let Err(e) = harness
+        .dependencies
+        .lock()
+        .await
+        .manager
+        .stop_reev_agent()
+        .await
+    {
+        warn!(error = %e, "Failed to stop reev-agent gracefully");
+    }
+    info!("All agents finished.");
+    if let Some(harness) = Arc::into_inner(harness) {
+        harness.close().await;
+    }
+    Ok(results)
+}
+
+/// Complete `run` as completed or failed, if this invocation owns it
+async fn finish_run(db: &FlowDatabaseWriter, run: &RunRecord, succeeded: bool) {
+    if !run.owned {
+        return;
+    }
+    let status = if succeeded { RUN_COMPLETED } else { RUN_FAILED };
+    if let Err(e) = db
+        .complete_run(&run.run_id, status, chrono::Utc::now().timestamp())
+        .await
+    {
+        warn!(run_id = %run.run_id, error = %e, "Failed to complete evaluation run");
+    }
+}
 
+/// Create, attach to or resume the evaluation run described by `options`
+async fn open_run(
+    db: &FlowDatabaseWriter,
+    benchmark_paths: &[PathBuf],
+    agent_name: &str,
+    options: &RunOptions,
+) -> Result<RunRecord> {
+    if let Some(run_id) = &options.resume {
+        let run = db
+            .get_run(run_id)
+            .await?
+            .with_context(|| format!("No evaluation run '{run_id}' to resume"))?;
+        if run.agent_type != agent_name {
+            anyhow::bail!(
+                "Run {run_id} was made with agent '{}', not '{agent_name}'",
+                run.agent_type
+            );
+        }
+        let interrupted = db
+            .interrupt_run_sessions(run_id, chrono::Utc::now().timestamp())
+            .await?;
+        let completed = db.completed_attempts(run_id).await?;
+        db.reopen_run(run_id).await?;
         info!(
-            benchmark_id = %test_case.id,
-            score = %score,
-            instructions_count = %actions.len(),
-            "Benchmark scoring completed"
+            run_id = %run_id,
+            interrupted_sessions = interrupted,
+            completed_attempts = completed.len(),
+            "Resuming evaluation run"
         );
+        return Ok(RunRecord {
+            run_id: run_id.clone(),
+            owned: true,
+            trials: run.trials,
+            completed,
+            timeout: options.timeout,
+            seed: run.seed,
+        });
+    }
 
-        // Complete session logging if enabled
-        if let Some(session_logger) = session_logger {
-            let start_time = SystemTime::now()
-                .duration_since(SystemTime::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs();
-
-            let _total_time_ms = (SystemTime::now()
-                .duration_since(SystemTime::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs()
-                - start_time)
-                * 1000;
-
-            let _final_status = if final_observation.last_transaction_status == "Success" {
-                FinalStatus::Succeeded
-            } else {
-                FinalStatus::Failed
-            };
-
-            // Store ExecutionTrace format directly for ASCII tree compatibility
-            match session_logger.complete_with_trace(trace.clone()) {
-                Ok(log_file) => {
-                    info!(
-                        benchmark_id = %test_case.id,
-                        log_file = %log_file.display(),
-                        "Session log with ExecutionTrace completed successfully"
-                    );
-
-                    // Store ExecutionTrace directly in database for ASCII tree compatibility
-                    let trace_content = serde_json::to_string(&trace).unwrap_or_default();
+    let (run_id, owned) = match &options.run_id {
+        Some(run_id) => (run_id.clone(), false),
+        None => {
+            let run =
+                new_evaluation_run(agent_name, benchmark_paths, options.trials, options.seed)?;
+            db.create_run(&run)
+                .await
+                .context("Failed to record evaluation run")?;
+            (run.run_id, true)
+        }
+    };
+    Ok(RunRecord {
+        run_id,
+        owned,
+        trials: options.trials,
+        completed: BTreeSet::new(),
+        timeout: options.timeout,
+        seed: options.seed,
+    })
+}
 
-                    if let Err(e) = db.store_complete_log(&session_id, &trace_content).await {
-                        warn!(
-                            benchmark_id = %test_case.id,
-                            error = %e,
-                            "Failed to store ExecutionTrace in database"
-                        );
-                    } else {
-                        info!(
-                            benchmark_id = %test_case.id,
-                            session_id = %session_id,
-                            "ExecutionTrace stored in database for ASCII tree compatibility"
-                        );
-                    }
-                }
-                Err(e) => {
-                    warn!(
-                        benchmark_id = %test_case.id,
-                        error = %e,
-                        "Failed to complete session logging with ExecutionTrace"
-                    );
+/// Run every trial of every benchmark not yet recorded under `run`
+///
+/// Alone, the agent gets a reev-agent of its own per attempt. In a matrix run,
+/// `provider_permits` bounds the attempts of the agent's provider running at once
+/// and the reev-agent is shared with the other agents.
+async fn run_attempts(
+    benchmark_paths: &[PathBuf],
+    agent_name: &str,
+    run: &RunRecord,
+    harness: &Harness,
+    provider_permits: Option<&Semaphore>,
+) -> Result<Vec<TestResult>> {
+    let db = &harness.db;
+    let shared_agent = provider_permits.is_some();
+    let mut results = vec![];
 
-                    // Fallback: store ExecutionTrace directly even if session logging fails
-                    let trace_content = serde_json::to_string(&trace).unwrap_or_default();
+    for path in benchmark_paths {
+        info!(path = %path.display(), "Running benchmark");
+        info!("Loading benchmark configuration...");
+        let f = fs::File::open(path)?;
+        let test_case: TestCase = serde_yaml::from_reader(f)?;
+        info!(id = %test_case.id, "Loaded test case");
 
-                    if let Err(e) = db.store_complete_log(&session_id, &trace_content).await {
-                        warn!(
-                            benchmark_id = %test_case.id,
-                            error = %e,
-                            "Failed to store fallback ExecutionTrace in database"
-                        );
-                    }
-                }
-            }
+        let pending: Vec<u32> = (0..run.trials)
+            .filter(|trial_index| {
+                !run.completed
+                    .contains(&(test_case.id.clone(), *trial_index))
+            })
+            .collect();
+        if pending.is_empty() {
+            info!(benchmark_id = %test_case.id, "Skipping benchmark, all trials already recorded");
+            continue;
         }
 
-        // A score >= 0.75 means the instruction was perfect, even if it failed on-chain.
-        // This is the primary signal for agent success.
-        let final_status = if score >= 0.75 {
-            FinalStatus::Succeeded
-        } else {
-            FinalStatus::Failed
+        // Benchmarks with a `faults:` section talk to surfpool through the fault proxy
+        let fault_proxy = match &test_case.faults {
+            Some(_) => Some(
+                harness
+                    .dependencies
+                    .lock()
+                    .await
+                    .manager
+                    .ensure_fault_proxy()
+                    .await
+                    .context("Failed to start fault proxy")?,
+            ),
+            None => None,
         };
 
-        // Complete session in database with results
-        let end_time = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
+        for trial_index in pending {
+            if run.trials > 1 {
+                info!(
+                    benchmark_id = %test_case.id,
+                    "Starting trial {}/{}",
+                    trial_index + 1,
+                    run.trials
+                );
+            }
 
-        let session_result = reev_lib::db::SessionResult {
-            end_time,
-            score,
-            final_status: final_status.to_string(),
-        };
+            let _permit = match provider_permits {
+                Some(permits) => Some(
+                    permits
+                        .acquire()
+                        .await
+                        .context("Provider concurrency limit closed")?,
+                ),
+                None => None,
+            };
+            let _faults_lock = match fault_proxy {
+                Some(_) => Some(harness.fault_lock.lock().await),
+                None => None,
+            };
 
-        // 🎯 CAPTURE TOOL CALLS FROM AGENT'S ENHANCED OTEL LOG FILES
-        // Since reev-agent runs in separate process, we need to read from its otel log files
-        let tool_calls = extract_tool_calls_from_agent_logs(&session_id).await;
+            if !shared_agent {
+                // Start reev-agent for this specific benchmark
+                info!(
+                    "Starting reev-agent for benchmark: {} with agent: {}",
+                    test_case.id, agent_name
+                );
+                harness
+                    .dependencies
+                    .lock()
+                    .await
+                    .manager
+                    .update_config_and_restart_agent(
+                        Some(agent_name.to_string()),
+                        Some(test_case.id.clone()),
+                    )
+                    .await
+                    .context("Failed to start reev-agent for benchmark")?;
+            }
 
-        if !tool_calls.is_empty() {
-            info!(
-                session_id = %session_id,
-                tool_calls_count = tool_calls.len(),
-                "Storing tool calls in database (from agent log files)"
-            );
+            // Every trial runs in a fresh session
+            let session_id = uuid::Uuid::new_v4().to_string();
+            let attempt = Attempt {
+                session_id: &session_id,
+                trial_index,
+                run_id: &run.run_id,
+                seed: run
+                    .seed
+                    .map(|seed| seed.wrapping_add(u64::from(trial_index))),
+            };
 
-            for tool_call in &tool_calls {
-                let tool_data = reev_db::writer::sessions::ToolCallData {
-                    session_id: session_id.clone(),
-                    tool_name: tool_call.tool_name.clone(),
-                    start_time: tool_call.timestamp.timestamp() as u64,
-                    execution_time_ms: tool_call.execution_time_ms,
-                    input_params: tool_call.input_params.clone(),
-                    output_result: tool_call.output_result.clone(),
-                    status: match tool_call.status {
-                        reev_flow::ToolExecutionStatus::Success => "success".to_string(),
-                        reev_flow::ToolExecutionStatus::Error => "error".to_string(),
-                        reev_flow::ToolExecutionStatus::Timeout => "timeout".to_string(),
-                    },
-                    error_message: tool_call.error_message.clone(),
-                };
-
-                if let Err(e) = db.store_tool_call_consolidated(&tool_data).await {
-                    warn!(
-                        session_id = %session_id,
-                        tool_name = %tool_call.tool_name,
-                        error = %e,
-                        "Failed to store consolidated tool call in database"
+            let result = match &test_case.flow {
+                Some(flow_steps) => {
+                    info!(
+                        benchmark_id = %test_case.id,
+                        steps_count = %flow_steps.len(),
+                        "Detected flow benchmark, executing step-by-step"
                     );
+                    run_flow_benchmark(
+                        &test_case,
+                        flow_steps,
+                        agent_name,
+                        Arc::clone(db),
+                        attempt,
+                        fault_proxy.as_ref(),
+                        run.timeout,
+                    )
+                    .await?
+                }
+                None => {
+                    run_single_benchmark(
+                        &test_case,
+                        agent_name,
+                        Arc::clone(db),
+                        attempt,
+                        fault_proxy.as_ref(),
+                        run.timeout,
+                    )
+                    .await?
                 }
+            };
+            // A timed out attempt has already cancelled its session's work in
+            // reev-agent, which leaves a shared reev-agent serving the other agents
+            results.push(result);
+            if shared_agent {
+                // Other agents still use the reev-agent; it is stopped after the matrix
+                continue;
             }
-        } else {
-            debug!("No tool calls found in agent log files");
-        }
 
-        if let Err(e) = db.complete_session(&session_id, &session_result).await {
-            warn!(
-                benchmark_id = %test_case.id,
-                session_id = %session_id,
-                error = %e,
-                "Failed to complete session in database"
-            );
-        } else {
-            info!(
-                benchmark_id = %test_case.id,
-                session_id = %session_id,
-                score = %score,
-                final_status = %final_status,
-                "Completed session in database"
-            );
+            // Stop reev-agent after benchmark completion
+            info!("Stopping reev-agent after benchmark: {}", test_case.id);
+            if let Err(e) = harness
+                .dependencies
+                .lock()
+                .await
+                .manager
+                .stop_reev_agent()
+                .await
+            {
+                warn!(
+                    benchmark_id = %test_case.id,
+                    error = %e,
+                    "Failed to stop reev-agent gracefully"
+                );
+            }
         }
+    }
 
-        // Store performance metrics
-        let performance_data = reev_lib::db::AgentPerformanceData {
-            session_id: session_id.clone(),
-            benchmark_id: test_case.id.clone(),
-            agent_type: agent_name.to_string(),
-            score,
-            final_status: match final_status {
-                FinalStatus::Succeeded => "succeeded".to_string(),
-                FinalStatus::Failed => "failed".to_string(),
-            },
-            execution_time_ms: (end_time - start_time) as u64,
-            timestamp: chrono::Utc::now().to_rfc3339(),
-            flow_log_id: None,
-            prompt_md5: None,
-        };
+    Ok(results)
+}
 
-        // Convert to shared AgentPerformance type for database insertion
-        let shared_performance = reev_lib::db::SharedPerformanceMetrics::from(performance_data);
-        db.insert_agent_performance(&shared_performance)
-            .await
-            .context("Failed to store performance metrics")?;
+/// Backward compatibility function - defaults to safe behavior (no API killing)
+pub async fn run_benchmarks_legacy(
+    path: PathBuf,
+    agent_name: &str,
+    shared_surfpool: bool,
+) -> Result<Vec<TestResult>> {
+    run_benchmarks(path, agent_name, shared_surfpool, false).await
+}
 
-        let result = TestResult::new(&test_case, final_status, score, trace);
-        results.push(result);
-
-        if let Err(e) = env.close() {
-            warn!(
-                benchmark_id = %test_case.id,
-                error = %e,
-                "Failed to close environment gracefully"
-            );
-        }
-
-        // Stop reev-agent after benchmark completion
-        info!("Stopping reev-agent after benchmark: {}", test_case.id);
-        if let Err(e) = dependency_guard.manager.stop_reev_agent().await {
-            warn!(
-                benchmark_id = %test_case.id,
-                error = %e,
-                "Failed to stop reev-agent gracefully"
-            );
-        }
-    }
-
-    info!("All benchmarks finished.");
-
-    // Close database connection properly to prevent lock issues
-    info!("Closing database connection...");
-    if let Err(e) = db.close().await {
-        warn!(error = %e, "Failed to close database connection gracefully");
-    } else {
-        info!("Database connection closed successfully");
-    }
-
-    Ok(results)
-}
-
-/// Backward compatibility function - defaults to safe behavior (no API killing)
-pub async fn run_benchmarks_legacy(
-    path: PathBuf,
-    agent_name: &str,
-    shared_surfpool: bool,
-) -> Result<Vec<TestResult>> {
-    run_benchmarks(path, agent_name, shared_surfpool, false).await
-}
-
-/// Clean up stale database WAL files that might cause lock issues
-async fn cleanup_stale_database_files() -> Result<()> {
-    use std::path::Path;
-
-    let db_path = Path::new("db/reev_results.db");
-    let wal_path = Path::new("db/reev_results.db-wal");
+/// Clean up stale database WAL files that might cause lock issues
+async fn cleanup_stale_database_files() -> Result<()> {
+    let db_path = &reev_config::get().database.path;
+    let wal_path = PathBuf::from(format!("{db_path}-wal"));
 
     // Check if WAL file exists but DB file is not locked
     if wal_path.exists() {
         // Try to check if any process is using the database
         match tokio::process::Command::new("lsof")
-            .args(["-t", db_path.to_str().unwrap_or("db/reev_results.db")])
+            .args(["-t", db_path])
             .output()
             .await
         {
@@ -549,7 +797,7 @@ async fn cleanup_stale_database_files() -> Result<()> {
                 if pids.trim().is_empty() {
                     // No processes using the DB, safe to remove WAL file
                     info!("🧹 Removing stale WAL file to prevent database lock issues");
-                    if let Err(e) = tokio::fs::remove_file(wal_path).await {
+                    if let Err(e) = tokio::fs::remove_file(&wal_path).await {
                         warn!("Failed to remove WAL file: {}", e);
                     } else {
                         info!("✅ Stale WAL file removed successfully");
@@ -580,7 +828,7 @@ async fn extract_tool_calls_from_agent_logs(session_id: &str) -> Vec<reev_flow::
     use std::path::Path;
 
     // Look for specific otel log file for this session
-    let logs_dir = Path::new("logs/sessions");
+    let logs_dir = Path::new(&reev_config::get().runner.session_log_dir);
     let otel_filename = format!("otel_{session_id}.jsonl");
     let otel_filepath = logs_dir.join(&otel_filename);
     let mut all_tool_calls = Vec::new();
@@ -621,48 +869,421 @@ async fn extract_tool_calls_from_agent_logs(session_id: &str) -> Vec<reev_flow::
     all_tool_calls
 }
 
+/// Execute a single-step benchmark in its own session
+async fn run_single_benchmark(
+    test_case: &TestCase,
+    agent_name: &str,
+    db: Arc<FlowDatabaseWriter>,
+    attempt: Attempt<'_>,
+    fault_proxy: Option<&FaultProxyHandle>,
+    default_timeout: Option<Duration>,
+) -> Result<TestResult> {
+    let Attempt {
+        session_id,
+        trial_index,
+        run_id,
+        seed,
+    } = attempt;
+    let started = Instant::now();
+
+    // Initialize unified session logging
+    let path = PathBuf::from(&reev_config::get().runner.session_log_dir);
+    std::fs::create_dir_all(&path)?;
+
+    let session_logger = Some(create_session_logger(
+        session_id.to_string(),
+        test_case.id.clone(),
+        agent_name.to_string(),
+        Some(path),
+    )?);
+
+    // Create session in database
+    start_session(&db, test_case, agent_name, session_id, run_id).await;
+
+    let mut llm_agent = LlmAgent::new_with_flow_logging(agent_name, None)?;
+    info!("[Runner] Setting session_id on LlmAgent: {}", session_id);
+    llm_agent.set_session_id(session_id.to_string());
+    info!("[Runner] Session_id set successfully");
+    llm_agent.set_ground_truth(&test_case.ground_truth);
+    llm_agent.set_budget(test_case.run_budget(reev_config::get().budget));
+    if let Some(seed) = seed {
+        llm_agent.set_seed(seed);
+    }
+    let mut agent = Box::new(llm_agent) as Box<dyn Agent + Send>;
+    let faults = FaultInjection::new(test_case, fault_proxy, seed);
+    let env = create_env(faults).context("Failed to create Solana environment")?;
+
+    let options =
+        serde_json::to_value(test_case).context("Failed to serialize test case for env options")?;
+    let initial_observation = reset_env(&env, options).await?;
+
+    let armed_faults = faults.map(|faults| faults.arm());
+    let mut recovery = RecoveryStats::default();
+    let evaluation = within_timeout(
+        Deadline::after(test_case.attempt_timeout(default_timeout)),
+        None,
+        run_step_with_faults(
+            &env,
+            agent.as_mut(),
+            test_case,
+            &initial_observation,
+            faults,
+            &mut recovery,
+        ),
+    )
+    .await;
+    drop(armed_faults);
+
+    let (final_observation, mut trace, actions) = match evaluation {
+        Ok(result) => result,
+        Err(e) if e.is::<StepTimedOut>() => {
+            let mut trace = ExecutionTrace::new(test_case.prompt.clone());
+            trace.initial_observation = Some(Box::new(initial_observation));
+            cancel_agent_work(agent.as_mut(), session_id).await;
+            let llm_usage = record_llm_usage(
+                &db,
+                test_case,
+                agent_name,
+                session_id,
+                run_id,
+                agent.token_usage(),
+            )
+            .await;
+            let result = record_timeout(
+                test_case,
+                agent_name,
+                &db,
+                attempt,
+                trace,
+                &e,
+                started.elapsed(),
+            )
+            .await
+            .map(|result| result.with_llm_usage(llm_usage));
+            close_timed_out_env(&env, test_case);
+            return result;
+        }
+        Err(e) => {
+            record_llm_usage(
+                &db,
+                test_case,
+                agent_name,
+                session_id,
+                run_id,
+                agent.token_usage(),
+            )
+            .await;
+
+            // Ensure session is marked as failed even if evaluation loop fails
+            complete_session(&db, test_case, session_id, 0.0, FinalStatus::Failed).await;
+
+            return Err(e).context(format!(
+                "Evaluation loop failed for benchmark: {}",
+                test_case.id
+            ));
+        }
+    };
+
+    trace.initial_observation = Some(Box::new(initial_observation.clone()));
+    trace.recovery = test_case.faults.as_ref().map(|_| recovery);
+
+    // Use the new comprehensive scoring function from reev-lib.
+    // Use the new comprehensive scoring function.
+    let efficiency_metrics = EfficiencyMetrics::from_trace(&trace);
+    let score = calculate_final_score_with_metrics(
+        test_case,
+        &actions,
+        &initial_observation,
+        &final_observation,
+        &efficiency_metrics,
+    );
+    let score = match &test_case.faults {
+        Some(faults) => faults.blend_recovery(score, &recovery),
+        None => score,
+    };
+    let budget_exceeded = agent.budget_exceeded();
+    let (score, final_status) = scored_outcome(test_case, score, budget_exceeded.is_some());
+    let mut scoring_breakdown = calculate_detailed_score_with_metrics(
+        test_case,
+        &actions,
+        &initial_observation,
+        &final_observation,
+        &efficiency_metrics,
+    );
+    scoring_breakdown.final_score = score;
+    if recovery.recovered_steps < recovery.faulted_steps {
+        scoring_breakdown.issues.push(format!(
+            "Recovered from injected faults in {}/{} steps",
+            recovery.recovered_steps, recovery.faulted_steps
+        ));
+    }
+    if let Some(limit) = &budget_exceeded {
+        scoring_breakdown
+            .issues
+            .push(format!("Agent stopped at its {limit} budget"));
+    }
+
+    info!(
+        benchmark_id = %test_case.id,
+        score = %score,
+        instructions_count = %actions.len(),
+        compute_units = %efficiency_metrics.compute_units,
+        fee_lamports = %efficiency_metrics.fee_lamports,
+        "Benchmark scoring completed"
+    );
+
+    // Complete session logging if enabled
+    if let Some(session_logger) = session_logger {
+        let start_time = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let _total_time_ms = (SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+            - start_time)
+            * 1000;
+
+        let _final_status = if final_observation.last_transaction_status == "Success" {
+            FinalStatus::Succeeded
+        } else {
+            FinalStatus::Failed
+        };
+
+        // Store ExecutionTrace format directly for ASCII tree compatibility
+        match session_logger.complete_with_trace(trace.clone()) {
+            Ok(log_file) => {
+                info!(
+                    benchmark_id = %test_case.id,
+                    log_file = %log_file.display(),
+                    "Session log with ExecutionTrace completed successfully"
+                );
+
+                // Store ExecutionTrace directly in database for ASCII tree compatibility
+                let trace_content = serde_json::to_string(&trace).unwrap_or_default();
+
+                if let Err(e) = db.store_complete_log(session_id, &trace_content).await {
+                    warn!(
+                        benchmark_id = %test_case.id,
+                        error = %e,
+                        "Failed to store ExecutionTrace in database"
+                    );
+                } else {
+                    info!(
+                        benchmark_id = %test_case.id,
+                        session_id = %session_id,
+                        "ExecutionTrace stored in database for ASCII tree compatibility"
+                    );
+                }
+            }
+            Err(e) => {
+                warn!(
+                    benchmark_id = %test_case.id,
+                    error = %e,
+                    "Failed to complete session logging with ExecutionTrace"
+                );
+
+                // Fallback: store ExecutionTrace directly even if session logging fails
+                let trace_content = serde_json::to_string(&trace).unwrap_or_default();
+
+                if let Err(e) = db.store_complete_log(session_id, &trace_content).await {
+                    warn!(
+                        benchmark_id = %test_case.id,
+                        error = %e,
+                        "Failed to store fallback ExecutionTrace in database"
+                    );
+                }
+            }
+        }
+    }
+
+    // Store pre/post balances of every account the transaction referenced
+    let balance_deltas = reev_lib::db::balance_deltas_from_trace(session_id, &trace);
+    if let Err(e) = db.store_balance_deltas(&balance_deltas).await {
+        warn!(
+            session_id = %session_id,
+            error = %e,
+            "Failed to store balance deltas in database"
+        );
+    }
+
+    // Store compute unit, fee and instruction counts for efficiency reporting
+    let session_efficiency =
+        reev_lib::db::session_efficiency(session_id, test_case, agent_name, &efficiency_metrics);
+    if let Err(e) = db.insert_session_efficiency(&session_efficiency).await {
+        warn!(
+            session_id = %session_id,
+            error = %e,
+            "Failed to store efficiency metrics in database"
+        );
+    }
+
+    let llm_usage = record_llm_usage(
+        &db,
+        test_case,
+        agent_name,
+        session_id,
+        run_id,
+        agent.token_usage(),
+    )
+    .await;
+
+    // 🎯 CAPTURE TOOL CALLS FROM AGENT'S ENHANCED OTEL LOG FILES
+    // Since reev-agent runs in separate process, we need to read from its otel log files
+    let tool_calls = extract_tool_calls_from_agent_logs(session_id).await;
+
+    if !tool_calls.is_empty() {
+        info!(
+            session_id = %session_id,
+            tool_calls_count = tool_calls.len(),
+            "Storing tool calls in database (from agent log files)"
+        );
+
+        for tool_call in &tool_calls {
+            let tool_data = reev_db::writer::sessions::ToolCallData {
+                session_id: session_id.to_string(),
+                tool_name: tool_call.tool_name.clone(),
+                start_time: tool_call.timestamp.timestamp() as u64,
+                execution_time_ms: tool_call.execution_time_ms,
+                input_params: tool_call.input_params.clone(),
+                output_result: tool_call.output_result.clone(),
+                status: match tool_call.status {
+                    reev_flow::ToolExecutionStatus::Success => "success".to_string(),
+                    reev_flow::ToolExecutionStatus::Error => "error".to_string(),
+                    reev_flow::ToolExecutionStatus::Timeout => "timeout".to_string(),
+                },
+                error_message: tool_call.error_message.clone(),
+            };
+
+            if let Err(e) = db.store_tool_call_consolidated(&tool_data).await {
+                warn!(
+                    session_id = %session_id,
+                    tool_name = %tool_call.tool_name,
+                    error = %e,
+                    "Failed to store consolidated tool call in database"
+                );
+            }
+        }
+    } else {
+        debug!("No tool calls found in agent log files");
+    }
+
+    record_agent_limits(
+        &db,
+        session_id,
+        budget_exceeded.as_deref(),
+        agent.argument_retries(),
+    )
+    .await;
+
+    // Complete session in database with results
+    complete_session(&db, test_case, session_id, score, final_status).await;
+
+    // Store performance metrics
+    let performance_data = reev_lib::db::AgentPerformanceData {
+        session_id: session_id.to_string(),
+        benchmark_id: test_case.id.clone(),
+        agent_type: agent_name.to_string(),
+        score,
+        final_status: final_status.to_string(),
+        execution_time_ms: started.elapsed().as_millis() as u64,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        flow_log_id: None,
+        prompt_md5: None,
+        trial_index,
+        run_id: Some(run_id.to_string()),
+    };
+
+    // Convert to shared AgentPerformance type for database insertion
+    let shared_performance = reev_lib::db::SharedPerformanceMetrics::from(performance_data);
+    db.insert_agent_performance(&shared_performance)
+        .await
+        .context("Failed to store performance metrics")?;
+
+    let result = TestResult::new(test_case, final_status, score, trace)
+        .with_scoring_breakdown(scoring_breakdown)
+        .with_trial_index(trial_index)
+        .with_run_id(run_id)
+        .with_llm_usage(llm_usage);
+
+    if let Err(e) = env.lock().await.close() {
+        warn!(
+            benchmark_id = %test_case.id,
+            error = %e,
+            "Failed to close environment gracefully"
+        );
+    }
+
+    Ok(result)
+}
+
 /// Execute a flow benchmark step-by-step
 async fn run_flow_benchmark(
     test_case: &TestCase,
     flow_steps: &[FlowStep],
     agent_name: &str,
-    _benchmark_path: &str,
-    _db: Arc<FlowDatabaseWriter>,
-    session_id: &str,
+    db: Arc<FlowDatabaseWriter>,
+    attempt: Attempt<'_>,
+    fault_proxy: Option<&FaultProxyHandle>,
+    default_timeout: Option<Duration>,
 ) -> Result<TestResult> {
+    let Attempt {
+        session_id,
+        trial_index,
+        run_id,
+        seed,
+    } = attempt;
+    let started = Instant::now();
+
     info!(
         benchmark_id = %test_case.id,
         total_steps = %flow_steps.len(),
         "Starting flow benchmark execution"
     );
 
+    start_session(&db, test_case, agent_name, session_id, run_id).await;
+
     // Initialize flow logging for flow benchmarks
     // Flow logging is always enabled
     let flow_logger = {
-        let output_path =
-            std::env::var("REEV_FLOW_LOG_PATH").unwrap_or_else(|_| "logs/flows".to_string());
-        let path = PathBuf::from(output_path);
+        let path = PathBuf::from(&reev_config::get().runner.flow_log_dir);
         std::fs::create_dir_all(&path)?;
 
-        Some(FlowLogger::new_with_database_preserve_session(
-            test_case.id.clone(),
-            agent_name.to_string(),
-            path,
-            _db.clone() as Arc<dyn reev_flow::logger::DatabaseWriter>,
-            Some(session_id.to_string()), // Preserve existing session_id
-        ))
+        Some(
+            FlowLogger::new_with_database_preserve_session(
+                test_case.id.clone(),
+                agent_name.to_string(),
+                path,
+                db.clone() as Arc<dyn reev_flow::logger::DatabaseWriter>,
+                Some(session_id.to_string()), // Preserve existing session_id
+            )
+            .with_trial_index(trial_index)
+            .with_run_id(Some(run_id.to_string())),
+        )
     };
 
     let mut agent = LlmAgent::new_with_flow_logging(agent_name, flow_logger)?;
     agent.set_session_id(session_id.to_string());
-    let mut env = SolanaEnv::new().context("Failed to create Solana environment")?;
+    agent.set_budget(test_case.run_budget(reev_config::get().budget));
+    if let Some(seed) = seed {
+        agent.set_seed(seed);
+    }
+    let faults = FaultInjection::new(test_case, fault_proxy, seed);
+    let env = create_env(faults).context("Failed to create Solana environment")?;
     let mut all_actions = Vec::new();
+    let mut recovery = RecoveryStats::default();
     let mut flow_trace = ExecutionTrace::new(test_case.prompt.clone());
 
     // Set up initial environment
     let options =
         serde_json::to_value(test_case).context("Failed to serialize test case for env options")?;
-    let mut initial_observation = env.reset(None, Some(options)).await?;
+    let initial_observation = reset_env(&env, options).await?;
+    flow_trace.initial_observation = Some(Box::new(initial_observation.clone()));
+    let mut current_observation = initial_observation.clone();
+    let armed_faults = faults.map(|faults| faults.arm());
+    let deadline = Deadline::after(test_case.attempt_timeout(default_timeout));
 
     // Execute each step in the flow
     for step in flow_steps.iter() {
@@ -680,19 +1301,74 @@ async fn run_flow_benchmark(
             initial_state: test_case.initial_state.clone(),
             prompt: step.prompt.clone(),
             flow: None, // No nested flows
-            ground_truth: test_case.ground_truth.clone(),
+            faults: None,
+            timeout: None,
+            budget: test_case.budget,
+            ground_truth: test_case.ground_truth.for_flow_step(step.step),
         };
+        agent.set_ground_truth(&step_test_case.ground_truth);
 
         // Execute step
-        let (step_observation, step_trace, step_actions) =
-            run_evaluation_loop(&mut env, &mut agent, &step_test_case, &initial_observation)
+        let step_result = within_timeout(
+            deadline,
+            Some(step),
+            run_step_with_faults(
+                &env,
+                &mut agent,
+                &step_test_case,
+                &current_observation,
+                faults,
+                &mut recovery,
+            ),
+        )
+        .await;
+        let (step_observation, step_trace, step_actions) = match step_result {
+            Ok(result) => result,
+            Err(e) if e.is::<StepTimedOut>() => {
+                drop(armed_faults);
+                cancel_agent_work(&mut agent, session_id).await;
+                let llm_usage = record_llm_usage(
+                    &db,
+                    test_case,
+                    agent_name,
+                    session_id,
+                    run_id,
+                    agent.token_usage(),
+                )
+                .await;
+                let result = record_timeout(
+                    test_case,
+                    agent_name,
+                    &db,
+                    attempt,
+                    flow_trace,
+                    &e,
+                    started.elapsed(),
+                )
                 .await
-                .with_context(|| {
+                .map(|result| result.with_llm_usage(llm_usage));
+                close_timed_out_env(&env, test_case);
+                return result;
+            }
+            Err(e) => {
+                record_llm_usage(
+                    &db,
+                    test_case,
+                    agent_name,
+                    session_id,
+                    run_id,
+                    agent.token_usage(),
+                )
+                .await;
+                complete_session(&db, test_case, session_id, 0.0, FinalStatus::Failed).await;
+                return Err(e).with_context(|| {
                     format!(
                         "Flow step {} failed for benchmark: {}",
                         step.step, test_case.id
                     )
-                })?;
+                });
+            }
+        };
 
         // Log step completion before moving actions
         info!(
@@ -706,31 +1382,76 @@ async fn run_flow_benchmark(
         flow_trace.steps.extend(step_trace.steps);
 
         // Update observation for next step
-        initial_observation = step_observation;
+        current_observation = step_observation;
+
+        // A step stopped at its budget ends the flow with what was done so far
+        if let Some(limit) = agent.budget_exceeded() {
+            warn!(
+                step = step.step,
+                limit = %limit,
+                "Flow step stopped at its budget, skipping the remaining steps"
+            );
+            break;
+        }
     }
 
-    // Calculate final score for the entire flow
-    let final_observation = initial_observation.clone();
-    let score = calculate_final_score(
+    drop(armed_faults);
+
+    // Calculate final score for the entire flow, comparing the state before its
+    // first step with the state after its last
+    let final_observation = current_observation;
+    flow_trace.recovery = test_case.faults.as_ref().map(|_| recovery);
+    let efficiency_metrics = EfficiencyMetrics::from_trace(&flow_trace);
+    let score = calculate_final_score_with_metrics(
         test_case,
         &all_actions,
         &initial_observation,
         &final_observation,
+        &efficiency_metrics,
     );
+    let score = match &test_case.faults {
+        Some(faults) => faults.blend_recovery(score, &recovery),
+        None => score,
+    };
+    let budget_exceeded = agent.budget_exceeded();
+    let (score, final_status) = scored_outcome(test_case, score, budget_exceeded.is_some());
 
     info!(
         benchmark_id = %test_case.id,
         score = %score,
         total_actions = %all_actions.len(),
+        injected_faults = recovery.injected_faults,
+        recovered_steps = recovery.recovered_steps,
+        faulted_steps = recovery.faulted_steps,
         "Flow benchmark completed"
     );
 
-    // Determine final status
-    let final_status = if score >= 0.6 {
-        // Use min_score from ground_truth if available
-        FinalStatus::Succeeded
-    } else {
-        FinalStatus::Failed
+    // Create a simple scoring breakdown for flow benchmarks
+    let scoring_breakdown = reev_lib::flow::ScoringBreakdown {
+        instruction_score: if score >= 0.75 { 1.0 } else { score },
+        onchain_score: if final_status == FinalStatus::Succeeded {
+            1.0
+        } else {
+            0.0
+        },
+        final_score: score,
+        issues: {
+            let mut issues = Vec::new();
+            if score < 1.0 {
+                issues.push(format!("Flow execution scored {:.1}%", score * 100.0));
+            }
+            if recovery.recovered_steps < recovery.faulted_steps {
+                issues.push(format!(
+                    "Recovered from injected faults in {}/{} steps",
+                    recovery.recovered_steps, recovery.faulted_steps
+                ));
+            }
+            if let Some(limit) = &budget_exceeded {
+                issues.push(format!("Agent stopped at its {limit} budget"));
+            }
+            issues
+        },
+        mismatches: vec![],
     };
 
     // Complete flow logging if enabled
@@ -742,29 +1463,12 @@ async fn run_flow_benchmark(
 
         let statistics = flow_logger.get_current_statistics();
 
-        // Create a simple scoring breakdown for flow benchmarks
-        let scoring_breakdown = reev_lib::flow::ScoringBreakdown {
-            instruction_score: if score >= 0.75 { 1.0 } else { score },
-            onchain_score: if final_status == FinalStatus::Succeeded {
-                1.0
-            } else {
-                0.0
-            },
-            final_score: score,
-            issues: if score < 1.0 {
-                vec![format!("Flow execution scored {:.1}%", score * 100.0)]
-            } else {
-                vec![]
-            },
-            mismatches: vec![],
-        };
-
         let execution_result = ExecutionResult {
             success: final_status == FinalStatus::Succeeded,
             score,
             total_time_ms,
             statistics,
-            scoring_breakdown: Some(scoring_breakdown),
+            scoring_breakdown: Some(scoring_breakdown.clone()),
         };
 
         // Auto-render flow as ASCII tree after completion
@@ -794,10 +1498,56 @@ async fn run_flow_benchmark(
         }
     }
 
-    let result = TestResult::new(test_case, final_status, score, flow_trace);
+    // Replaces the flow log, so `replay` and `rescore` find the flow's trace
+    store_trace(&db, test_case, session_id, &flow_trace).await;
+
+    record_agent_limits(
+        &db,
+        session_id,
+        budget_exceeded.as_deref(),
+        agent.argument_retries(),
+    )
+    .await;
+
+    complete_session(&db, test_case, session_id, score, final_status).await;
+
+    let balance_deltas = reev_lib::db::balance_deltas_from_trace(session_id, &flow_trace);
+    if let Err(e) = db.store_balance_deltas(&balance_deltas).await {
+        warn!(
+            session_id = %session_id,
+            error = %e,
+            "Failed to store flow balance deltas in database"
+        );
+    }
+
+    let session_efficiency =
+        reev_lib::db::session_efficiency(session_id, test_case, agent_name, &efficiency_metrics);
+    if let Err(e) = db.insert_session_efficiency(&session_efficiency).await {
+        warn!(
+            session_id = %session_id,
+            error = %e,
+            "Failed to store flow efficiency metrics in database"
+        );
+    }
+
+    let llm_usage = record_llm_usage(
+        &db,
+        test_case,
+        agent_name,
+        session_id,
+        run_id,
+        agent.token_usage(),
+    )
+    .await;
+
+    let result = TestResult::new(test_case, final_status, score, flow_trace)
+        .with_scoring_breakdown(scoring_breakdown)
+        .with_trial_index(trial_index)
+        .with_run_id(run_id)
+        .with_llm_usage(llm_usage);
 
     // Close environment
-    if let Err(e) = env.close() {
+    if let Err(e) = env.lock().await.close() {
         warn!(
             benchmark_id = %test_case.id,
             error = %e,
@@ -808,55 +1558,400 @@ async fn run_flow_benchmark(
     Ok(result)
 }
 
-/// Discovers benchmark files from a given path.
-fn discover_benchmarks(path: &Path) -> Result<Vec<PathBuf>> {
-    let mut benchmark_paths = vec![];
-    if path.is_dir() {
-        for entry in fs::read_dir(path)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.is_file()
-                && (path.extension() == Some("yml".as_ref())
-                    || path.extension() == Some("yaml".as_ref()))
-            {
-                benchmark_paths.push(path);
+/// Pass/fail status of an attempt scored `score`
+///
+/// A score >= 0.75 means the instruction was perfect, even if it failed on-chain.
+/// Flows pass from 0.6.
+pub fn scored_status(test_case: &TestCase, score: f64) -> FinalStatus {
+    let threshold = if test_case.flow.is_some() { 0.6 } else { 0.75 };
+    if score >= threshold {
+        FinalStatus::Succeeded
+    } else {
+        FinalStatus::Failed
+    }
+}
+
+/// Score and pass/fail status of an attempt scored `score`
+///
+/// An agent stopped at its budget is capped at [`BUDGET_EXCEEDED_MAX_SCORE`] and
+/// `BudgetExceeded`, so stored traces scored again keep the outcome of their run.
+pub fn scored_outcome(
+    test_case: &TestCase,
+    score: f64,
+    budget_exceeded: bool,
+) -> (f64, FinalStatus) {
+    if budget_exceeded {
+        (
+            score.min(BUDGET_EXCEEDED_MAX_SCORE),
+            FinalStatus::BudgetExceeded,
+        )
+    } else {
+        (score, scored_status(test_case, score))
+    }
+}
+
+/// Await `step`, failing with [`StepTimedOut`] once the attempt's `deadline` or
+/// the flow step's own `timeout` has passed, whichever comes first.
+///
+/// The agent call is dropped at the limit; its work in reev-agent is cancelled
+/// by [`cancel_agent_work`]. The environment step runs on the blocking thread pool
+/// ([`step_env`]), so a hung RPC call is cut off at the limit too.
+async fn within_timeout<T>(
+    deadline: Option<Deadline>,
+    flow_step: Option<&FlowStep>,
+    step: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    let step_number = flow_step.map(|step| step.step);
+    let step_limit = flow_step
+        .and_then(|step| step.timeout)
+        .map(|secs| Duration::from_secs(u64::from(secs)));
+    let (left, timed_out) = match (deadline, step_limit) {
+        (Some(deadline), step_limit) => {
+            let left = deadline.at.saturating_duration_since(Instant::now());
+            match step_limit {
+                Some(limit) if limit < left => (limit, (limit, false)),
+                _ => (left, (deadline.limit, true)),
             }
         }
-    } else if path.is_file() {
-        benchmark_paths.push(path.to_path_buf());
+        (None, Some(limit)) => (limit, (limit, false)),
+        (None, None) => return step.await,
+    };
+    match tokio::time::timeout(left, step).await {
+        Ok(result) => result,
+        Err(_) => Err(StepTimedOut {
+            step: step_number,
+            limit: timed_out.0,
+            attempt: timed_out.1,
+        }
+        .into()),
+    }
+}
+
+/// Create the execution session of an attempt, linked to its evaluation run
+async fn start_session(
+    db: &FlowDatabaseWriter,
+    test_case: &TestCase,
+    agent_name: &str,
+    session_id: &str,
+    run_id: &str,
+) {
+    let session_info = reev_lib::db::SessionInfo {
+        session_id: session_id.to_string(),
+        benchmark_id: test_case.id.clone(),
+        agent_type: agent_name.to_string(),
+        interface: "tui".to_string(),
+        start_time: chrono::Utc::now().timestamp(),
+        end_time: None,
+        status: "running".to_string(),
+        score: None,
+        final_status: None,
+        run_id: Some(run_id.to_string()),
+    };
+
+    if let Err(e) = db.create_session(&session_info).await {
+        warn!(
+            benchmark_id = %test_case.id,
+            session_id = %session_id,
+            error = %e,
+            "Failed to create session in database"
+        );
     } else {
-        return Err(anyhow!("Provided path is not a valid file or directory"));
+        info!(
+            benchmark_id = %test_case.id,
+            session_id = %session_id,
+            "Created session in database"
+        );
     }
+}
 
-    if benchmark_paths.is_empty() {
-        info!("No benchmark files found to run.");
+/// Complete the execution session of an attempt with its score and status
+async fn complete_session(
+    db: &FlowDatabaseWriter,
+    test_case: &TestCase,
+    session_id: &str,
+    score: f64,
+    final_status: FinalStatus,
+) {
+    let session_result = reev_lib::db::SessionResult {
+        end_time: chrono::Utc::now().timestamp(),
+        score,
+        final_status: final_status.to_string(),
+    };
+
+    if let Err(e) = db.complete_session(session_id, &session_result).await {
+        warn!(
+            benchmark_id = %test_case.id,
+            session_id = %session_id,
+            error = %e,
+            "Failed to complete session in database"
+        );
+    } else {
+        info!(
+            benchmark_id = %test_case.id,
+            session_id = %session_id,
+            score = %score,
+            final_status = %final_status,
+            "Completed session in database"
+        );
+    }
+}
+
+/// Store an attempt's execution trace, with its initial observation and fault
+/// recovery, as its session log
+async fn store_trace(
+    db: &FlowDatabaseWriter,
+    test_case: &TestCase,
+    session_id: &str,
+    trace: &ExecutionTrace,
+) {
+    let trace_content = serde_json::to_string(trace).unwrap_or_default();
+    if let Err(e) = db.store_complete_log(session_id, &trace_content).await {
+        warn!(
+            benchmark_id = %test_case.id,
+            error = %e,
+            "Failed to store ExecutionTrace in database"
+        );
     }
+}
+
+/// Record the budget limit that stopped the agent and how many of its tool calls
+/// had their arguments rejected and retried with the session
+async fn record_agent_limits(
+    db: &FlowDatabaseWriter,
+    session_id: &str,
+    budget_exceeded: Option<&str>,
+    argument_retries: u32,
+) {
+    if let Some(limit) = budget_exceeded
+        && let Err(e) = db.record_budget_exceeded(session_id, limit).await
+    {
+        warn!(
+            session_id = %session_id,
+            error = %e,
+            "Failed to record exceeded budget in database"
+        );
+    }
+
+    if argument_retries > 0
+        && let Err(e) = db
+            .record_argument_retries(session_id, argument_retries)
+            .await
+    {
+        warn!(
+            session_id = %session_id,
+            error = %e,
+            "Failed to record argument retries in database"
+        );
+    }
+}
 
-    benchmark_paths.sort();
-    Ok(benchmark_paths)
+/// Price the LLM tokens an agent reported with its model's registry entry and
+/// store them with the session; agents that called no model report none
+///
+/// Every way an attempt ends once its agent has run records them, timeouts and
+/// failed steps included, since the tokens were spent all the same.
+async fn record_llm_usage(
+    db: &FlowDatabaseWriter,
+    test_case: &TestCase,
+    agent_name: &str,
+    session_id: &str,
+    run_id: &str,
+    tokens: Option<reev_flow::TokenUsage>,
+) -> Option<LlmUsage> {
+    let usage = LlmUsage::priced(agent_name, tokens?);
+    info!(
+        session_id = %session_id,
+        prompt_tokens = usage.tokens.prompt_tokens,
+        cached_tokens = usage.tokens.cached_tokens,
+        completion_tokens = usage.tokens.completion_tokens,
+        cost_usd = ?usage.cost_usd,
+        "LLM usage of the session"
+    );
+    let session_usage =
+        reev_lib::db::session_llm_usage(session_id, test_case, agent_name, run_id, &usage);
+    if let Err(e) = db.insert_session_llm_usage(&session_usage).await {
+        warn!(
+            session_id = %session_id,
+            error = %e,
+            "Failed to store LLM usage in database"
+        );
+    }
+    Some(usage)
+}
+
+/// Cancel the work the agent still does for a session stopped at its time limit
+async fn cancel_agent_work(agent: &mut (dyn Agent + Send), session_id: &str) {
+    match agent.cancel().await {
+        Ok(cancelled) => info!(
+            session_id = %session_id,
+            cancelled,
+            "Cancelled the agent's work for the timed out session"
+        ),
+        Err(e) => warn!(
+            session_id = %session_id,
+            error = %e,
+            "Failed to cancel the agent's work for the timed out session"
+        ),
+    }
+}
+
+/// Record an attempt stopped at its time limit and build its `TimedOut` result
+async fn record_timeout(
+    test_case: &TestCase,
+    agent_name: &str,
+    db: &FlowDatabaseWriter,
+    attempt: Attempt<'_>,
+    trace: ExecutionTrace,
+    error: &anyhow::Error,
+    elapsed: Duration,
+) -> Result<TestResult> {
+    let Attempt {
+        session_id,
+        trial_index,
+        run_id,
+        ..
+    } = attempt;
+    warn!(
+        benchmark_id = %test_case.id,
+        session_id = %session_id,
+        error = %error,
+        "Benchmark timed out"
+    );
+
+    if let Err(e) = db
+        .time_out_session(session_id, chrono::Utc::now().timestamp())
+        .await
+    {
+        warn!(
+            session_id = %session_id,
+            error = %e,
+            "Failed to mark session as timed out in database"
+        );
+    }
+
+    let performance_data = reev_lib::db::AgentPerformanceData {
+        session_id: session_id.to_string(),
+        benchmark_id: test_case.id.clone(),
+        agent_type: agent_name.to_string(),
+        score: 0.0,
+        final_status: FinalStatus::TimedOut.to_string(),
+        execution_time_ms: elapsed.as_millis() as u64,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        flow_log_id: None,
+        prompt_md5: None,
+        trial_index,
+        run_id: Some(run_id.to_string()),
+    };
+    let shared_performance = reev_lib::db::SharedPerformanceMetrics::from(performance_data);
+    db.insert_agent_performance(&shared_performance)
+        .await
+        .context("Failed to store performance metrics")?;
+
+    let scoring_breakdown = reev_lib::flow::ScoringBreakdown {
+        instruction_score: 0.0,
+        onchain_score: 0.0,
+        final_score: 0.0,
+        issues: vec![error.to_string()],
+        mismatches: vec![],
+    };
+    Ok(
+        TestResult::new(test_case, FinalStatus::TimedOut, 0.0, trace)
+            .with_scoring_breakdown(scoring_breakdown)
+            .with_trial_index(trial_index)
+            .with_run_id(run_id),
+    )
+}
+
+/// Run one episode step, letting the agent retry when injected faults make it fail.
+///
+/// Without fault injection this is exactly [`run_evaluation_loop`]. With it, a step
+/// that fails while the proxy injected faults is retried (up to `max_attempts`) with
+/// the failed observation, and the outcome is recorded in `recovery`.
+async fn run_step_with_faults(
+    env: &SharedEnv,
+    agent: &mut (dyn Agent + Send),
+    test_case: &TestCase,
+    initial_observation: &AgentObservation,
+    faults: Option<FaultInjection<'_>>,
+    recovery: &mut RecoveryStats,
+) -> Result<(AgentObservation, ExecutionTrace, Vec<AgentAction>)> {
+    let Some(faults) = faults else {
+        return run_evaluation_loop(env, agent, test_case, initial_observation).await;
+    };
+
+    let max_attempts = faults.config.max_attempts.max(1);
+    let step_start = faults.proxy.injected_count();
+    let mut trace = ExecutionTrace::new(test_case.prompt.clone());
+    let mut observation = initial_observation.clone();
+    let mut actions = Vec::new();
+    let mut attempts = 0;
+
+    while attempts < max_attempts {
+        attempts += 1;
+        let attempt_start = faults.proxy.injected_count();
+        let result = run_evaluation_loop(env, agent, test_case, &observation).await;
+        let injected = faults.proxy.injected_count() - attempt_start;
+
+        match result {
+            Ok((attempt_observation, attempt_trace, attempt_actions)) => {
+                trace.steps.extend(attempt_trace.steps);
+                observation = attempt_observation;
+                actions = attempt_actions;
+            }
+            // An RPC failure caused by an injected fault is a failed attempt, not a runner error
+            Err(e) if injected > 0 => {
+                warn!(
+                    benchmark_id = %test_case.id,
+                    attempt = attempts,
+                    error = %e,
+                    "Step aborted by injected fault"
+                );
+                observation.last_transaction_status = "Failure".to_string();
+                observation.last_transaction_error = Some(format!("{e:#}"));
+                observation.last_transaction_logs.clear();
+                actions = Vec::new();
+            }
+            Err(e) => return Err(e),
+        }
+
+        if observation.last_transaction_status == "Success" || injected == 0 {
+            break;
+        }
+        info!(
+            benchmark_id = %test_case.id,
+            attempt = attempts,
+            max_attempts,
+            injected,
+            "Step failed under injected faults"
+        );
+    }
+
+    let succeeded = observation.last_transaction_status == "Success";
+    recovery.record_step(
+        faults.proxy.injected_count() - step_start,
+        attempts,
+        succeeded,
+    );
+    Ok((observation, trace, actions))
 }
 
 #[instrument(skip_all, name = "run_evaluation_loop")]
 async fn run_evaluation_loop(
-    env: &mut SolanaEnv,
+    env: &SharedEnv,
     agent: &mut (dyn Agent + Send),
     test_case: &TestCase,
     initial_observation: &AgentObservation,
-) -> Result<(
-    AgentObservation,
-    ExecutionTrace,
-    Vec<reev_lib::agent::AgentAction>,
-)> {
+) -> Result<(AgentObservation, ExecutionTrace, Vec<AgentAction>)> {
     let mut trace = ExecutionTrace::new(test_case.prompt.clone());
 
-    let fee_payer = env.fee_payer_placeholder();
+    let fee_payer = env.lock().await.fee_payer_placeholder().to_owned();
     // The agent now returns a vector of actions.
     let actions = agent
         .get_action(
             &test_case.id,
             &test_case.prompt,
             initial_observation,
-            Some(&fee_payer.to_owned()),
+            Some(&fee_payer),
             Some(test_case.ground_truth.skip_instruction_validation),
             Some(&test_case.initial_state),
         )
@@ -864,7 +1959,7 @@ async fn run_evaluation_loop(
 
     // The environment's step function now takes a vector of actions to be bundled
     // into a single transaction.
-    let step_result = env.step(actions.clone(), &test_case.ground_truth)?;
+    let step_result = step_env(env, actions.clone(), &test_case.ground_truth).await?;
 
     let trace_step = reev_lib::trace::TraceStep {
         thought: None,
@@ -876,3 +1971,652 @@ async fn run_evaluation_loop(
     info!("Episode finished.");
     Ok((step_result.observation, trace, actions))
 }
+
+/// Reset the environment on the blocking thread pool, as setting its accounts up
+/// is blocking RPC
+async fn reset_env(env: &SharedEnv, options: serde_json::Value) -> Result<AgentObservation> {
+    let env = Arc::clone(env);
+    let runtime = tokio::runtime::Handle::current();
+    tokio::task::spawn_blocking(move || {
+        runtime.block_on(async { env.lock().await.reset(None, Some(options)).await })
+    })
+    .await
+    .context("Environment reset panicked")?
+}
+
+/// Run an environment step, blocking RPC up to its confirmation, on the blocking
+/// thread pool
+///
+/// A hung RPC call then stalls no other attempt on the runtime, and the step's
+/// timeout stops waiting for it, leaving the thread to finish on its own.
+async fn step_env(
+    env: &SharedEnv,
+    actions: Vec<AgentAction>,
+    ground_truth: &GroundTruth,
+) -> Result<Step<AgentObservation>> {
+    let env = Arc::clone(env);
+    let ground_truth = ground_truth.clone();
+    tokio::task::spawn_blocking(move || env.blocking_lock().step(actions, &ground_truth))
+        .await
+        .context("Environment step panicked")?
+}
+
+/// Close the environment of a timed out attempt, unless a step still blocked on
+/// its RPC call holds it
+fn close_timed_out_env(env: &SharedEnv, test_case: &TestCase) {
+    let closed = match env.try_lock() {
+        Ok(mut env) => env.close(),
+        Err(_) => Err(anyhow::anyhow!("a timed out step still holds it")),
+    };
+    if let Err(e) = closed {
+        warn!(
+            benchmark_id = %test_case.id,
+            error = %e,
+            "Failed to close environment gracefully after timeout"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reev_mock_llm::{Conversation, MockLlmServer, Script, Turn};
+    use std::{collections::HashMap, sync::OnceLock};
+
+    /// How long the mock model takes to answer a `[slow]` prompt
+    const REPLY_DELAY: Duration = Duration::from_secs(3);
+    /// How long it takes to answer a `[busy]` prompt, which outlasts a 1s timeout
+    const BUSY_DELAY: Duration = Duration::from_secs(2);
+
+    fn transfer() -> Turn {
+        Turn::tool_call(
+            "sol_transfer",
+            serde_json::json!({
+                "user_pubkey": "USER_WALLET_PUBKEY",
+                "recipient_pubkey": "RECIPIENT_WALLET_PUBKEY",
+                "amount": 100000000,
+                "operation": "sol"
+            }),
+        )
+    }
+
+    /// A mock model behind a reev-agent served in-process, registered as the
+    /// `local` model and the agent API of the process configuration
+    fn agent_stack() -> &'static MockLlmServer {
+        static LLM: OnceLock<MockLlmServer> = OnceLock::new();
+        LLM.get_or_init(|| {
+            let script = Script::new()
+                .conversation(
+                    Conversation::new(
+                        "slow",
+                        vec![
+                            Turn::delayed(REPLY_DELAY.as_millis() as u64, transfer()),
+                            Turn::text(r#"{"transactions": {{tool_result}}, "summary": "Sent"}"#),
+                        ],
+                    )
+                    .matching("[slow]"),
+                )
+                .conversation(
+                    Conversation::new(
+                        "busy",
+                        vec![
+                            Turn::delayed(BUSY_DELAY.as_millis() as u64, transfer()),
+                            Turn::text(r#"{"transactions": {{tool_result}}, "summary": "Sent"}"#),
+                        ],
+                    )
+                    .matching("[busy]"),
+                )
+                .conversation(
+                    Conversation::new(
+                        "correcting",
+                        vec![
+                            // A fractional amount is rejected and retried
+                            Turn::tool_call(
+                                "sol_transfer",
+                                serde_json::json!({
+                                    "user_pubkey": "USER_WALLET_PUBKEY",
+                                    "recipient_pubkey": "RECIPIENT_WALLET_PUBKEY",
+                                    "amount": 0.1,
+                                    "operation": "sol"
+                                }),
+                            ),
+                            transfer(),
+                            Turn::text(r#"{"transactions": {{tool_result}}, "summary": "Sent"}"#),
+                        ],
+                    )
+                    .matching("[correcting]"),
+                )
+                .conversation(
+                    Conversation::new(
+                        "steps",
+                        vec![
+                            transfer(),
+                            Turn::text(r#"{"transactions": {{tool_result}}, "summary": "Sent"}"#),
+                        ],
+                    )
+                    .matching("[steps]"),
+                );
+            let llm = MockLlmServer::spawn(script).unwrap();
+
+            let (sender, receiver) = std::sync::mpsc::channel();
+            std::thread::spawn(move || {
+                let runtime = tokio::runtime::Runtime::new().unwrap();
+                runtime.block_on(async move {
+                    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+                    sender.send(listener.local_addr().unwrap()).unwrap();
+                    axum::serve(listener, reev_agent::router()).await.unwrap();
+                });
+            });
+            let agent_addr = receiver.recv().unwrap();
+
+            let mut config = reev_config::ReevConfig::default();
+            config.models.get_mut("local").unwrap().base_url = Some(llm.base_url());
+            config.llm.api_url = format!("http://{agent_addr}/gen/tx");
+            assert!(reev_config::init(config), "configuration already installed");
+            llm
+        })
+    }
+
+    /// Model requests made for prompts carrying `tag`
+    fn requests_for(llm: &MockLlmServer, tag: &str) -> usize {
+        llm.requests()
+            .iter()
+            .filter(|request| request.to_string().contains(tag))
+            .count()
+    }
+
+    /// The benchmark of `file` in the benchmarks directory
+    fn benchmark(file: &str) -> TestCase {
+        let source = fs::read_to_string(
+            project_root::get_project_root()
+                .unwrap()
+                .join("benchmarks")
+                .join(file),
+        )
+        .unwrap();
+        serde_yaml::from_str(&source).unwrap()
+    }
+
+    /// Benchmark 001 with its prompt prefixed by `prefix`
+    fn sol_transfer(prefix: &str) -> TestCase {
+        let mut test_case = benchmark("001-sol-transfer.yml");
+        test_case.prompt = format!("{prefix} {}", test_case.prompt);
+        test_case
+    }
+
+    /// The swap then lend flow benchmark
+    fn flow_benchmark() -> TestCase {
+        benchmark("200-jup-swap-then-lend-deposit.yml")
+    }
+
+    /// A results database in a temporary directory, removed with it
+    async fn results_db() -> (tempfile::TempDir, FlowDatabaseWriter) {
+        let dir = tempfile::tempdir().unwrap();
+        let db_config = DatabaseConfig::new(dir.path().join("results.db").to_string_lossy());
+        let db = FlowDatabaseWriter::new(DatabaseWriter::new(db_config).await.unwrap());
+        (dir, db)
+    }
+
+    fn observation() -> AgentObservation {
+        AgentObservation {
+            last_transaction_status: "Success".to_string(),
+            last_transaction_error: None,
+            last_transaction_logs: vec![],
+            account_states: HashMap::new(),
+            key_map: HashMap::from([
+                (
+                    "USER_WALLET_PUBKEY".to_string(),
+                    solana_sdk::pubkey::Pubkey::new_unique().to_string(),
+                ),
+                (
+                    "RECIPIENT_WALLET_PUBKEY".to_string(),
+                    solana_sdk::pubkey::Pubkey::new_unique().to_string(),
+                ),
+            ]),
+            balance_deltas: vec![],
+            last_transaction_metrics: None,
+        }
+    }
+
+    fn session_agent(session_id: &str) -> LlmAgent {
+        let mut agent = LlmAgent::new("local").unwrap();
+        agent.set_session_id(session_id.to_string());
+        agent
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_attempt_is_recorded_and_cancelled() {
+        let llm = agent_stack();
+        let (_dir, db) = results_db().await;
+
+        let mut test_case = sol_transfer("[slow] [single]");
+        test_case.timeout = Some(1);
+
+        let session_id = uuid::Uuid::new_v4().to_string();
+        let attempt = Attempt {
+            session_id: &session_id,
+            trial_index: 0,
+            run_id: "run-1",
+            seed: None,
+        };
+        start_session(&db, &test_case, "local", &session_id, "run-1").await;
+
+        let mut agent = session_agent(&session_id);
+        let observation = observation();
+
+        let started = Instant::now();
+        let error = within_timeout(
+            Deadline::after(test_case.attempt_timeout(None)),
+            None,
+            agent.get_action(
+                &test_case.id,
+                &test_case.prompt,
+                &observation,
+                None,
+                None,
+                None,
+            ),
+        )
+        .await
+        .unwrap_err();
+        assert!(error.is::<StepTimedOut>(), "{error:#}");
+        assert_eq!(error.to_string(), "Benchmark timed out after 1s");
+        assert!(started.elapsed() < REPLY_DELAY);
+        assert_eq!(requests_for(llm, "[single]"), 1);
+
+        cancel_agent_work(&mut agent, &session_id).await;
+        let trace = ExecutionTrace::new(test_case.prompt.clone());
+        let result = record_timeout(
+            &test_case,
+            "local",
+            &db,
+            attempt,
+            trace,
+            &error,
+            started.elapsed(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.final_status, FinalStatus::TimedOut);
+        assert_eq!(result.score, 0.0);
+
+        let session = db.get_session(&session_id).await.unwrap().unwrap();
+        assert_eq!(session.status, "timeout");
+        assert_eq!(session.final_status.as_deref(), Some("timed_out"));
+        assert_eq!(session.score, Some(0.0));
+
+        // An agent run still going would call the tool once the model answers
+        // and ask the model again
+        tokio::time::sleep(REPLY_DELAY + Duration::from_secs(1)).await;
+        assert_eq!(requests_for(llm, "[single]"), 1);
+        assert!(!agent.cancel().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_flow_session_is_listed_with_its_run() {
+        let (_dir, db) = results_db().await;
+        let test_case = flow_benchmark();
+        let session_id = uuid::Uuid::new_v4().to_string();
+
+        start_session(&db, &test_case, "local", &session_id, "run-1").await;
+        complete_session(&db, &test_case, &session_id, 0.8, FinalStatus::Succeeded).await;
+
+        let sessions = db.list_run_sessions("run-1").await.unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].session_id, session_id);
+        assert_eq!(sessions[0].benchmark_id, test_case.id);
+        assert_eq!(sessions[0].score, Some(0.8));
+        assert_eq!(sessions[0].final_status.as_deref(), Some("succeeded"));
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_flow_is_recorded() {
+        let (_dir, db) = results_db().await;
+        let test_case = flow_benchmark();
+        let session_id = uuid::Uuid::new_v4().to_string();
+        let attempt = Attempt {
+            session_id: &session_id,
+            trial_index: 0,
+            run_id: "run-1",
+            seed: None,
+        };
+        start_session(&db, &test_case, "local", &session_id, "run-1").await;
+
+        let step: FlowStep =
+            serde_yaml::from_str("step: 1\ndescription: swap\nprompt: swap\ntimeout: 1").unwrap();
+        let error = within_timeout(None, Some(&step), async {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            Ok(())
+        })
+        .await
+        .unwrap_err();
+        let trace = ExecutionTrace::new(test_case.prompt.clone());
+        let result = record_timeout(
+            &test_case,
+            "local",
+            &db,
+            attempt,
+            trace,
+            &error,
+            Duration::from_secs(1),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.final_status, FinalStatus::TimedOut);
+
+        let session = db.get_session(&session_id).await.unwrap().unwrap();
+        assert_eq!(session.status, "timeout");
+        assert_eq!(session.final_status.as_deref(), Some("timed_out"));
+        assert_eq!(session.run_id.as_deref(), Some("run-1"));
+    }
+
+    #[tokio::test]
+    async fn test_matrix_timeout_cancels_only_its_session() {
+        let llm = agent_stack();
+        let mut slow_case = sol_transfer("[slow] [matrix]");
+        slow_case.timeout = Some(1);
+        let busy_case = sol_transfer("[busy] [matrix]");
+
+        // Two agents of a matrix run share the reev-agent, each in its own session
+        let slow_session = uuid::Uuid::new_v4().to_string();
+        let busy_session = uuid::Uuid::new_v4().to_string();
+        let mut slow_agent = session_agent(&slow_session);
+        let mut busy_agent = session_agent(&busy_session);
+        let observation = observation();
+
+        let slow = async {
+            let error = within_timeout(
+                Deadline::after(slow_case.attempt_timeout(None)),
+                None,
+                slow_agent.get_action(
+                    &slow_case.id,
+                    &slow_case.prompt,
+                    &observation,
+                    None,
+                    None,
+                    None,
+                ),
+            )
+            .await
+            .unwrap_err();
+            assert!(error.is::<StepTimedOut>(), "{error:#}");
+            cancel_agent_work(&mut slow_agent, &slow_session).await;
+        };
+        // Still waiting for the model when the slow session is cancelled
+        let busy = async {
+            busy_agent
+                .get_action(
+                    &busy_case.id,
+                    &busy_case.prompt,
+                    &observation,
+                    None,
+                    None,
+                    None,
+                )
+                .await
+        };
+        let ((), actions) = tokio::join!(slow, busy);
+        assert!(!actions.unwrap().is_empty());
+
+        tokio::time::sleep(REPLY_DELAY + Duration::from_secs(1)).await;
+        assert_eq!(requests_for(llm, "[slow] [matrix]"), 1);
+        assert_eq!(requests_for(llm, "[busy] [matrix]"), 2);
+        assert!(!slow_agent.cancel().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_flow_steps_share_one_budget() {
+        let llm = agent_stack();
+        let test_case = sol_transfer("[steps] [shared]");
+        let mut agent = session_agent(&uuid::Uuid::new_v4().to_string());
+        agent.set_budget(reev_config::BudgetSettings {
+            max_turns: Some(3),
+            ..Default::default()
+        });
+        let observation = observation();
+
+        // The first step spends two of the three turns
+        let first = agent
+            .get_action(
+                &test_case.id,
+                &test_case.prompt,
+                &observation,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(!first.is_empty());
+        assert_eq!(agent.budget_exceeded(), None);
+
+        // The second is sent the turn left and stopped after it, with its transfer
+        let second = agent
+            .get_action(
+                &test_case.id,
+                &test_case.prompt,
+                &observation,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(!second.is_empty());
+        assert_eq!(agent.budget_exceeded().as_deref(), Some("max_turns"));
+        assert_eq!(requests_for(llm, "[steps] [shared]"), 3);
+    }
+
+    #[tokio::test]
+    async fn test_flow_budget_stop_is_recorded() {
+        agent_stack();
+        let (_dir, db) = results_db().await;
+        let mut test_case = flow_benchmark();
+        test_case.prompt = format!("[steps] [stop] {}", test_case.prompt);
+        let session_id = uuid::Uuid::new_v4().to_string();
+        start_session(&db, &test_case, "local", &session_id, "run-1").await;
+
+        let mut agent = session_agent(&session_id);
+        agent.set_budget(reev_config::BudgetSettings {
+            max_turns: Some(1),
+            ..Default::default()
+        });
+        agent
+            .get_action(
+                &test_case.id,
+                &test_case.prompt,
+                &observation(),
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(agent.budget_exceeded().as_deref(), Some("max_turns"));
+
+        record_agent_limits(
+            &db,
+            &session_id,
+            agent.budget_exceeded().as_deref(),
+            agent.argument_retries(),
+        )
+        .await;
+        assert_eq!(
+            db.get_budget_exceeded(&session_id)
+                .await
+                .unwrap()
+                .as_deref(),
+            Some("max_turns")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_flow_argument_retries_are_recorded() {
+        agent_stack();
+        let (_dir, db) = results_db().await;
+        let mut test_case = flow_benchmark();
+        test_case.prompt = format!("[correcting] {}", test_case.prompt);
+        let session_id = uuid::Uuid::new_v4().to_string();
+        start_session(&db, &test_case, "local", &session_id, "run-1").await;
+
+        let mut agent = session_agent(&session_id);
+        let actions = agent
+            .get_action(
+                &test_case.id,
+                &test_case.prompt,
+                &observation(),
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(!actions.is_empty());
+        assert_eq!(agent.argument_retries(), 1);
+
+        record_agent_limits(
+            &db,
+            &session_id,
+            agent.budget_exceeded().as_deref(),
+            agent.argument_retries(),
+        )
+        .await;
+        assert_eq!(db.get_argument_retries(&session_id).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_hung_env_step_is_cut_off() {
+        use solana_sdk::signature::{Keypair, Signer};
+
+        // Takes RPC connections but never answers them
+        let rpc = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut env =
+            SolanaEnv::with_rpc_url(&format!("http://{}", rpc.local_addr().unwrap())).unwrap();
+        let payer = Keypair::new();
+        let transfer = solana_system_interface::instruction::transfer(
+            &payer.pubkey(),
+            &solana_sdk::pubkey::Pubkey::new_unique(),
+            1,
+        );
+        env.keypair_map
+            .insert("USER_WALLET_PUBKEY".to_string(), payer);
+        env.fee_payer = Some("USER_WALLET_PUBKEY".to_string());
+        let env: SharedEnv = Arc::new(Mutex::new(env));
+        let test_case = sol_transfer("[hung]");
+
+        let started = Instant::now();
+        let error = within_timeout(
+            Deadline::after(Some(Duration::from_secs(1))),
+            None,
+            step_env(&env, vec![AgentAction(transfer)], &test_case.ground_truth),
+        )
+        .await
+        .unwrap_err();
+        assert!(error.is::<StepTimedOut>(), "{error:#}");
+        assert!(started.elapsed() < Duration::from_secs(3));
+
+        // The step still blocked on its RPC call keeps the environment
+        assert!(env.try_lock().is_err());
+        close_timed_out_env(&env, &test_case);
+    }
+
+    #[tokio::test]
+    async fn test_stored_flow_is_rescored() {
+        let (dir, db) = results_db().await;
+        let db = Arc::new(db);
+        let test_case = flow_benchmark();
+        let session_id = uuid::Uuid::new_v4().to_string();
+        start_session(&db, &test_case, "local", &session_id, "run-1").await;
+
+        // The flow logger stores its log and result, then the runner its trace
+        let mut flow_logger = FlowLogger::new_with_database_preserve_session(
+            test_case.id.clone(),
+            "local".to_string(),
+            dir.path().to_path_buf(),
+            db.clone() as Arc<dyn reev_flow::logger::DatabaseWriter>,
+            Some(session_id.clone()),
+        )
+        .with_run_id(Some("run-1".to_string()));
+        let statistics = flow_logger.get_current_statistics();
+        flow_logger
+            .complete(ExecutionResult {
+                success: false,
+                score: 0.5,
+                total_time_ms: 1000,
+                statistics,
+                scoring_breakdown: None,
+            })
+            .await
+            .unwrap();
+        let mut trace = ExecutionTrace::new(test_case.prompt.clone());
+        trace.initial_observation = Some(Box::new(observation()));
+        for _ in 0..2 {
+            trace.add_step(reev_lib::trace::TraceStep {
+                thought: None,
+                action: vec![],
+                observation: observation(),
+                info: serde_json::Value::Null,
+            });
+        }
+        store_trace(&db, &test_case, &session_id, &trace).await;
+
+        let reader = reev_db::DatabaseReader::from_config(DatabaseConfig::new(
+            dir.path().join("results.db").to_string_lossy(),
+        ))
+        .await
+        .unwrap();
+        let records = reader
+            .get_agent_performance(Some(reev_db::QueryFilter::new().run_id("run-1")))
+            .await
+            .unwrap();
+        let benchmarks = selection::load_benchmarks(
+            &project_root::get_project_root().unwrap().join("benchmarks"),
+        )
+        .unwrap();
+        let (rescored, skipped) = cli::rescore::rescore_results(&reader, &benchmarks, records)
+            .await
+            .unwrap();
+        assert!(skipped.is_empty(), "{skipped:?}");
+        assert_eq!(rescored.len(), 1);
+        assert_eq!(rescored[0].original.session_id, session_id);
+        assert_eq!(
+            rescored[0].score,
+            cli::replay::rescore_trace(&test_case, &trace).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_flow_step_limit_within_the_deadline() {
+        let step: FlowStep =
+            serde_yaml::from_str("step: 2\ndescription: swap\nprompt: swap\ntimeout: 2").unwrap();
+        let slow = || async {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            Ok(())
+        };
+
+        // The step's own limit runs out before the attempt's
+        let deadline = Deadline::after(Some(Duration::from_secs(30)));
+        let error = within_timeout(deadline, Some(&step), slow())
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Flow step 2 timed out after 2s");
+
+        // What is left of the attempt is less than the step's limit
+        let deadline = Deadline::after(Some(Duration::from_secs(1)));
+        let error = within_timeout(deadline, Some(&step), slow())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Benchmark timed out after 1s in flow step 2"
+        );
+
+        // A passed deadline fails the next step at once
+        let started = Instant::now();
+        let error = within_timeout(deadline, Some(&step), slow())
+            .await
+            .unwrap_err();
+        assert!(error.is::<StepTimedOut>());
+        assert!(started.elapsed() < Duration::from_millis(100));
+    }
+}
@@ -6,32 +6,10 @@ use opentelemetry::trace::TracerProvider;
 use opentelemetry_sdk::Resource;
 use opentelemetry_sdk::trace as sdktrace;
 use project_root::get_project_root;
-use reev_runner::renderer;
-use std::path::PathBuf;
+use reev_runner::cli::Cli;
 use tracing::{info, subscriber};
 use tracing_subscriber::{EnvFilter, Registry, fmt, prelude::*};
 
-/// A command-line runner for the Reev evaluation framework.
-#[derive(Parser, Debug)]
-#[command(version, about, long_about = None)]
-struct Cli {
-    /// Path to a specific benchmark YAML file, a directory containing multiple benchmarks, or a flow log file.
-    path: PathBuf,
-
-    /// The agent to run the benchmarks with.
-    /// Can be 'deterministic', 'local', or a specific model name like 'glm-4.6'.
-    #[arg(long, default_value = "deterministic")]
-    agent: String,
-
-    /// Render flow log as ASCII tree (only works with .yml flow files)
-    #[arg(long)]
-    render_flow: bool,
-
-    /// Use shared surfpool instances instead of creating fresh ones
-    #[arg(long)]
-    shared_surfpool: bool,
-}
-
 /// Initializes OpenTelemetry pipeline for tracing with console output.
 fn init_tracing() -> Result<sdktrace::SdkTracerProvider> {
     // Use regular tracing instead of enhanced otel logging to avoid file conflicts
@@ -49,7 +27,7 @@ fn init_tracing() -> Result<sdktrace::SdkTracerProvider> {
             EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| EnvFilter::new("info,reev_lib=debug,reev_runner=debug")),
         )
-        .with(fmt::layer())
+        .with(fmt::layer().with_writer(std::io::stderr))
         .with(tracing_opentelemetry::layer().with_tracer(tracer));
 
     subscriber::set_global_default(subscriber)
@@ -73,43 +51,9 @@ async fn main() -> Result<()> {
 
     let cli = Cli::parse();
     info!("--- Reev Evaluation Runner ---");
-
-    // Check if this is a flow log file that should be rendered
-    if cli.render_flow {
-        if cli.path.extension().and_then(|s| s.to_str()) == Some("yml") {
-            info!("Rendering flow log as ASCII tree: '{}'", cli.path.display());
-            match reev_lib::flow::render_flow_file_as_ascii_tree(&cli.path) {
-                Ok(tree_output) => {
-                    println!("\n{tree_output}");
-                }
-                Err(e) => {
-                    eprintln!("Error rendering flow log: {e}");
-                    std::process::exit(1);
-                }
-            }
-        } else {
-            eprintln!("Error: --render-flow requires a .yml flow log file");
-            std::process::exit(1);
-        }
-    } else {
-        info!(
-            "Running benchmarks at: '{}' with agent: '{}'",
-            cli.path.display(),
-            cli.agent
-        );
-
-        // Run the benchmarks using the library function.
-        let results =
-            reev_runner::run_benchmarks(cli.path, &cli.agent, cli.shared_surfpool, true).await?;
-
-        // Render the results.
-        for result in &results {
-            let tree_output = renderer::render_result_as_tree(result);
-            info!("\n{tree_output}");
-        }
-    }
+    let outcome = cli.execute().await;
 
     // Shutdown tracing.
     tracer_provider.shutdown()?;
-    Ok(())
+    outcome
 }
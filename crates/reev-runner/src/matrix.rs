@@ -0,0 +1,88 @@
+//! Matrix runs: the same benchmarks with several agents in one invocation
+//!
+//! `run --agents deterministic,glm-4.6,local` starts surfpool, reev-agent and the
+//! database once and runs every agent against them, each recorded as its own
+//! evaluation run. Agents of different providers run side by side; attempts of the
+//! same provider are limited by `--max-concurrency <PROVIDER>=<N>` (default 1) so
+//! API rate limits are respected.
+
+use std::{collections::BTreeMap, str::FromStr, sync::Arc};
+use tokio::sync::Semaphore;
+
+/// Concurrent attempts per provider unless `--max-concurrency` says otherwise
+pub const DEFAULT_PROVIDER_CONCURRENCY: usize = 1;
+
+/// The provider serving `agent_name` in the model registry, `unknown` for names it
+/// does not have, which fail once their agent is created
+pub fn agent_provider(agent_name: &str) -> &'static str {
+    reev_config::get()
+        .model(agent_name)
+        .map_or("unknown", |entry| entry.provider.as_str())
+}
+
+/// A concurrency limit given with `--max-concurrency <PROVIDER>=<N>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderLimit {
+    pub provider: String,
+    pub limit: usize,
+}
+
+impl FromStr for ProviderLimit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (provider, limit) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected <PROVIDER>=<N>, got '{s}'"))?;
+        let limit: usize = limit
+            .parse()
+            .map_err(|_| format!("invalid concurrency '{limit}' in '{s}'"))?;
+        if provider.is_empty() || limit == 0 {
+            return Err(format!(
+                "expected a provider and a limit of at least 1, got '{s}'"
+            ));
+        }
+        Ok(Self {
+            provider: provider.to_string(),
+            limit,
+        })
+    }
+}
+
+/// Permits bounding the concurrent attempts of each provider
+#[derive(Debug)]
+pub struct ProviderPermits {
+    /// Limit and permits of each provider
+    semaphores: BTreeMap<&'static str, (usize, Arc<Semaphore>)>,
+}
+
+impl ProviderPermits {
+    /// Permits for the providers of `agents`, with `limits` overriding the default
+    pub fn new(agents: &[String], limits: &[ProviderLimit]) -> Self {
+        let semaphores = agents
+            .iter()
+            .map(|agent| {
+                let provider = agent_provider(agent);
+                let limit = limits
+                    .iter()
+                    .rev()
+                    .find(|l| l.provider == provider)
+                    .map_or(DEFAULT_PROVIDER_CONCURRENCY, |l| l.limit);
+                (provider, (limit, Arc::new(Semaphore::new(limit))))
+            })
+            .collect();
+        Self { semaphores }
+    }
+
+    /// The permits shared by the attempts of `agent_name`'s provider
+    pub fn for_agent(&self, agent_name: &str) -> Arc<Semaphore> {
+        Arc::clone(&self.semaphores[agent_provider(agent_name)].1)
+    }
+
+    /// Each provider with the number of its attempts that may run at once
+    pub fn limits(&self) -> impl Iterator<Item = (&'static str, usize)> + '_ {
+        self.semaphores
+            .iter()
+            .map(|(provider, (limit, _))| (*provider, *limit))
+    }
+}
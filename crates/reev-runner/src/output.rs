@@ -0,0 +1,342 @@
+//! Machine-readable run outputs
+//!
+//! Besides the ASCII trees from [`crate::renderer`], a run can write its results to
+//! files for CI: `--output junit=results.xml --output md=summary.md --output json=results.json`.
+//!
+//! - **JUnit XML**: one `<testcase>` per benchmark with the score as a property; failed
+//!   benchmarks carry the issues from their scoring breakdown.
+//! - **JSON**: the full list of [`TestResult`]s, traces included.
+//! - **Markdown**: a summary table suitable for a PR comment, with the LLM cost of
+//!   each benchmark and the score per dollar when the agent reported its usage.
+//!
+//! A matrix run (`run --agents a,b`) writes a testsuite per agent, the results
+//! grouped by agent, and a benchmark × agent score table.
+
+use anyhow::{Context, Result};
+use reev_db::ScoreMatrix;
+use reev_lib::results::{FinalStatus, TestResult};
+use serde_json::json;
+use std::{
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// Format of a run output file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Junit,
+    Json,
+    Markdown,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "junit" | "xml" => Ok(Self::Junit),
+            "json" => Ok(Self::Json),
+            "md" | "markdown" => Ok(Self::Markdown),
+            other => Err(format!(
+                "unknown output format '{other}' (expected junit, json or md)"
+            )),
+        }
+    }
+}
+
+/// An output file requested with `--output <FORMAT>=<PATH>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputTarget {
+    pub format: OutputFormat,
+    pub path: PathBuf,
+}
+
+impl FromStr for OutputTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (format, path) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected <FORMAT>=<PATH>, got '{s}'"))?;
+        if path.is_empty() {
+            return Err(format!("missing path in '{s}'"));
+        }
+        Ok(Self {
+            format: format.parse()?,
+            path: PathBuf::from(path),
+        })
+    }
+}
+
+/// Write `results` to every requested output
+pub fn write_outputs(results: &[TestResult], agent: &str, targets: &[OutputTarget]) -> Result<()> {
+    for target in targets {
+        let content = match target.format {
+            OutputFormat::Junit => render_junit(results, agent),
+            OutputFormat::Json => render_json(results)?,
+            OutputFormat::Markdown => render_markdown(results, agent),
+        };
+        write_file(&target.path, &content)?;
+    }
+    Ok(())
+}
+
+/// Write the results of a matrix run, given per agent, to every requested output
+pub fn write_matrix_outputs(
+    results: &[(&str, &[TestResult])],
+    matrix: &ScoreMatrix,
+    targets: &[OutputTarget],
+) -> Result<()> {
+    for target in targets {
+        let content = match target.format {
+            OutputFormat::Junit => render_junit_suites(results),
+            OutputFormat::Json => render_matrix_json(results)?,
+            OutputFormat::Markdown => render_matrix_markdown(matrix),
+        };
+        write_file(&target.path, &content)?;
+    }
+    Ok(())
+}
+
+fn write_file(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory '{}'", parent.display()))?;
+    }
+    fs::write(path, content).with_context(|| format!("Failed to write '{}'", path.display()))
+}
+
+/// Issues that lowered a result's score
+fn issues(result: &TestResult) -> Vec<String> {
+    result
+        .scoring_breakdown
+        .as_ref()
+        .map(|b| b.issues.iter().chain(&b.mismatches).cloned().collect())
+        .unwrap_or_default()
+}
+
+fn passed(result: &TestResult) -> bool {
+    result.final_status == FinalStatus::Succeeded
+}
+
+/// Render results as a JUnit XML report
+pub fn render_junit(results: &[TestResult], agent: &str) -> String {
+    render_junit_suites(&[(agent, results)])
+}
+
+/// Render the results of several agents as a JUnit XML report with a testsuite per agent
+pub fn render_junit_suites(suites: &[(&str, &[TestResult])]) -> String {
+    let tests: usize = suites.iter().map(|(_, results)| results.len()).sum();
+    let failures: usize = suites
+        .iter()
+        .map(|(_, results)| results.iter().filter(|r| !passed(r)).count())
+        .sum();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"reev\" tests=\"{tests}\" failures=\"{failures}\">"
+    );
+    for (agent, results) in suites {
+        junit_suite(&mut xml, results, agent);
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn junit_suite(xml: &mut String, results: &[TestResult], agent: &str) {
+    let failures = results.iter().filter(|r| !passed(r)).count();
+    let suite = xml_escape(&format!("reev.{agent}"));
+
+    let _ = writeln!(
+        xml,
+        "  <testsuite name=\"{suite}\" tests=\"{}\" failures=\"{failures}\">",
+        results.len()
+    );
+    for result in results {
+        let _ = writeln!(
+            xml,
+            "    <testcase name=\"{}\" classname=\"{suite}\">",
+            xml_escape(&result.id)
+        );
+        let _ = writeln!(
+            xml,
+            "      <properties>\n        <property name=\"score\" value=\"{:.4}\"/>\n      </properties>",
+            result.score
+        );
+        if result.final_status == FinalStatus::TimedOut {
+            let _ = writeln!(
+                xml,
+                "      <failure message=\"Timed out\" type=\"BenchmarkTimedOut\">{}</failure>",
+                xml_escape(&issues(result).join("\n"))
+            );
+        } else if result.final_status == FinalStatus::BudgetExceeded {
+            let _ = writeln!(
+                xml,
+                "      <failure message=\"Budget exceeded\" type=\"BenchmarkBudgetExceeded\">{}</failure>",
+                xml_escape(&issues(result).join("\n"))
+            );
+        } else if !passed(result) {
+            let _ = writeln!(
+                xml,
+                "      <failure message=\"Scored {:.1}%\" type=\"BenchmarkFailed\">{}</failure>",
+                result.score * 100.0,
+                xml_escape(&issues(result).join("\n"))
+            );
+        }
+        xml.push_str("    </testcase>\n");
+    }
+    xml.push_str("  </testsuite>\n");
+}
+
+/// Render results as pretty-printed JSON
+pub fn render_json(results: &[TestResult]) -> Result<String> {
+    serde_json::to_string_pretty(results).context("Failed to serialize test results")
+}
+
+/// Render the results of several agents as JSON, as a list of `{agent, results}`
+pub fn render_matrix_json(results: &[(&str, &[TestResult])]) -> Result<String> {
+    let agents: Vec<_> = results
+        .iter()
+        .map(|(agent, results)| json!({ "agent": agent, "results": results }))
+        .collect();
+    serde_json::to_string_pretty(&agents).context("Failed to serialize test results")
+}
+
+/// Render results as a Markdown summary table
+pub fn render_markdown(results: &[TestResult], agent: &str) -> String {
+    let passed_count = results.iter().filter(|r| passed(r)).count();
+    let mean = if results.is_empty() {
+        0.0
+    } else {
+        results.iter().map(|r| r.score).sum::<f64>() / results.len() as f64
+    };
+
+    let costed = results.iter().any(|r| r.llm_usage.is_some());
+
+    let mut md = format!("## Reev results: `{agent}`\n\n");
+    if costed {
+        md.push_str("| Benchmark | Status | Score | Cost | Issues |\n");
+        md.push_str("| :--- | :---: | ---: | ---: | :--- |\n");
+    } else {
+        md.push_str("| Benchmark | Status | Score | Issues |\n");
+        md.push_str("| :--- | :---: | ---: | :--- |\n");
+    }
+    for result in results {
+        let status = if passed(result) { "✅" } else { "❌" };
+        let issues = issues(result)
+            .iter()
+            .map(|issue| md_escape(issue))
+            .collect::<Vec<_>>()
+            .join("<br>");
+        let cost = match &result.llm_usage {
+            _ if !costed => String::new(),
+            Some(usage) => format!(" {} |", format_cost(usage.cost_usd)),
+            None => " - |".to_string(),
+        };
+        let _ = writeln!(
+            md,
+            "| `{}` | {status} | {:.1}% |{cost} {issues} |",
+            md_escape(&result.id),
+            result.score * 100.0
+        );
+    }
+    let _ = write!(
+        md,
+        "\n**{passed_count}/{} passed**, mean score **{:.1}%**",
+        results.len(),
+        mean * 100.0
+    );
+
+    // Score per dollar is taken over the attempts of priced models
+    let priced: Vec<(f64, f64)> = results
+        .iter()
+        .filter_map(|r| Some((r.score, r.llm_usage.as_ref()?.cost_usd?)))
+        .collect();
+    if !priced.is_empty() {
+        let cost: f64 = priced.iter().map(|(_, cost)| cost).sum();
+        let _ = write!(md, ", LLM cost **{}**", format_cost(Some(cost)));
+        if cost > 0.0 {
+            let score: f64 = priced.iter().map(|(score, _)| score).sum();
+            let _ = write!(md, ", **{:.1}** score per dollar", score / cost);
+        }
+    }
+    md.push('\n');
+    md
+}
+
+/// A cost in USD, `-` for an unpriced model
+fn format_cost(cost_usd: Option<f64>) -> String {
+    cost_usd.map_or_else(|| "-".to_string(), |cost| format!("${cost:.4}"))
+}
+
+/// Render a score matrix as a Markdown table with a column per agent
+pub fn render_matrix_markdown(matrix: &ScoreMatrix) -> String {
+    let mut md = String::from("## Reev results: agent matrix\n\n| Benchmark |");
+    for agent in &matrix.agents {
+        let _ = write!(md, " `{}` |", md_escape(agent));
+    }
+    md.push_str("\n| :--- |");
+    md.push_str(&" :---: |".repeat(matrix.agents.len()));
+    md.push('\n');
+
+    for row in &matrix.rows {
+        let _ = write!(md, "| `{}` |", md_escape(&row.benchmark_id));
+        for cell in &row.cells {
+            match cell {
+                Some(cell) => {
+                    let status = if cell.passed == cell.trials {
+                        "✅"
+                    } else {
+                        "❌"
+                    };
+                    let _ = write!(md, " {status} {:.1}%", cell.mean_score * 100.0);
+                    if cell.trials > 1 {
+                        let _ = write!(md, " ({}/{})", cell.passed, cell.trials);
+                    }
+                    md.push_str(" |");
+                }
+                None => md.push_str(" - |"),
+            }
+        }
+        md.push('\n');
+    }
+
+    md.push_str("| **Mean** |");
+    for mean in matrix.agent_means() {
+        match mean {
+            Some(mean) => {
+                let _ = write!(md, " **{:.1}%** |", mean * 100.0);
+            }
+            None => md.push_str(" - |"),
+        }
+    }
+    md.push_str("\n| **Passed** |");
+    for (passed, mean) in matrix.agent_passes().into_iter().zip(matrix.agent_means()) {
+        match mean {
+            Some(_) => {
+                let _ = write!(md, " {passed}/{} |", matrix.rows.len());
+            }
+            None => md.push_str(" - |"),
+        }
+    }
+    md.push('\n');
+    md
+}
+
+/// Escape text for a Markdown table cell: pipes would end the cell and newlines the row
+fn md_escape(text: &str) -> String {
+    text.replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace(['\n', '\r'], "<br>")
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
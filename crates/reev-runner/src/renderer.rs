@@ -1,5 +1,10 @@
 use ascii_tree::{Tree, write_tree};
-use reev_lib::{results::TestResult, trace::TraceStep};
+use reev_lib::{
+    balance_delta::{AccountBalanceDelta, format_balance_delta_table},
+    program_logs::ProgramLogTree,
+    results::{FinalStatus, TestResult},
+    trace::TraceStep,
+};
 use solana_sdk::{bs58, instruction::AccountMeta};
 
 /// Renders a `TestResult` object into a human-readable ASCII tree format.
@@ -7,10 +12,11 @@ use solana_sdk::{bs58, instruction::AccountMeta};
 /// This provides a quick, high-level overview of the agent's execution trace
 /// directly in the terminal.
 pub fn render_result_as_tree(result: &TestResult) -> String {
-    let status_icon = if result.final_status == reev_lib::results::FinalStatus::Succeeded {
-        "✅"
-    } else {
-        "❌"
+    let status_icon = match result.final_status {
+        FinalStatus::Succeeded => "✅",
+        FinalStatus::Failed => "❌",
+        FinalStatus::TimedOut => "⏱️",
+        FinalStatus::BudgetExceeded => "💸",
     };
     let score_percent = result.score * 100.0;
     let root_label = format!(
@@ -102,7 +108,57 @@ fn render_step_node(step_number: usize, step: &TraceStep) -> Tree {
         observation_children.push(Tree::Leaf(vec![message.to_string()]));
     }
 
+    let log_tree = ProgramLogTree::parse(&step.observation.last_transaction_logs);
+    for (program_id, error) in log_tree.errors() {
+        observation_children.push(Tree::Leaf(vec![format!(
+            "Program Error: {} failed: {}",
+            reev_lib::program_logs::program_name(program_id),
+            error
+        )]));
+    }
+    let breakdown = log_tree.compute_units_by_program();
+    if !breakdown.is_empty() {
+        let programs = breakdown
+            .iter()
+            .map(|program| format!("{} {}", program.program_name, program.compute_units))
+            .collect::<Vec<_>>()
+            .join(", ");
+        observation_children.push(Tree::Leaf(vec![format!(
+            "Compute Units: {} ({programs})",
+            log_tree.total_compute_units()
+        )]));
+    }
+    if let Some(metrics) = &step.observation.last_transaction_metrics {
+        observation_children.push(Tree::Leaf(vec![format!(
+            "Cost: {} CU, {} lamports fee, {} instruction(s)",
+            metrics.compute_units, metrics.fee_lamports, metrics.instruction_count
+        )]));
+    }
+
     let observation_node = Tree::Node(observation_label, observation_children);
 
-    Tree::Node(step_label, vec![action_node, observation_node])
+    let mut step_children = vec![action_node, observation_node];
+    if let Some(balance_node) = render_balance_deltas_node(&step.observation.balance_deltas) {
+        step_children.push(balance_node);
+    }
+
+    Tree::Node(step_label, step_children)
+}
+
+/// Renders the accounts whose balances changed during the step as a delta table.
+///
+/// Returns `None` when no referenced account changed (or none were captured).
+fn render_balance_deltas_node(deltas: &[AccountBalanceDelta]) -> Option<Tree> {
+    let changed: Vec<AccountBalanceDelta> =
+        deltas.iter().filter(|d| d.has_change()).cloned().collect();
+    if changed.is_empty() {
+        return None;
+    }
+
+    let table = format_balance_delta_table(&changed)
+        .lines()
+        .map(|line| format!("     {line}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(Tree::Leaf(vec![format!("BALANCE CHANGES:\n{table}")]))
 }
@@ -0,0 +1,272 @@
+//! Benchmark discovery and selection
+//!
+//! Shared by every runner subcommand that works on benchmark files: `run`, `list`
+//! and `validate` all discover files the same way and apply the same filters.
+//!
+//! A [`BenchmarkSelection`] combines substring filters, tags (`jupiter`, or `!slow`
+//! to exclude) and id globs (`11*`). It may also name a [`Suite`]: a selection stored
+//! in `benchmarks/suites/<name>.yml` whose results are reported with an aggregate
+//! score. Every kind of criterion that is set must match.
+
+use anyhow::{Context, Result, anyhow};
+use clap::Args;
+use reev_lib::{benchmark::TestCase, results::TestResult};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+use tracing::info;
+
+/// Directory, relative to the benchmarks directory, holding suite manifests
+pub const SUITES_DIR: &str = "suites";
+
+/// A benchmark file together with its parsed test case
+#[derive(Debug, Clone)]
+pub struct BenchmarkFile {
+    pub path: PathBuf,
+    pub test_case: TestCase,
+}
+
+impl BenchmarkFile {
+    /// Load and parse a single benchmark file
+    pub fn load(path: &Path) -> Result<Self> {
+        let f = fs::File::open(path)
+            .with_context(|| format!("Failed to open benchmark: {}", path.display()))?;
+        let test_case: TestCase = serde_yaml::from_reader(f)
+            .with_context(|| format!("Failed to parse benchmark: {}", path.display()))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            test_case,
+        })
+    }
+
+    /// File name without extension, e.g. `001-sol-transfer`
+    pub fn stem(&self) -> String {
+        self.path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+}
+
+/// Discovers benchmark files from a given path, sorted by file name.
+pub fn discover_benchmarks(path: &Path) -> Result<Vec<PathBuf>> {
+    let mut benchmark_paths = vec![];
+    if path.is_dir() {
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            let path = entry.path();
+            if path.is_file()
+                && (path.extension() == Some("yml".as_ref())
+                    || path.extension() == Some("yaml".as_ref()))
+            {
+                benchmark_paths.push(path);
+            }
+        }
+    } else if path.is_file() {
+        benchmark_paths.push(path.to_path_buf());
+    } else {
+        return Err(anyhow!("Provided path is not a valid file or directory"));
+    }
+
+    if benchmark_paths.is_empty() {
+        info!("No benchmark files found to run.");
+    }
+
+    benchmark_paths.sort();
+    Ok(benchmark_paths)
+}
+
+/// Discovers and parses every benchmark file at `path`
+pub fn load_benchmarks(path: &Path) -> Result<Vec<BenchmarkFile>> {
+    discover_benchmarks(path)?
+        .iter()
+        .map(|path| BenchmarkFile::load(path))
+        .collect()
+}
+
+/// Which benchmarks to run or list
+#[derive(Args, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BenchmarkSelection {
+    /// Only benchmarks whose id or file name contains this text (repeatable)
+    #[arg(long = "filter", value_name = "TEXT")]
+    pub filters: Vec<String>,
+
+    /// Only benchmarks with this tag; prefix with `!` to exclude a tag (repeatable)
+    #[arg(long = "tag", value_name = "TAG")]
+    pub tags: Vec<String>,
+
+    /// Only benchmarks whose id or file name matches this glob, e.g. `11*` (repeatable)
+    #[arg(long = "id", value_name = "GLOB")]
+    pub ids: Vec<String>,
+
+    /// Only benchmarks in this suite from `<benchmarks>/suites/<SUITE>.yml`
+    #[arg(long, value_name = "SUITE")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suite: Option<String>,
+}
+
+impl BenchmarkSelection {
+    /// Whether the selection keeps every benchmark
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+            && self.tags.is_empty()
+            && self.ids.is_empty()
+            && self.suite.is_none()
+    }
+
+    /// Whether `benchmark` is selected by the filters, tags and ids
+    ///
+    /// The suite is not consulted here; use [`BenchmarkSelection::resolve`] to apply it.
+    pub fn matches(&self, benchmark: &BenchmarkFile) -> bool {
+        let id = benchmark.test_case.id.to_lowercase();
+        let stem = benchmark.stem().to_lowercase();
+
+        let filter_match = self.filters.is_empty()
+            || self.filters.iter().any(|filter| {
+                let filter = filter.to_lowercase();
+                id.contains(&filter) || stem.contains(&filter)
+            });
+
+        let id_match = self.ids.is_empty()
+            || self.ids.iter().any(|pattern| {
+                let pattern = pattern.to_lowercase();
+                glob_match(&pattern, &id) || glob_match(&pattern, &stem)
+            });
+
+        let has_tag = |tag: &str| {
+            benchmark
+                .test_case
+                .tags
+                .iter()
+                .any(|t| t.eq_ignore_ascii_case(tag))
+        };
+        let (excluded, included): (Vec<&str>, Vec<&str>) = self
+            .tags
+            .iter()
+            .map(String::as_str)
+            .partition(|tag| tag.starts_with('!'));
+        let tag_match = (included.is_empty() || included.iter().any(|tag| has_tag(tag)))
+            && !excluded.iter().any(|tag| has_tag(&tag[1..]));
+
+        filter_match && id_match && tag_match
+    }
+
+    /// Keep only the benchmarks selected by the filters, tags and ids
+    pub fn select(&self, benchmarks: Vec<BenchmarkFile>) -> Vec<BenchmarkFile> {
+        benchmarks.into_iter().filter(|b| self.matches(b)).collect()
+    }
+
+    /// Load the benchmarks at `path` and apply the whole selection, including the suite
+    pub fn resolve(&self, path: &Path) -> Result<Vec<BenchmarkFile>> {
+        let mut benchmarks = load_benchmarks(path)?;
+        if let Some(name) = &self.suite {
+            let suite = Suite::load(&suites_dir(path), name)?;
+            benchmarks = suite.selection.select(benchmarks);
+        }
+        Ok(self.select(benchmarks))
+    }
+}
+
+/// A named, reusable benchmark selection
+///
+/// ```yaml
+/// name: lending
+/// description: Jupiter lending operations
+/// tags: ["lend", "!flow"]
+/// weights:
+///   110-jup-lend-deposit-sol: 2.0
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Suite {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(flatten)]
+    pub selection: BenchmarkSelection,
+    /// Weight of a benchmark in the suite's aggregate score; unlisted benchmarks weigh 1.0
+    #[serde(default)]
+    pub weights: HashMap<String, f64>,
+}
+
+impl Suite {
+    /// Load the suite `name` from `suites_dir`
+    pub fn load(suites_dir: &Path, name: &str) -> Result<Self> {
+        let path = suites_dir.join(format!("{name}.yml"));
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read suite '{name}' at {}", path.display()))?;
+        let suite: Suite = serde_yaml::from_str(&content)
+            .with_context(|| format!("Failed to parse suite: {}", path.display()))?;
+        if suite.selection.suite.is_some() {
+            anyhow::bail!("Suite '{name}' must not reference another suite");
+        }
+        Ok(suite)
+    }
+
+    /// Weighted mean score of `results`; `None` if nothing was run
+    pub fn aggregate_score(&self, results: &[TestResult]) -> Option<f64> {
+        let (total, weights) = results.iter().fold((0.0, 0.0), |(total, weights), r| {
+            let weight = self.weights.get(&r.id).copied().unwrap_or(1.0);
+            (total + r.score * weight, weights + weight)
+        });
+        (weights > 0.0).then(|| total / weights)
+    }
+}
+
+/// Load every suite manifest in `suites_dir`, sorted by name
+pub fn load_suites(suites_dir: &Path) -> Result<Vec<Suite>> {
+    if !suites_dir.is_dir() {
+        return Ok(vec![]);
+    }
+    let mut suites = discover_benchmarks(suites_dir)?
+        .iter()
+        .filter_map(|path| path.file_stem())
+        .map(|name| Suite::load(suites_dir, &name.to_string_lossy()))
+        .collect::<Result<Vec<_>>>()?;
+    suites.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(suites)
+}
+
+/// The suites directory belonging to a benchmark file or directory
+pub fn suites_dir(path: &Path) -> PathBuf {
+    let benchmarks_dir = if path.is_file() {
+        path.parent().unwrap_or(Path::new("."))
+    } else {
+        path
+    };
+    benchmarks_dir.join(SUITES_DIR)
+}
+
+/// Match `text` against a glob `pattern` supporting `*` and `?`
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` swallow one more character and retry
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
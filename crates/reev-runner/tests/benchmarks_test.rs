@@ -37,6 +37,7 @@ use tokio::time::sleep;
 use tracing::{error, info};
 
 use common::helpers::{
+    create_durable_nonce_transfer_instructions, create_multisig_transfer_instruction,
     mock_perfect_instruction, prepare_jupiter_lend_deposit, prepare_jupiter_lend_deposit_usdc,
     prepare_jupiter_lend_withdraw_sol, prepare_jupiter_lend_withdraw_usdc, prepare_jupiter_swap,
     setup_env_for_benchmark,
@@ -280,6 +281,21 @@ async fn test_all_benchmarks_are_solvable(
                     )
                     .await?
                 }
+                "006-durable-nonce-sol-payment" => {
+                    info!("[Test] Durable nonce benchmark detected. Advancing the nonce...");
+                    create_durable_nonce_transfer_instructions(
+                        &initial_observation.key_map,
+                        100_000_000,
+                    )?
+                }
+                "007-multisig-usdc-payment" => {
+                    info!("[Test] Multisig benchmark detected. Signing with 2 of 3 signers...");
+                    vec![create_multisig_transfer_instruction(
+                        &initial_observation.key_map,
+                        &["SIGNER_A", "SIGNER_B"],
+                        15_000_000,
+                    )?]
+                }
                 _ => {
                     info!("[Test] Simple benchmark detected. Creating mock instruction...");
                     vec![mock_perfect_instruction(
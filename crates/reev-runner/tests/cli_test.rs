@@ -0,0 +1,708 @@
+//! Tests for the runner CLI
+//!
+//! These cover argument parsing and the subcommands that work on benchmark files or
+//! result records only, so no surfpool or agent is needed.
+
+use clap::Parser;
+use project_root::get_project_root;
+use reev_config::ReevConfig;
+use reev_db::{
+    EvaluationRun, EvaluationRunSummary, RunAgentConfig, ScoreMatrix, types::SessionInfo,
+};
+use reev_lib::{
+    benchmark::{FlowStep, TestCase},
+    results::{FinalStatus, TestResult},
+    trace::ExecutionTrace,
+};
+use reev_runner::{
+    cli::{
+        Cli, Command,
+        compare::{Thresholds, compare_results},
+        config::format_config,
+        export::{ResultExport, ResultRecord},
+        report::{format_matrix, summarize},
+        run::trial_stats,
+        runs::{format_run, format_runs},
+        validate::validate_benchmarks,
+    },
+    evaluation::{benchmark_hashes, new_evaluation_run, resume_benchmarks},
+    matrix::{ProviderLimit, ProviderPermits, agent_provider},
+    selection::{BenchmarkSelection, Suite, glob_match, load_benchmarks, load_suites, suites_dir},
+};
+use std::{collections::BTreeMap, path::PathBuf, time::Duration};
+
+fn benchmarks_dir() -> PathBuf {
+    get_project_root().unwrap().join("benchmarks")
+}
+
+fn record(benchmark_id: &str, agent_type: &str, score: f64, final_status: &str) -> ResultRecord {
+    ResultRecord {
+        benchmark_id: benchmark_id.to_string(),
+        agent_type: agent_type.to_string(),
+        session_id: format!("{agent_type}-{benchmark_id}"),
+        score,
+        final_status: final_status.to_string(),
+        timestamp: "2025-01-01T00:00:00Z".to_string(),
+        execution_time_ms: Some(1000),
+        trial_index: 0,
+        run_id: None,
+        rescored: false,
+        argument_retries: 0,
+    }
+}
+
+#[test]
+fn test_bare_path_still_runs() {
+    let cli = Cli::try_parse_from([
+        "reev-runner",
+        "benchmarks/001-sol-transfer.yml",
+        "--agent",
+        "glm-4.6",
+    ])
+    .unwrap();
+    assert!(cli.command.is_none());
+    assert_eq!(
+        cli.run.path,
+        Some(PathBuf::from("benchmarks/001-sol-transfer.yml"))
+    );
+    assert_eq!(cli.run.agent, "glm-4.6");
+}
+
+#[test]
+fn test_subcommands_parse() {
+    let cli = Cli::try_parse_from([
+        "reev-runner",
+        "run",
+        "--filter",
+        "jup",
+        "--filter",
+        "sol-transfer",
+        "--tag",
+        "jupiter",
+        "--tag",
+        "!slow",
+        "--id",
+        "11*",
+        "--suite",
+        "lending",
+    ])
+    .unwrap();
+    match cli.command {
+        Some(Command::Run(args)) => {
+            assert_eq!(args.path(), PathBuf::from("benchmarks"));
+            assert_eq!(args.agent, "deterministic");
+            assert_eq!(args.selection.filters, vec!["jup", "sol-transfer"]);
+            assert_eq!(args.selection.tags, vec!["jupiter", "!slow"]);
+            assert_eq!(args.selection.ids, vec!["11*"]);
+            assert_eq!(args.selection.suite.as_deref(), Some("lending"));
+        }
+        other => panic!("expected run, got {other:?}"),
+    }
+
+    let cli =
+        Cli::try_parse_from(["reev-runner", "compare", "deterministic", "base.json"]).unwrap();
+    match cli.command {
+        Some(Command::Compare(args)) => {
+            assert_eq!(args.baseline, "deterministic");
+            assert_eq!(args.candidate, "base.json");
+            assert_eq!(args.db.db, None);
+            assert_eq!(args.db.path(), PathBuf::from("db/reev_results.db"));
+        }
+        other => panic!("expected compare, got {other:?}"),
+    }
+
+    let cli = Cli::try_parse_from(["reev-runner", "replay", "abc", "--db", "other.db"]).unwrap();
+    assert!(
+        matches!(cli.command, Some(Command::Replay(args)) if args.session_id == "abc"
+        && args.db.path() == std::path::Path::new("other.db"))
+    );
+
+    assert!(Cli::try_parse_from(["reev-runner", "export", "--all", "-o", "out.json"]).is_ok());
+    assert!(Cli::try_parse_from(["reev-runner", "report", "--agent", "local"]).is_ok());
+    assert!(Cli::try_parse_from(["reev-runner", "list", "benchmarks"]).is_ok());
+    assert!(Cli::try_parse_from(["reev-runner", "validate"]).is_ok());
+}
+
+#[test]
+fn test_filter_selection() {
+    let benchmarks = load_benchmarks(&benchmarks_dir()).unwrap();
+    assert!(!benchmarks.is_empty());
+
+    let all = BenchmarkSelection::default().select(benchmarks.clone());
+    assert_eq!(all.len(), benchmarks.len());
+
+    let selection = BenchmarkSelection {
+        filters: vec!["SOL-TRANSFER".to_string(), "200-".to_string()],
+        ..Default::default()
+    };
+    let ids: Vec<String> = selection
+        .select(benchmarks)
+        .into_iter()
+        .map(|b| b.test_case.id)
+        .collect();
+    assert_eq!(ids.len(), 2);
+    assert!(ids.contains(&"001-sol-transfer".to_string()));
+    assert!(ids.iter().any(|id| id.starts_with("200-")));
+}
+
+fn selected_ids(selection: &BenchmarkSelection) -> Vec<String> {
+    selection
+        .resolve(&benchmarks_dir())
+        .unwrap()
+        .into_iter()
+        .map(|b| b.test_case.id)
+        .collect()
+}
+
+#[test]
+fn test_tag_and_id_selection() {
+    let jupiter_not_lend = BenchmarkSelection {
+        tags: vec!["Jupiter".to_string(), "!lend".to_string()],
+        ..Default::default()
+    };
+    assert_eq!(
+        selected_ids(&jupiter_not_lend),
+        vec!["100-jup-swap-sol-usdc", "114-jup-positions-and-earnings"]
+    );
+
+    let lending_glob = BenchmarkSelection {
+        ids: vec!["11?-jup-lend-*".to_string()],
+        tags: vec!["!jtoken".to_string()],
+        ..Default::default()
+    };
+    assert_eq!(
+        selected_ids(&lending_glob),
+        vec![
+            "110-jup-lend-deposit-sol",
+            "111-jup-lend-deposit-usdc",
+            "112-jup-lend-withdraw-sol",
+            "113-jup-lend-withdraw-usdc",
+        ]
+    );
+
+    assert!(glob_match("11*", "115-jup-lend-mint-usdc"));
+    assert!(glob_match("*usdc", "115-jup-lend-mint-usdc"));
+    assert!(glob_match("*-*-*", "a-b-c"));
+    assert!(!glob_match("11*", "200-jup-swap-then-lend-deposit"));
+    assert!(!glob_match("00?", "0012"));
+}
+
+#[test]
+fn test_suites() {
+    let suites = load_suites(&suites_dir(&benchmarks_dir())).unwrap();
+    let names: Vec<&str> = suites.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["flows", "lending", "smoke"]);
+
+    let flows = BenchmarkSelection {
+        suite: Some("flows".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(selected_ids(&flows), vec!["200-jup-swap-then-lend-deposit"]);
+
+    // Extra criteria narrow the suite further
+    let lending_deposits = BenchmarkSelection {
+        suite: Some("lending".to_string()),
+        filters: vec!["deposit".to_string()],
+        ..Default::default()
+    };
+    assert_eq!(
+        selected_ids(&lending_deposits),
+        vec!["110-jup-lend-deposit-sol", "111-jup-lend-deposit-usdc"]
+    );
+
+    let missing = BenchmarkSelection {
+        suite: Some("does-not-exist".to_string()),
+        ..Default::default()
+    };
+    assert!(missing.resolve(&benchmarks_dir()).is_err());
+}
+
+#[test]
+fn test_suite_aggregate_score() {
+    let suite: Suite = serde_yaml::from_str(
+        r#"
+name: weighted
+ids: ["*"]
+weights:
+  a: 3.0
+"#,
+    )
+    .unwrap();
+    assert_eq!(suite.selection.ids, vec!["*"]);
+    assert_eq!(suite.aggregate_score(&[]), None);
+
+    let result = |id: &str, score: f64| TestResult {
+        id: id.to_string(),
+        prompt: String::new(),
+        final_status: FinalStatus::Succeeded,
+        score,
+        trace: ExecutionTrace::new(String::new()),
+        scoring_breakdown: None,
+        trial_index: 0,
+        run_id: None,
+        llm_usage: None,
+    };
+    let score = suite
+        .aggregate_score(&[result("a", 1.0), result("b", 0.2)])
+        .unwrap();
+    assert!((score - 0.8).abs() < 1e-9);
+}
+
+#[test]
+fn test_repo_benchmarks_are_valid() {
+    let reports = validate_benchmarks(&benchmarks_dir()).unwrap();
+    assert!(!reports.is_empty());
+    for report in reports {
+        assert!(
+            report.is_valid(),
+            "{} is invalid: {:?}",
+            report.path.display(),
+            report.problems
+        );
+    }
+}
+
+#[test]
+fn test_validate_reports_duplicates_and_parse_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = std::fs::read_to_string(benchmarks_dir().join("001-sol-transfer.yml")).unwrap();
+    std::fs::write(dir.path().join("a.yml"), &source).unwrap();
+    std::fs::write(dir.path().join("b.yml"), &source).unwrap();
+    std::fs::write(dir.path().join("c.yml"), "id: [not a benchmark").unwrap();
+
+    let reports = validate_benchmarks(dir.path()).unwrap();
+    assert_eq!(reports.len(), 3);
+    assert!(reports[0].problems[0].contains("also used by"));
+    assert!(reports[1].problems[0].contains("also used by"));
+    assert!(reports[2].problems[0].contains("Failed to parse benchmark"));
+}
+
+#[test]
+fn test_compare_results() {
+    let baseline = vec![
+        record("001", "deterministic", 1.0, "succeeded"),
+        record("002", "deterministic", 0.8, "succeeded"),
+        record("003", "deterministic", 0.5, "failed"),
+    ];
+    let candidate = vec![
+        record("001", "glm-4.6", 1.0, "succeeded"),
+        record("002", "glm-4.6", 0.4, "failed"),
+        record("004", "glm-4.6", 1.0, "succeeded"),
+    ];
+
+    let comparison = compare_results(&baseline, &candidate);
+    assert_eq!(comparison.deltas.len(), 2);
+    assert_eq!(comparison.missing, vec!["003"]);
+    assert_eq!(comparison.added, vec!["004"]);
+
+    let newly_failing = comparison.newly_failing();
+    assert_eq!(newly_failing.len(), 1);
+    assert_eq!(newly_failing[0].benchmark_id, "002");
+    assert!((newly_failing[0].delta() + 0.4).abs() < 1e-9);
+    assert!((comparison.baseline_mean() - 0.9).abs() < 1e-9);
+    assert!((comparison.mean_delta() + 0.2).abs() < 1e-9);
+}
+
+#[test]
+fn test_summarize_and_export_roundtrip() {
+    let records = vec![
+        record("001", "deterministic", 1.0, "succeeded"),
+        record("002", "deterministic", 0.5, "failed"),
+        record("001", "local", 0.9, "succeeded"),
+    ];
+
+    let summary = summarize(&records);
+    assert_eq!(summary.len(), 2);
+    assert_eq!(summary[0].agent_type, "deterministic");
+    assert_eq!(summary[0].passed, 1);
+    assert!((summary[0].mean_score - 0.75).abs() < 1e-9);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("results.json");
+    let export = ResultExport::new(records.clone());
+    std::fs::write(&path, serde_json::to_string(&export).unwrap()).unwrap();
+    assert_eq!(ResultExport::load(&path).unwrap().records, records);
+}
+
+#[test]
+fn test_regression_gate() {
+    let baseline = vec![
+        record("001", "deterministic", 1.0, "succeeded"),
+        record("002", "deterministic", 0.9, "succeeded"),
+        record("003", "deterministic", 0.8, "succeeded"),
+    ];
+    let thresholds = Thresholds::default();
+
+    // Small drops within both limits pass
+    let candidate = vec![
+        record("001", "glm-4.6", 1.0, "succeeded"),
+        record("002", "glm-4.6", 0.88, "succeeded"),
+        record("003", "glm-4.6", 0.8, "succeeded"),
+    ];
+    assert!(
+        compare_results(&baseline, &candidate)
+            .violations(&thresholds)
+            .is_empty()
+    );
+
+    // A drop of exactly the limit is allowed
+    let candidate = vec![
+        record("001", "glm-4.6", 1.0, "succeeded"),
+        record("002", "glm-4.6", 0.9, "succeeded"),
+        record("003", "glm-4.6", 0.7, "succeeded"),
+    ];
+    let comparison = compare_results(&baseline, &candidate);
+    let violations = comparison.violations(&Thresholds {
+        max_mean_drop: 0.05,
+        ..Default::default()
+    });
+    assert!(violations.is_empty(), "{violations:?}");
+    // ... but the mean dropped by ~0.033, over the default 0.02
+    let violations = comparison.violations(&thresholds);
+    assert_eq!(violations.len(), 1);
+    assert!(violations[0].starts_with("Mean score dropped 3.3 points"));
+
+    // Large drops, new failures and missing benchmarks all fail the gate
+    let candidate = vec![
+        record("001", "glm-4.6", 1.0, "succeeded"),
+        record("002", "glm-4.6", 0.5, "failed"),
+    ];
+    let comparison = compare_results(&baseline, &candidate);
+    let violations = comparison.violations(&thresholds);
+    assert_eq!(
+        violations,
+        vec![
+            "002 dropped 40.0 points (limit 10.0)",
+            "002 is newly failing",
+            "Mean score dropped 20.0 points (limit 2.0)",
+            "Missing from candidate: 003",
+        ]
+    );
+    let relaxed = Thresholds {
+        max_benchmark_drop: 0.5,
+        max_mean_drop: 0.5,
+        allow_new_failures: true,
+        allow_missing: true,
+    };
+    assert!(comparison.violations(&relaxed).is_empty());
+}
+
+#[test]
+fn test_gate_args() {
+    let cli = Cli::try_parse_from(["reev-runner", "gate", "baseline.json", "glm-4.6"]).unwrap();
+    match cli.command {
+        Some(Command::Gate(args)) => assert_eq!(args.thresholds(), Thresholds::default()),
+        other => panic!("expected gate, got {other:?}"),
+    }
+
+    let cli = Cli::try_parse_from([
+        "reev-runner",
+        "gate",
+        "deterministic",
+        "candidate.json",
+        "--max-benchmark-drop",
+        "0.25",
+        "--max-mean-drop",
+        "0",
+        "--allow-missing",
+    ])
+    .unwrap();
+    match cli.command {
+        Some(Command::Gate(args)) => {
+            let thresholds = args.thresholds();
+            assert_eq!(thresholds.max_benchmark_drop, 0.25);
+            assert_eq!(thresholds.max_mean_drop, 0.0);
+            assert!(thresholds.allow_missing);
+            assert!(!thresholds.allow_new_failures);
+        }
+        other => panic!("expected gate, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_trials() {
+    let cli = Cli::try_parse_from(["reev-runner", "run", "--trials", "5"]).unwrap();
+    match cli.command {
+        Some(Command::Run(args)) => assert_eq!(args.trials, 5),
+        other => panic!("expected run, got {other:?}"),
+    }
+    assert_eq!(Cli::try_parse_from(["reev-runner"]).unwrap().run.trials, 1);
+    assert!(Cli::try_parse_from(["reev-runner", "run", "--trials", "0"]).is_err());
+
+    let cli = Cli::try_parse_from(["reev-runner", "report", "--trials", "--k", "2"]).unwrap();
+    assert!(matches!(cli.command, Some(Command::Report(args)) if args.trials && args.k == Some(2)));
+    assert!(Cli::try_parse_from(["reev-runner", "report", "--k", "2"]).is_err());
+
+    let result = |id: &str, score: f64, trial_index: u32| {
+        let final_status = if score >= 0.75 {
+            FinalStatus::Succeeded
+        } else {
+            FinalStatus::Failed
+        };
+        TestResult {
+            id: id.to_string(),
+            prompt: String::new(),
+            final_status,
+            score,
+            trace: ExecutionTrace::new(String::new()),
+            scoring_breakdown: None,
+            trial_index,
+            run_id: None,
+            llm_usage: None,
+        }
+    };
+    let results = [
+        result("001", 1.0, 0),
+        result("001", 0.5, 1),
+        result("001", 1.0, 2),
+        result("002", 0.0, 0),
+        result("002", 0.0, 1),
+        result("002", 0.0, 2),
+    ];
+    let stats = trial_stats(&results, "glm-4.6");
+    assert_eq!(stats.len(), 2);
+    assert_eq!(stats[0].benchmark_id, "001");
+    assert_eq!(stats[0].agent_type, "glm-4.6");
+    assert_eq!((stats[0].passed, stats[0].trials), (2, 3));
+    assert!((stats[0].pass_at_1 - 2.0 / 3.0).abs() < 1e-9);
+    assert_eq!(stats[0].pass_at_k, 1.0);
+    assert_eq!(stats[1].pass_at_k, 0.0);
+    assert_eq!(stats[1].std_dev, 0.0);
+}
+
+#[test]
+fn test_runs() {
+    let cli = Cli::try_parse_from(["reev-runner", "run", "--seed", "42"]).unwrap();
+    match cli.command {
+        Some(Command::Run(args)) => assert_eq!(args.seed, Some(42)),
+        other => panic!("expected run, got {other:?}"),
+    }
+    let cli = Cli::try_parse_from(["reev-runner", "runs", "--limit", "5"]).unwrap();
+    assert!(
+        matches!(cli.command, Some(Command::Runs(args)) if args.run_id.is_none() && args.limit == 5)
+    );
+    let cli = Cli::try_parse_from(["reev-runner", "runs", "run-1"]).unwrap();
+    assert!(
+        matches!(cli.command, Some(Command::Runs(args)) if args.run_id.as_deref() == Some("run-1"))
+    );
+
+    let path = benchmarks_dir().join("001-sol-transfer.yml");
+    let hashes = benchmark_hashes(std::slice::from_ref(&path)).unwrap();
+    assert_eq!(hashes.len(), 1);
+    assert_eq!(hashes["001-sol-transfer"].len(), 64);
+    assert_eq!(hashes, benchmark_hashes(&[path]).unwrap());
+
+    let run = EvaluationRun {
+        run_id: "run-1".to_string(),
+        agent_type: "glm-4.6".to_string(),
+        agent_config: RunAgentConfig {
+            model: "glm-4.6".to_string(),
+            temperature: Some(0.2),
+            prompt_version: None,
+        },
+        git_commit: Some("0123456789abcdef".to_string()),
+        benchmark_hashes: BTreeMap::from([("001-sol-transfer".to_string(), "ab".repeat(32))]),
+        seed: Some(42),
+        trials: 3,
+        status: "completed".to_string(),
+        start_time: 1_700_000_000,
+        end_time: Some(1_700_000_100),
+    };
+    let table = format_runs(&[EvaluationRunSummary {
+        run: run.clone(),
+        total: 2,
+        passed: 1,
+        average_score: Some(0.75),
+        cost_usd: Some(0.0425),
+        score_per_dollar: Some(35.3),
+    }]);
+    assert!(table.contains("run-1"));
+    assert!(table.contains("$0.0425"));
+    assert!(table.contains("1/2"));
+    assert!(table.contains("01234567"));
+    assert!(!table.contains("0123456789"));
+
+    let session = SessionInfo {
+        session_id: "session-1".to_string(),
+        benchmark_id: "001-sol-transfer".to_string(),
+        agent_type: "glm-4.6".to_string(),
+        interface: "tui".to_string(),
+        start_time: 1_700_000_000,
+        end_time: Some(1_700_000_050),
+        status: "completed".to_string(),
+        score: Some(1.0),
+        final_status: Some("Succeeded".to_string()),
+        run_id: Some("run-1".to_string()),
+    };
+    let details = format_run(&run, &[session]);
+    assert!(details.contains("0123456789abcdef"));
+    assert!(details.contains(&"ab".repeat(32)));
+    assert!(details.contains("session-1"));
+    assert!(details.contains("Seed:            42"));
+    assert!(details.contains("Trials:          3"));
+    assert!(format_run(&run, &[]).contains("No sessions recorded"));
+}
+
+#[test]
+fn test_resume() {
+    let cli = Cli::try_parse_from(["reev-runner", "run", "--resume", "run-1"]).unwrap();
+    match cli.command {
+        Some(Command::Run(args)) => assert_eq!(args.resume.as_deref(), Some("run-1")),
+        other => panic!("expected run, got {other:?}"),
+    }
+    for conflicting in [
+        ["--agent", "glm-4.6"],
+        ["--trials", "3"],
+        ["--tag", "jupiter"],
+        ["--suite", "smoke"],
+    ] {
+        let mut args = vec!["reev-runner", "run", "--resume", "run-1"];
+        args.extend(conflicting);
+        assert!(Cli::try_parse_from(args).is_err(), "{conflicting:?}");
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let source = std::fs::read_to_string(benchmarks_dir().join("001-sol-transfer.yml")).unwrap();
+    let path = dir.path().join("001-sol-transfer.yml");
+    std::fs::write(&path, &source).unwrap();
+    let run = new_evaluation_run("deterministic", std::slice::from_ref(&path), 2, None).unwrap();
+    assert_eq!(run.trials, 2);
+    assert_eq!(
+        resume_benchmarks(&run, dir.path()).unwrap(),
+        vec![path.clone()]
+    );
+
+    // A benchmark edited since the run started cannot be resumed
+    std::fs::write(&path, format!("{source}\n# edited\n")).unwrap();
+    let error = resume_benchmarks(&run, dir.path()).unwrap_err();
+    assert!(error.to_string().contains("changed since run"));
+
+    std::fs::remove_file(&path).unwrap();
+    let error = resume_benchmarks(&run, dir.path()).unwrap_err();
+    assert!(error.to_string().contains("not found"));
+}
+
+#[test]
+fn test_timeouts() {
+    let cli = Cli::try_parse_from(["reev-runner", "run", "--timeout", "30"]).unwrap();
+    match cli.command {
+        Some(Command::Run(args)) => assert_eq!(args.timeout, Some(30)),
+        other => panic!("expected run, got {other:?}"),
+    }
+    assert!(Cli::try_parse_from(["reev-runner", "run", "--timeout", "0"]).is_err());
+
+    let source = std::fs::read_to_string(benchmarks_dir().join("001-sol-transfer.yml")).unwrap();
+    let mut test_case: TestCase = serde_yaml::from_str(&source).unwrap();
+    let global = Some(Duration::from_secs(60));
+    assert_eq!(test_case.timeout, None);
+    assert_eq!(test_case.attempt_timeout(None), None);
+    assert_eq!(test_case.attempt_timeout(global), global);
+
+    // The benchmark's limit wins over the global one
+    test_case.timeout = Some(20);
+    assert_eq!(
+        test_case.attempt_timeout(global),
+        Some(Duration::from_secs(20))
+    );
+    let step: FlowStep =
+        serde_yaml::from_str("step: 1\ndescription: swap\nprompt: swap\ntimeout: 5").unwrap();
+    assert_eq!(step.timeout, Some(5));
+
+    test_case.timeout = Some(0);
+    assert!(
+        test_case
+            .validate()
+            .contains(&"timeout must be at least 1 second".to_string())
+    );
+}
+
+#[test]
+fn test_agent_matrix() {
+    let cli = Cli::try_parse_from([
+        "reev-runner",
+        "run",
+        "--agents",
+        "deterministic,glm-4.6,gpt-4o",
+        "--max-concurrency",
+        "zai=2",
+    ])
+    .unwrap();
+    let args = match cli.command {
+        Some(Command::Run(args)) => args,
+        other => panic!("expected run, got {other:?}"),
+    };
+    assert_eq!(args.agents, ["deterministic", "glm-4.6", "gpt-4o"]);
+    assert!(
+        Cli::try_parse_from(["reev-runner", "run", "--agents", "a,b", "--agent", "c"]).is_err()
+    );
+    assert!(Cli::try_parse_from(["reev-runner", "run", "--max-concurrency", "zai=2"]).is_err());
+    assert!("zai=0".parse::<ProviderLimit>().is_err());
+    assert!("zai".parse::<ProviderLimit>().is_err());
+
+    assert_eq!(agent_provider("glm-4.6"), "zai");
+    assert_eq!(agent_provider("gpt-4o"), "openai");
+    assert_eq!(agent_provider("local"), "local");
+    assert_eq!(agent_provider("deterministic"), "deterministic");
+    assert_eq!(agent_provider("glm"), "zai");
+    assert_eq!(agent_provider("gpt-5-turbo"), "unknown");
+    let err = reev_lib::llm_agent::LlmAgent::new("gpt-5-turbo")
+        .err()
+        .expect("unregistered models are rejected");
+    assert!(
+        err.to_string().starts_with("Unknown model 'gpt-5-turbo'"),
+        "{err}"
+    );
+    let permits = ProviderPermits::new(&args.agents, &args.max_concurrency);
+    let limits: Vec<(&str, usize)> = permits.limits().collect();
+    assert_eq!(limits, [("deterministic", 1), ("openai", 1), ("zai", 2)]);
+
+    let result = |id: &str, score: f64, final_status: FinalStatus| TestResult {
+        id: id.to_string(),
+        prompt: String::new(),
+        final_status,
+        score,
+        trace: ExecutionTrace::new(String::new()),
+        scoring_breakdown: None,
+        trial_index: 0,
+        run_id: None,
+        llm_usage: None,
+    };
+    let results = [
+        result("001-sol-transfer", 1.0, FinalStatus::Succeeded),
+        result("002-spl-transfer", 0.5, FinalStatus::Failed),
+    ];
+    let stats = trial_stats(&results, "glm-4.6");
+    let matrix = ScoreMatrix::from_stats(&args.agents, &stats);
+    let table = format_matrix(&matrix);
+    assert!(table.starts_with("BENCHMARK         deterministic  glm-4.6       gpt-4o"));
+    assert!(table.contains("002-spl-transfer  -              50.0% (0/1)   -"));
+    assert!(table.contains("MEAN              -              75.0%         -"));
+    assert!(table.contains("PASSED            -              1/2           -"));
+}
+
+#[test]
+fn test_config_profile() {
+    let cli = Cli::try_parse_from(["reev-runner", "config", "show", "--profile", "ci"]).unwrap();
+    assert_eq!(cli.profile.as_deref(), Some("ci"));
+    assert!(matches!(cli.command, Some(Command::Config(_))));
+
+    let cli = Cli::try_parse_from(["reev-runner", "benchmarks", "--profile", "local-llm"]).unwrap();
+    assert_eq!(cli.profile.as_deref(), Some("local-llm"));
+    assert!(cli.command.is_none());
+
+    let source = std::fs::read_to_string(get_project_root().unwrap().join("reev.toml")).unwrap();
+    let config = ReevConfig::from_toml(&source, Some("ci")).unwrap();
+    let shown = format_config(
+        &config,
+        &get_project_root().unwrap().join("reev.toml"),
+        Some("ci"),
+        &ReevConfig::profiles(&source).unwrap(),
+    );
+    assert!(shown.contains("# Profile: ci (available: ci, cloud, local-llm)"));
+    assert!(shown.contains("path = \"db/ci_results.db\""));
+
+    let missing = format_config(&config, std::path::Path::new("missing.toml"), None, &[]);
+    assert!(missing.starts_with(
+        "# Configuration file: missing.toml (not found, using defaults)\n# Profile: - (available: none)"
+    ));
+}
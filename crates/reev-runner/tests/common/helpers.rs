@@ -67,7 +67,9 @@ pub fn mock_perfect_instruction(
     key_map: &HashMap<String, String>,
 ) -> Result<Instruction> {
     match test_case.id.as_str() {
-        "001-sol-transfer" => create_sol_transfer_instruction(key_map, 100_000_000), // 0.1 SOL
+        "001-sol-transfer" | "005-sol-payment-efficiency" => {
+            create_sol_transfer_instruction(key_map, 100_000_000) // 0.1 SOL
+        }
         "002-spl-transfer" | "003-spl-transfer-fail" => {
             create_spl_transfer_instruction(key_map, 15_000_000) // 15 USDC
         }
@@ -101,6 +103,58 @@ fn create_sol_transfer_instruction(
     ))
 }
 
+/// Creates the durable transaction of `006-durable-nonce-sol-payment`: an
+/// `AdvanceNonceAccount` followed by a SOL transfer of `lamports`.
+pub fn create_durable_nonce_transfer_instructions(
+    key_map: &HashMap<String, String>,
+    lamports: u64,
+) -> Result<Vec<Instruction>> {
+    let nonce_str = key_map
+        .get("NONCE_ACCOUNT")
+        .ok_or_else(|| anyhow!("Pubkey placeholder 'NONCE_ACCOUNT' not found in key_map"))?;
+    let authority_str = key_map
+        .get("USER_WALLET_PUBKEY")
+        .ok_or_else(|| anyhow!("Pubkey placeholder 'USER_WALLET_PUBKEY' not found in key_map"))?;
+
+    let advance = system_instruction::advance_nonce_account(
+        &Pubkey::from_str(nonce_str)?,
+        &Pubkey::from_str(authority_str)?,
+    );
+    Ok(vec![
+        advance,
+        create_sol_transfer_instruction(key_map, lamports)?,
+    ])
+}
+
+/// Creates the transfer of `007-multisig-usdc-payment`, moving `amount` out of the
+/// multisig-owned treasury with the approval of the `signers` placeholders.
+pub fn create_multisig_transfer_instruction(
+    key_map: &HashMap<String, String>,
+    signers: &[&str],
+    amount: u64,
+) -> Result<Instruction> {
+    let pubkey = |placeholder: &str| -> Result<Pubkey> {
+        let pubkey_str = key_map
+            .get(placeholder)
+            .ok_or_else(|| anyhow!("Pubkey placeholder '{placeholder}' not found in key_map"))?;
+        Ok(Pubkey::from_str(pubkey_str)?)
+    };
+    let signer_pubkeys = signers
+        .iter()
+        .map(|signer| pubkey(signer))
+        .collect::<Result<Vec<Pubkey>>>()?;
+    let signer_refs: Vec<&Pubkey> = signer_pubkeys.iter().collect();
+
+    Ok(spl_token::instruction::transfer(
+        &spl_token::ID,
+        &pubkey("TREASURY_USDC_ATA")?,
+        &pubkey("RECIPIENT_USDC_ATA")?,
+        &pubkey("TREASURY_MULTISIG")?,
+        &signer_refs,
+        amount,
+    )?)
+}
+
 /// Helper to create an SPL transfer instruction.
 fn create_spl_transfer_instruction(
     key_map: &HashMap<String, String>,
@@ -31,6 +31,8 @@ async fn test_agent_performance_timestamp_ordering() {
             .format("%Y-%m-%d %H:%M:%S%.3f")
             .to_string(),
         prompt_md5: None,
+        trial_index: 0,
+        run_id: None,
     };
 
     db.insert_agent_performance(&reev_lib::db::DbAgentPerformance::from(older_result))
@@ -48,6 +50,8 @@ async fn test_agent_performance_timestamp_ordering() {
         execution_time_ms: 800,
         timestamp: base_time.format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
         prompt_md5: None,
+        trial_index: 0,
+        run_id: None,
     };
 
     db.insert_agent_performance(&reev_lib::db::DbAgentPerformance::from(newer_result))
@@ -67,6 +71,8 @@ async fn test_agent_performance_timestamp_ordering() {
             .format("%Y-%m-%d %H:%M:%S%.3f")
             .to_string(),
         prompt_md5: None,
+        trial_index: 0,
+        run_id: None,
     };
 
     db.insert_agent_performance(&reev_lib::db::DbAgentPerformance::from(other_result))
@@ -86,6 +92,7 @@ async fn test_agent_performance_timestamp_ordering() {
             max_score: None,
             date_from: None,
             date_to: None,
+            run_id: None,
             limit: None,
             offset: None,
             sort_by: None,
@@ -144,6 +151,8 @@ async fn test_flow_log_id_null_handling() {
             .format("%Y-%m-%d %H:%M:%S%.3f")
             .to_string(),
         prompt_md5: None,
+        trial_index: 0,
+        run_id: None,
     };
 
     // This should not fail due to foreign key constraint
@@ -164,6 +173,7 @@ async fn test_flow_log_id_null_handling() {
             max_score: None,
             date_from: None,
             date_to: None,
+            run_id: None,
             limit: None,
             offset: None,
             sort_by: None,
@@ -1,6 +1,18 @@
 //! Tests for dependency management functionality
 
-use reev_runner::dependency::{DependencyConfig, DependencyManager, DependencyType};
+use axum::{
+    Json, Router,
+    routing::{get, post},
+};
+use reev_config::ReevConfig;
+use reev_runner::dependency::{
+    DependencyConfig, DependencyManager, DependencyType, HealthChecker, ServiceHealth,
+};
+use serde_json::json;
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
 use tempfile::TempDir;
 
 #[tokio::test]
@@ -17,8 +29,8 @@ async fn test_dependency_manager_creation() {
 }
 
 #[tokio::test]
-async fn test_dependency_config_from_env() {
-    let config = DependencyConfig::from_env();
+async fn test_dependency_config_from_config() {
+    let config = DependencyConfig::from_config(&ReevConfig::default());
 
     // Should have default values
     assert_eq!(config.reev_agent_port, 9090);
@@ -26,6 +38,17 @@ async fn test_dependency_config_from_env() {
     assert!(config.auto_start);
     assert_eq!(config.cache_dir, ".surfpool/cache");
     assert_eq!(config.log_dir, "logs");
+
+    let reev_config = ReevConfig::from_toml(
+        "[surfpool]\nrpc_port = 9899\n[runner]\nauto_start = false\nlog_dir = \"ci-logs\"\n",
+        None,
+    )
+    .unwrap();
+    let config = DependencyConfig::from_config(&reev_config);
+    assert_eq!(config.surfpool_rpc_port, 9899);
+    assert!(!config.auto_start);
+    assert_eq!(config.log_dir, "ci-logs");
+    assert_eq!(config.reev_agent_port, 9090);
 }
 
 #[tokio::test]
@@ -216,3 +239,98 @@ async fn test_surfpool_logging_env_vars() {
 // more complex setup and are omitted here to keep tests fast and reliable.
 // In a real testing environment, you might want to mock the external services
 // or use test fixtures that don't require actual process spawning.
+
+/// Starts a stub of an Ollama server with `llama3.1` pulled and of a llama.cpp server
+/// running with a 4096-token context, counting the pulls requested
+async fn start_stub_model_server(pulls: Arc<AtomicUsize>) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = Router::new()
+        .route(
+            "/api/tags",
+            get(|| async { Json(json!({ "models": [{ "name": "llama3.1:latest" }] })) }),
+        )
+        .route(
+            "/api/pull",
+            post(move || async move {
+                pulls.fetch_add(1, Ordering::SeqCst);
+                Json(json!({ "status": "success" }))
+            }),
+        )
+        .route("/health", get(|| async { Json(json!({ "status": "ok" })) }))
+        .route(
+            "/props",
+            get(|| async { Json(json!({ "default_generation_settings": { "n_ctx": 4096 } })) }),
+        );
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn test_local_model_health_checks() {
+    let pulls = Arc::new(AtomicUsize::new(0));
+    let url = start_stub_model_server(pulls.clone()).await;
+    let config = ReevConfig::from_toml(
+        &format!(
+            r#"
+[models.ollama]
+base_url = "{url}"
+model = "llama3.1"
+
+[models.qwen]
+provider = "ollama"
+base_url = "{url}"
+
+[models.llama-cpp]
+base_url = "{url}"
+context_size = 8192
+"#
+        ),
+        None,
+    )
+    .unwrap();
+    let checker = HealthChecker::default();
+
+    // Tags are matched with and without the implied `latest`
+    let result = checker
+        .check_local_model("ollama", config.model("ollama").unwrap(), false)
+        .await;
+    assert!(result.is_healthy(), "{:?}", result.status);
+
+    let result = checker
+        .check_local_model("qwen", config.model("qwen").unwrap(), false)
+        .await;
+    assert!(
+        matches!(&result.status, ServiceHealth::Unhealthy(reason) if reason.contains("ollama pull qwen")),
+        "{:?}",
+        result.status
+    );
+    assert_eq!(pulls.load(Ordering::SeqCst), 0);
+    let result = checker
+        .check_local_model("qwen", config.model("qwen").unwrap(), true)
+        .await;
+    assert!(result.is_healthy(), "{:?}", result.status);
+    assert_eq!(pulls.load(Ordering::SeqCst), 1);
+
+    let llama_cpp = config.model("llama-cpp").unwrap();
+    let result = checker
+        .check_local_model("llama-cpp", llama_cpp, false)
+        .await;
+    assert!(
+        matches!(&result.status, ServiceHealth::Unhealthy(reason) if reason.contains("-c 8192")),
+        "{:?}",
+        result.status
+    );
+    let mut smaller = llama_cpp.clone();
+    smaller.context_size = Some(4096);
+    let result = checker
+        .check_local_model("llama-cpp", &smaller, false)
+        .await;
+    assert!(result.is_healthy(), "{:?}", result.status);
+
+    // Hosted models need no local server
+    let result = checker
+        .check_local_model("glm-4.6", config.model("glm-4.6").unwrap(), false)
+        .await;
+    assert!(result.is_healthy());
+}
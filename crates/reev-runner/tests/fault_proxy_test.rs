@@ -0,0 +1,92 @@
+//! Tests for the failure-injection JSON-RPC proxy
+//!
+//! The proxy is exercised against a stub upstream, so these tests do not need surfpool.
+
+use axum::{Json, Router, routing::post};
+use reev_lib::faults::FaultConfig;
+use reev_runner::dependency::FaultProxy;
+use serde_json::{Value, json};
+
+/// Starts a stub JSON-RPC server that answers every request with `"ok"`
+async fn start_stub_upstream() -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = Router::new().route(
+        "/",
+        post(|Json(request): Json<Value>| async move {
+            Json(json!({ "jsonrpc": "2.0", "id": request["id"], "result": "ok" }))
+        }),
+    );
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{addr}")
+}
+
+async fn rpc(url: &str, method: &str) -> reqwest::Response {
+    reqwest::Client::new()
+        .post(url)
+        .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": [] }))
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_fault_proxy_injection() {
+    let upstream = start_stub_upstream().await;
+    let proxy = FaultProxy::start(0, upstream).await.unwrap();
+
+    // Disarmed: requests are forwarded untouched
+    let body: Value = rpc(proxy.url(), "sendTransaction")
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["result"], "ok");
+    assert_eq!(proxy.injected_count(), 0);
+
+    let config: FaultConfig = serde_yaml::from_str(
+        r#"
+seed: 1
+rules:
+  - type: BlockhashNotFound
+    max_injections: 2
+  - type: Drop
+    methods: ["getBalance"]
+"#,
+    )
+    .unwrap();
+    proxy.arm(config);
+
+    let body: Value = rpc(proxy.url(), "sendTransaction")
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["error"]["code"], -32002);
+    assert_eq!(body["error"]["data"]["err"], "BlockhashNotFound");
+
+    let body: Value = rpc(proxy.url(), "simulateTransaction")
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["result"]["value"]["err"], "BlockhashNotFound");
+
+    let response = rpc(proxy.url(), "getBalance").await;
+    assert_eq!(response.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+
+    // max_injections reached for transactions; other methods pass through
+    let body: Value = rpc(proxy.url(), "sendTransaction")
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["result"], "ok");
+    assert_eq!(proxy.injected_count(), 3);
+
+    proxy.disarm();
+    let response = rpc(proxy.url(), "getBalance").await;
+    assert!(response.status().is_success());
+
+    proxy.shutdown().await;
+}
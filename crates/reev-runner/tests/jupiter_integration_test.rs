@@ -47,12 +47,18 @@ async fn test_jupiter_swap_insufficient_balance() -> Result<()> {
             owner: "11111111111111111111111111111111".to_string(),
             lamports: 50000000, // 0.05 SOL (insufficient)
             data: None,
+            nonce: None,
+            multisig: None,
         }],
         flow: Some(vec![]),
+        faults: None,
+        timeout: None,
+        budget: None,
         ground_truth: reev_lib::benchmark::GroundTruth {
             final_state_assertions: vec![],
             expected_instructions: vec![],
             skip_instruction_validation: false,
+            efficiency: None,
             transaction_status: "Failure".to_string(),
         },
     };
@@ -76,6 +82,8 @@ async fn test_jupiter_swap_insufficient_balance() -> Result<()> {
         last_transaction_logs: vec![],
         account_states: std::collections::HashMap::new(),
         key_map,
+        balance_deltas: vec![],
+        last_transaction_metrics: None,
     };
 
     // Create mock Jupiter instruction that should fail
@@ -133,12 +141,18 @@ async fn test_jupiter_malformed_instruction() -> Result<()> {
             owner: "11111111111111111111111111111111".to_string(),
             lamports: 1000000000, // 1 SOL
             data: None,
+            nonce: None,
+            multisig: None,
         }],
         flow: Some(vec![]),
+        faults: None,
+        timeout: None,
+        budget: None,
         ground_truth: reev_lib::benchmark::GroundTruth {
             final_state_assertions: vec![],
             expected_instructions: vec![],
             skip_instruction_validation: false,
+            efficiency: None,
             transaction_status: "Failure".to_string(),
         },
     };
@@ -161,6 +175,8 @@ async fn test_jupiter_malformed_instruction() -> Result<()> {
         last_transaction_logs: vec![],
         account_states: std::collections::HashMap::new(),
         key_map,
+        balance_deltas: vec![],
+        last_transaction_metrics: None,
     };
 
     // Create malformed instruction (empty accounts, invalid data)
@@ -216,6 +232,8 @@ async fn test_jupiter_valid_instruction_execution() -> Result<()> {
                 owner: "11111111111111111111111111111111".to_string(),
                 lamports: 1000000000, // 1 SOL
                 data: None,
+                nonce: None,
+                multisig: None,
             },
             reev_lib::benchmark::InitialStateItem {
                 pubkey: "USER_USDC_ATA".to_string(),
@@ -226,13 +244,19 @@ async fn test_jupiter_valid_instruction_execution() -> Result<()> {
                     owner: user_wallet.to_string(),
                     amount: "0".to_string(),
                 }),
+                nonce: None,
+                multisig: None,
             },
         ],
         flow: Some(vec![]),
+        faults: None,
+        timeout: None,
+        budget: None,
         ground_truth: reev_lib::benchmark::GroundTruth {
             final_state_assertions: vec![],
             expected_instructions: vec![],
             skip_instruction_validation: false,
+            efficiency: None,
             transaction_status: "Success".to_string(),
         },
     };
@@ -256,6 +280,8 @@ async fn test_jupiter_valid_instruction_execution() -> Result<()> {
         last_transaction_logs: vec![],
         account_states: std::collections::HashMap::new(),
         key_map,
+        balance_deltas: vec![],
+        last_transaction_metrics: None,
     };
 
     // Create a valid Jupiter swap instruction structure
@@ -323,6 +349,8 @@ async fn test_jupiter_multiple_operations() -> Result<()> {
                 owner: "11111111111111111111111111111111".to_string(),
                 lamports: 2000000000, // 2 SOL
                 data: None,
+                nonce: None,
+                multisig: None,
             },
             reev_lib::benchmark::InitialStateItem {
                 pubkey: "USER_USDC_ATA".to_string(),
@@ -333,13 +361,19 @@ async fn test_jupiter_multiple_operations() -> Result<()> {
                     owner: user_wallet.to_string(),
                     amount: "0".to_string(),
                 }),
+                nonce: None,
+                multisig: None,
             },
         ],
         flow: Some(vec![]),
+        faults: None,
+        timeout: None,
+        budget: None,
         ground_truth: reev_lib::benchmark::GroundTruth {
             final_state_assertions: vec![],
             expected_instructions: vec![],
             skip_instruction_validation: false,
+            efficiency: None,
             transaction_status: "Success".to_string(),
         },
     };
@@ -363,6 +397,8 @@ async fn test_jupiter_multiple_operations() -> Result<()> {
         last_transaction_logs: vec![],
         account_states: std::collections::HashMap::new(),
         key_map,
+        balance_deltas: vec![],
+        last_transaction_metrics: None,
     };
 
     // First operation: Jupiter swap
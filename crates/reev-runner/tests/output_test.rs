@@ -0,0 +1,239 @@
+//! Tests for the JUnit, JSON and Markdown run outputs
+
+use clap::Parser;
+use reev_db::{ScoreMatrix, TrialStats};
+use reev_lib::{
+    flow::ScoringBreakdown,
+    results::{FinalStatus, LlmUsage, TestResult},
+    trace::ExecutionTrace,
+};
+use reev_runner::{
+    cli::{Cli, Command},
+    output::{
+        OutputFormat, OutputTarget, render_json, render_junit, render_junit_suites,
+        render_markdown, render_matrix_json, render_matrix_markdown, write_outputs,
+    },
+};
+use std::path::PathBuf;
+
+fn result(id: &str, score: f64, final_status: FinalStatus, issues: &[&str]) -> TestResult {
+    TestResult {
+        id: id.to_string(),
+        prompt: "prompt".to_string(),
+        final_status,
+        score,
+        trace: ExecutionTrace::new("prompt".to_string()),
+        scoring_breakdown: Some(ScoringBreakdown {
+            instruction_score: score,
+            onchain_score: score,
+            final_score: score,
+            issues: issues.iter().map(|s| s.to_string()).collect(),
+            mismatches: vec![],
+        }),
+        trial_index: 0,
+        run_id: None,
+        llm_usage: None,
+    }
+}
+
+fn results() -> Vec<TestResult> {
+    vec![
+        result("001-sol-transfer", 1.0, FinalStatus::Succeeded, &[]),
+        result(
+            "002-spl-transfer",
+            0.25,
+            FinalStatus::Failed,
+            &["Transaction failed on-chain execution", "a < b & c"],
+        ),
+    ]
+}
+
+#[test]
+fn test_parse_output_targets() {
+    let target: OutputTarget = "junit=out/results.xml".parse().unwrap();
+    assert_eq!(target.format, OutputFormat::Junit);
+    assert_eq!(target.path, PathBuf::from("out/results.xml"));
+    assert_eq!(
+        "md=summary.md".parse::<OutputTarget>().unwrap().format,
+        OutputFormat::Markdown
+    );
+    assert!("html=report.html".parse::<OutputTarget>().is_err());
+    assert!("junit".parse::<OutputTarget>().is_err());
+    assert!("junit=".parse::<OutputTarget>().is_err());
+
+    let cli = Cli::try_parse_from([
+        "reev-runner",
+        "run",
+        "--output",
+        "junit=results.xml",
+        "--output",
+        "md=summary.md",
+    ])
+    .unwrap();
+    match cli.command {
+        Some(Command::Run(args)) => assert_eq!(args.outputs.len(), 2),
+        other => panic!("expected run, got {other:?}"),
+    }
+    assert!(Cli::try_parse_from(["reev-runner", "run", "--output", "pdf=x"]).is_err());
+}
+
+#[test]
+fn test_junit() {
+    let xml = render_junit(&results(), "glm-4.6");
+    assert!(xml.starts_with("<?xml"));
+    assert!(xml.contains("<testsuites name=\"reev\" tests=\"2\" failures=\"1\">"));
+    assert!(xml.contains("<testcase name=\"001-sol-transfer\" classname=\"reev.glm-4.6\">"));
+    assert!(xml.contains("<property name=\"score\" value=\"1.0000\"/>"));
+    assert!(xml.contains("<property name=\"score\" value=\"0.2500\"/>"));
+    assert_eq!(xml.matches("<failure").count(), 1);
+    assert!(xml.contains("Transaction failed on-chain execution\na &lt; b &amp; c</failure>"));
+}
+
+#[test]
+fn test_markdown() {
+    let md = render_markdown(&results(), "deterministic");
+    assert!(md.contains("| `001-sol-transfer` | ✅ | 100.0% |  |"));
+    assert!(md.contains(
+        "| `002-spl-transfer` | ❌ | 25.0% | Transaction failed on-chain execution<br>a < b & c |"
+    ));
+    assert!(md.contains("**1/2 passed**, mean score **62.5%**"));
+
+    // Pipes and line breaks in a cell would break the table
+    let md = render_markdown(
+        &[result(
+            "900-a|b",
+            0.0,
+            FinalStatus::Failed,
+            &["expected a | b\r\ngot c\nd"],
+        )],
+        "deterministic",
+    );
+    assert!(md.contains("| `900-a\\|b` | ❌ | 0.0% | expected a \\| b<br>got c<br>d |"));
+}
+
+#[test]
+fn test_markdown_cost() {
+    let usage = |cost_usd| {
+        Some(LlmUsage {
+            model: "gpt-4o".to_string(),
+            tokens: reev_flow::TokenUsage {
+                prompt_tokens: 1000,
+                completion_tokens: 100,
+                cached_tokens: 0,
+            },
+            cost_usd,
+        })
+    };
+    let mut results = results();
+    results[0].llm_usage = usage(Some(0.02));
+    results[1].llm_usage = usage(Some(0.03));
+    let md = render_markdown(&results, "gpt-4o");
+    assert!(md.contains("| Benchmark | Status | Score | Cost | Issues |"));
+    assert!(md.contains("| `001-sol-transfer` | ✅ | 100.0% | $0.0200 |  |"));
+    assert!(md.contains("LLM cost **$0.0500**, **25.0** score per dollar"));
+
+    // Unpriced models report their benchmarks without a cost
+    results[1].llm_usage = usage(None);
+    let md = render_markdown(&results, "gpt-4o");
+    assert!(md.contains("| `002-spl-transfer` | ❌ | 25.0% | - |"));
+    assert!(md.contains("LLM cost **$0.0200**, **50.0** score per dollar"));
+}
+
+#[test]
+fn test_json_and_write_outputs() {
+    let json = render_json(&results()).unwrap();
+    let parsed: Vec<TestResult> = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.len(), 2);
+    assert_eq!(
+        parsed[1].scoring_breakdown.as_ref().unwrap().issues.len(),
+        2
+    );
+
+    let dir = tempfile::tempdir().unwrap();
+    let targets = vec![
+        OutputTarget {
+            format: OutputFormat::Junit,
+            path: dir.path().join("nested/results.xml"),
+        },
+        OutputTarget {
+            format: OutputFormat::Json,
+            path: dir.path().join("results.json"),
+        },
+        OutputTarget {
+            format: OutputFormat::Markdown,
+            path: dir.path().join("summary.md"),
+        },
+    ];
+    write_outputs(&results(), "deterministic", &targets).unwrap();
+    for target in targets {
+        assert!(target.path.exists(), "{} missing", target.path.display());
+    }
+}
+
+#[test]
+fn test_junit_timed_out() {
+    let results = vec![result(
+        "003-slow",
+        0.0,
+        FinalStatus::TimedOut,
+        &["Step timed out after 30s"],
+    )];
+    let xml = render_junit(&results, "glm-4.6");
+    assert!(xml.contains("failures=\"1\""));
+    assert!(xml.contains(
+        "<failure message=\"Timed out\" type=\"BenchmarkTimedOut\">Step timed out after 30s</failure>"
+    ));
+}
+
+#[test]
+fn test_junit_budget_exceeded() {
+    let results = vec![result(
+        "004-looping",
+        0.5,
+        FinalStatus::BudgetExceeded,
+        &["Agent stopped at its max_turns budget"],
+    )];
+    let xml = render_junit(&results, "glm-4.6");
+    assert!(xml.contains("failures=\"1\""));
+    assert!(xml.contains(
+        "<failure message=\"Budget exceeded\" type=\"BenchmarkBudgetExceeded\">Agent stopped at its max_turns budget</failure>"
+    ));
+}
+
+#[test]
+fn test_matrix_outputs() {
+    let glm = results();
+    let deterministic = vec![result("001-sol-transfer", 1.0, FinalStatus::Succeeded, &[])];
+    let suites: Vec<(&str, &[TestResult])> =
+        vec![("deterministic", &deterministic), ("glm-4.6", &glm)];
+
+    let xml = render_junit_suites(&suites);
+    assert!(xml.contains("<testsuites name=\"reev\" tests=\"3\" failures=\"1\">"));
+    assert!(xml.contains("<testsuite name=\"reev.deterministic\" tests=\"1\" failures=\"0\">"));
+    assert!(xml.contains("<testsuite name=\"reev.glm-4.6\" tests=\"2\" failures=\"1\">"));
+
+    let json: serde_json::Value =
+        serde_json::from_str(&render_matrix_json(&suites).unwrap()).unwrap();
+    assert_eq!(json[1]["agent"], "glm-4.6");
+    assert_eq!(json[1]["results"].as_array().unwrap().len(), 2);
+
+    let agents = vec!["deterministic".to_string(), "glm-4.6".to_string()];
+    let stats = [
+        TrialStats::from_outcomes("001-sol-transfer", "deterministic", &[(1.0, true)], None),
+        TrialStats::from_outcomes("001-sol-transfer", "glm-4.6", &[(1.0, true)], None),
+        TrialStats::from_outcomes(
+            "002-spl-transfer",
+            "glm-4.6",
+            &[(0.25, false), (0.75, true)],
+            None,
+        ),
+    ]
+    .map(Option::unwrap);
+    let md = render_matrix_markdown(&ScoreMatrix::from_stats(&agents, &stats));
+    assert!(md.contains("| Benchmark | `deterministic` | `glm-4.6` |"));
+    assert!(md.contains("| `001-sol-transfer` | ✅ 100.0% | ✅ 100.0% |"));
+    assert!(md.contains("| `002-spl-transfer` | - | ❌ 50.0% (1/2) |"));
+    assert!(md.contains("| **Mean** | **100.0%** | **75.0%** |"));
+    assert!(md.contains("| **Passed** | 1/2 | 1/2 |"));
+    println!("✅ Matrix outputs rendered per agent");
+}
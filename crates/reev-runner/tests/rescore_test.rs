@@ -0,0 +1,267 @@
+//! Tests for re-scoring stored traces
+//!
+//! Traces are stored in a temporary results database and scored again against the
+//! repository's benchmarks, without starting an agent or surfpool.
+
+use project_root::get_project_root;
+use reev_db::{DatabaseConfig, DatabaseReader, DatabaseWriter, QueryFilter, types::SessionInfo};
+use reev_lib::{
+    agent::{AgentAction, AgentObservation},
+    faults::{FaultConfig, RecoveryStats},
+    results::FinalStatus,
+    score::BUDGET_EXCEEDED_MAX_SCORE,
+    trace::{ExecutionTrace, TraceStep},
+};
+use reev_runner::{
+    cli::{
+        replay::rescore_trace,
+        rescore::{record_rescored, rescore_results},
+    },
+    evaluation::new_evaluation_run,
+    scored_status,
+    selection::load_benchmarks,
+};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+
+const BENCHMARK_ID: &str = "001-sol-transfer";
+
+fn trace() -> ExecutionTrace {
+    let observation = AgentObservation {
+        last_transaction_status: "Success".to_string(),
+        last_transaction_error: None,
+        last_transaction_logs: vec![],
+        account_states: HashMap::new(),
+        key_map: HashMap::new(),
+        balance_deltas: vec![],
+        last_transaction_metrics: None,
+    };
+    let mut trace = ExecutionTrace::new("Send 0.1 SOL".to_string());
+    trace.add_step(TraceStep {
+        thought: None,
+        action: vec![],
+        observation,
+        info: serde_json::Value::Null,
+    });
+    trace
+}
+
+/// A trace of the expected transfer, which the benchmark scores in full
+fn transfer_trace() -> ExecutionTrace {
+    let user = Pubkey::new_unique();
+    let recipient = Pubkey::new_unique();
+    let mut trace = trace();
+    let step = &mut trace.steps[0];
+    step.observation.key_map = HashMap::from([
+        ("USER_WALLET_PUBKEY".to_string(), user.to_string()),
+        ("RECIPIENT_WALLET_PUBKEY".to_string(), recipient.to_string()),
+    ]);
+    step.action = vec![AgentAction(solana_system_interface::instruction::transfer(
+        &user,
+        &recipient,
+        100_000_000,
+    ))];
+    trace
+}
+
+fn performance(session_id: &str, score: f64, run_id: &str) -> reev_db::AgentPerformance {
+    reev_db::AgentPerformance {
+        id: None,
+        session_id: session_id.to_string(),
+        benchmark_id: BENCHMARK_ID.to_string(),
+        agent_type: "glm-4.6".to_string(),
+        score,
+        final_status: "succeeded".to_string(),
+        execution_time_ms: Some(1000),
+        timestamp: "2025-01-01T00:00:00Z".to_string(),
+        flow_log_id: None,
+        prompt_md5: None,
+        trial_index: 0,
+        run_id: Some(run_id.to_string()),
+        rescored: false,
+        additional_metrics: HashMap::new(),
+    }
+}
+
+#[tokio::test]
+async fn test_rescore_run() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = tempfile::tempdir()?;
+    let config = DatabaseConfig::new(temp_dir.path().join("results.db").to_string_lossy());
+    let db = DatabaseWriter::new(config.clone()).await?;
+    let benchmarks_dir = get_project_root()?.join("benchmarks");
+    let benchmark_path = benchmarks_dir.join(format!("{BENCHMARK_ID}.yml"));
+
+    let mut run = new_evaluation_run("glm-4.6", &[benchmark_path], 1, Some(7))?;
+    run.run_id = "run-1".to_string();
+    db.create_run(&run).await?;
+    for session_id in ["with-trace", "without-trace"] {
+        db.create_session(&SessionInfo {
+            session_id: session_id.to_string(),
+            benchmark_id: BENCHMARK_ID.to_string(),
+            agent_type: "glm-4.6".to_string(),
+            interface: "tui".to_string(),
+            start_time: 1_700_000_000,
+            end_time: None,
+            status: "running".to_string(),
+            score: None,
+            final_status: None,
+            run_id: Some("run-1".to_string()),
+        })
+        .await?;
+        db.insert_agent_performance(&performance(session_id, 1.0, "run-1"))
+            .await?;
+    }
+    db.store_complete_log("with-trace", &serde_json::to_string(&trace())?)
+        .await?;
+
+    let reader = DatabaseReader::from_config(config.clone()).await?;
+    let records = reader
+        .get_agent_performance(Some(QueryFilter::new().run_id("run-1")))
+        .await?;
+    let benchmarks = load_benchmarks(&benchmarks_dir)?;
+    let (rescored, skipped) = rescore_results(&reader, &benchmarks, records).await?;
+
+    assert_eq!(skipped.len(), 1);
+    assert_eq!(skipped[0].0.session_id, "without-trace");
+    assert_eq!(skipped[0].1, "no stored trace");
+    assert_eq!(rescored.len(), 1);
+    let expected = rescore_trace(&benchmarks[0].test_case, &trace())?;
+    assert_eq!(rescored[0].score, expected);
+    println!("✅ Stored trace scored again");
+
+    let run_ids = record_rescored(&db, &rescored, Some("run-1")).await?;
+    assert_eq!(run_ids.len(), 1);
+    let reader = DatabaseReader::from_config(config).await?;
+    let new_run = reader.get_run(&run_ids[0]).await?.unwrap();
+    assert_eq!(new_run.status, "completed");
+    assert_eq!(new_run.seed, Some(7));
+
+    let stored = reader.get_latest_run_results(&run_ids[0]).await?;
+    assert_eq!(stored.len(), 1);
+    assert!(stored[0].rescored);
+    assert_eq!(stored[0].session_id, "with-trace");
+    assert_eq!(stored[0].score, expected);
+
+    // The original results are left as they were
+    let original = reader.get_latest_run_results("run-1").await?;
+    assert!(original.iter().all(|p| !p.rescored && p.score == 1.0));
+    println!("✅ New scores recorded as a re-scoring run");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rescore_keeps_budget_exceeded() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = tempfile::tempdir()?;
+    let config = DatabaseConfig::new(temp_dir.path().join("results.db").to_string_lossy());
+    let db = DatabaseWriter::new(config.clone()).await?;
+    for session_id in ["finished", "looping"] {
+        db.create_session(&SessionInfo {
+            session_id: session_id.to_string(),
+            benchmark_id: BENCHMARK_ID.to_string(),
+            agent_type: "glm-4.6".to_string(),
+            interface: "cli".to_string(),
+            start_time: 1_700_000_000,
+            end_time: None,
+            status: "running".to_string(),
+            score: None,
+            final_status: None,
+            run_id: Some("run-1".to_string()),
+        })
+        .await?;
+        db.insert_agent_performance(&performance(session_id, 1.0, "run-1"))
+            .await?;
+        db.store_complete_log(session_id, &serde_json::to_string(&transfer_trace())?)
+            .await?;
+    }
+    db.record_budget_exceeded("looping", "max_turns").await?;
+
+    let reader = DatabaseReader::from_config(config).await?;
+    let records = reader
+        .get_agent_performance(Some(QueryFilter::new().run_id("run-1")))
+        .await?;
+    let benchmarks = load_benchmarks(&get_project_root()?.join("benchmarks"))?;
+    let (rescored, _) = rescore_results(&reader, &benchmarks, records).await?;
+    let benchmark = &benchmarks
+        .iter()
+        .find(|b| b.test_case.id == BENCHMARK_ID)
+        .unwrap()
+        .test_case;
+    let expected = rescore_trace(benchmark, &transfer_trace())?;
+    assert!(expected > BUDGET_EXCEEDED_MAX_SCORE);
+
+    let finished = rescored
+        .iter()
+        .find(|r| r.original.session_id == "finished")
+        .unwrap();
+    assert_eq!(finished.score, expected);
+    assert_eq!(finished.final_status, FinalStatus::Succeeded);
+    let looping = rescored
+        .iter()
+        .find(|r| r.original.session_id == "looping")
+        .unwrap();
+    assert_eq!(looping.score, BUDGET_EXCEEDED_MAX_SCORE);
+    assert_eq!(looping.final_status, FinalStatus::BudgetExceeded);
+    println!("✅ Re-scored runs stopped at their budget stay capped and failed");
+
+    Ok(())
+}
+
+#[test]
+fn test_rescore_blends_fault_recovery() -> Result<(), Box<dyn std::error::Error>> {
+    let benchmarks = load_benchmarks(&get_project_root()?.join("benchmarks"))?;
+    let mut test_case = benchmarks
+        .into_iter()
+        .find(|b| b.test_case.id == BENCHMARK_ID)
+        .unwrap()
+        .test_case;
+    let plain = rescore_trace(&test_case, &trace())?;
+
+    test_case.faults = Some(FaultConfig {
+        seed: None,
+        max_attempts: 2,
+        recovery_weight: 0.5,
+        rules: vec![],
+    });
+    let mut faulted = trace();
+    let mut recovery = RecoveryStats::default();
+    recovery.record_step(1, 2, false);
+    faulted.recovery = Some(recovery);
+    let faults = test_case.faults.as_ref().unwrap();
+
+    // The stored recovery survives the round trip through the session log
+    let stored: ExecutionTrace = serde_json::from_str(&serde_json::to_string(&faulted)?)?;
+    assert_eq!(stored.recovery, Some(recovery));
+    assert_eq!(
+        rescore_trace(&test_case, &stored)?,
+        faults.blend_recovery(plain, &recovery)
+    );
+    assert_eq!(rescore_trace(&test_case, &stored)?, plain * 0.5);
+    // Traces without recovery stats keep the plain score
+    assert_eq!(rescore_trace(&test_case, &trace())?, plain);
+    println!("✅ Stored fault recovery blended into the re-score");
+
+    Ok(())
+}
+
+#[test]
+fn test_scored_status_thresholds() -> Result<(), Box<dyn std::error::Error>> {
+    let benchmarks = load_benchmarks(&get_project_root()?.join("benchmarks"))?;
+    let single = &benchmarks
+        .iter()
+        .find(|b| b.test_case.flow.is_none())
+        .unwrap()
+        .test_case;
+    let flow = &benchmarks
+        .iter()
+        .find(|b| b.test_case.flow.is_some())
+        .unwrap()
+        .test_case;
+
+    assert_eq!(scored_status(single, 0.75), FinalStatus::Succeeded);
+    assert_eq!(scored_status(single, 0.7), FinalStatus::Failed);
+    assert_eq!(scored_status(flow, 0.6), FinalStatus::Succeeded);
+    assert_eq!(scored_status(flow, 0.59), FinalStatus::Failed);
+
+    Ok(())
+}
@@ -0,0 +1,194 @@
+//! # Core Testing Philosophy: Surfpool + Real Mainnet Programs
+//!
+//! All integration tests in the `reev` framework operate on `surfpool`, a high-speed
+//! local Solana test validator. `surfpool` instantly forks Solana mainnet, meaning
+//! any on-chain account not explicitly mocked in the test setup is fetched live from
+//! mainnet. This allows tests to interact with real, deployed programs (like SPL Token
+//! or Jupiter) without any mocking of program logic. Test assertions are based on the
+//! real outcomes of these transactions. This approach ensures that a passing test gives
+//! a strong signal of real-world viability.
+
+//! # Durable Nonce and Multisig Signer Test
+//!
+//! Runs the nonce and multisig example benchmarks against surfpool: the accounts
+//! created during reset, the durable blockhash a nonce transaction is signed with,
+//! the multisig signers the environment signs for, and the `NonceAdvanced` assertion.
+
+use anyhow::{Context, Result};
+use project_root::get_project_root;
+use reev_lib::{
+    agent::{AgentAction, AgentObservation},
+    benchmark::TestCase,
+    env::GymEnv,
+    score::{calculate_detailed_score, calculate_final_score},
+    solana_env::environment::SolanaEnv,
+};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+use solana_system_interface::instruction as system_instruction;
+use std::{collections::HashMap, path::Path, str::FromStr};
+
+const NONCE_BENCHMARK: &str = "benchmarks/006-durable-nonce-sol-payment.yml";
+const MULTISIG_BENCHMARK: &str = "benchmarks/007-multisig-usdc-payment.yml";
+
+/// A durable transaction advances the seeded nonce and earns the full score; a plain
+/// transfer of the same amount succeeds but leaves the nonce assertion unmet.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_durable_nonce_benchmark() -> Result<()> {
+    // Skip test if validator is not available
+    if !is_validator_available().await {
+        println!("⚠️  Skipping test: Solana validator not available at http://127.0.0.1:8899");
+        return Ok(());
+    }
+    let _ = tracing_subscriber::fmt::try_init();
+    let benchmark_path = get_project_root()?.join(NONCE_BENCHMARK);
+
+    let (mut env, test_case, initial_observation) = setup_env(&benchmark_path).await?;
+    let nonce_state = &initial_observation.account_states["NONCE_ACCOUNT"];
+    assert!(nonce_state["nonce"].is_string(), "{nonce_state}");
+    assert_eq!(
+        nonce_state["nonce_authority"],
+        initial_observation.key_map["USER_WALLET_PUBKEY"]
+    );
+    // The nonce account belongs to the environment, not to an agent signer
+    assert!(!env.keypair_map.contains_key("NONCE_ACCOUNT"));
+
+    let key_map = &initial_observation.key_map;
+    let advance = system_instruction::advance_nonce_account(
+        &pubkey(key_map, "NONCE_ACCOUNT")?,
+        &pubkey(key_map, "USER_WALLET_PUBKEY")?,
+    );
+    let actions = vec![AgentAction(advance), AgentAction(sol_transfer(key_map)?)];
+    let step_result = env.step(actions.clone(), &test_case.ground_truth)?;
+    assert_eq!(step_result.observation.last_transaction_status, "Success");
+    assert_ne!(
+        step_result.observation.account_states["NONCE_ACCOUNT"]["nonce"],
+        nonce_state["nonce"]
+    );
+    let score = calculate_final_score(
+        &test_case,
+        &actions,
+        &initial_observation,
+        &step_result.observation,
+    );
+    assert_eq!(score, 1.0);
+    env.close()?;
+
+    // Without AdvanceNonceAccount the transfer lands but the nonce stays put
+    let (mut env, test_case, initial_observation) = setup_env(&benchmark_path).await?;
+    let actions = vec![AgentAction(sol_transfer(&initial_observation.key_map)?)];
+    let step_result = env.step(actions.clone(), &test_case.ground_truth)?;
+    assert_eq!(step_result.observation.last_transaction_status, "Success");
+    let breakdown = calculate_detailed_score(
+        &test_case,
+        &actions,
+        &initial_observation,
+        &step_result.observation,
+    );
+    assert!(breakdown.final_score < 1.0, "{breakdown:?}");
+    assert!(
+        breakdown
+            .issues
+            .iter()
+            .any(|issue| issue.contains("was not advanced")),
+        "{:?}",
+        breakdown.issues
+    );
+    env.close()?;
+    Ok(())
+}
+
+/// Two of the three multisig signers move the treasury's USDC; one alone cannot.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_multisig_benchmark() -> Result<()> {
+    // Skip test if validator is not available
+    if !is_validator_available().await {
+        println!("⚠️  Skipping test: Solana validator not available at http://127.0.0.1:8899");
+        return Ok(());
+    }
+    let _ = tracing_subscriber::fmt::try_init();
+    let benchmark_path = get_project_root()?.join(MULTISIG_BENCHMARK);
+
+    let (mut env, test_case, initial_observation) = setup_env(&benchmark_path).await?;
+    let multisig_state = &initial_observation.account_states["TREASURY_MULTISIG"];
+    assert_eq!(multisig_state["multisig_m"], 2);
+    assert_eq!(
+        multisig_state["multisig_signers"].as_array().map(Vec::len),
+        Some(3)
+    );
+    // The multisig never signs; its signers do
+    assert!(!env.keypair_map.contains_key("TREASURY_MULTISIG"));
+    assert!(env.keypair_map.contains_key("SIGNER_A"));
+
+    let one_signer = multisig_transfer(&initial_observation.key_map, &["SIGNER_A"])?;
+    let step_result = env.step(vec![AgentAction(one_signer)], &test_case.ground_truth)?;
+    assert_eq!(step_result.observation.last_transaction_status, "Failure");
+
+    let two_signers = multisig_transfer(&initial_observation.key_map, &["SIGNER_A", "SIGNER_B"])?;
+    let actions = vec![AgentAction(two_signers)];
+    let step_result = env.step(actions.clone(), &test_case.ground_truth)?;
+    assert_eq!(step_result.observation.last_transaction_status, "Success");
+    let score = calculate_final_score(
+        &test_case,
+        &actions,
+        &initial_observation,
+        &step_result.observation,
+    );
+    assert_eq!(score, 1.0);
+
+    env.close()?;
+    Ok(())
+}
+
+/// Reset a fresh environment for the benchmark at `path`
+async fn setup_env(path: &Path) -> Result<(SolanaEnv, TestCase, AgentObservation)> {
+    let test_case: TestCase = serde_yaml::from_str(&std::fs::read_to_string(path)?)?;
+    let mut env = SolanaEnv::new()?;
+    let initial_observation = env
+        .reset(None, Some(serde_json::to_value(&test_case)?))
+        .await?;
+    Ok((env, test_case, initial_observation))
+}
+
+fn pubkey(key_map: &HashMap<String, String>, placeholder: &str) -> Result<Pubkey> {
+    let address = key_map
+        .get(placeholder)
+        .with_context(|| format!("Pubkey placeholder '{placeholder}' not found in key_map"))?;
+    Ok(Pubkey::from_str(address)?)
+}
+
+fn sol_transfer(key_map: &HashMap<String, String>) -> Result<Instruction> {
+    Ok(system_instruction::transfer(
+        &pubkey(key_map, "USER_WALLET_PUBKEY")?,
+        &pubkey(key_map, "RECIPIENT_WALLET_PUBKEY")?,
+        100_000_000,
+    ))
+}
+
+fn multisig_transfer(key_map: &HashMap<String, String>, signers: &[&str]) -> Result<Instruction> {
+    let signers = signers
+        .iter()
+        .map(|signer| pubkey(key_map, signer))
+        .collect::<Result<Vec<Pubkey>>>()?;
+    Ok(spl_token::instruction::transfer(
+        &spl_token::ID,
+        &pubkey(key_map, "TREASURY_USDC_ATA")?,
+        &pubkey(key_map, "RECIPIENT_USDC_ATA")?,
+        &pubkey(key_map, "TREASURY_MULTISIG")?,
+        &signers.iter().collect::<Vec<_>>(),
+        15_000_000,
+    )?)
+}
+
+/// Check if the Solana validator is available
+async fn is_validator_available() -> bool {
+    use std::time::Duration;
+
+    let client = reqwest::Client::new();
+    let url = "http://127.0.0.1:8899/";
+
+    // Try to connect with a short timeout
+    match client.get(url).timeout(Duration::from_secs(2)).send().await {
+        Ok(response) => response.status().is_success(),
+        Err(_) => false,
+    }
+}
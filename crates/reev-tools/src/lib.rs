@@ -1,3 +1,4 @@
 pub mod tool_names;
 pub mod tools;
 pub mod tracker;
+pub mod validation;
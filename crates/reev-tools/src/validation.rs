@@ -0,0 +1,682 @@
+//! Tool argument validation with model self-correction
+//!
+//! Models often call tools with arguments that cannot work: a placeholder that
+//! is not in the key map, `0.1` SOL where lamports are expected, a missing
+//! `mint_address`. Without validation the tool errors and rig ends the whole
+//! prompt. [`Validated`] wraps a tool and checks its arguments against the
+//! tool's JSON schema and the semantic checks of [`ValidateArgs`] before the
+//! call; agents that make tool calls themselves check them with
+//! [`ValidationContext::check_call`]. Rejected arguments are answered with a
+//! structured error naming each problem and how to fix it, so the model can call
+//! the tool again; after `agent.max_tool_retries` rejections in a run the call
+//! fails instead.
+//!
+//! Each rejection is written to the session's enhanced otel log as a failed
+//! tool call with an `argument_retry` number, and counted as
+//! `argument_retries` in its summary.
+
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::atomic::{AtomicU32, Ordering},
+    sync::Arc,
+};
+
+use reev_flow::{get_enhanced_otel_logger, EnhancedToolCall, ToolExecutionStatus};
+use reev_lib::constants::{sol_mint, usdc_mint};
+use rig::{completion::ToolDefinition, tool::Tool};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use thiserror::Error;
+use tracing::warn;
+
+use crate::tools::{
+    AccountBalanceTool, JupiterEarnTool, JupiterLendEarnDepositTool, JupiterLendEarnMintTool,
+    JupiterLendEarnRedeemTool, JupiterLendEarnWithdrawTool, JupiterSwapFlowTool, JupiterSwapTool,
+    LendEarnTokensTool, PositionInfoTool, SolTransferTool, SplTransferTool,
+};
+
+const SOL_DECIMALS: u32 = 9;
+const USDC_DECIMALS: u32 = 6;
+
+/// One problem with the arguments of a tool call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArgIssue {
+    /// The argument at fault
+    pub field: String,
+    pub problem: String,
+    /// How to fix it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl ArgIssue {
+    pub fn new(field: &str, problem: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            problem: problem.into(),
+            hint: None,
+        }
+    }
+
+    pub fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+/// What the arguments of one run are checked against, shared by its tools
+pub struct ValidationContext {
+    key_map: HashMap<String, String>,
+    account_states: HashMap<String, Value>,
+    max_retries: u32,
+    retries: AtomicU32,
+}
+
+impl ValidationContext {
+    /// Check arguments against `key_map` and the balances of `account_states`,
+    /// allowing `max_retries` rejections
+    pub fn new(
+        key_map: HashMap<String, String>,
+        account_states: HashMap<String, Value>,
+        max_retries: u32,
+    ) -> Self {
+        Self {
+            key_map,
+            account_states,
+            max_retries,
+            retries: AtomicU32::new(0),
+        }
+    }
+
+    /// Tool calls rejected so far
+    pub fn retries(&self) -> u32 {
+        self.retries.load(Ordering::SeqCst)
+    }
+
+    /// The address `value` stands for: its key map entry, or itself when it is a pubkey
+    fn address(&self, value: &str) -> Option<String> {
+        match self.key_map.get(value) {
+            Some(address) => Some(address.clone()),
+            None => Pubkey::from_str(value).ok().map(|_| value.to_string()),
+        }
+    }
+
+    /// The placeholder naming `value`: itself, or the key map entry holding that address
+    fn placeholder<'a>(&'a self, value: &'a str) -> &'a str {
+        if self.key_map.contains_key(value) {
+            return value;
+        }
+        self.key_map
+            .iter()
+            .find(|(_, address)| address.as_str() == value)
+            .map_or(value, |(placeholder, _)| placeholder.as_str())
+    }
+
+    fn placeholders(&self) -> String {
+        let mut placeholders: Vec<&str> = self.key_map.keys().map(String::as_str).collect();
+        placeholders.sort_unstable();
+        placeholders.join(", ")
+    }
+
+    /// `field` must be a key map placeholder or a pubkey
+    fn check_account(&self, args: &Value, field: &str, issues: &mut Vec<ArgIssue>) {
+        let Some(value) = args[field].as_str() else {
+            return;
+        };
+        if self.address(value).is_none() {
+            issues.push(
+                ArgIssue::new(
+                    field,
+                    format!("'{value}' is neither a known placeholder nor a base58 pubkey"),
+                )
+                .hint(format!("Use one of: {}", self.placeholders())),
+            );
+        }
+    }
+
+    /// `field` must be a mint address written out, which is what the tools parse
+    fn check_mint(&self, args: &Value, field: &str, issues: &mut Vec<ArgIssue>) {
+        let Some(value) = args[field].as_str() else {
+            return;
+        };
+        if Pubkey::from_str(value).is_ok() {
+            return;
+        }
+        let issue = ArgIssue::new(field, format!("'{value}' is not a mint address"));
+        issues.push(match self.key_map.get(value) {
+            Some(address) => issue.hint(format!("Pass the address itself: {address}")),
+            None => issue.hint(format!(
+                "Pass the token's mint address, such as {} for USDC or {} for SOL",
+                usdc_mint(),
+                sol_mint()
+            )),
+        });
+    }
+
+    /// `field` must be a whole number of base units, no more than `balance` of
+    /// `holder` when it is known
+    fn check_amount(
+        &self,
+        args: &Value,
+        field: &str,
+        mint: Option<&str>,
+        balance: Option<(&str, u64)>,
+        issues: &mut Vec<ArgIssue>,
+    ) {
+        let Some(number) = args[field].as_f64() else {
+            return;
+        };
+        let token = mint.and_then(known_token);
+        let unit = match mint {
+            Some(mint) if mint == sol_mint().to_string() => "lamports",
+            _ => "base units",
+        };
+
+        if args[field].as_u64().is_none() {
+            let mut issue = ArgIssue::new(
+                field,
+                format!("{number} is not a whole, positive number of {unit}"),
+            );
+            if let Some((symbol, decimals)) = token.filter(|_| number > 0.0) {
+                let base_units = (number * 10f64.powi(decimals as i32)).round() as u64;
+                issue = issue.hint(format!(
+                    "Amounts are in {unit} ({decimals} decimals): {number} {symbol} is {base_units}"
+                ));
+            }
+            issues.push(issue);
+            return;
+        }
+
+        let amount = args[field].as_u64().unwrap_or_default();
+        if amount == 0 {
+            issues.push(ArgIssue::new(field, "The amount must be greater than 0"));
+        } else if let Some((holder, balance)) = balance.filter(|(_, balance)| amount > *balance) {
+            let issue = ArgIssue::new(
+                field,
+                format!("{amount} {unit} is more than the {balance} {holder} holds"),
+            );
+            issues.push(match token {
+                Some((_, decimals)) => issue.hint(format!(
+                    "Amounts are in {unit} ({decimals} decimals); send at most {balance}"
+                )),
+                None => issue.hint(format!("Send at most {balance}")),
+            });
+        }
+    }
+
+    /// Lamports held by the account `owner` names
+    fn lamports<'a>(&'a self, owner: &'a str) -> Option<(&'a str, u64)> {
+        let placeholder = self.placeholder(owner);
+        let lamports = self.account_states.get(placeholder)?["lamports"].as_u64()?;
+        Some((placeholder, lamports))
+    }
+
+    /// Tokens of `mint` held by the account of `owner`, or its lamports for SOL
+    fn token_balance<'a>(&'a self, owner: &'a str, mint: &str) -> Option<(&'a str, u64)> {
+        if mint == sol_mint().to_string() {
+            return self.lamports(owner);
+        }
+        let owner = self.address(owner)?;
+        self.account_states.iter().find_map(|(placeholder, state)| {
+            (state["mint"] == mint && state["token_account_owner"] == owner.as_str())
+                .then(|| state["amount"].as_u64())
+                .flatten()
+                .map(|amount| (placeholder.as_str(), amount))
+        })
+    }
+}
+
+/// Symbol and decimals of the mints whose amounts can be sanity-checked
+fn known_token(mint: &str) -> Option<(&'static str, u32)> {
+    if mint == sol_mint().to_string() {
+        Some(("SOL", SOL_DECIMALS))
+    } else if mint == usdc_mint().to_string() {
+        Some(("USDC", USDC_DECIMALS))
+    } else {
+        None
+    }
+}
+
+/// Semantic checks of a tool's arguments, run after they match its schema
+pub trait ValidateArgs {
+    /// Problems with `args` the schema cannot express
+    fn check_args(&self, _args: &Value, _context: &ValidationContext) -> Vec<ArgIssue> {
+        Vec::new()
+    }
+}
+
+impl ValidateArgs for SolTransferTool {
+    fn check_args(&self, args: &Value, context: &ValidationContext) -> Vec<ArgIssue> {
+        let mut issues = Vec::new();
+        context.check_account(args, "user_pubkey", &mut issues);
+        context.check_account(args, "recipient_pubkey", &mut issues);
+        if args["operation"] == "spl" {
+            issues.push(
+                ArgIssue::new("operation", "sol_transfer only moves SOL")
+                    .hint("Call spl_transfer with the token's mint_address instead"),
+            );
+        }
+        let balance = args["user_pubkey"]
+            .as_str()
+            .and_then(|user| context.lamports(user));
+        context.check_amount(
+            args,
+            "amount",
+            Some(&sol_mint().to_string()),
+            balance,
+            &mut issues,
+        );
+        issues
+    }
+}
+
+impl ValidateArgs for SplTransferTool {
+    fn check_args(&self, args: &Value, context: &ValidationContext) -> Vec<ArgIssue> {
+        let mut issues = Vec::new();
+        context.check_account(args, "user_pubkey", &mut issues);
+        context.check_account(args, "recipient_pubkey", &mut issues);
+        context.check_mint(args, "mint_address", &mut issues);
+        let mint = args["mint_address"].as_str();
+        let balance = match (args["user_pubkey"].as_str(), mint) {
+            (Some(user), Some(mint)) => context.token_balance(user, mint),
+            _ => None,
+        };
+        context.check_amount(args, "amount", mint, balance, &mut issues);
+        issues
+    }
+}
+
+impl ValidateArgs for JupiterSwapTool {
+    fn check_args(&self, args: &Value, context: &ValidationContext) -> Vec<ArgIssue> {
+        let mut issues = Vec::new();
+        context.check_account(args, "user_pubkey", &mut issues);
+        context.check_account(args, "input_mint", &mut issues);
+        context.check_account(args, "output_mint", &mut issues);
+
+        let input_mint = args["input_mint"].as_str().and_then(|m| context.address(m));
+        let output_mint = args["output_mint"]
+            .as_str()
+            .and_then(|m| context.address(m));
+        if input_mint.is_some() && input_mint == output_mint {
+            issues.push(ArgIssue::new(
+                "output_mint",
+                "The input and output mints are the same",
+            ));
+        }
+        let balance = match (args["user_pubkey"].as_str(), &input_mint) {
+            (Some(user), Some(mint)) => context.token_balance(user, mint),
+            _ => None,
+        };
+        context.check_amount(args, "amount", input_mint.as_deref(), balance, &mut issues);
+        issues
+    }
+}
+
+impl ValidateArgs for JupiterSwapFlowTool {}
+impl ValidateArgs for JupiterLendEarnDepositTool {}
+impl ValidateArgs for JupiterLendEarnWithdrawTool {}
+impl ValidateArgs for JupiterLendEarnMintTool {}
+impl ValidateArgs for JupiterLendEarnRedeemTool {}
+impl ValidateArgs for JupiterEarnTool {}
+impl ValidateArgs for AccountBalanceTool {}
+impl ValidateArgs for LendEarnTokensTool {}
+impl ValidateArgs for PositionInfoTool {}
+
+/// Problems with `args` against the JSON schema `schema`
+///
+/// Covers what tool schemas use: the object's `required` fields and the `type`
+/// and `enum` of its properties.
+pub fn check_schema(schema: &Value, args: &Value) -> Vec<ArgIssue> {
+    let Some(args) = args.as_object() else {
+        return vec![ArgIssue::new(
+            "arguments",
+            format!("Expected a JSON object, got {args}"),
+        )];
+    };
+    let mut issues = Vec::new();
+
+    for field in schema["required"].as_array().into_iter().flatten() {
+        let Some(field) = field.as_str() else {
+            continue;
+        };
+        if args.get(field).is_none_or(Value::is_null) {
+            let description = schema["properties"][field]["description"]
+                .as_str()
+                .unwrap_or_default();
+            issues
+                .push(ArgIssue::new(field, "This required argument is missing").hint(description));
+        }
+    }
+
+    let properties = schema["properties"].as_object();
+    for (field, value) in args {
+        let Some(property) = properties.and_then(|p| p.get(field)) else {
+            continue;
+        };
+        if value.is_null() {
+            continue;
+        }
+        if let Some(expected) = property["type"].as_str() {
+            if !has_type(value, expected) {
+                issues.push(ArgIssue::new(
+                    field,
+                    format!("Expected a {expected}, got {value}"),
+                ));
+                continue;
+            }
+        }
+        if let Some(allowed) = property["enum"].as_array() {
+            if !allowed.contains(value) {
+                let allowed: Vec<String> = allowed.iter().map(Value::to_string).collect();
+                issues.push(
+                    ArgIssue::new(field, format!("{value} is not allowed"))
+                        .hint(format!("Use one of: {}", allowed.join(", "))),
+                );
+            }
+        }
+    }
+    issues
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_u64() || value.is_i64(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        _ => true,
+    }
+}
+
+/// Errors of a [`Validated`] tool
+#[derive(Debug, Error)]
+pub enum ValidatedToolError<E> {
+    #[error("{tool} arguments were rejected {attempts} times; last: {issues}")]
+    RetriesExhausted {
+        tool: &'static str,
+        attempts: u32,
+        issues: String,
+    },
+    #[error(transparent)]
+    Tool(E),
+}
+
+impl ValidationContext {
+    /// Check the arguments of a call to `tool` before it is made
+    ///
+    /// Returns `None` when `args` can be passed to the tool, else the structured
+    /// rejection to answer the model with. Fails once more than `max_retries`
+    /// calls of the run were rejected.
+    pub async fn check_call<T>(
+        &self,
+        tool: &T,
+        args: &Value,
+    ) -> Result<Option<String>, ValidatedToolError<T::Error>>
+    where
+        T: Tool + ValidateArgs,
+    {
+        let issues = self.issues(tool, args).await;
+        if issues.is_empty() {
+            return Ok(None);
+        }
+        self.reject::<T>(args, &issues).map(Some)
+    }
+
+    /// The problems with the `args` of a call to `tool`, the schema's first
+    pub async fn issues<T>(&self, tool: &T, args: &Value) -> Vec<ArgIssue>
+    where
+        T: Tool + ValidateArgs,
+    {
+        let definition: ToolDefinition = tool.definition(String::new()).await;
+        let issues = check_schema(&definition.parameters, args);
+        if !issues.is_empty() {
+            return issues;
+        }
+        let issues = tool.check_args(args, self);
+        if !issues.is_empty() {
+            return issues;
+        }
+        match serde_json::from_value::<T::Args>(args.clone()) {
+            Ok(_) => Vec::new(),
+            Err(e) => vec![ArgIssue::new("arguments", e.to_string())],
+        }
+    }
+
+    /// Count a rejected call and record it in the session log
+    fn reject<T>(
+        &self,
+        args: &Value,
+        issues: &[ArgIssue],
+    ) -> Result<String, ValidatedToolError<T::Error>>
+    where
+        T: Tool,
+    {
+        let attempt = self.retries.fetch_add(1, Ordering::SeqCst) + 1;
+        let summary = issues
+            .iter()
+            .map(|issue| format!("{}: {}", issue.field, issue.problem))
+            .collect::<Vec<_>>()
+            .join("; ");
+        warn!(
+            "[{}] Rejected arguments ({attempt}/{}): {summary}",
+            T::NAME,
+            self.max_retries
+        );
+
+        if reev_flow::enhanced_otel::enhanced_otel_enabled() {
+            if let Ok(logger) = get_enhanced_otel_logger() {
+                let rejected = EnhancedToolCall {
+                    session_id: logger.session_id().to_string(),
+                    tool_name: T::NAME.to_string(),
+                    timestamp: chrono::Utc::now(),
+                    execution_time_ms: 0,
+                    input_params: args.clone(),
+                    output_result: json!({}),
+                    status: ToolExecutionStatus::Error,
+                    error_message: Some(format!("Rejected arguments: {summary}")),
+                    metadata: json!({"argument_retry": attempt, "issues": issues}),
+                };
+                if let Err(e) = logger.log_tool_call(rejected) {
+                    warn!("Failed to log rejected tool call: {e}");
+                }
+            }
+        }
+
+        if attempt > self.max_retries {
+            return Err(ValidatedToolError::RetriesExhausted {
+                tool: T::NAME,
+                attempts: attempt,
+                issues: summary,
+            });
+        }
+        Ok(json!({
+            "status": "invalid_arguments",
+            "tool": T::NAME,
+            "issues": issues,
+            "retries_left": self.max_retries - attempt,
+            "next_action": format!("Fix the arguments and call {} again", T::NAME),
+        })
+        .to_string())
+    }
+}
+
+/// A tool whose arguments are validated before it is called
+pub struct Validated<T> {
+    inner: T,
+    context: Arc<ValidationContext>,
+}
+
+impl<T> Validated<T> {
+    pub fn new(inner: T, context: Arc<ValidationContext>) -> Self {
+        Self { inner, context }
+    }
+
+    /// The problems with `args`, the schema's first
+    pub async fn issues(&self, args: &Value) -> Vec<ArgIssue>
+    where
+        T: Tool + ValidateArgs,
+    {
+        self.context.issues(&self.inner, args).await
+    }
+}
+
+impl<T> Tool for Validated<T>
+where
+    T: Tool<Output = String> + ValidateArgs,
+{
+    const NAME: &'static str = T::NAME;
+    type Error = ValidatedToolError<T::Error>;
+    type Args = Value;
+    type Output = String;
+
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    async fn definition(&self, prompt: String) -> ToolDefinition {
+        self.inner.definition(prompt).await
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        if let Some(rejection) = self.context.check_call(&self.inner, &args).await? {
+            return Ok(rejection);
+        }
+        let args = serde_json::from_value(args).expect("arguments were checked to deserialize");
+        self.inner
+            .call(args)
+            .await
+            .map_err(ValidatedToolError::Tool)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(max_retries: u32) -> (Arc<ValidationContext>, Pubkey) {
+        let user = Pubkey::new_unique();
+        let user_ata = Pubkey::new_unique();
+        let key_map = HashMap::from([
+            ("USER_WALLET_PUBKEY".to_string(), user.to_string()),
+            (
+                "RECIPIENT_WALLET_PUBKEY".to_string(),
+                Pubkey::new_unique().to_string(),
+            ),
+            ("USER_USDC_ATA".to_string(), user_ata.to_string()),
+        ]);
+        let account_states = HashMap::from([
+            (
+                "USER_WALLET_PUBKEY".to_string(),
+                json!({"lamports": 1_000_000_000u64}),
+            ),
+            (
+                "USER_USDC_ATA".to_string(),
+                json!({
+                    "lamports": 2_039_280,
+                    "mint": usdc_mint().to_string(),
+                    "token_account_owner": user.to_string(),
+                    "amount": 50_000_000
+                }),
+            ),
+        ]);
+        let context = ValidationContext::new(key_map.clone(), account_states, max_retries);
+        (Arc::new(context), user)
+    }
+
+    #[tokio::test]
+    async fn test_issues_are_actionable() {
+        let (context, _) = context(2);
+        let sol = Validated::new(
+            SolTransferTool {
+                key_map: HashMap::new(),
+            },
+            context.clone(),
+        );
+
+        let issues = sol
+            .issues(&json!({
+                "user_pubkey": "USER_WALLET_PUBKEY",
+                "recipient_pubkey": "BOB_WALLET",
+                "amount": 0.1,
+                "operation": "sol"
+            }))
+            .await;
+        assert_eq!(issues.len(), 2, "{issues:?}");
+        assert_eq!(issues[0].field, "recipient_pubkey");
+        assert!(issues[0]
+            .hint
+            .as_ref()
+            .unwrap()
+            .contains("RECIPIENT_WALLET_PUBKEY"));
+        assert_eq!(issues[1].field, "amount");
+        assert!(issues[1].hint.as_ref().unwrap().contains("100000000"));
+
+        let issues = sol
+            .issues(&json!({
+                "user_pubkey": "USER_WALLET_PUBKEY",
+                "recipient_pubkey": "RECIPIENT_WALLET_PUBKEY",
+                "amount": 2_000_000_000u64,
+                "operation": "sol"
+            }))
+            .await;
+        assert_eq!(issues.len(), 1, "{issues:?}");
+        assert!(issues[0]
+            .problem
+            .contains("1000000000 USER_WALLET_PUBKEY holds"));
+
+        let spl = Validated::new(
+            SplTransferTool {
+                key_map: HashMap::new(),
+            },
+            context,
+        );
+        let issues = spl
+            .issues(&json!({
+                "user_pubkey": "USER_WALLET_PUBKEY",
+                "recipient_pubkey": "RECIPIENT_WALLET_PUBKEY",
+                "amount": 15_000_000
+            }))
+            .await;
+        assert_eq!(issues.len(), 1, "{issues:?}");
+        assert_eq!(issues[0].field, "mint_address");
+
+        let issues = spl
+            .issues(&json!({
+                "user_pubkey": "USER_WALLET_PUBKEY",
+                "recipient_pubkey": "RECIPIENT_WALLET_PUBKEY",
+                "mint_address": usdc_mint().to_string(),
+                "amount": 60_000_000
+            }))
+            .await;
+        assert_eq!(issues.len(), 1, "{issues:?}");
+        assert!(issues[0].problem.contains("50000000 USER_USDC_ATA holds"));
+    }
+
+    #[tokio::test]
+    async fn test_rejections_are_bounded() {
+        let (context, _) = context(1);
+        let sol = Validated::new(
+            SolTransferTool {
+                key_map: HashMap::new(),
+            },
+            context.clone(),
+        );
+        let bad = json!({"user_pubkey": "USER_WALLET_PUBKEY", "amount": 5});
+
+        let answer: Value = serde_json::from_str(&sol.call(bad.clone()).await.unwrap()).unwrap();
+        assert_eq!(answer["status"], "invalid_arguments");
+        assert_eq!(answer["issues"][0]["field"], "recipient_pubkey");
+        assert_eq!(answer["retries_left"], 0);
+
+        let err = sol.call(bad).await.unwrap_err();
+        assert!(matches!(
+            err,
+            ValidatedToolError::RetriesExhausted { attempts: 2, .. }
+        ));
+        assert_eq!(context.retries(), 2);
+    }
+}
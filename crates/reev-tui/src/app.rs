@@ -3,11 +3,18 @@ use ratatui::{
     text::Text,
     widgets::{ListState, ScrollbarState},
 };
+use reev_lib::db::{DatabaseConfig, DatabaseReader};
+use reev_lib::program_logs::ProgramLogTree;
 use reev_lib::results::{FinalStatus, TestResult};
-use std::{fs, path::PathBuf};
-use strum::{Display, EnumIter, FromRepr};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 use tokio::sync::mpsc::{self, Receiver, Sender};
 
+/// Number of evaluation runs shown in the runs view
+const RUNS_LIMIT: u32 = 20;
+
 #[derive(Clone, PartialEq, Debug)]
 pub enum BenchmarkStatus {
     Pending,
@@ -16,61 +23,70 @@ pub enum BenchmarkStatus {
     Failed,
 }
 
-#[derive(Default, Clone, Copy, Debug, Display, FromRepr, EnumIter, PartialEq, Eq)]
-pub enum SelectedAgent {
-    #[default]
-    #[strum(to_string = " Deterministic ")]
-    Deterministic,
-    #[strum(to_string = " Gemini ")]
-    Gemini,
-    #[strum(to_string = " Local ")]
-    Local,
-    #[strum(to_string = " GLM 4.6 ")]
-    Glm46,
-    #[strum(to_string = " GLM 4.6 Coding")]
-    Glm46Coding,
+/// The agent selector, offering the models of the registry in reev.toml
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SelectedAgent {
+    names: Vec<String>,
+    index: usize,
+}
+
+impl Default for SelectedAgent {
+    fn default() -> Self {
+        Self::new(reev_config::get().models.keys().cloned().collect())
+    }
 }
 
 impl SelectedAgent {
-    pub fn to_agent_name(self) -> &'static str {
-        match self {
-            SelectedAgent::Deterministic => "deterministic",
-            SelectedAgent::Gemini => "gemini-2.5-flash-lite",
-            SelectedAgent::Local => "local",
-            SelectedAgent::Glm46 => "glm-4.6",
-            SelectedAgent::Glm46Coding => "glm-4.6-coding",
-        }
+    /// A selector over `names`, starting at the deterministic agent when offered
+    pub fn new(names: Vec<String>) -> Self {
+        let index = names
+            .iter()
+            .position(|name| name == "deterministic")
+            .unwrap_or(0);
+        Self { names, index }
+    }
+
+    /// The agents offered, in order
+    pub fn names(&self) -> &[String] {
+        &self.names
     }
 
-    pub fn previous(self) -> Self {
-        let current_index: usize = self as usize;
-        let previous_index = current_index.saturating_sub(1);
-        Self::from_repr(previous_index).unwrap_or(self)
+    /// Position of the selected agent in [`SelectedAgent::names`]
+    pub fn index(&self) -> usize {
+        self.index
     }
 
-    pub fn next(self) -> Self {
-        let current_index = self as usize;
-        let next_index = current_index.saturating_add(1);
-        Self::from_repr(next_index).unwrap_or(self)
+    pub fn to_agent_name(&self) -> &str {
+        &self.names[self.index]
+    }
+
+    /// Select the closest enabled agent before the current one
+    pub fn previous(&mut self) {
+        if let Some(index) = (0..self.index)
+            .rev()
+            .find(|&i| !Self::is_disabled(&self.names[i], false))
+        {
+            self.index = index;
+        }
+    }
+
+    /// Select the closest enabled agent after the current one
+    pub fn next(&mut self) {
+        if let Some(index) =
+            (self.index + 1..self.names.len()).find(|&i| !Self::is_disabled(&self.names[i], false))
+        {
+            self.index = index;
+        }
     }
 
-    pub fn is_disabled(&self, is_running: bool) -> bool {
+    /// Whether the agent `name` cannot be selected: while a benchmark runs, or when
+    /// the API key its model needs is not set
+    pub fn is_disabled(name: &str, is_running: bool) -> bool {
         if is_running {
             return true;
         }
 
-        // Check if GLM environment variables are properly configured
-        if matches!(self, SelectedAgent::Glm46) {
-            let has_glm_key = std::env::var("ZAI_API_KEY").is_ok();
-            let has_glm_url = std::env::var("ZAI_API_URL").is_ok();
-            !(has_glm_key && has_glm_url)
-        } else if matches!(self, SelectedAgent::Glm46Coding) {
-            let has_glm_key = std::env::var("GLM_CODING_API_KEY").is_ok();
-            let has_glm_url = std::env::var("GLM_CODING_API_URL").is_ok();
-            !(has_glm_key && has_glm_url)
-        } else {
-            false
-        }
+        reev_config::get().require_api_key(name).is_err()
     }
 }
 
@@ -90,7 +106,7 @@ pub struct Benchmark<'a> {
 
 pub enum TuiEvent {
     BenchmarkStarted(usize),
-    BenchmarkCompleted(usize, Result<TestResult>),
+    BenchmarkCompleted(usize, Box<Result<TestResult>>),
 }
 
 pub struct App<'a> {
@@ -99,6 +115,7 @@ pub struct App<'a> {
     pub is_running_benchmark: bool,
     pub active_panel: ActivePanel,
     pub show_log_panel: bool,
+    pub show_runs: bool,
     pub selected_agent: SelectedAgent,
     pub benchmarks: Vec<Benchmark<'a>>,
     pub benchmark_state: ListState,
@@ -108,6 +125,7 @@ pub struct App<'a> {
     pub details_horizontal_scroll: u16,
     pub details_scroll_state: ScrollbarState,
     pub transaction_log_content: Text<'a>,
+    pub runs_content: Text<'a>,
     pub shared_surfpool: bool,
     pub log_scroll: u16,
     pub log_horizontal_scroll: u16,
@@ -129,6 +147,7 @@ impl<'a> App<'a> {
             is_running_benchmark: false,
             active_panel: ActivePanel::BenchmarkNavigator,
             show_log_panel: true,
+            show_runs: false,
             selected_agent: SelectedAgent::default(),
             benchmarks,
             benchmark_state,
@@ -139,6 +158,7 @@ impl<'a> App<'a> {
             details_horizontal_scroll: 0,
             details_scroll_state: ScrollbarState::default(),
             transaction_log_content: Text::from(""),
+            runs_content: Text::from(""),
             log_scroll: 0,
             log_horizontal_scroll: 0,
             log_scroll_state: ScrollbarState::default(),
@@ -186,11 +206,13 @@ impl<'a> App<'a> {
             TuiEvent::BenchmarkCompleted(index, result) => {
                 self.is_running_benchmark = false;
                 if let Some(benchmark) = self.benchmarks.get_mut(index) {
-                    match result {
+                    match *result {
                         Ok(test_result) => {
                             benchmark.status = match test_result.final_status {
                                 FinalStatus::Succeeded => BenchmarkStatus::Succeeded,
-                                FinalStatus::Failed => BenchmarkStatus::Failed,
+                                FinalStatus::Failed
+                                | FinalStatus::TimedOut
+                                | FinalStatus::BudgetExceeded => BenchmarkStatus::Failed,
                             };
                             let rendered_tree =
                                 reev_runner::renderer::render_result_as_tree(&test_result);
@@ -227,7 +249,7 @@ impl<'a> App<'a> {
         let new_logs = if let Some(benchmark) = self.get_selected_benchmark() {
             if let Some(result) = &benchmark.result {
                 if let Some(step) = result.trace.steps.first() {
-                    step.observation.last_transaction_logs.join("\n")
+                    ProgramLogTree::parse(&step.observation.last_transaction_logs).render_text(true)
                 } else {
                     String::from("No transaction steps found for this result.")
                 }
@@ -249,7 +271,7 @@ impl<'a> App<'a> {
         if let Some(selected_index) = self.benchmark_state.selected() {
             let path = self.benchmarks[selected_index].path.clone();
             let sender = self.event_sender.clone();
-            let agent_name = self.selected_agent.to_agent_name();
+            let agent_name = self.selected_agent.to_agent_name().to_string();
             let shared_surfpool = self.shared_surfpool;
 
             tokio::task::spawn_blocking(move || {
@@ -261,7 +283,7 @@ impl<'a> App<'a> {
                         .send(TuiEvent::BenchmarkStarted(selected_index))
                         .await;
 
-                    reev_runner::run_benchmarks(path, agent_name, shared_surfpool, true).await
+                    reev_runner::run_benchmarks(path, &agent_name, shared_surfpool, true).await
                 });
 
                 let final_result = match result {
@@ -274,7 +296,10 @@ impl<'a> App<'a> {
                 drop(rt_handle);
                 rt.block_on(async {
                     let _ = sender
-                        .send(TuiEvent::BenchmarkCompleted(selected_index, final_result))
+                        .send(TuiEvent::BenchmarkCompleted(
+                            selected_index,
+                            Box::new(final_result),
+                        ))
                         .await;
                 });
             });
@@ -332,17 +357,15 @@ impl<'a> App<'a> {
     }
 
     pub fn on_left(&mut self) {
-        if !self.is_running_benchmark && !self.selected_agent.is_disabled(self.is_running_benchmark)
-        {
-            self.selected_agent = self.selected_agent.previous();
+        if !self.is_running_benchmark {
+            self.selected_agent.previous();
             self.reset_benchmarks();
         }
     }
 
     pub fn on_right(&mut self) {
-        if !self.is_running_benchmark && !self.selected_agent.is_disabled(self.is_running_benchmark)
-        {
-            self.selected_agent = self.selected_agent.next();
+        if !self.is_running_benchmark {
+            self.selected_agent.next();
             self.reset_benchmarks();
         }
     }
@@ -378,7 +401,7 @@ impl<'a> App<'a> {
         self.reset_scroll();
     }
 
-    pub fn get_selected_benchmark(&self) -> Option<&Benchmark> {
+    pub fn get_selected_benchmark(&self) -> Option<&Benchmark<'a>> {
         self.benchmark_state
             .selected()
             .and_then(|i| self.benchmarks.get(i))
@@ -392,9 +415,12 @@ impl<'a> App<'a> {
     }
 
     pub fn scroll_down(&mut self) {
-        let content_height = self
-            .get_selected_benchmark()
-            .map_or(0, |b| b.details.height());
+        let content_height = if self.show_runs {
+            self.runs_content.height()
+        } else {
+            self.get_selected_benchmark()
+                .map_or(0, |b| b.details.height())
+        };
         self.details_scroll = self.details_scroll.saturating_add(1);
         if self.details_scroll > content_height as u16 {
             self.details_scroll = content_height as u16;
@@ -421,6 +447,33 @@ impl<'a> App<'a> {
         }
     }
 
+    /// Switch panel B between the selected benchmark and the recorded evaluation runs
+    pub async fn on_toggle_runs(&mut self) {
+        self.show_runs = !self.show_runs;
+        self.details_scroll = 0;
+        self.details_horizontal_scroll = 0;
+        if self.show_runs {
+            self.runs_content = match Self::load_runs().await {
+                Ok(runs) => Text::from(runs),
+                Err(e) => Text::from(format!("Error: {e:#}")),
+            };
+        }
+    }
+
+    async fn load_runs() -> Result<String> {
+        // The database the runner records results into
+        let settings = &reev_config::get().database;
+        if !Path::new(&settings.path).exists() {
+            return Ok("No evaluation runs recorded yet.".to_string());
+        }
+        let reader = DatabaseReader::from_config(DatabaseConfig::from_settings(settings)).await?;
+        let runs = reader.list_runs(Some(RUNS_LIMIT)).await?;
+        if runs.is_empty() {
+            return Ok("No evaluation runs recorded yet.".to_string());
+        }
+        Ok(reev_runner::cli::runs::format_runs(&runs))
+    }
+
     pub fn scroll_left(&mut self) {
         self.details_horizontal_scroll = self.details_horizontal_scroll.saturating_sub(4);
     }
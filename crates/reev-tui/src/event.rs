@@ -25,6 +25,7 @@ pub async fn handle_events(app: &mut App<'_>) -> Result<()> {
                     KeyCode::Char('s') if !app.is_running_benchmark => {
                         app.on_toggle_shared_surfpool()
                     }
+                    KeyCode::Char('e') if !app.is_running_benchmark => app.on_toggle_runs().await,
                     _ => match app.active_panel {
                         ActivePanel::BenchmarkNavigator => match key.code {
                             KeyCode::Up | KeyCode::Char('k') => app.on_up(),
@@ -6,7 +6,6 @@ use ratatui::{
     widgets::{Block, Borders, List, ListItem, Paragraph, Scrollbar, ScrollbarOrientation},
     Frame,
 };
-use strum::IntoEnumIterator;
 
 fn create_percentage_spans(score_str: String, percentage: u32) -> Vec<Span<'static>> {
     let mut spans = Vec::new();
@@ -87,12 +86,12 @@ fn render_header(f: &mut Frame, app: &mut App, area: Rect) {
     let disabled_style = Style::default().fg(Color::DarkGray);
 
     // Create custom tabs with individual styling
-    let agents: Vec<_> = SelectedAgent::iter().collect();
+    let agents = app.selected_agent.names();
     let mut tab_spans = Vec::new();
 
     for (index, agent) in agents.iter().enumerate() {
-        let is_selected = *agent == app.selected_agent;
-        let is_disabled = agent.is_disabled(app.is_running_benchmark);
+        let is_selected = index == app.selected_agent.index();
+        let is_disabled = SelectedAgent::is_disabled(agent, app.is_running_benchmark);
 
         let style = if is_selected {
             highlight_style
@@ -102,7 +101,7 @@ fn render_header(f: &mut Frame, app: &mut App, area: Rect) {
             normal_style
         };
 
-        let tab_text = agent.to_string();
+        let tab_text = format!(" {agent} ");
         tab_spans.push(Span::styled(tab_text, style));
 
         // Add separator between tabs
@@ -208,6 +207,7 @@ fn render_scrollable_text_panel(
     app: &mut App,
     area: Rect,
     title: &str,
+    text: Text,
     is_active: bool,
 ) {
     let border_style = if is_active {
@@ -220,11 +220,6 @@ fn render_scrollable_text_panel(
         .borders(Borders::ALL)
         .border_style(border_style);
 
-    let text = app.get_selected_benchmark().map_or_else(
-        || Text::from("No benchmark selected"),
-        |b| b.details.clone(),
-    );
-
     let content_height = text.height();
 
     let paragraph = Paragraph::new(text)
@@ -249,13 +244,19 @@ fn render_scrollable_text_panel(
 }
 
 fn render_trace_view(f: &mut Frame, app: &mut App, area: Rect) {
-    render_scrollable_text_panel(
-        f,
-        app,
-        area,
-        "B: Execution Trace View",
-        app.active_panel == ActivePanel::ExecutionTrace,
-    );
+    let (title, text) = if app.show_runs {
+        ("B: Evaluation Runs", app.runs_content.clone())
+    } else {
+        (
+            "B: Execution Trace View",
+            app.get_selected_benchmark().map_or_else(
+                || Text::from("No benchmark selected"),
+                |b| b.details.clone(),
+            ),
+        )
+    };
+    let is_active = app.active_panel == ActivePanel::ExecutionTrace;
+    render_scrollable_text_panel(f, app, area, title, text, is_active);
 }
 
 fn render_agent_log_view(f: &mut Frame, app: &mut App, area: Rect) {
@@ -306,6 +307,8 @@ fn render_footer(f: &mut Frame, area: Rect) {
         Span::raw("ll | "),
         Span::styled("[S]", Style::default().add_modifier(Modifier::BOLD)),
         Span::raw("hared | "),
+        Span::styled("[E]", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw("val runs | "),
         Span::styled("[Q]", Style::default().add_modifier(Modifier::BOLD)),
         Span::raw("uit"),
     ])